@@ -0,0 +1,145 @@
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use shared::channel::CardType;
+use shared::targeting::{check_enemy_target, TargetSource};
+use crate::{Bot, BotAction, BotView, Creature};
+
+// The tuned weights that ship with the bots; servers may be pointed at another file at startup
+pub const WEIGHTS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/weights.toml");
+
+// Every weight has to be in the file, so a typo can't quietly leave one at zero
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EvalWeights {
+    pub health: f32,
+    pub board_power: f32,
+    pub board_health: f32,
+    pub card_advantage: f32,
+    pub tempo: f32,
+    pub lethal: f32,
+}
+
+pub fn load_weights_from(path: &Path) -> Result<EvalWeights, Box<dyn std::error::Error>> {
+    let config_str = std::fs::read_to_string(path)?;
+    Ok(toml::from_str(&config_str)?)
+}
+
+// Higher is better for the bot
+pub fn evaluate(view: &BotView, weights: &EvalWeights) -> f32 {
+    if view.opponent_health == 0 {
+        return weights.lethal;
+    }
+    if view.health == 0 {
+        return -weights.lethal;
+    }
+
+    let (own_power, own_health) = view.board.iter().flatten()
+        .fold((0, 0), |(power, health), creature| (power + creature.card.power, health + creature.card.health));
+    let (enemy_power, enemy_health) = view.opponent_board.iter().flatten()
+        .fold((0, 0), |(power, health), card| (power + card.power, health + card.health));
+
+    weights.health * (view.health as f32 - view.opponent_health as f32)
+        + weights.board_power * (own_power as f32 - enemy_power as f32)
+        + weights.board_health * (own_health as f32 - enemy_health as f32)
+        + weights.card_advantage * (view.hand.len() as f32 - view.opponent_hand_count as f32)
+        - weights.tempo * view.mana as f32
+}
+
+// Whether the creatures ready to attack can finish the opponent this turn
+pub fn has_lethal(view: &BotView) -> bool {
+    let ready_power: u32 = view.board.iter().flatten()
+        .filter(|creature| creature.can_attack)
+        .map(|creature| creature.card.power)
+        .sum();
+    view.opponent_health > 0 && ready_power >= view.opponent_health
+}
+
+// The view after an action, in a rough model of the rules: creatures fight by trading damage and
+// spells hit the opponent for their power, since their effects aren't simulated
+pub fn simulate(view: &BotView, action: BotAction) -> BotView {
+    let mut next = view.clone();
+    match action {
+        BotAction::PlayCard { card_id, slot, .. } => {
+            let Some(index) = next.hand.iter().position(|card| card.card_id == card_id) else { return next };
+            let card = next.hand.remove(index);
+            next.mana = next.mana.saturating_sub(card.play_cost());
+            let is_creature = matches!(card.card_type, CardType::Creature);
+            match (is_creature, slot.and_then(|slot| next.board.get_mut(slot))) {
+                (true, Some(entry)) => *entry = Some(Creature { card, can_attack: false }),
+                (true, None) => {}
+                (false, _) => next.opponent_health = next.opponent_health.saturating_sub(card.power),
+            }
+        }
+        BotAction::Attack { attacker, target } => {
+            let Some(Some(creature)) = next.board.get_mut(attacker) else { return next };
+            creature.can_attack = false;
+            let defender = target.and_then(|target| next.opponent_board.get_mut(target)).and_then(Option::as_mut);
+            match defender {
+                None => next.opponent_health = next.opponent_health.saturating_sub(creature.card.power),
+                Some(defender) => {
+                    defender.health = defender.health.saturating_sub(creature.card.power);
+                    creature.card.health = creature.card.health.saturating_sub(defender.power);
+                }
+            }
+            next.board.iter_mut().for_each(|slot| { slot.take_if(|creature| creature.card.health == 0); });
+            next.opponent_board.iter_mut().for_each(|slot| { slot.take_if(|card| card.health == 0); });
+        }
+        BotAction::EndTurn => {}
+    }
+    next
+}
+
+// Every action the rules would allow, as far as the view shows, starting with EndTurn
+pub fn candidate_actions(view: &BotView) -> Vec<BotAction> {
+    let free_slot = view.board.iter().position(Option::is_none);
+    let mut actions = vec![BotAction::EndTurn];
+
+    for card in view.hand.iter().filter(|card| card.play_cost() <= view.mana) {
+        let is_creature = matches!(card.card_type, CardType::Creature);
+        if is_creature && free_slot.is_none() {
+            continue;
+        }
+        actions.push(BotAction::PlayCard { card_id: card.card_id, target: None, slot: free_slot.filter(|_| is_creature) });
+    }
+
+    let targets: Vec<usize> = view.opponent_board.iter().enumerate()
+        .filter(|(_, card)| card.as_ref().is_some_and(|card| check_enemy_target(card, TargetSource::Attack).is_ok()))
+        .map(|(slot, _)| slot)
+        .collect();
+    for (attacker, _) in view.board.iter().enumerate().filter(|(_, slot)| slot.as_ref().is_some_and(|creature| creature.can_attack)) {
+        actions.push(BotAction::Attack { attacker, target: None });
+        actions.extend(targets.iter().map(|&target| BotAction::Attack { attacker, target: Some(target) }));
+    }
+    actions
+}
+
+// Looks one action ahead and takes whichever leaves the best-scoring board, going face whenever it has lethal
+#[derive(Clone, Debug)]
+pub struct HeuristicBot {
+    pub weights: EvalWeights,
+}
+
+impl HeuristicBot {
+    pub fn new(weights: EvalWeights) -> Self {
+        Self { weights }
+    }
+}
+
+impl Bot for HeuristicBot {
+    fn choose_action(&mut self, view: &BotView) -> BotAction {
+        if has_lethal(view) {
+            if let Some(attacker) = view.board.iter().position(|slot| slot.as_ref().is_some_and(|creature| creature.can_attack)) {
+                return BotAction::Attack { attacker, target: None };
+            }
+        }
+
+        // EndTurn comes first, so anything else has to score strictly better
+        let mut best = (BotAction::EndTurn, evaluate(view, &self.weights));
+        for action in candidate_actions(view).into_iter().skip(1) {
+            let score = evaluate(&simulate(view, action), &self.weights);
+            if score > best.1 {
+                best = (action, score);
+            }
+        }
+        best.0
+    }
+}
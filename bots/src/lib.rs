@@ -0,0 +1,126 @@
+// Decision making shared by the server's AI opponent, the balance simulator and stress-test clients.
+// Bots only see what a player at the table could, so one bot can drive any of them.
+use shared::channel::{CardData, CardType, GameMessage, GameStateSnapshot, BOARD_SLOTS};
+use shared::targeting::{check_enemy_target, TargetSource};
+use shared::EntityID;
+
+pub mod heuristic;
+pub mod mcts;
+
+#[derive(Clone, Debug)]
+pub struct Creature {
+    pub card: CardData,
+    // False while summoning sick or after attacking this turn
+    pub can_attack: bool,
+}
+
+// The table from the bot's seat
+#[derive(Clone, Debug, Default)]
+pub struct BotView {
+    // With effective_cost filled in wherever modifiers apply
+    pub hand: Vec<CardData>,
+    pub mana: u32,
+    pub health: u32,
+    pub board: Vec<Option<Creature>>,
+    pub opponent_health: u32,
+    pub opponent_hand_count: u32,
+    pub opponent_board: Vec<Option<CardData>>,
+}
+
+impl BotView {
+    // For bots playing over the network. Clients can't see summoning sickness, so every creature is assumed
+    // ready; attacks the server refuses come back as ActionRejected
+    pub fn from_snapshot(snapshot: &GameStateSnapshot, player_id: EntityID) -> Self {
+        let mut view = BotView { hand: snapshot.hand.clone(), ..Default::default() };
+        for player in &snapshot.players {
+            if player.player_id == player_id {
+                view.mana = player.mana;
+                view.health = player.health;
+                view.board = player.board.iter()
+                    .map(|slot| slot.clone().map(|card| Creature { card, can_attack: true }))
+                    .collect();
+            } else {
+                view.opponent_health = player.health;
+                view.opponent_hand_count = player.hand_count;
+                view.opponent_board = player.board.clone();
+            }
+        }
+        if view.board.is_empty() {
+            view.board = vec![None; BOARD_SLOTS];
+        }
+        view
+    }
+
+    fn free_slot(&self) -> Option<usize> {
+        self.board.iter().position(Option::is_none)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BotAction {
+    PlayCard {
+        card_id: EntityID,
+        target: Option<EntityID>,
+        slot: Option<usize>,
+    },
+    Attack {
+        attacker: usize,
+        target: Option<usize>,
+    },
+    EndTurn,
+}
+
+impl From<BotAction> for GameMessage {
+    fn from(action: BotAction) -> Self {
+        match action {
+            BotAction::PlayCard { card_id, target, slot } => GameMessage::PlayCard { card_id, target, slot },
+            BotAction::Attack { attacker, target } => GameMessage::Attack { attacker, target },
+            BotAction::EndTurn => GameMessage::EndTurn,
+        }
+    }
+}
+
+pub trait Bot: Send + Sync {
+    // Every view the bot is shown, including the opponent's turns, for bots that keep track of the game
+    fn observe(&mut self, _view: &BotView) {}
+
+    // One action per call; the caller applies it and asks again until the bot ends its turn
+    fn choose_action(&mut self, view: &BotView) -> BotAction;
+}
+
+// The priciest affordable card, then favourable trades or face attacks, then ending the turn
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GreedyBot;
+
+impl Bot for GreedyBot {
+    fn choose_action(&mut self, view: &BotView) -> BotAction {
+        let free_slot = view.free_slot();
+        let playable = view.hand.iter()
+            .filter(|card| card.play_cost() <= view.mana)
+            .filter(|card| free_slot.is_some() || !matches!(card.card_type, CardType::Creature))
+            .max_by_key(|card| card.play_cost());
+        if let Some(card) = playable {
+            let is_creature = matches!(card.card_type, CardType::Creature);
+            return BotAction::PlayCard {
+                card_id: card.card_id,
+                target: None,
+                slot: free_slot.filter(|_| is_creature),
+            };
+        }
+
+        let attacker = view.board.iter()
+            .enumerate()
+            .find_map(|(slot, creature)| creature.as_ref().filter(|creature| creature.can_attack).map(|creature| (slot, &creature.card)));
+        if let Some((attacker, card)) = attacker {
+            // Trade only when the attacker kills the defender and survives, otherwise go face
+            let target = view.opponent_board.iter().position(|defender| defender.as_ref().is_some_and(|defender| {
+                check_enemy_target(defender, TargetSource::Attack).is_ok()
+                    && defender.health <= card.power
+                    && defender.power < card.health
+            }));
+            return BotAction::Attack { attacker, target };
+        }
+
+        BotAction::EndTurn
+    }
+}
@@ -0,0 +1,158 @@
+use std::time::{Duration, Instant};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use crate::heuristic::{candidate_actions, evaluate, simulate, EvalWeights, HeuristicBot};
+use crate::{Bot, BotAction, BotView};
+
+// UCT's exploration constant; higher tries more of the less promising actions
+const EXPLORATION: f32 = 1.4;
+// Random actions per rollout before the turn is scored, in case a playout never ends its turn
+const MAX_ROLLOUT_ACTIONS: usize = 20;
+// Score difference that counts as a clear edge when squashing evaluations into 0..1 rewards
+const SCORE_SCALE: f32 = 10.0;
+
+// How the search plays actions forward. `State` is whatever the model needs to apply them; the search only
+// reads it through the view of the bot's seat
+pub trait ForwardModel {
+    type State: Clone;
+    fn view(&self, state: &Self::State) -> BotView;
+    fn apply(&self, state: &Self::State, action: BotAction) -> Self::State;
+}
+
+// The heuristic's rough model of the rules, for callers that can't run the real ones
+pub struct HeuristicModel;
+
+impl ForwardModel for HeuristicModel {
+    type State = BotView;
+
+    fn view(&self, state: &BotView) -> BotView {
+        state.clone()
+    }
+
+    fn apply(&self, state: &BotView, action: BotAction) -> BotView {
+        simulate(state, action)
+    }
+}
+
+struct Node<S> {
+    state: S,
+    view: BotView,
+    // None for the root
+    action: Option<BotAction>,
+    children: Vec<usize>,
+    untried: Vec<BotAction>,
+    visits: u32,
+    reward: f32,
+}
+
+impl<S> Node<S> {
+    fn new(state: S, view: BotView, action: Option<BotAction>) -> Self {
+        let terminal = action == Some(BotAction::EndTurn) || view.opponent_health == 0;
+        let untried = if terminal { Vec::new() } else { candidate_actions(&view) };
+        Self { state, view, action, children: Vec::new(), untried, visits: 0, reward: 0.0 }
+    }
+
+    fn uct(&self, parent_visits: u32) -> f32 {
+        let visits = self.visits.max(1) as f32;
+        self.reward / visits + EXPLORATION * ((parent_visits.max(1) as f32).ln() / visits).sqrt()
+    }
+}
+
+// Searches the rest of the turn with Monte Carlo tree search: every action is played out through a forward
+// model with random follow-ups until the turn ends, and the end positions are scored with the heuristic
+// evaluation. Thinks for `budget` per action, then takes the most explored action. As a plain `Bot` it only
+// has the heuristic model; the server searches with its own rules through `search`.
+#[derive(Clone)]
+pub struct MctsBot {
+    pub weights: EvalWeights,
+    pub budget: Duration,
+    // Seeded, so the same position and budget tend to get the same answer
+    rng: StdRng,
+}
+
+impl MctsBot {
+    pub fn new(weights: EvalWeights, budget: Duration) -> Self {
+        Self { weights, budget, rng: StdRng::seed_from_u64(0) }
+    }
+
+    fn reward(&self, view: &BotView, baseline: f32) -> f32 {
+        let score = evaluate(view, &self.weights) - baseline;
+        1.0 / (1.0 + (-score / SCORE_SCALE).exp())
+    }
+
+    fn rollout<M: ForwardModel>(&mut self, model: &M, state: &M::State, view: &BotView, terminal: bool) -> BotView {
+        if terminal {
+            return view.clone();
+        }
+        let (mut state, mut view) = (state.clone(), view.clone());
+        for _ in 0..MAX_ROLLOUT_ACTIONS {
+            let actions = candidate_actions(&view);
+            let Some(&action) = actions.choose(&mut self.rng) else { break };
+            if action == BotAction::EndTurn {
+                break;
+            }
+            state = model.apply(&state, action);
+            view = model.view(&state);
+            if view.opponent_health == 0 {
+                break;
+            }
+        }
+        view
+    }
+
+    pub fn search<M: ForwardModel>(&mut self, model: &M, root: M::State) -> BotAction {
+        let started = Instant::now();
+        let view = model.view(&root);
+        let baseline = evaluate(&view, &self.weights);
+        let mut nodes = vec![Node::new(root, view.clone(), None)];
+
+        while started.elapsed() < self.budget {
+            // Selection: follow the best UCT score down to a node with actions left to try
+            let mut path = vec![0];
+            let mut current = 0;
+            while nodes[current].untried.is_empty() && !nodes[current].children.is_empty() {
+                let parent_visits = nodes[current].visits;
+                current = *nodes[current].children.iter()
+                    .max_by(|&&a, &&b| nodes[a].uct(parent_visits).total_cmp(&nodes[b].uct(parent_visits)))
+                    .expect("children is not empty");
+                path.push(current);
+            }
+
+            // Expansion: one untried action becomes a child
+            if !nodes[current].untried.is_empty() {
+                let index = self.rng.gen_range(0..nodes[current].untried.len());
+                let action = nodes[current].untried.swap_remove(index);
+                let state = model.apply(&nodes[current].state, action);
+                let child_view = model.view(&state);
+                nodes.push(Node::new(state, child_view, Some(action)));
+                let child = nodes.len() - 1;
+                nodes[current].children.push(child);
+                current = child;
+                path.push(current);
+            }
+
+            // Simulation and backpropagation
+            let terminal = nodes[current].untried.is_empty() && nodes[current].children.is_empty();
+            let end = self.rollout(model, &nodes[current].state, &nodes[current].view, terminal);
+            let reward = self.reward(&end, baseline);
+            for &index in &path {
+                nodes[index].visits += 1;
+                nodes[index].reward += reward;
+            }
+        }
+
+        // Out of time before trying anything; fall back to the one-step heuristic
+        let best = nodes[0].children.iter().max_by_key(|&&child| nodes[child].visits);
+        match best.and_then(|&child| nodes[child].action) {
+            Some(action) => action,
+            None => HeuristicBot::new(self.weights.clone()).choose_action(&view),
+        }
+    }
+}
+
+impl Bot for MctsBot {
+    fn choose_action(&mut self, view: &BotView) -> BotAction {
+        self.search(&HeuristicModel, view.clone())
+    }
+}
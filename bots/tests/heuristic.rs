@@ -0,0 +1,83 @@
+use std::path::Path;
+use bots::heuristic::{evaluate, has_lethal, load_weights_from, EvalWeights, HeuristicBot, WEIGHTS_PATH};
+use bots::{Bot, BotAction, BotView, Creature};
+use shared::channel::{CardData, CardType, BOARD_SLOTS};
+use shared::test_support::CardBuilder;
+use shared::EntityID;
+
+fn card(card_id: EntityID, card_type: CardType, cost: u32, power: u32, health: u32) -> CardData {
+    CardBuilder::new(card_id).card_type(card_type).cost(cost).stats(power, health).build()
+}
+
+fn weights() -> EvalWeights {
+    load_weights_from(Path::new(WEIGHTS_PATH)).expect("assets/weights.toml should parse")
+}
+
+fn view(mana: u32, hand: Vec<CardData>) -> BotView {
+    BotView {
+        hand,
+        mana,
+        health: 30,
+        board: vec![None; BOARD_SLOTS],
+        opponent_health: 30,
+        opponent_hand_count: 5,
+        opponent_board: vec![None; BOARD_SLOTS],
+    }
+}
+
+fn ready(card: CardData) -> Option<Creature> {
+    Some(Creature { card, can_attack: true })
+}
+
+#[test]
+fn bundled_weights_parse() {
+    let weights = weights();
+    assert!(weights.lethal > weights.card_advantage);
+}
+
+#[test]
+fn a_dead_opponent_outscores_any_board() {
+    let weights = weights();
+    let mut winning = view(0, Vec::new());
+    winning.opponent_health = 0;
+    let mut strong = view(0, Vec::new());
+    strong.board = (0..BOARD_SLOTS as u128).map(|id| ready(card(id, CardType::Creature, 0, 9, 9))).collect();
+    assert!(evaluate(&winning, &weights) > evaluate(&strong, &weights));
+}
+
+#[test]
+fn goes_face_with_lethal_on_board() {
+    let mut state = view(0, Vec::new());
+    state.opponent_health = 4;
+    state.board[1] = ready(card(10, CardType::Creature, 0, 2, 2));
+    state.board[3] = ready(card(11, CardType::Creature, 0, 2, 2));
+    // A trade this good would win any other turn
+    state.opponent_board[0] = Some(card(20, CardType::Creature, 0, 1, 1));
+    assert!(has_lethal(&state));
+    assert_eq!(HeuristicBot::new(weights()).choose_action(&state), BotAction::Attack { attacker: 1, target: None });
+}
+
+#[test]
+fn spends_mana_on_the_strongest_creature() {
+    let state = view(3, vec![
+        card(10, CardType::Creature, 1, 1, 1),
+        card(11, CardType::Creature, 3, 3, 3),
+    ]);
+    assert_eq!(
+        HeuristicBot::new(weights()).choose_action(&state),
+        BotAction::PlayCard { card_id: 11, target: None, slot: Some(0) },
+    );
+}
+
+#[test]
+fn prefers_face_over_losing_trades() {
+    let mut state = view(0, Vec::new());
+    state.board[0] = ready(card(10, CardType::Creature, 0, 1, 1));
+    state.opponent_board[0] = Some(card(20, CardType::Creature, 0, 5, 5));
+    state.opponent_board[1] = Some(card(21, CardType::Creature, 0, 4, 4));
+    // Going face is still better than suiciding into a 5/5
+    assert_eq!(HeuristicBot::new(weights()).choose_action(&state), BotAction::Attack { attacker: 0, target: None });
+
+    state.board[0].as_mut().unwrap().can_attack = false;
+    assert_eq!(HeuristicBot::new(weights()).choose_action(&state), BotAction::EndTurn);
+}
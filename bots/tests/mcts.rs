@@ -0,0 +1,51 @@
+use std::path::Path;
+use std::time::Duration;
+use bots::heuristic::{load_weights_from, WEIGHTS_PATH};
+use bots::mcts::MctsBot;
+use bots::{Bot, BotAction, BotView, Creature};
+use shared::channel::{CardData, BOARD_SLOTS};
+use shared::test_support::CardBuilder;
+use shared::EntityID;
+
+fn creature(card_id: EntityID, cost: u32, power: u32, health: u32) -> CardData {
+    CardBuilder::new(card_id).cost(cost).stats(power, health).build()
+}
+
+fn view(mana: u32, hand: Vec<CardData>) -> BotView {
+    BotView {
+        hand,
+        mana,
+        health: 30,
+        board: vec![None; BOARD_SLOTS],
+        opponent_health: 30,
+        opponent_hand_count: 5,
+        opponent_board: vec![None; BOARD_SLOTS],
+    }
+}
+
+fn bot() -> MctsBot {
+    MctsBot::new(load_weights_from(Path::new(WEIGHTS_PATH)).unwrap(), Duration::from_millis(100))
+}
+
+#[test]
+fn attacks_toward_lethal() {
+    let mut state = view(0, Vec::new());
+    state.opponent_health = 4;
+    state.board[1] = Some(Creature { card: creature(10, 0, 2, 2), can_attack: true });
+    state.board[3] = Some(Creature { card: creature(11, 0, 2, 2), can_attack: true });
+    let action = bot().choose_action(&state);
+    assert!(matches!(action, BotAction::Attack { target: None, .. }), "got {action:?}");
+}
+
+#[test]
+fn spends_mana_instead_of_passing() {
+    let state = view(3, vec![creature(20, 3, 3, 3)]);
+    let action = bot().choose_action(&state);
+    assert!(matches!(action, BotAction::PlayCard { card_id: 20, .. }), "got {action:?}");
+}
+
+#[test]
+fn passes_with_nothing_to_do() {
+    let state = view(0, Vec::new());
+    assert_eq!(bot().choose_action(&state), BotAction::EndTurn);
+}
@@ -0,0 +1,71 @@
+use std::collections::VecDeque;
+use bevy::prelude::*;
+use bevy_inspector_egui::egui;
+
+// Announcements kept for the accessible view, oldest dropped first
+const MAX_ANNOUNCEMENTS: usize = 30;
+pub(crate) const TEXT_SCALE_RANGE: std::ops::RangeInclusive<f32> = 1.0..=2.0;
+
+// Accessible mode describes the table in plain text and speaks game events through egui's screen reader
+// output. bevy_egui doesn't forward egui's AccessKit tree, so widget labels are what assistive tools get
+#[derive(Resource, Debug)]
+pub(crate) struct Accessibility {
+    pub(crate) enabled: bool,
+    pub(crate) text_scale: f32,
+    announcements: VecDeque<String>,
+    // Announcements not yet handed to egui this frame
+    unspoken: Vec<String>,
+    applied_scale: Option<f32>,
+}
+
+impl Default for Accessibility {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            text_scale: 1.0,
+            announcements: VecDeque::new(),
+            unspoken: Vec::new(),
+            applied_scale: None,
+        }
+    }
+}
+
+impl Accessibility {
+    // Recorded either way so turning the mode on mid-match still shows what happened
+    pub(crate) fn announce(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        if self.announcements.len() == MAX_ANNOUNCEMENTS {
+            self.announcements.pop_front();
+        }
+        self.announcements.push_back(text.clone());
+        if self.enabled {
+            self.unspoken.push(text);
+        }
+    }
+
+    pub(crate) fn announcements(&self) -> impl Iterator<Item = &String> {
+        self.announcements.iter().rev()
+    }
+
+    // Called once per frame before any UI is drawn
+    pub(crate) fn apply(&mut self, ctx: &egui::Context) {
+        ctx.options_mut(|options| options.screen_reader = self.enabled);
+
+        // Scaled from egui's defaults each time, so repeated changes don't compound
+        if self.applied_scale != Some(self.text_scale) {
+            let scale = self.text_scale;
+            ctx.style_mut(|style| {
+                style.text_styles = egui::Style::default().text_styles.into_iter()
+                    .map(|(text_style, font)| (text_style, egui::FontId::new(font.size * scale, font.family)))
+                    .collect();
+            });
+            self.applied_scale = Some(scale);
+        }
+
+        for text in self.unspoken.drain(..) {
+            ctx.output_mut(|output| output.events.push(egui::output::OutputEvent::ValueChanged(
+                egui::WidgetInfo::labeled(egui::WidgetType::Label, true, text),
+            )));
+        }
+    }
+}
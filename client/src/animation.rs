@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+
+// Shaping of an animation's progress over its duration
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Easing {
+    // Fast start, gentle landing; the default for cards settling into place
+    #[default]
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    fn apply(self, t: f32) -> f32 {
+        match self {
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => if t < 0.5 {
+                4.0 * t * t * t
+            } else {
+                1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+            },
+        }
+    }
+}
+
+// Moves a card between two transforms, overriding whatever else would place it until finished
+#[derive(Component, Clone, Debug)]
+pub(crate) struct CardAnimation {
+    start: Transform,
+    pub(crate) end: Transform,
+    elapsed: f32,
+    duration: f32,
+    easing: Easing,
+}
+
+impl CardAnimation {
+    pub(crate) fn new(start: Transform, end: Transform, duration: f32, easing: Easing) -> Self {
+        Self { start, end, elapsed: 0.0, duration, easing }
+    }
+
+    fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return 1.0;
+        }
+        (self.elapsed / self.duration).clamp(0.0, 1.0)
+    }
+
+    fn sample(&self) -> Transform {
+        let t = self.easing.apply(self.progress());
+        Transform {
+            translation: self.start.translation.lerp(self.end.translation, t),
+            rotation: self.start.rotation.slerp(self.end.rotation, t),
+            scale: self.start.scale.lerp(self.end.scale, t),
+        }
+    }
+}
+
+// Card no longer in the hand, despawned once its animation lands
+#[derive(Component)]
+pub(crate) struct Departing;
+
+pub(crate) fn animate_cards(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut cards: Query<(Entity, &mut Transform, &mut CardAnimation, Has<Departing>)>,
+) {
+    for (entity, mut transform, mut animation, departing) in cards.iter_mut() {
+        animation.elapsed += time.delta_secs();
+        if animation.progress() < 1.0 {
+            *transform = animation.sample();
+            continue;
+        }
+
+        // Exactly the end, so the layout doesn't see a rounding error and start another animation
+        *transform = animation.end;
+
+        if departing {
+            commands.entity(entity).despawn_recursive();
+        } else {
+            commands.entity(entity).remove::<CardAnimation>();
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::f32::consts::{FRAC_PI_2, PI};
+use bevy::prelude::*;
+use bevy_cobweb::prelude::{ReactRes, ReactResMut};
+use shared::channel::{GameMessage, BOARD_SLOTS};
+use shared::EntityID;
+use crate::hand::{spawn_card_model, CardAssets};
+use crate::client::Client;
+use crate::state::GameState;
+
+// Side of the square PlayFieldArea centred on the origin; our half is toward +z
+pub(crate) const PLAY_FIELD_SIZE: f32 = 15.0;
+// Keeps board cards from z-fighting with the play field
+const BOARD_HEIGHT: f32 = 0.02;
+
+// A creature in play, drawn lying face up in its slot's column
+#[derive(Component, Debug)]
+pub(crate) struct BoardCard {
+    own: bool,
+    slot: usize,
+    card_id: EntityID,
+    // Transformed creatures can keep their id, so the name is compared too
+    card_name: String,
+}
+
+// Each side's row runs through the middle of its half; the opponent's cards face them
+pub(crate) fn slot_transform(own: bool, slot: usize) -> Transform {
+    let width = PLAY_FIELD_SIZE / BOARD_SLOTS as f32;
+    let x = -PLAY_FIELD_SIZE / 2.0 + (slot as f32 + 0.5) * width;
+    let face_up = Quat::from_rotation_x(-FRAC_PI_2);
+    if own {
+        Transform::from_xyz(x, BOARD_HEIGHT, PLAY_FIELD_SIZE / 4.0).with_rotation(face_up)
+    } else {
+        Transform::from_xyz(x, BOARD_HEIGHT, -PLAY_FIELD_SIZE / 4.0).with_rotation(Quat::from_rotation_y(PI) * face_up)
+    }
+}
+
+// Mirrors play_field and opponent_field, respawning only the slots whose creature changed
+pub(crate) fn update_board(
+    mut commands: Commands,
+    game_state: ReactRes<GameState>,
+    cards: Query<(Entity, &BoardCard)>,
+    mut card_assets: ResMut<CardAssets>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut shown: HashMap<(bool, usize), (Entity, &BoardCard)> = cards.iter()
+        .map(|(entity, card)| ((card.own, card.slot), (entity, card)))
+        .collect();
+
+    for (own, field) in [(true, &game_state.play_field), (false, &game_state.opponent_field)] {
+        for (slot, entry) in field.iter().enumerate() {
+            let current = shown.remove(&(own, slot));
+            let unchanged = match (entry, current) {
+                (Some(card), Some((_, displayed))) => displayed.card_id == card.card_id && displayed.card_name == card.card_name,
+                (None, None) => true,
+                _ => false,
+            };
+            if unchanged {
+                continue;
+            }
+
+            if let Some((entity, _)) = current {
+                commands.entity(entity).despawn_recursive();
+            }
+            if let Some(card) = entry {
+                spawn_card_model(&mut commands, &mut card_assets, &mut images, &mut materials, card.card_name.clone(), slot_transform(own, slot))
+                    .insert(BoardCard { own, slot, card_id: card.card_id, card_name: card.card_name.clone() })
+                    .observe(inspect_creature)
+                    .observe(forget_creature);
+            }
+        }
+    }
+
+    // Slots that no longer exist, e.g. after a snapshot with a shorter board
+    for (entity, _) in shown.into_values() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// Hovering a creature asks the server how it got its current stats, shown until the pointer leaves it
+fn inspect_creature(
+    trigger: Trigger<Pointer<Over>>,
+    cards: Query<&BoardCard>,
+    mut c: Commands,
+    mut game_state: ReactResMut<GameState>,
+    client: Res<Client>,
+) {
+    let Ok(card) = cards.get(trigger.entity()) else { return };
+    let owner = if card.own {
+        Some(client.id())
+    } else {
+        game_state.match_profiles.iter().map(|profile| profile.player_id).find(|&id| id != client.id())
+    };
+    let Some(owner) = owner else { return };
+    let state = game_state.get_mut(&mut c);
+    state.hovered_creature = Some((owner, card.slot));
+    state.creature_history = None;
+    let _ = client.request(GameMessage::InspectCreature { player_id: owner, slot: card.slot });
+}
+
+fn forget_creature(trigger: Trigger<Pointer<Out>>, cards: Query<&BoardCard>, mut c: Commands, mut game_state: ReactResMut<GameState>) {
+    if cards.contains(trigger.entity()) {
+        let state = game_state.get_mut(&mut c);
+        state.hovered_creature = None;
+        state.creature_history = None;
+    }
+}
@@ -0,0 +1,144 @@
+use bevy::input::mouse::{MouseMotion, MouseWheel};
+use bevy::prelude::*;
+use bevy_cobweb::prelude::ReactRes;
+use bevy_inspector_egui::bevy_egui::EguiContextSettings;
+use bevy_inspector_egui::egui;
+use bevy_window::PrimaryWindow;
+use crate::client::Client;
+use crate::state::{GameState, TurnPlayer, UiState};
+use crate::ui::MainCamera;
+
+// How far the player may zoom, as a multiple of the view's default distance
+const ZOOM_MIN: f32 = 0.6;
+const ZOOM_MAX: f32 = 1.5;
+const ZOOM_STEP: f32 = 0.1;
+// Furthest the look target can be dragged from the view's centre, in world units
+const PAN_LIMIT: f32 = 4.0;
+const PAN_SPEED: f32 = 0.02;
+// Higher settles faster; the camera covers about 95% of the way in 3 / CAMERA_SMOOTHING seconds
+const CAMERA_SMOOTHING: f32 = 4.0;
+
+// Framings the camera moves between as the game goes on
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum CameraView {
+    // Whole table, used outside of matches
+    #[default]
+    Board,
+    // Own hand and half of the board, during our turn
+    Hand,
+    // Opponent's half of the board, while they play
+    Opponent,
+}
+
+impl CameraView {
+    // Eye position and the point it looks at
+    fn framing(self) -> (Vec3, Vec3) {
+        match self {
+            CameraView::Board => (Vec3::new(0.0, 14.0, 14.0), Vec3::new(0.0, 0.0, 1.0)),
+            CameraView::Hand => (Vec3::new(0.0, 3.0, 20.0), Vec3::new(0.0, 0.0, 10.0)),
+            CameraView::Opponent => (Vec3::new(0.0, 10.0, 7.0), Vec3::new(0.0, 0.0, -4.0)),
+        }
+    }
+}
+
+// Where the main camera wants to be; the transform eases toward it every frame
+#[derive(Component, Debug)]
+pub(crate) struct CameraRig {
+    pub(crate) view: CameraView,
+    zoom: f32,
+    pan: Vec2,
+}
+
+impl Default for CameraRig {
+    fn default() -> Self {
+        Self { view: CameraView::default(), zoom: 1.0, pan: Vec2::ZERO }
+    }
+}
+
+impl CameraRig {
+    pub(crate) fn goal(&self) -> Transform {
+        let (eye, center) = self.view.framing();
+        let target = center + Vec3::new(self.pan.x, 0.0, self.pan.y);
+        Transform::from_translation(target + (eye - center) * self.zoom).looking_at(target, Vec3::Y)
+    }
+
+    fn zoom_by(&mut self, steps: f32) {
+        self.zoom = (self.zoom - steps * ZOOM_STEP).clamp(ZOOM_MIN, ZOOM_MAX);
+    }
+
+    fn pan_by(&mut self, delta: Vec2) {
+        self.pan = (self.pan + delta).clamp(Vec2::splat(-PAN_LIMIT), Vec2::splat(PAN_LIMIT));
+    }
+
+    fn reset(&mut self) {
+        self.zoom = 1.0;
+        self.pan = Vec2::ZERO;
+    }
+}
+
+// Switches framing on turn changes and when a match starts or ends
+pub(crate) fn follow_game_phase(
+    game_state: ReactRes<GameState>,
+    turn_player: ReactRes<TurnPlayer>,
+    client: Res<Client>,
+    mut rigs: Query<&mut CameraRig>,
+) {
+    let in_match = !game_state.match_profiles.is_empty() && game_state.match_summary.is_none();
+    let view = match (in_match, turn_player.display_id()) {
+        (false, _) | (true, None) => CameraView::Board,
+        (true, Some(id)) if id == client.id() => CameraView::Hand,
+        (true, Some(_)) => CameraView::Opponent,
+    };
+    for mut rig in rigs.iter_mut() {
+        if rig.view != view {
+            rig.view = view;
+        }
+    }
+}
+
+// Scroll to zoom and right-drag to pan while the pointer is over the 3D view; Home resets both
+pub(crate) fn camera_controls(
+    mut wheel: EventReader<MouseWheel>,
+    mut motion: EventReader<MouseMotion>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    ui_state: Res<UiState>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    egui_settings: Query<&EguiContextSettings>,
+    mut rigs: Query<&mut CameraRig>,
+) {
+    let scroll: f32 = wheel.read().map(|event| event.y).sum();
+    let drag: Vec2 = motion.read().map(|event| event.delta).sum();
+
+    let egui_scale = egui_settings.get_single().map_or(1.0, |settings| settings.scale_factor);
+    let over_view = window.get_single().ok()
+        .and_then(|window| window.cursor_position())
+        .is_some_and(|cursor| ui_state.viewport_rect.contains(egui::pos2(cursor.x, cursor.y) / egui_scale));
+
+    for mut rig in rigs.iter_mut() {
+        if keys.just_pressed(KeyCode::Home) {
+            rig.reset();
+        }
+        if !over_view {
+            continue;
+        }
+        if scroll != 0.0 {
+            rig.zoom_by(scroll);
+        }
+        if buttons.pressed(MouseButton::Right) && drag != Vec2::ZERO {
+            rig.pan_by(-drag * PAN_SPEED * rig.zoom);
+        }
+    }
+}
+
+pub(crate) fn move_camera(
+    time: Res<Time>,
+    mut cameras: Query<(&CameraRig, &mut Transform), With<MainCamera>>,
+) {
+    let t = 1.0 - (-CAMERA_SMOOTHING * time.delta_secs()).exp();
+    for (rig, mut transform) in cameras.iter_mut() {
+        let goal = rig.goal();
+        transform.translation = transform.translation.lerp(goal.translation, t);
+        transform.rotation = transform.rotation.slerp(goal.rotation, t);
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::{BTreeSet, VecDeque};
+use bevy::prelude::*;
+use bevy_inspector_egui::egui;
+use shared::channel::{GameMessage, MessageType, MAX_CHAT_LENGTH};
+use wasm_timer::{SystemTime, UNIX_EPOCH};
+use crate::client::Client;
+use crate::state::Language;
+
+const HISTORY_CAPACITY: usize = 200;
+// Typed before a name to whisper to that player instead of the whole table
+const WHISPER_PREFIX: &str = "/w ";
+
+struct ChatLine {
+    // Wall clock when it arrived, HH:MM in UTC
+    time: String,
+    message: MessageType,
+}
+
+impl ChatLine {
+    fn sender(&self) -> Option<&str> {
+        match &self.message {
+            MessageType::Room { sender, .. } | MessageType::Private { sender, .. } => sender.as_deref(),
+            MessageType::System(_) => None,
+        }
+    }
+}
+
+// Room chat as it arrived, plus who the player doesn't want to hear from
+#[derive(Resource, Default)]
+pub(crate) struct ChatLog {
+    lines: VecDeque<ChatLine>,
+    input: String,
+    // Hides everything but system messages
+    muted: bool,
+    // Lowercase sender names; whispers from them are hidden too
+    ignored: BTreeSet<String>,
+    unread: usize,
+}
+
+impl ChatLog {
+    pub(crate) fn receive(&mut self, message: MessageType) {
+        if self.lines.len() == HISTORY_CAPACITY {
+            self.lines.pop_front();
+        }
+        let line = ChatLine { time: clock_time(), message };
+        if self.shows(&line) {
+            self.unread += 1;
+        }
+        self.lines.push_back(line);
+    }
+
+    fn shows(&self, line: &ChatLine) -> bool {
+        match line.sender() {
+            None => true,
+            Some(_) if self.muted => false,
+            Some(sender) => !self.ignored.contains(&sender.to_lowercase()),
+        }
+    }
+
+    pub(crate) fn unread(&self) -> usize {
+        self.unread
+    }
+}
+
+fn clock_time() -> String {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+    format!("{:02}:{:02}", secs / 3600 % 24, secs / 60 % 60)
+}
+
+// "/w name message" whispers, anything else goes to the whole table
+fn parse_input(input: &str) -> Option<MessageType> {
+    let input = input.trim();
+    if let Some(rest) = input.strip_prefix(WHISPER_PREFIX) {
+        let (recipient, content) = rest.trim_start().split_once(' ')?;
+        return Some(MessageType::Private { sender: None, recipient: recipient.to_string(), content: content.trim().to_string() });
+    }
+    (!input.is_empty()).then(|| MessageType::Room { sender: None, content: input.to_string() })
+}
+
+pub(crate) fn render_chat(world: &mut World, ui: &mut egui::Ui) {
+    let own_name = world.resource::<Client>().username().map(str::to_lowercase);
+    let language = *world.resource::<Language>();
+    let mut chat = world.resource_mut::<ChatLog>();
+    chat.unread = 0;
+
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut chat.muted, "Mute chat");
+        let ignored: Vec<String> = chat.ignored.iter().cloned().collect();
+        if !ignored.is_empty() {
+            ui.menu_button(format!("Ignored ({})", ignored.len()), |ui| {
+                for name in ignored {
+                    if ui.button(format!("Stop ignoring {name}")).clicked() {
+                        chat.ignored.remove(&name);
+                        ui.close_menu();
+                    }
+                }
+            });
+        }
+    });
+    ui.separator();
+
+    let mut ignore = None;
+    let history_height = (ui.available_height() - 40.0).max(60.0);
+    egui::ScrollArea::vertical()
+        .max_height(history_height)
+        .auto_shrink([false, false])
+        .stick_to_bottom(true)
+        .show(ui, |ui| {
+            for line in chat.lines.iter().filter(|line| chat.shows(line)) {
+                ui.horizontal_wrapped(|ui| {
+                    ui.weak(&line.time).on_hover_text("UTC");
+                    let (name, content) = match &line.message {
+                        MessageType::Room { sender, content } => (sender.clone().unwrap_or_default(), content.clone()),
+                        MessageType::Private { sender, recipient, content } => {
+                            let sender = sender.clone().unwrap_or_default();
+                            if own_name.as_deref() == Some(sender.to_lowercase().as_str()) {
+                                (format!("To {recipient}"), content.clone())
+                            } else {
+                                (format!("From {sender}"), content.clone())
+                            }
+                        }
+                        MessageType::System(content) => {
+                            ui.colored_label(egui::Color32::LIGHT_BLUE, language.message(content));
+                            return;
+                        }
+                    };
+                    let label = ui.add(egui::Label::new(egui::RichText::new(format!("{name}:")).strong()).sense(egui::Sense::click()));
+                    // Anyone but ourselves can be ignored from their name
+                    if let Some(sender) = line.sender().map(str::to_lowercase).filter(|sender| own_name.as_ref() != Some(sender)) {
+                        label.context_menu(|ui| {
+                            if ui.button(format!("Ignore {sender}")).clicked() {
+                                ignore = Some(sender.clone());
+                                ui.close_menu();
+                            }
+                        });
+                    }
+                    ui.label(content);
+                });
+            }
+        });
+    if let Some(sender) = ignore {
+        chat.ignored.insert(sender);
+    }
+
+    ui.separator();
+    let mut send = false;
+    ui.horizontal(|ui| {
+        let input = ui.add(
+            egui::TextEdit::singleline(&mut chat.input)
+                .char_limit(MAX_CHAT_LENGTH)
+                .hint_text("Say something, or /w name to whisper")
+                .desired_width(ui.available_width() - 50.0),
+        );
+        if input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            send = true;
+            input.request_focus();
+        }
+        send |= ui.button("Send").clicked();
+    });
+    if !send {
+        return;
+    }
+    let Some(message) = parse_input(&chat.input) else { return };
+    chat.input.clear();
+    let _ = world.resource::<Client>().request(GameMessage::Chat(message));
+}
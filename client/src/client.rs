@@ -1,24 +1,147 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
 use bevy::prelude::*;
 use bevy_cobweb::prelude::{CommandsSyscallExt, ReactRes, ReactResMut};
 use shared::api::API_VERSION;
-use shared::channel::{GameChannel, GameMessage};
-use crate::state::{ConnectionStatus, TurnPlayer, EndTurn, GameState};
+use shared::channel::{CardData, CardType, GameChannel, GameMessage};
+use shared::checksum::MirroredState;
+use shared::codec::{Codec, Frame};
+use shared::errors::{ErrorCode, GameError};
+use shared::lethal::RUSH;
+use shared::rules::{check_play, check_turn};
+use shared::EntityID;
+use crate::accessibility::Accessibility;
+use crate::chat::ChatLog;
+use crate::puzzles::PuzzleBrowser;
+use crate::hand::{CardLeftHand, HandExit};
+use crate::latency::LatencyStats;
+use crate::login::LoginForm;
+use crate::message_log::{Direction, MessageLog};
+use crate::network_sim::NetworkSimulator;
+use crate::state::{ConnectionStatus, DeckResource, TurnPlayer, EndTurn, GameState, Locale, TurnClock};
 
-pub type Client = bevy_simplenet::Client<GameChannel>;
 pub type ClientEvent = bevy_simplenet::ClientEventFrom<GameChannel>;
 
+// The simplenet client plus the codec the server picked, so the game keeps requesting with plain GameMessages
+#[derive(Resource)]
+pub struct Client {
+    inner: bevy_simplenet::Client<GameChannel>,
+    codec: Codec,
+    // Requests are sent through &self from all over the UI, so the log needs its own lock.
+    // Shared with the crash reporter, which reads it from the panic hook
+    log: Arc<Mutex<MessageLog>>,
+    // Account from the last LoggedIn, kept across reconnects so the table still knows which side is ours
+    player_id: Option<EntityID>,
+    // Kept in memory only, so a dropped connection can log straight back in
+    credentials: Option<(String, String)>,
+}
+
+impl Client {
+    pub fn new(inner: bevy_simplenet::Client<GameChannel>) -> Self {
+        Self { inner, codec: Codec::Json, log: Arc::new(Mutex::new(MessageLog::new())), player_id: None, credentials: None }
+    }
+
+    // Our player id. Shadows simplenet's connection id, which the server no longer shows to game code
+    pub fn id(&self) -> EntityID {
+        self.player_id.unwrap_or_else(|| self.inner.id())
+    }
+
+    pub fn is_logged_in(&self) -> bool {
+        self.player_id.is_some()
+    }
+
+    // Name we logged in with, as typed; the server may show it with different capitals
+    pub fn username(&self) -> Option<&str> {
+        self.credentials.as_ref().map(|(username, _)| username.as_str())
+    }
+
+    pub fn log_in(&mut self, username: String, password: String, register: bool) {
+        let message = if register {
+            GameMessage::Register { username: username.clone(), password: password.clone() }
+        } else {
+            GameMessage::Login { username: username.clone(), password: password.clone() }
+        };
+        self.credentials = Some((username, password));
+        let _ = self.request(message);
+    }
+
+    // Returns false when there is nothing to log in with
+    fn log_in_again(&self) -> bool {
+        let Some((username, password)) = self.credentials.clone() else { return false };
+        let _ = self.request(GameMessage::Login { username, password });
+        true
+    }
+
+    pub fn request(&self, message: GameMessage) -> Result<bevy_simplenet::RequestSignal, ()> {
+        self.message_log().record(Direction::Sent, &message);
+        self.inner.request(self.codec.encode(message))
+    }
+
+    pub fn decode(&self, frame: Frame) -> Result<GameMessage, String> {
+        let message = frame.decode()?;
+        self.message_log().record(Direction::Received, &message);
+        Ok(message)
+    }
+
+    pub(crate) fn message_log(&self) -> MutexGuard<'_, MessageLog> {
+        // A panic while logging leaves nothing half-written worth refusing
+        self.log.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub(crate) fn shared_message_log(&self) -> Arc<Mutex<MessageLog>> {
+        self.log.clone()
+    }
+}
+
+impl Deref for Client {
+    type Target = bevy_simplenet::Client<GameChannel>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for Client {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+// Plays a card the way EndTurn predicts the turn: checked locally with the shared rules, then shown as done
+// until the server confirms with CardPlayed or refuses and the play is rolled back
+pub(crate) fn play_card(
+    client: &Client,
+    state: &mut GameState,
+    turn_player: Option<EntityID>,
+    card: CardData,
+    slot: Option<usize>,
+) -> Result<(), GameError> {
+    check_turn(turn_player, client.id())?;
+    let cost = card.play_cost();
+    check_play(&card, cost, state.available_mana, slot, |slot| matches!(state.play_field.get(slot), Some(None)))?;
+    // Spells and artifacts never take a slot, whatever they were dropped on
+    let slot = slot.filter(|_| matches!(card.card_type, CardType::Creature));
+    if let Ok(signal) = client.request(GameMessage::PlayCard { card_id: card.card_id, target: None, slot }) {
+        state.predict_play(signal, card, slot, cost);
+    }
+    Ok(())
+}
+
 pub fn client_factory() -> bevy_simplenet::ClientFactory<GameChannel> {
     bevy_simplenet::ClientFactory::<GameChannel>::new(API_VERSION)
 }
 
 fn set_new_server_state(
-    In(server_state): In<Option<u128>>,
+    In((server_state, time_bank)): In<(Option<u128>, Option<Duration>)>,
     mut c: Commands,
     // client: Res<Client>,
     pending_select: ReactRes<EndTurn>,
     mut owner: ReactResMut<TurnPlayer>
 ) {
-    owner.get_mut(&mut c).server_determined_player_id = server_state;
+    let turn_player = owner.get_mut(&mut c);
+    turn_player.server_determined_player_id = server_state;
+    turn_player.time_bank = time_bank;
 
     if pending_select.is_predicted() { return; }
 
@@ -30,38 +153,367 @@ fn set_new_server_state(
 pub fn handle_client_events(
     mut c: Commands,
     mut client: ResMut<Client>,
+    mut network: ResMut<NetworkSimulator>,
     mut status: ReactResMut<ConnectionStatus>,
     mut pending_select: ReactResMut<EndTurn>,
     mut turn_player: ReactResMut<TurnPlayer>,
     mut game_state: ReactResMut<GameState>,
+    mut accessibility: ResMut<Accessibility>,
+    mut latency: ResMut<LatencyStats>,
+    mut departures: EventWriter<CardLeftHand>,
+    time: Res<Time>,
+    (deck, locale): (Res<DeckResource>, Locale),
+    mut login: ResMut<LoginForm>,
+    mut chat: ResMut<ChatLog>,
+    mut puzzles: ResMut<PuzzleBrowser>,
+    mut desync_reported: Local<bool>,
 ) {
     let mut next_status = *status;
+    // TurnPlayer is updated through a deferred syscall, so track the latest turn locally for checksums
+    let mut mirrored_turn = turn_player.server_determined_player_id;
 
-    while let Some(client_event) = client.next() {
+    while let Some(client_event) = network.next(&mut client) {
         match client_event {
             ClientEvent::Report(connection_report) => match connection_report {
-                bevy_simplenet::ClientReport::Connected => next_status = ConnectionStatus::Connected,
+                bevy_simplenet::ClientReport::Connected => {
+                    next_status = ConnectionStatus::Connected;
+                    // Every connection starts logged out; the login window takes over when we have nothing to send
+                    login.pending = client.log_in_again();
+                }
                 bevy_simplenet::ClientReport::Disconnected |
                 bevy_simplenet::ClientReport::ClosedByServer(_) |
                 bevy_simplenet::ClientReport::ClosedBySelf => next_status = ConnectionStatus::Connecting,
                 bevy_simplenet::ClientReport::IsDead(aborted_reqs) => {
                     for aborted_req in aborted_reqs {
+                        if game_state.is_play_predicted(aborted_req) {
+                            game_state.get_mut(&mut c).roll_back_play(aborted_req);
+                        }
                         if !pending_select.equals_request(aborted_req) { continue; }
                     }
                     next_status = ConnectionStatus::Dead;
                 }
             }
-            ClientEvent::Msg(message) => match message {
-                GameMessage::CurrentTurn(new_id) => {
-                    c.syscall(new_id, set_new_server_state);
+            ClientEvent::Msg(frame) => match client.decode(frame).map(|message| locale.cards.localized(message)) {
+                Err(e) => warn!("Dropping undecodable message: {}", e),
+                Ok(GameMessage::CurrentTurn(new_id, time_bank)) => {
+                    mirrored_turn = new_id;
+                    let state = game_state.get_mut(&mut c);
+                    state.last_rejection = None;
+                    if new_id == Some(client.id()) {
+                        state.exhausted.clear();
+                    }
+                    match new_id {
+                        Some(id) if id == client.id() => accessibility.announce("Your turn"),
+                        Some(_) => accessibility.announce("Opponent's turn"),
+                        None => {}
+                    }
+                    c.syscall((new_id, time_bank), set_new_server_state);
                 }
-                GameMessage::CardsDrawn(mut cards) => {
+                Ok(GameMessage::TurnTimeRemaining { remaining, total }) => {
+                    game_state.get_mut(&mut c).turn_clock = Some(TurnClock { remaining, total, received_at: time.elapsed_secs() });
+                }
+                Ok(GameMessage::CardsDrawn(mut cards)) => {
                     let state = game_state.get_mut(&mut c);
                     state.player_hand.append(&mut cards);
                     let hand_size = state.player_hand.len();
                     println!("{hand_size} cards in hand");
                 }
-                _ => {}
+                Ok(GameMessage::CardPlayed(player_id, card, slot)) => {
+                    let state = game_state.get_mut(&mut c);
+                    let own = player_id == client.id();
+                    let who = if own { "You" } else { "Opponent" };
+                    match slot {
+                        Some(slot) => accessibility.announce(format!("{who} played {} into slot {}", card.card_name, slot + 1)),
+                        None => accessibility.announce(format!("{who} played {}", card.card_name)),
+                    }
+                    if own {
+                        if slot.is_some() && !card.has_keyword(RUSH) {
+                            state.exhausted.insert(card.card_id);
+                        }
+                        // A predicted play already left the hand when it was made
+                        if !state.confirm_play(card.card_id) {
+                            let exit = slot.map_or(HandExit::Played, HandExit::Board);
+                            departures.send(CardLeftHand { card_id: card.card_id, exit });
+                        }
+                        state.player_hand.retain(|held| held.card_id != card.card_id);
+                        state.hand_order.retain(|&id| id != card.card_id);
+                    }
+                    if let Some(slot) = slot {
+                        let field = if own { &mut state.play_field } else { &mut state.opponent_field };
+                        if let Some(entry) = field.get_mut(slot) {
+                            *entry = Some(card);
+                        }
+                    }
+                }
+                Ok(GameMessage::CardDiscarded(player_id, card)) => {
+                    let state = game_state.get_mut(&mut c);
+                    if player_id != client.id() {
+                        accessibility.announce(format!("Opponent discarded {}", card.card_name));
+                        state.opponent_discarded.push(card);
+                        continue;
+                    }
+                    accessibility.announce(format!("You discarded {}", card.card_name));
+                    let card_id = card.card_id;
+                    state.player_hand.retain(|held| held.card_id != card_id);
+                    state.hand_order.retain(|&id| id != card_id);
+                    state.discarded.push(card);
+                    departures.send(CardLeftHand { card_id, exit: HandExit::Discarded });
+                }
+                Ok(GameMessage::BoardUpdate(player_id, board)) => {
+                    let state = game_state.get_mut(&mut c);
+                    if player_id == client.id() {
+                        state.play_field = board;
+                    } else {
+                        state.opponent_field = board;
+                    }
+                }
+                Ok(GameMessage::CardTransformed { player_id, slot, card }) => {
+                    let state = game_state.get_mut(&mut c);
+                    let field = if player_id == client.id() { &mut state.play_field } else { &mut state.opponent_field };
+                    if let Some(entry) = field.get_mut(slot) {
+                        *entry = Some(card);
+                    }
+                }
+                Ok(GameMessage::HealthUpdate { player_id, health }) => {
+                    let state = game_state.get_mut(&mut c);
+                    if player_id == client.id() {
+                        state.player_health = health;
+                        accessibility.announce(format!("Your health is now {health}"));
+                    } else {
+                        state.opponent_health = health;
+                        accessibility.announce(format!("Opponent's health is now {health}"));
+                    }
+                }
+                Ok(GameMessage::Pong(sent)) => {
+                    latency.record_pong(sent, time.elapsed());
+                }
+                Ok(GameMessage::CodecSelected(codec)) => {
+                    client.codec = codec;
+                }
+                Ok(GameMessage::LoggedIn(player_id)) => {
+                    client.player_id = Some(player_id);
+                    login.pending = false;
+                    login.error = None;
+                    let _ = client.request(GameMessage::RequestStats);
+                    // Reconnecting mid-match: ask for our seat back before the server gives it away
+                    let in_match = !game_state.match_profiles.is_empty() && game_state.match_summary.is_none();
+                    if in_match {
+                        let _ = client.request(GameMessage::Resume);
+                    } else {
+                        let deck = (!deck.cards.is_empty()).then(|| deck.cards.clone());
+                        let _ = client.request(GameMessage::JoinGame { deck });
+                    }
+                }
+                Ok(GameMessage::GameStateSnapshot(snapshot)) => {
+                    mirrored_turn = snapshot.current_turn;
+                    c.syscall((snapshot.current_turn, snapshot.time_bank), set_new_server_state);
+                    let own_id = client.id();
+                    let state = game_state.get_mut(&mut c);
+                    // The snapshot already reflects whatever the server made of our plays
+                    state.predicted_plays.clear();
+                    state.player_hand = snapshot.hand;
+                    for player in snapshot.players {
+                        if player.player_id == own_id {
+                            state.play_field = player.board;
+                            state.player_health = player.health;
+                            state.available_mana = player.mana;
+                            state.max_mana = player.max_mana;
+                            state.deck_size = player.deck_size;
+                            state.graveyard_size = player.graveyard_size;
+                        } else {
+                            state.opponent_field = player.board;
+                            state.opponent_health = player.health;
+                            state.opponent_hand_count = player.hand_count;
+                            state.opponent_deck_size = player.deck_size;
+                            state.opponent_graveyard_size = player.graveyard_size;
+                        }
+                    }
+                }
+                Ok(GameMessage::CardsInDeck(count)) => {
+                    game_state.get_mut(&mut c).deck_size = count;
+                }
+                Ok(GameMessage::OpponentHandSize(size)) => {
+                    game_state.get_mut(&mut c).opponent_hand_count = size;
+                }
+                Ok(GameMessage::GraveyardSize { player_id, size }) => {
+                    let state = game_state.get_mut(&mut c);
+                    if player_id == client.id() {
+                        state.graveyard_size = size;
+                    } else {
+                        state.opponent_graveyard_size = size;
+                    }
+                }
+                Ok(GameMessage::Graveyard { player_id, cards }) => {
+                    game_state.get_mut(&mut c).graveyard_view = Some((player_id, cards));
+                }
+                Ok(GameMessage::CreatureHistory { player_id, slot, history }) => {
+                    let state = game_state.get_mut(&mut c);
+                    if state.hovered_creature == Some((player_id, slot)) {
+                        state.creature_history = Some((player_id, slot, history));
+                    }
+                }
+                Ok(GameMessage::HandCosts(costs)) => {
+                    let state = game_state.get_mut(&mut c);
+                    for card in state.player_hand.iter_mut() {
+                        card.effective_cost = costs.iter()
+                            .find(|(card_id, _)| *card_id == card.card_id)
+                            .map(|&(_, cost)| cost);
+                    }
+                }
+                Ok(GameMessage::DeckList(decks, default)) => {
+                    let state = game_state.get_mut(&mut c);
+                    state.saved_decks = decks;
+                    state.default_deck = default;
+                }
+                Ok(GameMessage::CardSearchResults(results)) => {
+                    game_state.get_mut(&mut c).card_search_results = Some(results);
+                }
+                Ok(GameMessage::ManaUpdate { player_id, current, max }) => {
+                    // The server owns the pool; the opponent's updates aren't shown yet
+                    if player_id == client.id() {
+                        let state = game_state.get_mut(&mut c);
+                        state.available_mana = current;
+                        state.max_mana = max;
+                        state.settle_mana();
+                    }
+                }
+                Ok(GameMessage::MatchList(matches)) => {
+                    game_state.get_mut(&mut c).live_matches = matches;
+                }
+                Ok(GameMessage::MatchSummary(summary)) => {
+                    accessibility.announce(match summary.winner {
+                        Some(_) if summary.conceded_by.is_some_and(|player_id| player_id != client.id()) => "Your opponent conceded, you won",
+                        Some(winner) if winner == client.id() => "Game over, you won",
+                        Some(_) => "Game over, you lost",
+                        None => "Game over, it's a draw",
+                    });
+                    let state = game_state.get_mut(&mut c);
+                    state.match_summary = Some(summary);
+                    state.rematch_from = None;
+                    state.confirm_concede = false;
+                    state.ready_prompt = false;
+                    state.plan.clear();
+                    state.running_plan.clear();
+                    state.turn_clock = None;
+                    state.final_reveal.clear();
+                    let _ = client.request(GameMessage::RequestStats);
+                }
+                Ok(GameMessage::StatsResponse(stats)) => {
+                    game_state.get_mut(&mut c).stats = Some(stats);
+                }
+                Ok(GameMessage::FinalReveal(reveal)) => {
+                    game_state.get_mut(&mut c).final_reveal = reveal;
+                }
+                Ok(GameMessage::Profile(profile)) => {
+                    game_state.get_mut(&mut c).profile = Some(profile);
+                }
+                Ok(GameMessage::MatchFound(profiles)) => {
+                    let state = game_state.get_mut(&mut c);
+                    state.match_profiles = profiles;
+                    state.ready_prompt = true;
+                    state.lobby_code = None;
+                    state.hand_order.clear();
+                    state.discarded.clear();
+                    state.opponent_discarded.clear();
+                    state.emotes.clear();
+                    state.turn_clock = None;
+                    state.match_id = None;
+                    // A rematch is found in place, so the last game's results are still up
+                    state.rematch_from = None;
+                    state.match_summary = None;
+                    state.final_reveal.clear();
+                    state.plan.clear();
+                    state.running_plan.clear();
+                    state.predicted_plays.clear();
+                }
+                Ok(GameMessage::RematchRequested(from)) => {
+                    if from != client.id() {
+                        accessibility.announce("Your opponent wants a rematch");
+                    }
+                    game_state.get_mut(&mut c).rematch_from = Some(from);
+                }
+                Ok(GameMessage::MatchId(match_id)) => {
+                    game_state.get_mut(&mut c).match_id = Some(match_id);
+                }
+                Ok(GameMessage::Chat(message)) => {
+                    chat.receive(message);
+                }
+                Ok(GameMessage::PuzzleList(list)) => {
+                    puzzles.receive_list(list);
+                }
+                Ok(GameMessage::PuzzleResult { puzzle_id, failure }) => {
+                    if failure.is_none() {
+                        accessibility.announce("Puzzle solved");
+                    }
+                    puzzles.receive_result(puzzle_id, failure);
+                }
+                Ok(GameMessage::Emote { emote, sender: Some(sender) }) => {
+                    if sender != client.id() {
+                        accessibility.announce(format!("Opponent: {}", emote.text()));
+                    }
+                    game_state.get_mut(&mut c).emotes.insert(sender, (emote, time.elapsed_secs()));
+                }
+                Ok(GameMessage::ActionRejected(error)) => {
+                    accessibility.announce(format!("Action refused: {}", locale.language.error(&error)));
+                    let state = game_state.get_mut(&mut c);
+                    state.roll_back_plays();
+                    // The rest of a running plan was built on this move going through
+                    state.running_plan.clear();
+                    state.last_rejection = Some(error);
+                }
+                Ok(GameMessage::Error(error)) if login.pending => {
+                    // Stale credentials can't be retried on the next reconnect
+                    client.player_id = None;
+                    client.credentials = None;
+                    login.pending = false;
+                    login.error = Some(locale.language.error(&error));
+                }
+                Ok(GameMessage::Error(error)) => {
+                    warn!("Server error {:?}: {}", error.code, error);
+                    let state = game_state.get_mut(&mut c);
+                    state.running_plan.clear();
+                    // The seat we tried to resume is gone, so queue up like a fresh connection
+                    if error.code == ErrorCode::SessionExpired {
+                        state.match_profiles.clear();
+                        let deck = (!deck.cards.is_empty()).then(|| deck.cards.clone());
+                        let _ = client.request(GameMessage::JoinGame { deck });
+                    }
+                    // We lost our seat and have to queue again from the Live Matches tab
+                    if error.code == ErrorCode::MatchTimedOut {
+                        state.match_profiles.clear();
+                        state.ready_prompt = false;
+                    }
+                    state.last_rejection = Some(error);
+                }
+                Ok(GameMessage::LobbyCreated(code)) => {
+                    game_state.get_mut(&mut c).lobby_code = Some(code);
+                }
+                Ok(GameMessage::StarterRewards { cards, packs }) => {
+                    let card_count: u32 = cards.values().sum();
+                    game_state.get_mut(&mut c).welcome_steps.extend([
+                        "Welcome, commander! Your fleet awaits.".to_string(),
+                        format!("You received a starter collection of {card_count} cards."),
+                        format!("You also received {packs} card pack{} to open.", if packs == 1 { "" } else { "s" }),
+                        "Build your first deck in the Card Collection tab, then find a match.".to_string(),
+                    ]);
+                }
+                Ok(GameMessage::KeywordGlossary(keywords)) => {
+                    game_state.get_mut(&mut c).keywords = keywords;
+                }
+                Ok(GameMessage::StateChecksum(expected)) => {
+                    let mirrored = MirroredState {
+                        current_turn: mirrored_turn,
+                        hand: game_state.player_hand.iter().map(|card| card.card_id).collect(),
+                    };
+                    if mirrored.checksum() == expected {
+                        *desync_reported = false;
+                    } else if !*desync_reported {
+                        // Upload once per divergence so the server can log where we went wrong
+                        warn!("State checksum mismatch, uploading mirrored state");
+                        *desync_reported = client.request(GameMessage::MirroredState(mirrored)).is_ok();
+                    }
+                }
+                Ok(_) => {}
             }
             ClientEvent::Ack(request_id) => {
                 if !pending_select.equals_request(request_id) { continue; }
@@ -74,6 +526,10 @@ pub fn handle_client_events(
             ClientEvent::Response((), request_id) |
             ClientEvent::SendFailed(request_id) |
             ClientEvent::ResponseLost(request_id) => {
+                // Refused before it became a game event, so the server never touched the table
+                if game_state.is_play_predicted(request_id) {
+                    game_state.get_mut(&mut c).roll_back_play(request_id);
+                }
                 if !pending_select.equals_request(request_id) { continue; }
             }
         }
@@ -0,0 +1,234 @@
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, TryLockError};
+use bevy::log::{tracing, tracing_subscriber, BoxedLayer};
+use bevy::log::tracing_subscriber::Layer;
+use bevy::prelude::*;
+use bevy_inspector_egui::egui;
+use serde::Serialize;
+use shared::api::API_VERSION;
+use wasm_timer::{SystemTime, UNIX_EPOCH};
+use crate::accessibility::Accessibility;
+use crate::message_log::MessageLog;
+use crate::network_sim::NETWORK_SIM_ENV;
+
+// Relative to the working directory, like the asset folder
+const REPORT_DIR: &str = "crash_reports";
+// Env var naming the endpoint reports are uploaded to; without it bundles are only written to disk
+const REPORT_URL_ENV: &str = "GAME_CRASH_REPORT_URL";
+const LOG_TAIL_LINES: usize = 200;
+// Appended to a bundle's file name once the player has decided, so it's only offered once
+const SENT_SUFFIX: &str = "sent";
+const KEPT_SUFFIX: &str = "kept";
+
+// The panic hook can't reach the World, so everything it reports lives out here
+static LOG_TAIL: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+static SETTINGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+static MESSAGE_LOG: OnceLock<Arc<Mutex<MessageLog>>> = OnceLock::new();
+
+#[derive(Serialize)]
+struct CrashReport {
+    version: String,
+    api_version: String,
+    // Unix time in milliseconds
+    timestamp: u64,
+    panic: String,
+    location: Option<String>,
+    backtrace: String,
+    settings: Vec<String>,
+    log_tail: Vec<String>,
+    messages: Vec<String>,
+}
+
+// The panic may have happened while one of these was held, so never wait on them
+fn snapshot<T, R>(mutex: &Mutex<T>, read: impl FnOnce(&T) -> R) -> Option<R> {
+    match mutex.try_lock() {
+        Ok(guard) => Some(read(&guard)),
+        Err(TryLockError::Poisoned(poisoned)) => Some(read(&poisoned.into_inner())),
+        Err(TryLockError::WouldBlock) => None,
+    }
+}
+
+impl CrashReport {
+    fn new(info: &PanicHookInfo) -> Self {
+        let payload = info.payload();
+        let panic = payload.downcast_ref::<&str>().map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic payload".to_string());
+        let messages = MESSAGE_LOG.get()
+            .and_then(|log| snapshot(log.as_ref(), |log| log.entries()
+                .map(|entry| format!("{:>9.3}s {} {}", entry.at.as_secs_f32(), entry.arrow(), entry.summary))
+                .collect()))
+            .unwrap_or_default();
+
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            api_version: API_VERSION.to_string(),
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64,
+            panic,
+            location: info.location().map(|location| location.to_string()),
+            backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+            settings: snapshot(&SETTINGS, Vec::clone).unwrap_or_default(),
+            log_tail: snapshot(&LOG_TAIL, |tail| tail.iter().cloned().collect()).unwrap_or_default(),
+            messages,
+        }
+    }
+
+    fn write(&self) -> Result<PathBuf, String> {
+        std::fs::create_dir_all(REPORT_DIR).map_err(|e| e.to_string())?;
+        let path = Path::new(REPORT_DIR).join(format!("crash-{}.toml", self.timestamp));
+        let contents = toml::to_string(self).map_err(|e| e.to_string())?;
+        std::fs::write(&path, contents).map_err(|e| e.to_string())?;
+        Ok(path)
+    }
+}
+
+// Writes a bundle on any panic, then hands over to the previous hook so the usual message still prints
+pub(crate) fn install(message_log: Arc<Mutex<MessageLog>>) {
+    let _ = MESSAGE_LOG.set(message_log);
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // Straight to stderr, the logger may be what panicked
+        match CrashReport::new(info).write() {
+            Ok(path) => eprintln!("Crash report written to {}", path.display()),
+            Err(e) => eprintln!("Failed to write crash report: {}", e),
+        }
+        previous(info);
+    }));
+}
+
+// Plugged into LogPlugin::custom_layer so reports carry the lines leading up to a crash
+pub(crate) fn log_tail_layer(_app: &mut App) -> Option<BoxedLayer> {
+    Some(Box::new(LogTail))
+}
+
+struct LogTail;
+
+impl<S: tracing::Subscriber> Layer<S> for LogTail {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: tracing_subscriber::layer::Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut line = format!("{} {}:", metadata.level(), metadata.target());
+        event.record(&mut FieldWriter(&mut line));
+
+        let mut tail = LOG_TAIL.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if tail.len() == LOG_TAIL_LINES {
+            tail.pop_front();
+        }
+        tail.push_back(line);
+    }
+}
+
+struct FieldWriter<'a>(&'a mut String);
+
+impl tracing::field::Visit for FieldWriter<'_> {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.0, " {:?}", value);
+        } else {
+            let _ = write!(self.0, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+pub(crate) fn record_settings(accessibility: Res<Accessibility>) {
+    if !accessibility.is_changed() {
+        return;
+    }
+    let settings = vec![
+        format!("accessible_mode={}", accessibility.enabled),
+        format!("text_scale={}", accessibility.text_scale),
+        format!("network_sim={}", std::env::var(NETWORK_SIM_ENV).unwrap_or_default()),
+        format!("binary_codec={}", cfg!(feature = "binary_codec")),
+        format!("debug_ui={}", cfg!(feature = "debug_ui")),
+    ];
+    *SETTINGS.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = settings;
+}
+
+// Bundles left by earlier crashes, offered for upload once at startup
+#[derive(Resource, Default)]
+pub(crate) struct CrashReports {
+    endpoint: Option<String>,
+    pending: Vec<PathBuf>,
+}
+
+impl CrashReports {
+    pub(crate) fn from_env() -> Self {
+        let Ok(endpoint) = std::env::var(REPORT_URL_ENV) else {
+            return Self::default();
+        };
+        let pending = std::fs::read_dir(REPORT_DIR).into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|extension| extension == "toml"))
+            .collect();
+        Self { endpoint: Some(endpoint), pending }
+    }
+}
+
+// Renamed rather than deleted so the bundle can still be attached to a bug report by hand
+fn mark(path: &Path, suffix: &str) {
+    if let Err(e) = std::fs::rename(path, path.with_extension(format!("toml.{suffix}"))) {
+        warn!("Failed to mark crash report {}: {}", path.display(), e);
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn upload(endpoint: String, path: PathBuf) {
+    // Off the main thread so a slow endpoint doesn't stall the game
+    std::thread::spawn(move || {
+        let result = std::fs::read_to_string(&path).map_err(|e| e.to_string())
+            .and_then(|body| ureq::post(&endpoint)
+                .set("Content-Type", "application/toml")
+                .send_string(&body)
+                .map_err(|e| e.to_string()));
+        match result {
+            Ok(_) => {
+                info!("Uploaded crash report {}", path.display());
+                mark(&path, SENT_SUFFIX);
+            }
+            // Left as is, so it's offered again next launch
+            Err(e) => warn!("Failed to upload crash report {}: {}", path.display(), e),
+        }
+    });
+}
+
+#[cfg(target_family = "wasm")]
+fn upload(_endpoint: String, path: PathBuf) {
+    warn!("Crash report upload isn't supported in the browser, keeping {}", path.display());
+}
+
+pub(crate) fn show_crash_report_prompt(world: &mut World, ctx: &egui::Context) {
+    let mut reports = world.resource_mut::<CrashReports>();
+    let Some(endpoint) = reports.endpoint.clone() else { return };
+    if reports.pending.is_empty() {
+        return;
+    }
+
+    let mut send = None;
+    egui::Window::new("Crash report")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label(format!("The game crashed {} time(s) since the last report.", reports.pending.len()));
+            ui.label("Reports hold the game version, your settings, recent log lines and network messages. \
+                Passwords are never included.");
+            ui.label(format!("Saved in the {} folder either way.", REPORT_DIR));
+            ui.horizontal(|ui| {
+                if ui.button("Send").clicked() {
+                    send = Some(true);
+                }
+                if ui.button("Don't send").clicked() {
+                    send = Some(false);
+                }
+            });
+        });
+
+    match send {
+        Some(true) => reports.pending.drain(..).for_each(|path| upload(endpoint.clone(), path)),
+        Some(false) => reports.pending.drain(..).for_each(|path| mark(&path, KEPT_SUFFIX)),
+        None => {}
+    }
+}
@@ -0,0 +1,146 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{EguiContext, EguiPostUpdateSet};
+use bevy_inspector_egui::bevy_inspector;
+use bevy_inspector_egui::bevy_inspector::hierarchy::{hierarchy_ui, SelectedEntities};
+use bevy_inspector_egui::egui;
+use bevy_inspector_egui::DefaultInspectorConfigPlugin;
+use bevy_window::PrimaryWindow;
+use egui_dock::{DockArea, DockState, NodeIndex, Style};
+use crate::ui::show_ui_system;
+
+// Debug inspector promoted from the experiments binary, enabled with the `debug_ui` feature
+pub struct DebugUiPlugin;
+
+impl Plugin for DebugUiPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugins(DefaultInspectorConfigPlugin)
+            .insert_resource(DebugUiState::new())
+            .add_systems(Update, toggle_debug_ui)
+            .add_systems(
+                PostUpdate,
+                show_debug_ui_system
+                    .after(show_ui_system)
+                    .before(EguiPostUpdateSet::ProcessOutput)
+                    .before(bevy_inspector_egui::bevy_egui::end_pass_system),
+            );
+    }
+}
+
+#[derive(Debug)]
+enum DebugWindow {
+    Hierarchy,
+    Inspector,
+    Resources,
+    Assets,
+}
+
+#[derive(Resource)]
+struct DebugUiState {
+    state: DockState<DebugWindow>,
+    selected_entities: SelectedEntities,
+    open: bool,
+}
+
+impl DebugUiState {
+    fn new() -> Self {
+        let mut state = DockState::new(vec![DebugWindow::Hierarchy]);
+        let tree = state.main_surface_mut();
+
+        let [_hierarchy, _inspector] =
+            tree.split_right(NodeIndex::root(), 0.4, vec![DebugWindow::Inspector]);
+        let [_hierarchy, _resources] =
+            tree.split_below(NodeIndex::root(), 0.6, vec![DebugWindow::Resources, DebugWindow::Assets]);
+
+        Self {
+            state,
+            selected_entities: SelectedEntities::default(),
+            open: false,
+        }
+    }
+
+    fn ui(&mut self, world: &mut World, ctx: &mut egui::Context) {
+        let mut tab_viewer = DebugTabViewer {
+            world,
+            selected_entities: &mut self.selected_entities,
+        };
+
+        egui::Window::new("Debug")
+            .default_size([600.0, 500.0])
+            .open(&mut self.open)
+            .show(ctx, |ui| {
+                DockArea::new(&mut self.state)
+                    .id(egui::Id::new("debug_dock"))
+                    .style(Style::from_egui(ui.style().as_ref()))
+                    .show_inside(ui, &mut tab_viewer);
+            });
+    }
+}
+
+// F12 toggles the debug window
+fn toggle_debug_ui(keys: Res<ButtonInput<KeyCode>>, mut debug_ui: ResMut<DebugUiState>) {
+    if keys.just_pressed(KeyCode::F12) {
+        debug_ui.open = !debug_ui.open;
+    }
+}
+
+fn show_debug_ui_system(world: &mut World) {
+    if !world.resource::<DebugUiState>().open {
+        return;
+    }
+
+    let Ok(egui_context) = world
+        .query_filtered::<&mut EguiContext, With<PrimaryWindow>>()
+        .get_single(world)
+    else {
+        return;
+    };
+    let mut egui_context = egui_context.clone();
+
+    world.resource_scope::<DebugUiState, _>(|world, mut debug_ui| {
+        debug_ui.ui(world, egui_context.get_mut())
+    });
+}
+
+struct DebugTabViewer<'a> {
+    world: &'a mut World,
+    selected_entities: &'a mut SelectedEntities,
+}
+
+impl egui_dock::TabViewer for DebugTabViewer<'_> {
+    type Tab = DebugWindow;
+
+    fn ui(&mut self, ui: &mut egui_dock::egui::Ui, window: &mut Self::Tab) {
+        match window {
+            DebugWindow::Hierarchy => {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    hierarchy_ui(self.world, ui, self.selected_entities);
+                });
+            }
+            DebugWindow::Inspector => match self.selected_entities.as_slice() {
+                &[entity] => {
+                    bevy_inspector::ui_for_entity_with_children(self.world, entity, ui);
+                }
+                entities if !entities.is_empty() => {
+                    bevy_inspector::ui_for_entities_shared_components(self.world, entities, ui);
+                }
+                _ => {
+                    ui.label("No entity selected");
+                }
+            },
+            DebugWindow::Resources => bevy_inspector::ui_for_resources(self.world, ui),
+            DebugWindow::Assets => {
+                ui.collapsing("Images", |ui| {
+                    bevy_inspector::ui_for_assets::<Image>(self.world, ui);
+                });
+                ui.collapsing("Materials", |ui| {
+                    bevy_inspector::ui_for_assets::<StandardMaterial>(self.world, ui);
+                });
+            }
+        }
+    }
+
+    fn title(&mut self, window: &mut Self::Tab) -> egui_dock::egui::WidgetText {
+        format!("{window:?}").into()
+    }
+}
@@ -1,6 +1,10 @@
 use bevy::color::Color;
 use bevy::image::Image;
 use bevy::math::Vec3;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::time::Duration;
+use bevy::diagnostic::Diagnostics;
 use bevy::pbr::{MeshMaterial3d, StandardMaterial};
 use bevy::{
     prelude::*,
@@ -10,13 +14,25 @@ use bevy::{
     },
 };
 use bevy::render::render_resource::encase::private::RuntimeSizedArray;
-use bevy_cobweb::prelude::ReactRes;
+use bevy_cobweb::prelude::{ReactRes, ReactResMut};
 use fontdue::Font;
-use crate::state::GameState;
+use serde::Deserialize;
+use wasm_timer::Instant;
+use shared::channel::BOARD_SLOTS;
+use shared::EntityID;
+use crate::animation::{CardAnimation, Departing, Easing};
+use crate::board::{slot_transform, PLAY_FIELD_SIZE};
+use crate::client::{play_card, Client};
+use crate::perf_hud::{HAND_REBUILD, TEXT_TEXTURES};
+use crate::piles::{Pile, PileKind, PileSide};
+use crate::state::{GameState, SelectedCard, TurnPlayer};
 use crate::texture::uv_debug_texture;
+use crate::ui::MainCamera;
 
-#[derive(Resource, Clone, Debug)]
+#[derive(Resource, Clone, Debug, Deserialize)]
+#[serde(default)]
 pub(crate) struct HandLayoutParams {
+    #[serde(skip)]
     pub(crate) count: usize,
     ideal_spacing: f32,
     pub(crate) spread_width: f32,
@@ -29,10 +45,139 @@ pub(crate) struct HandLayoutParams {
     card_curve_threshold: usize,
 }
 
+// Card dimensions
+pub(crate) const CARD_SIZE: Vec3 = Vec3::new(2.0, 3.0, 0.01);
+const IMAGE_SIZE: Vec3 = Vec3::new(CARD_SIZE.x * 0.8, CARD_SIZE.y * 0.5, 0.02);
+const TEXT_SIZE: Vec3 = Vec3::new(CARD_SIZE.x * 0.8, CARD_SIZE.y * 0.2, 0.02);
+
+// Cards further than this from the camera drop their art and name until focused
+const FULL_DETAIL_DISTANCE: f32 = 12.0;
+
+// Dragged cards float this high over the table
+const DRAG_HEIGHT: f32 = 1.5;
+
+// Seconds for a card to settle into its hand position, and to fly off to the board or graveyard
+const LAYOUT_DURATION: f32 = 0.3;
+const DEPARTURE_DURATION: f32 = 0.5;
+
+// Every card reuses these handles, so Bevy can batch them into a few instanced draws instead of one per quad
+#[derive(Resource)]
+pub(crate) struct CardAssets {
+    card_mesh: Handle<Mesh>,
+    image_mesh: Handle<Mesh>,
+    text_mesh: Handle<Mesh>,
+    base_material: Handle<StandardMaterial>,
+    image_material: Handle<StandardMaterial>,
+    font: Font,
+    // The name texture is the only per-card material, and copies of a card share it
+    text_materials: HashMap<String, Handle<StandardMaterial>>,
+    // Spent rasterizing names since update_card_count last reported it
+    texture_time: Duration,
+}
+
+impl CardAssets {
+    fn new(meshes: &mut Assets<Mesh>, images: &mut Assets<Image>, materials: &mut Assets<StandardMaterial>) -> Self {
+        let font_data = include_bytes!("../assets/fonts/FiraMono-Medium.ttf");
+        Self {
+            card_mesh: meshes.add(Cuboid::new(CARD_SIZE.x, CARD_SIZE.y, CARD_SIZE.z)),
+            image_mesh: meshes.add(Cuboid::new(IMAGE_SIZE.x, IMAGE_SIZE.y, IMAGE_SIZE.z)),
+            text_mesh: meshes.add(Cuboid::new(TEXT_SIZE.x, TEXT_SIZE.y, TEXT_SIZE.z)),
+            base_material: materials.add(StandardMaterial {
+                base_color_texture: Some(images.add(uv_debug_texture())),
+                ..default()
+            }),
+            image_material: materials.add(StandardMaterial {
+                base_color: Color::srgb(0.9, 0.9, 0.9),
+                ..default()
+            }),
+            font: Font::from_bytes(font_data as &[u8], fontdue::FontSettings::default()).unwrap(),
+            text_materials: HashMap::new(),
+            texture_time: Duration::ZERO,
+        }
+    }
+
+    fn text_material(&mut self, card_name: &str, images: &mut Assets<Image>, materials: &mut Assets<StandardMaterial>) -> Handle<StandardMaterial> {
+        if let Some(material) = self.text_materials.get(card_name) {
+            return material.clone();
+        }
+        let started = Instant::now();
+        let texture = create_text_texture(card_name, &self.font);
+        self.texture_time += started.elapsed();
+        let material = materials.add(StandardMaterial {
+            base_color_texture: Some(images.add(texture)),
+            unlit: true,
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        });
+        self.text_materials.insert(card_name.to_string(), material.clone());
+        material
+    }
+}
+
 // Component to mark our card entities
 #[derive(Component)]
 pub struct Card {
     index: usize,
+    card_id: EntityID,
+}
+
+// Sent by the client when one of our cards leaves the hand, so it flies to where it went instead of vanishing
+#[derive(Event, Clone, Copy, Debug)]
+pub(crate) struct CardLeftHand {
+    pub(crate) card_id: EntityID,
+    pub(crate) exit: HandExit,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum HandExit {
+    // A creature entering this board slot
+    Board(usize),
+    // A spell or artifact, which resolves in the middle of our side
+    Played,
+    Discarded,
+}
+
+impl HandExit {
+    fn transform(self) -> Transform {
+        let face_up = Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2);
+        match self {
+            HandExit::Board(slot) => slot_transform(true, slot),
+            HandExit::Played => Transform::from_xyz(0.0, DRAG_HEIGHT, PLAY_FIELD_SIZE / 4.0).with_rotation(face_up),
+            HandExit::Discarded => Transform::from_translation(Pile::new(PileSide::Own, PileKind::Graveyard).position())
+                .with_rotation(face_up),
+        }
+    }
+}
+
+// Simplified cards only draw their base, skipping the alpha-blended name texture
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CardDetail {
+    #[default]
+    Full,
+    Simplified,
+}
+
+// Follows the pointer instead of the hand layout until dropped
+#[derive(Component)]
+pub(crate) struct Dragging;
+
+// Where on the table a dragged card was let go
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DropTarget {
+    Slot(usize),
+    Outside,
+}
+
+impl DropTarget {
+    // Our half of the play field is split into one column per board slot
+    fn at(point: Vec3) -> Self {
+        let half = PLAY_FIELD_SIZE / 2.0;
+        if point.x.abs() > half || point.z < 0.0 || point.z > half {
+            return DropTarget::Outside;
+        }
+        let slot = ((point.x + half) / (PLAY_FIELD_SIZE / BOARD_SLOTS as f32)) as usize;
+        DropTarget::Slot(slot.min(BOARD_SLOTS - 1))
+    }
 }
 
 // Component for the card's image section
@@ -42,7 +187,7 @@ pub struct CardImage;
 
 // Component for the card's text section
 #[derive(Component)]
-struct CardText;
+pub(crate) struct CardText;
 
 impl Default for HandLayoutParams {
     fn default() -> Self {
@@ -61,34 +206,32 @@ impl Default for HandLayoutParams {
     }
 }
 
+impl HandLayoutParams {
+    // Loads values exported by the experiments layout workbench, falling back to the defaults
+    pub(crate) fn load(asset_path: &Path) -> Self {
+        let path = asset_path.join("hand_layout.toml");
+        let Ok(contents) = std::fs::read_to_string(&path) else {
+            return Self::default();
+        };
+
+        match toml::from_str(&contents) {
+            Ok(params) => params,
+            Err(e) => {
+                warn!("Ignoring invalid hand layout {:?}: {}", path, e);
+                Self::default()
+            }
+        }
+    }
+}
+
 pub fn setup_hand(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut images: ResMut<Assets<Image>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    mut layout_params: ResMut<HandLayoutParams>,
 ) {
-    let debug_material = materials.add(StandardMaterial {
-        base_color_texture: Some(images.add(uv_debug_texture())),
-        ..default()
-    });
-
-    let font_data = include_bytes!("../assets/fonts/FiraMono-Medium.ttf");
-    let font = Font::from_bytes(font_data as &[u8], fontdue::FontSettings::default()).unwrap();
-
-    // Spawn initial cards
-    for i in 0..layout_params.count {
-        spawn_card(
-            &mut commands,
-            &mut meshes,
-            &mut images,
-            &mut materials,
-            &debug_material,
-            &font,
-            i,
-            "TEMP".to_string(),
-        );
-    }
+    // Cards are spawned by update_card_count once the first hand arrives
+    commands.insert_resource(CardAssets::new(&mut meshes, &mut images, &mut materials));
 
     commands.spawn((
         PointLight {
@@ -104,69 +247,130 @@ pub fn setup_hand(
 
 fn spawn_card(
     commands: &mut Commands,
-    meshes: &mut Assets<Mesh>,
+    card_assets: &mut CardAssets,
     images: &mut Assets<Image>,
     materials: &mut Assets<StandardMaterial>,
-    debug_material: &Handle<StandardMaterial>,
-    font: &Font,
     index: usize,
+    card_id: EntityID,
     card_name: String
 ) {
-    // Card dimensions
-    let card_size = Vec3::new(2.0, 3.0, 0.01);
-    let image_size = Vec3::new(card_size.x * 0.8, card_size.y * 0.5, 0.02);
-    let text_size = Vec3::new(card_size.x * 0.8, card_size.y * 0.2, 0.02);
-
-    // Create card mesh and material
-    let card_mesh = meshes.add(Cuboid::new(card_size.x, card_size.y, card_size.z));
-    let image_mesh = meshes.add(Cuboid::new(image_size.x, image_size.y, image_size.z));
-    let text_mesh = meshes.add(Cuboid::new(text_size.x, text_size.y, text_size.z));
-
-    let image_material = materials.add(StandardMaterial {
-        base_color: Color::srgb(0.9, 0.9, 0.9),
-        ..default()
-    });
+    // Drawn cards start face down on our deck and are animated into the hand by update_card_positions
+    let deck = Transform::from_translation(Pile::new(PileSide::Own, PileKind::Deck).position())
+        .with_rotation(Quat::from_rotation_x(std::f32::consts::FRAC_PI_2));
+
+    spawn_card_model(commands, card_assets, images, materials, card_name, deck)
+        .insert((Card { index, card_id }, CardDetail::Full))
+        .observe(start_drag)
+        .observe(drag_card)
+        .observe(drop_card);
+}
 
-    // Create text material for this card
-    let text_material = materials.add(StandardMaterial {
-        base_color_texture: Some(images.add(create_text_texture(&card_name, font))),
-        unlit: true,
-        alpha_mode: AlphaMode::Blend,
-        ..default()
+// The card body with its art and name sections, shared by hand and board cards
+pub(crate) fn spawn_card_model<'a>(
+    commands: &'a mut Commands,
+    card_assets: &mut CardAssets,
+    images: &mut Assets<Image>,
+    materials: &mut Assets<StandardMaterial>,
+    card_name: String,
+    transform: Transform,
+) -> EntityCommands<'a> {
+    let text_material = card_assets.text_material(&card_name, images, materials);
+
+    let mut entity = commands.spawn((
+        transform,
+        GlobalTransform::default(),
+        Visibility::default(),
+        Name::new(card_name),
+    ));
+    entity.with_children(|parent| {
+        // Card base
+        parent.spawn((
+            Mesh3d(card_assets.card_mesh.clone()),
+            MeshMaterial3d(card_assets.base_material.clone()),
+            Transform::default(),
+        ));
+
+        // Image section
+        parent.spawn((
+            Mesh3d(card_assets.image_mesh.clone()),
+            MeshMaterial3d(card_assets.image_material.clone()),
+            Transform::from_xyz(0.0, 0.1, CARD_SIZE.z + IMAGE_SIZE.z/2.0),
+            CardImage,
+        ));
+
+        // Text section
+        parent.spawn((
+            Mesh3d(card_assets.text_mesh.clone()),
+            MeshMaterial3d(text_material),
+            Transform::from_xyz(0.0, 1.2, CARD_SIZE.z + TEXT_SIZE.z/2.0 + 0.005),
+            CardText,
+        ));
     });
+    entity
+}
 
-    commands
-        .spawn((
-            Transform::default(),
-            GlobalTransform::default(),
-            Card { index },
-            Visibility::default(),
-            Name::new(card_name),
-        ))
-        .with_children(|parent| {
-            // Card base
-            parent.spawn((
-                Mesh3d(card_mesh.clone()),
-                MeshMaterial3d(debug_material.clone()),
-                Transform::default(),
-            ));
-
-            // Image section
-            parent.spawn((
-                Mesh3d(image_mesh.clone()),
-                MeshMaterial3d(image_material.clone()),
-                Transform::from_xyz(0.0, 0.1, card_size.z + image_size.z/2.0),
-                CardImage,
-            ));
-
-            // Text section
-            parent.spawn((
-                Mesh3d(text_mesh.clone()),
-                MeshMaterial3d(text_material),
-                Transform::from_xyz(0.0, 1.2, card_size.z + text_size.z/2.0 + 0.005),
-                CardText,
-            ));
-        });
+// Where the pointer's ray crosses the horizontal plane at `height`
+fn pointer_on_plane(camera: (&Camera, &GlobalTransform), position: Vec2, height: f32) -> Option<Vec3> {
+    let (camera, camera_transform) = camera;
+    // Pointer positions are window relative, but the camera only renders into the dock's viewport
+    let origin = camera.logical_viewport_rect().map_or(Vec2::ZERO, |rect| rect.min);
+    let ray = camera.viewport_to_world(camera_transform, position - origin).ok()?;
+    let distance = ray.intersect_plane(Vec3::Y * height, InfinitePlane3d::new(Vec3::Y))?;
+    Some(ray.get_point(distance))
+}
+
+fn start_drag(
+    trigger: Trigger<Pointer<DragStart>>,
+    mut commands: Commands,
+    cards: Query<&Card>,
+    mut selected: ResMut<SelectedCard>,
+) {
+    if trigger.event().button != PointerButton::Primary {
+        return;
+    }
+    let Ok(card) = cards.get(trigger.entity()) else { return };
+    selected.index = Some(card.index);
+    commands.entity(trigger.entity()).remove::<CardAnimation>().insert(Dragging);
+}
+
+// Lifts the card toward the table and lays it flat so the player sees where it will land
+fn drag_card(
+    trigger: Trigger<Pointer<Drag>>,
+    camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mut cards: Query<&mut Transform, (With<Card>, With<Dragging>)>,
+) {
+    let Ok(mut transform) = cards.get_mut(trigger.entity()) else { return };
+    let Ok(camera) = camera.get_single() else { return };
+    let Some(point) = pointer_on_plane(camera, trigger.event().pointer_location.position, DRAG_HEIGHT) else { return };
+    transform.translation = point;
+    transform.rotation = Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2);
+}
+
+// Plays the card on a valid drop; otherwise the layout snaps it back into the hand
+fn drop_card(
+    trigger: Trigger<Pointer<DragEnd>>,
+    mut commands: Commands,
+    camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    cards: Query<&Card, With<Dragging>>,
+    mut game_state: ReactResMut<GameState>,
+    turn_player: ReactRes<TurnPlayer>,
+    client: Res<Client>,
+) {
+    let entity = trigger.entity();
+    let Ok(card) = cards.get(entity) else { return };
+    commands.entity(entity).remove::<Dragging>();
+
+    let Some(card) = game_state.arranged_hand().get(card.index).cloned() else { return };
+    let Ok(camera) = camera.get_single() else { return };
+    let Some(point) = pointer_on_plane(camera, trigger.event().pointer_location.position, 0.0) else { return };
+
+    let DropTarget::Slot(slot) = DropTarget::at(point) else { return };
+
+    // Plays the server would refuse never leave the client, and say why just as it would
+    let state = game_state.get_mut(&mut commands);
+    if let Err(error) = play_card(&client, state, turn_player.display_id(), card, Some(slot)) {
+        state.last_rejection = Some(error);
+    }
 }
 
 fn create_text_texture(text: &str, font: &Font) -> Image {
@@ -236,59 +440,64 @@ fn create_text_texture(text: &str, font: &Font) -> Image {
     )
 }
 
-// System to handle card count changes
+// Keeps one card entity per card in the hand, so reordering and draws move cards instead of respawning them
 pub(crate) fn update_card_count(
     mut commands: Commands,
     mut params: ResMut<HandLayoutParams>,
     game_state: ReactRes<GameState>,
-    card_query: Query<Entity, With<Card>>,
-    mut meshes: ResMut<Assets<Mesh>>,
+    mut cards: Query<(Entity, &mut Card, &Transform)>,
+    mut card_assets: ResMut<CardAssets>,
     mut images: ResMut<Assets<Image>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    mut departures: EventReader<CardLeftHand>,
+    mut diagnostics: Diagnostics,
 ) {
-    if params.count != game_state.player_hand.len(){
-        params.count = game_state.player_hand.len();
+    let started = Instant::now();
+    let hand = game_state.arranged_hand();
+    if params.count != hand.len(){
+        params.count = hand.len();
     }
 
-    if params.is_changed() {
-        let current_count = card_query.iter().count();
-
-        if current_count != params.count {
-            // Despawn all existing cards
-            for entity in card_query.iter() {
-                commands.entity(entity).despawn_recursive();
+    let exits: HashMap<EntityID, HandExit> = departures.read().map(|left| (left.card_id, left.exit)).collect();
+    let mut shown = HashSet::new();
+    for (entity, mut card, transform) in cards.iter_mut() {
+        if let Some(index) = hand.iter().position(|held| held.card_id == card.card_id) {
+            if card.index != index {
+                card.index = index;
             }
+            shown.insert(card.card_id);
+            continue;
+        }
 
-            // Create shared resources
-            let debug_material = materials.add(StandardMaterial {
-                base_color_texture: Some(images.add(uv_debug_texture())),
-                ..default()
-            });
-
-            let font_data = include_bytes!("../assets/fonts/FiraMono-Medium.ttf");
-            let font = Font::from_bytes(font_data as &[u8], fontdue::FontSettings::default()).unwrap();
-
-            // Spawn new cards
-            for i in 0..game_state.player_hand.len() {
-                let c = game_state.player_hand[i].clone();
-                spawn_card(
-                    &mut commands,
-                    &mut meshes,
-                    &mut images,
-                    &mut materials,
-                    &debug_material,
-                    &font,
-                    i,
-                    c.card_name
-                );
-            }
+        // Predicted plays leave before the server says so. Cards cleared without an exit, e.g. by a new match, just disappear
+        let predicted = game_state.predicted_plays.iter()
+            .find(|play| play.card.card_id == card.card_id)
+            .map(|play| play.slot.map_or(HandExit::Played, HandExit::Board));
+        let Some(exit) = exits.get(&card.card_id).copied().or(predicted) else {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        };
+        commands.entity(entity)
+            .remove::<(Card, Dragging)>()
+            .insert((Departing, CardAnimation::new(*transform, exit.transform(), DEPARTURE_DURATION, Easing::EaseInOut)));
+    }
+
+    for (index, card) in hand.into_iter().enumerate() {
+        if !shown.contains(&card.card_id) {
+            spawn_card(&mut commands, &mut card_assets, &mut images, &mut materials, index, card.card_id, card.card_name);
         }
     }
+
+    let texture_time = std::mem::take(&mut card_assets.texture_time);
+    diagnostics.add_measurement(&TEXT_TEXTURES, || texture_time.as_secs_f64() * 1000.0);
+    diagnostics.add_measurement(&HAND_REBUILD, || started.elapsed().as_secs_f64() * 1000.0);
 }
 
+// Animates every hand card toward its place in the layout, retargeting cards already on the move
 pub(crate) fn update_card_positions(
+    mut commands: Commands,
     params: Res<HandLayoutParams>,
-    mut query: Query<(&Card, &mut Transform)>,
+    query: Query<(Entity, &Card, &Transform, Option<&CardAnimation>), Without<Dragging>>,
 ) {
     if params.is_changed() {
         println!("Applying new card positions with params: {:?}", *params);
@@ -309,7 +518,7 @@ pub(crate) fn update_card_positions(
 
     let z_overlap_factor = 0.1;
 
-    for (card, mut transform) in query.iter_mut() {
+    for (entity, card, transform, animation) in query.iter() {
         let i = card.index as f32;
 
         let x = if params.count <= 1 {
@@ -335,7 +544,7 @@ pub(crate) fn update_card_positions(
             params.base_z
         };
 
-        transform.translation = Vec3::new(x, y, z);
+        let translation = Vec3::new(x, y, z);
 
         // Similarly, only apply y-rotation if we're over the threshold
         let rotation = if params.count > params.card_curve_threshold {
@@ -346,6 +555,42 @@ pub(crate) fn update_card_positions(
             Quat::from_rotation_x(params.rotation_x)
         };
 
-        transform.rotation = rotation;
+        let target = Transform::from_translation(translation).with_rotation(rotation);
+        let heading = animation.map_or(*transform, |animation| animation.end);
+        if heading != target {
+            commands.entity(entity).insert(CardAnimation::new(*transform, target, LAYOUT_DURATION, Easing::EaseOut));
+        }
+    }
+}
+
+// Full detail for the selected or hovered card and anything close to the camera
+pub(crate) fn update_card_detail(
+    selected: Res<SelectedCard>,
+    camera: Query<&GlobalTransform, With<MainCamera>>,
+    mut cards: Query<(&Card, &GlobalTransform, &mut CardDetail, &Children)>,
+    mut sections: Query<&mut Visibility, Or<(With<CardImage>, With<CardText>)>>,
+) {
+    let Ok(camera) = camera.get_single() else {
+        return;
+    };
+
+    for (card, transform, mut detail, children) in cards.iter_mut() {
+        let focused = selected.index == Some(card.index) || selected.hovered == Some(card.index);
+        let near = transform.translation().distance(camera.translation()) <= FULL_DETAIL_DISTANCE;
+        let wanted = if focused || near { CardDetail::Full } else { CardDetail::Simplified };
+        if *detail == wanted {
+            continue;
+        }
+
+        *detail = wanted;
+        let visibility = match wanted {
+            CardDetail::Full => Visibility::Inherited,
+            CardDetail::Simplified => Visibility::Hidden,
+        };
+        for &child in children.iter() {
+            if let Ok(mut section) = sections.get_mut(child) {
+                *section = visibility;
+            }
+        }
     }
 }
@@ -0,0 +1,53 @@
+use std::time::Duration;
+use bevy::prelude::*;
+use bevy_cobweb::prelude::ReactRes;
+use shared::channel::{GameMessage, HEARTBEAT_INTERVAL};
+use crate::client::Client;
+use crate::state::ConnectionStatus;
+
+// Weight of the newest sample in the running average
+const AVERAGE_WEIGHT: f64 = 0.2;
+
+// Round-trip times measured from Ping/Pong heartbeats
+#[derive(Resource, Debug, Default)]
+pub(crate) struct LatencyStats {
+    pub(crate) last: Option<Duration>,
+    pub(crate) average: Option<Duration>,
+    // Heartbeats sent without a Pong yet
+    pub(crate) outstanding: u32,
+}
+
+impl LatencyStats {
+    // `sent` is the timestamp echoed back in Pong, in milliseconds since startup
+    pub(crate) fn record_pong(&mut self, sent: u64, now: Duration) {
+        let rtt = now.saturating_sub(Duration::from_millis(sent));
+        self.last = Some(rtt);
+        self.average = Some(match self.average {
+            Some(average) => average.mul_f64(1.0 - AVERAGE_WEIGHT) + rtt.mul_f64(AVERAGE_WEIGHT),
+            None => rtt,
+        });
+        self.outstanding = 0;
+    }
+}
+
+// Doubles as the heartbeat that keeps the server from closing a quiet connection
+pub(crate) fn send_heartbeat(
+    client: Res<Client>,
+    status: ReactRes<ConnectionStatus>,
+    time: Res<Time>,
+    mut stats: ResMut<LatencyStats>,
+    mut since_last: Local<Duration>,
+) {
+    if *status != ConnectionStatus::Connected {
+        *since_last = HEARTBEAT_INTERVAL;
+        return;
+    }
+    *since_last += time.delta();
+    if *since_last < HEARTBEAT_INTERVAL {
+        return;
+    }
+    *since_last = Duration::ZERO;
+    if client.request(GameMessage::Ping(time.elapsed().as_millis() as u64)).is_ok() {
+        stats.outstanding += 1;
+    }
+}
@@ -0,0 +1,33 @@
+use bevy::prelude::*;
+use shared::lethal::{find_lethal, LethalPlan};
+use crate::state::{GameState, Turn};
+
+// Whether to point out a winning turn when one is on the table
+#[derive(Resource)]
+pub(crate) struct LethalHint {
+    pub(crate) enabled: bool,
+}
+
+impl Default for LethalHint {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+// Worked out from what this client can see, so it can miss lethal the opponent's hidden cards would stop, never the other way
+pub(crate) fn lethal_plan(world: &World) -> Option<LethalPlan> {
+    if !world.resource::<LethalHint>().enabled {
+        return None;
+    }
+    let game_state = world.resource::<GameState>();
+    if game_state.current_turn != Turn::Player || game_state.match_summary.is_some() {
+        return None;
+    }
+    find_lethal(
+        &game_state.play_field,
+        |card| !game_state.exhausted.contains(&card.card_id),
+        &game_state.player_hand,
+        game_state.available_mana,
+        game_state.opponent_health,
+    )
+}
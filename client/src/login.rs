@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::egui;
+use crate::client::Client;
+
+// What the player typed into the login window, and why the last attempt failed
+#[derive(Resource, Default)]
+pub(crate) struct LoginForm {
+    username: String,
+    // Cleared as soon as it's sent; the Client keeps its own copy for reconnects
+    password: String,
+    pub(crate) error: Option<String>,
+    // Waiting for the server to answer Register or Login
+    pub(crate) pending: bool,
+}
+
+// Shown instead of the table until the server accepts a login
+pub(crate) fn show_login(world: &mut World, ctx: &egui::Context) {
+    let mut submitted = None;
+    let mut form = world.resource_mut::<LoginForm>();
+    egui::Window::new("Log in")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            egui::Grid::new("login_fields").num_columns(2).show(ui, |ui| {
+                ui.label("Username");
+                ui.text_edit_singleline(&mut form.username);
+                ui.end_row();
+                ui.label("Password");
+                ui.add(egui::TextEdit::singleline(&mut form.password).password(true));
+                ui.end_row();
+            });
+            if let Some(error) = &form.error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+
+            let ready = !form.pending && !form.username.trim().is_empty() && !form.password.is_empty();
+            ui.add_enabled_ui(ready, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Log in").clicked() {
+                        submitted = Some(false);
+                    }
+                    if ui.button("Create account").clicked() {
+                        submitted = Some(true);
+                    }
+                });
+            });
+            if form.pending {
+                ui.label("Logging in...");
+            }
+        });
+
+    let Some(register) = submitted else { return };
+    form.pending = true;
+    form.error = None;
+    let username = form.username.trim().to_string();
+    let password = std::mem::take(&mut form.password);
+    world.resource_mut::<Client>().log_in(username, password, register);
+}
@@ -1,5 +1,6 @@
 use std::env;
 use std::path::PathBuf;
+use bevy::diagnostic::{Diagnostic, EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin, RegisterDiagnostic};
 use bevy::prelude::*;
 use bevy::window::WindowTheme;
 use bevy_cobweb::prelude::*;
@@ -13,22 +14,44 @@ mod ui;
 mod client;
 mod hand;
 mod texture;
+mod network_sim;
+mod camera;
+mod accessibility;
+mod latency;
+mod piles;
+mod animation;
+mod board;
+mod opponent_hand;
+mod perf_hud;
+mod message_log;
+mod crash_report;
+mod login;
+mod chat;
+mod puzzles;
+mod lethal;
+mod settings;
+#[cfg(feature = "debug_ui")]
+mod debug_ui;
 
 use state::{ConnectionStatus, TurnPlayer, EndTurn};
-use client::{client_factory, handle_client_events};
+use client::{client_factory, handle_client_events, Client};
 use crate::hand::{setup_hand, HandLayoutParams};
-use crate::state::{setup_game_state, GameState, SelectedCard, UiState};
+use crate::network_sim::NetworkSimulator;
+use crate::settings::ClientSettings;
+use crate::state::{setup_game_state, CardLibrary, DeckResource, GameState, Language, SelectedCard, UiState};
+use shared::codec::ConnectOptions;
 use crate::texture::uv_debug_texture;
-use crate::ui::{show_ui_system, set_camera_viewport, setup_camera, setup_lighting, setup_play_field};
+use crate::ui::{show_ui_system, set_camera_viewport, setup_camera, setup_lighting, setup_play_field, step_plan};
 
 #[derive(Resource)]
 struct AssetDirectory(PathBuf);
 
 fn main() {
     // simplenet client setup
-    let client = client_factory().new_client(
+    let client = Client::new(client_factory().new_client(
         enfync::builtin::Handle::default(),
         url::Url::parse("ws://127.0.0.1:48888/ws").unwrap(),
+        // Only names the connection; the player id comes from logging in
         bevy_simplenet::AuthRequest::None{
             client_id: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis()
         },
@@ -37,8 +60,9 @@ fn main() {
             reconnect_on_server_close : true,
             ..Default::default()
         },
-        ()
-    );
+        ConnectOptions::default(),
+    ));
+    crash_report::install(client.shared_message_log());
 
     // prepare bevy plugins
     let bevy_plugins = DefaultPlugins
@@ -47,6 +71,12 @@ fn main() {
                 primary_window: Some(Window{ window_theme: Some(WindowTheme::Dark), ..Default::default() }),
                 ..Default::default()
             }
+        )
+        .set(
+            bevy::log::LogPlugin{
+                custom_layer: crash_report::log_tail_layer,
+                ..Default::default()
+            }
         );
 
     // reduce input lag on native targets
@@ -55,32 +85,64 @@ fn main() {
 
     let mut asset_path = env::current_dir().expect("Failed to get current directory");
     asset_path.push("client/assets");
+    let language = Language::from_env();
 
     // run client
-    App::new()
+    let mut app = App::new();
+    app
         .add_plugins((
             bevy_plugins,
             ReactPlugin,
             CobwebUiPlugin,
-            EguiPlugin
+            EguiPlugin,
+            MeshPickingPlugin,
+            FrameTimeDiagnosticsPlugin,
+            EntityCountDiagnosticsPlugin,
         ))
+        .register_diagnostic(Diagnostic::new(perf_hud::HAND_REBUILD).with_suffix("ms"))
+        .register_diagnostic(Diagnostic::new(perf_hud::TEXT_TEXTURES).with_suffix("ms"))
         // .add_plugins(WorldInspectorPlugin::new())
         .insert_resource(client)
-        .insert_resource(HandLayoutParams::default())
+        .insert_resource(NetworkSimulator::from_env())
+        .insert_resource(HandLayoutParams::load(&asset_path))
         .insert_resource(AssetDirectory(asset_path.clone()))
         .insert_react_resource(ConnectionStatus::Connecting)
         .insert_resource(UiState::new())
         .insert_resource(GameState::default())
+        .insert_resource(ClientSettings::load())
+        .insert_resource(language)
+        .insert_resource(CardLibrary::for_language(language))
         .init_resource::<HandLayoutParams>()
         .init_resource::<SelectedCard>()
+        .init_resource::<accessibility::Accessibility>()
+        .init_resource::<latency::LatencyStats>()
+        .init_resource::<message_log::MessageLogOverlay>()
+        .init_resource::<login::LoginForm>()
+        .init_resource::<chat::ChatLog>()
+        .init_resource::<puzzles::PuzzleBrowser>()
+        .init_resource::<lethal::LethalHint>()
+        .init_resource::<perf_hud::PerfHud>()
+        .insert_resource(crash_report::CrashReports::from_env())
+        .init_resource::<DeckResource>()
         .init_react_resource::<TurnPlayer>()
         .init_react_resource::<EndTurn>()
         .init_react_resource::<GameState>()
-        .add_systems(Startup, (setup, setup_hand))
+        .add_event::<hand::CardLeftHand>()
+        .add_systems(Startup, (setup, setup_hand, piles::setup_piles, opponent_hand::setup_opponent_hand))
         .add_systems(Update, (
             handle_client_events,
-            hand::update_card_positions,
-            hand::update_card_count
+            latency::send_heartbeat,
+            message_log::toggle_message_log,
+            crash_report::record_settings,
+            settings::save_hand_order.after(handle_client_events),
+            (perf_hud::toggle_perf_hud, perf_hud::detect_spikes),
+            (hand::update_card_count.after(handle_client_events), hand::update_card_positions, animation::animate_cards).chain(),
+            hand::update_card_detail,
+            piles::update_piles,
+            board::update_board.after(handle_client_events),
+            step_plan.after(handle_client_events),
+            opponent_hand::update_opponent_hand.after(handle_client_events).before(animation::animate_cards),
+            (camera::follow_game_phase, camera::camera_controls, camera::move_camera).chain(),
         ))
         .add_systems(
             PostUpdate,
@@ -91,8 +153,12 @@ fn main() {
         )
         .add_systems(PostUpdate, set_camera_viewport.after(show_ui_system))
         .register_type::<Option<Handle<Image>>>()
-        .register_type::<AlphaMode>()
-        .run();
+        .register_type::<AlphaMode>();
+
+    #[cfg(feature = "debug_ui")]
+    app.add_plugins(debug_ui::DebugUiPlugin);
+
+    app.run();
 }
 
 fn setup(
@@ -100,8 +166,11 @@ fn setup(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut game_state: ResMut<GameState>,
+    settings: Res<ClientSettings>,
 ) {
     setup_game_state(&mut game_state);
+    // Picks the arrangement back up if we're rejoining the match it was made in
+    game_state.hand_order = settings.hand_order.clone();
     setup_camera(&mut commands);
     setup_lighting(&mut commands);
     setup_play_field(&mut commands, &mut meshes, &mut materials);
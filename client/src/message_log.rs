@@ -0,0 +1,118 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+use bevy::prelude::*;
+use bevy_inspector_egui::egui;
+use shared::channel::GameMessage;
+use wasm_timer::Instant;
+use crate::client::Client;
+
+const LOG_CAPACITY: usize = 200;
+// Long messages like snapshots are cut so one entry doesn't fill the overlay
+const SUMMARY_LENGTH: usize = 200;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Direction {
+    Sent,
+    Received,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct LoggedMessage {
+    // Since the client started
+    pub(crate) at: Duration,
+    pub(crate) direction: Direction,
+    pub(crate) heartbeat: bool,
+    pub(crate) summary: String,
+}
+
+// Last requests and server messages, kept by the Client so every send is recorded no matter who sends it
+pub(crate) struct MessageLog {
+    started: Instant,
+    entries: VecDeque<LoggedMessage>,
+}
+
+impl MessageLog {
+    pub(crate) fn new() -> Self {
+        Self { started: Instant::now(), entries: VecDeque::new() }
+    }
+
+    pub(crate) fn record(&mut self, direction: Direction, message: &GameMessage) {
+        if self.entries.len() == LOG_CAPACITY {
+            self.entries.pop_front();
+        }
+        // Passwords would let anyone holding a log or crash report take over the account
+        let mut summary = match message {
+            GameMessage::Register { username, .. } => format!("Register {{ username: {username:?}, password: <redacted> }}"),
+            GameMessage::Login { username, .. } => format!("Login {{ username: {username:?}, password: <redacted> }}"),
+            _ => format!("{message:?}"),
+        };
+        if let Some((cut, _)) = summary.char_indices().nth(SUMMARY_LENGTH) {
+            summary.truncate(cut);
+            summary.push_str("...");
+        }
+        self.entries.push_back(LoggedMessage {
+            at: self.started.elapsed(),
+            direction,
+            heartbeat: matches!(message, GameMessage::Ping(_) | GameMessage::Pong(_)),
+            summary,
+        });
+    }
+
+    pub(crate) fn entries(&self) -> impl Iterator<Item = &LoggedMessage> {
+        self.entries.iter()
+    }
+}
+
+impl LoggedMessage {
+    pub(crate) fn arrow(&self) -> &'static str {
+        match self.direction {
+            Direction::Sent => "->",
+            Direction::Received => "<-",
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub(crate) struct MessageLogOverlay {
+    open: bool,
+    show_heartbeats: bool,
+}
+
+// F11 toggles the overlay; F12 is taken by the debug inspector
+pub(crate) fn toggle_message_log(keys: Res<ButtonInput<KeyCode>>, mut overlay: ResMut<MessageLogOverlay>) {
+    if keys.just_pressed(KeyCode::F11) {
+        overlay.open = !overlay.open;
+    }
+}
+
+pub(crate) fn show_message_log(world: &mut World, ctx: &egui::Context) {
+    world.resource_scope::<MessageLogOverlay, _>(|world, mut overlay| {
+        if !overlay.open {
+            return;
+        }
+        let overlay = &mut *overlay;
+        let client = world.resource::<Client>();
+        let log = client.message_log();
+
+        egui::Window::new("Message log")
+            .default_size([520.0, 360.0])
+            .open(&mut overlay.open)
+            .show(ctx, |ui| {
+                ui.checkbox(&mut overlay.show_heartbeats, "Show heartbeats");
+                ui.separator();
+                egui::ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
+                    for entry in log.entries().filter(|entry| overlay.show_heartbeats || !entry.heartbeat) {
+                        let color = match entry.direction {
+                            Direction::Sent => egui::Color32::LIGHT_BLUE,
+                            Direction::Received => egui::Color32::LIGHT_GREEN,
+                        };
+                        ui.horizontal_wrapped(|ui| {
+                            ui.monospace(format!("{:>9.3}s", entry.at.as_secs_f32()));
+                            ui.colored_label(color, entry.arrow());
+                            ui.monospace(&entry.summary);
+                        });
+                    }
+                });
+            });
+    });
+}
@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+use rand::Rng;
+use wasm_timer::Instant;
+use std::time::Duration;
+use crate::client::{Client, ClientEvent};
+
+// Env var holding e.g. "latency=150,jitter=50,loss=0.05,reorder=0.1" (times in ms)
+pub(crate) const NETWORK_SIM_ENV: &str = "GAME_NETWORK_SIM";
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct NetworkConditions {
+    pub(crate) latency: Duration,
+    pub(crate) jitter: Duration,
+    // Chance of dropping a server message, 0.0 - 1.0
+    pub(crate) loss: f64,
+    // Chance of holding a message back long enough for later ones to overtake it
+    pub(crate) reorder: f64,
+}
+
+impl NetworkConditions {
+    fn parse(spec: &str) -> Result<Self, String> {
+        let mut conditions = Self::default();
+        for pair in spec.split(',').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').ok_or(format!("expected key=value, got '{pair}'"))?;
+            let number: f64 = value.trim().parse().map_err(|_| format!("invalid number '{value}'"))?;
+            match key.trim() {
+                "latency" => conditions.latency = Duration::from_secs_f64(number / 1000.0),
+                "jitter" => conditions.jitter = Duration::from_secs_f64(number / 1000.0),
+                "loss" => conditions.loss = number.clamp(0.0, 1.0),
+                "reorder" => conditions.reorder = number.clamp(0.0, 1.0),
+                other => return Err(format!("unknown network condition '{other}'")),
+            }
+        }
+        Ok(conditions)
+    }
+}
+
+// Sits between the simplenet client and the game, delaying, shuffling and dropping incoming events
+#[derive(Resource, Default)]
+pub(crate) struct NetworkSimulator {
+    conditions: Option<NetworkConditions>,
+    in_flight: Vec<(Instant, ClientEvent)>,
+}
+
+impl NetworkSimulator {
+    pub(crate) fn from_env() -> Self {
+        let Ok(spec) = std::env::var(NETWORK_SIM_ENV) else {
+            return Self::default();
+        };
+
+        match NetworkConditions::parse(&spec) {
+            Ok(conditions) => {
+                info!("Simulating network conditions: {:?}", conditions);
+                Self { conditions: Some(conditions), in_flight: Vec::new() }
+            }
+            Err(e) => {
+                warn!("Ignoring {}: {}", NETWORK_SIM_ENV, e);
+                Self::default()
+            }
+        }
+    }
+
+    // Drop-in replacement for client.next()
+    pub(crate) fn next(&mut self, client: &mut Client) -> Option<ClientEvent> {
+        let Some(conditions) = self.conditions.clone() else {
+            return client.next();
+        };
+
+        let mut rng = rand::thread_rng();
+        while let Some(event) = client.next() {
+            // Only game messages are lost; connection reports and request results always arrive
+            if matches!(event, ClientEvent::Msg(_)) && rng.gen_bool(conditions.loss) {
+                continue;
+            }
+
+            let mut delay = conditions.latency;
+            if !conditions.jitter.is_zero() {
+                delay += conditions.jitter.mul_f64(rng.gen_range(0.0..1.0));
+            }
+            if rng.gen_bool(conditions.reorder) {
+                delay += conditions.latency + conditions.jitter;
+            }
+            self.in_flight.push((Instant::now() + delay, event));
+        }
+
+        let now = Instant::now();
+        let (index, _) = self.in_flight.iter()
+            .enumerate()
+            .filter(|(_, (due, _))| *due <= now)
+            .min_by_key(|(_, (due, _))| *due)?;
+        Some(self.in_flight.remove(index).1)
+    }
+}
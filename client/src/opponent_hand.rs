@@ -0,0 +1,86 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+use bevy::prelude::*;
+use bevy_cobweb::prelude::ReactRes;
+use crate::animation::{CardAnimation, Easing};
+use crate::board::PLAY_FIELD_SIZE;
+use crate::hand::CARD_SIZE;
+use crate::piles::{Pile, PileKind, PileSide};
+use crate::state::GameState;
+
+// Gap between neighbouring card backs, squeezed once the row would outgrow the table
+const SPACING: f32 = 1.2;
+// Height above the table and distance beyond its far edge
+const HAND_HEIGHT: f32 = 1.0;
+const HAND_MARGIN: f32 = 1.5;
+// Tilt toward our camera so the backs read as a hand rather than a line
+const TILT: f32 = 0.6;
+const LAYOUT_DURATION: f32 = 0.3;
+
+#[derive(Component)]
+pub(crate) struct OpponentCard {
+    index: usize,
+}
+
+#[derive(Resource)]
+pub(crate) struct CardBackAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+pub(crate) fn setup_opponent_hand(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(CardBackAssets {
+        mesh: meshes.add(Cuboid::new(CARD_SIZE.x, CARD_SIZE.y, CARD_SIZE.z)),
+        // Same colour as the decks, so it's clear where these came from
+        material: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.15, 0.2, 0.45),
+            ..default()
+        }),
+    });
+}
+
+fn slot_transform(index: usize, count: usize) -> Transform {
+    let width = (SPACING * count.saturating_sub(1) as f32).min(PLAY_FIELD_SIZE);
+    let x = if count <= 1 { 0.0 } else { -width / 2.0 + width * index as f32 / (count - 1) as f32 };
+    // Mirrored left to right, since we look at their hand from across the table
+    Transform::from_xyz(-x, HAND_HEIGHT, -PLAY_FIELD_SIZE / 2.0 - HAND_MARGIN)
+        .with_rotation(Quat::from_rotation_y(PI) * Quat::from_rotation_x(-TILT))
+}
+
+// One card back per card the opponent holds. Draws come off their deck; played cards simply vanish
+// from the end of the row, since we can't tell which one it was
+pub(crate) fn update_opponent_hand(
+    mut commands: Commands,
+    game_state: ReactRes<GameState>,
+    assets: Res<CardBackAssets>,
+    cards: Query<(Entity, &OpponentCard, &Transform, Option<&CardAnimation>)>,
+) {
+    let count = game_state.opponent_hand_count as usize;
+    let mut shown = 0;
+    for (entity, card, transform, animation) in cards.iter() {
+        if card.index >= count {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        shown += 1;
+        let target = slot_transform(card.index, count);
+        if animation.map_or(*transform, |animation| animation.end) != target {
+            commands.entity(entity).insert(CardAnimation::new(*transform, target, LAYOUT_DURATION, Easing::EaseOut));
+        }
+    }
+
+    let deck = Transform::from_translation(Pile::new(PileSide::Opponent, PileKind::Deck).position())
+        .with_rotation(Quat::from_rotation_x(FRAC_PI_2));
+    for index in shown..count {
+        commands.spawn((
+            Mesh3d(assets.mesh.clone()),
+            MeshMaterial3d(assets.material.clone()),
+            deck,
+            OpponentCard { index },
+            CardAnimation::new(deck, slot_transform(index, count), LAYOUT_DURATION, Easing::EaseOut),
+        ));
+    }
+}
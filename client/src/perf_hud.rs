@@ -0,0 +1,80 @@
+use std::collections::VecDeque;
+use bevy::diagnostic::{DiagnosticPath, DiagnosticsStore, EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin};
+use bevy::prelude::*;
+use bevy_inspector_egui::egui;
+
+// Milliseconds spent in update_card_count, and in generating name textures for newly seen cards
+pub(crate) const HAND_REBUILD: DiagnosticPath = DiagnosticPath::const_new("hand/rebuild");
+pub(crate) const TEXT_TEXTURES: DiagnosticPath = DiagnosticPath::const_new("hand/text_textures");
+
+// A quarter of a 60 fps frame; either card system going over it in one frame is worth a look
+const SPIKE_MS: f64 = 4.0;
+const MAX_SPIKES: usize = 10;
+
+#[derive(Resource, Default)]
+pub(crate) struct PerfHud {
+    open: bool,
+    // Most recent last
+    spikes: VecDeque<String>,
+}
+
+// F10 toggles the HUD, next to the message log on F11
+pub(crate) fn toggle_perf_hud(keys: Res<ButtonInput<KeyCode>>, mut hud: ResMut<PerfHud>) {
+    if keys.just_pressed(KeyCode::F10) {
+        hud.open = !hud.open;
+    }
+}
+
+// Runs whether or not the HUD is open, so spikes from before it was opened still show up
+pub(crate) fn detect_spikes(store: Res<DiagnosticsStore>, time: Res<Time>, mut hud: ResMut<PerfHud>) {
+    for (path, label) in [(&HAND_REBUILD, "Hand rebuild"), (&TEXT_TEXTURES, "Text texture generation")] {
+        let Some(ms) = store.get(path).and_then(|diagnostic| diagnostic.value()) else { continue };
+        if ms <= SPIKE_MS {
+            continue;
+        }
+        warn!("{} took {:.1} ms in one frame", label, ms);
+        if hud.spikes.len() == MAX_SPIKES {
+            hud.spikes.pop_front();
+        }
+        hud.spikes.push_back(format!("{:>8.1}s  {} took {:.1} ms", time.elapsed_secs(), label, ms));
+    }
+}
+
+pub(crate) fn show_perf_hud(world: &mut World, ctx: &egui::Context) {
+    world.resource_scope::<PerfHud, _>(|world, mut hud| {
+        if !hud.open {
+            return;
+        }
+        let hud = &mut *hud;
+        let store = world.resource::<DiagnosticsStore>();
+        let smoothed = |path: &DiagnosticPath| store.get(path).and_then(|diagnostic| diagnostic.smoothed());
+        let average = |path: &DiagnosticPath| store.get(path).and_then(|diagnostic| diagnostic.average());
+
+        egui::Window::new("Performance")
+            .default_width(280.0)
+            .open(&mut hud.open)
+            .show(ctx, |ui| {
+                egui::Grid::new("perf_hud").num_columns(2).show(ui, |ui| {
+                    let mut row = |label: &str, value: Option<f64>, unit: &str| {
+                        ui.label(label);
+                        ui.monospace(value.map_or("-".to_string(), |value| format!("{value:.1} {unit}")));
+                        ui.end_row();
+                    };
+                    row("FPS", smoothed(&FrameTimeDiagnosticsPlugin::FPS), "");
+                    row("Frame time", smoothed(&FrameTimeDiagnosticsPlugin::FRAME_TIME), "ms");
+                    row("Entities", smoothed(&EntityCountDiagnosticsPlugin::ENTITY_COUNT), "");
+                    row("Hand rebuild (avg)", average(&HAND_REBUILD), "ms");
+                    row("Text textures (avg)", average(&TEXT_TEXTURES), "ms");
+                });
+
+                ui.separator();
+                ui.label(format!("Spikes over {SPIKE_MS} ms"));
+                if hud.spikes.is_empty() {
+                    ui.label("None so far");
+                }
+                for spike in hud.spikes.iter().rev() {
+                    ui.colored_label(egui::Color32::YELLOW, spike);
+                }
+            });
+    });
+}
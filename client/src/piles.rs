@@ -0,0 +1,128 @@
+use bevy::prelude::*;
+use bevy_cobweb::prelude::ReactRes;
+use shared::channel::GameMessage;
+use crate::client::Client;
+use crate::state::GameState;
+
+// Every card in a pile adds this much height
+const CARD_THICKNESS: f32 = 0.02;
+const PILE_SIZE: Vec2 = Vec2::new(1.4, 2.0);
+// Distance of the piles from the table's centre lines
+const PILE_X: f32 = 6.2;
+const PILE_Z: f32 = 5.5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PileSide {
+    Own,
+    Opponent,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PileKind {
+    Deck,
+    Graveyard,
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+pub(crate) struct Pile {
+    side: PileSide,
+    kind: PileKind,
+}
+
+impl Pile {
+    pub(crate) const fn new(side: PileSide, kind: PileKind) -> Self {
+        Self { side, kind }
+    }
+
+    pub(crate) fn position(&self) -> Vec3 {
+        let x = match self.kind {
+            PileKind::Deck => PILE_X,
+            PileKind::Graveyard => -PILE_X,
+        };
+        let z = match self.side {
+            PileSide::Own => PILE_Z,
+            PileSide::Opponent => -PILE_Z,
+        };
+        Vec3::new(x, 0.0, z)
+    }
+
+    fn count(&self, game_state: &GameState) -> u32 {
+        match (self.side, self.kind) {
+            (PileSide::Own, PileKind::Deck) => game_state.deck_size,
+            (PileSide::Own, PileKind::Graveyard) => game_state.graveyard_size,
+            (PileSide::Opponent, PileKind::Deck) => game_state.opponent_deck_size,
+            (PileSide::Opponent, PileKind::Graveyard) => game_state.opponent_graveyard_size,
+        }
+    }
+}
+
+pub(crate) fn setup_piles(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // A unit-high box scaled by the card count, shared by all four piles
+    let mesh = meshes.add(Cuboid::new(PILE_SIZE.x, 1.0, PILE_SIZE.y));
+    let deck_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.15, 0.2, 0.45),
+        ..default()
+    });
+    let graveyard_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.3, 0.3, 0.3),
+        ..default()
+    });
+
+    for side in [PileSide::Own, PileSide::Opponent] {
+        for kind in [PileKind::Deck, PileKind::Graveyard] {
+            let material = match kind {
+                PileKind::Deck => deck_material.clone(),
+                PileKind::Graveyard => graveyard_material.clone(),
+            };
+            let pile = Pile { side, kind };
+            commands
+                .spawn((
+                    Mesh3d(mesh.clone()),
+                    MeshMaterial3d(material),
+                    Transform::from_translation(pile.position()),
+                    Visibility::Hidden,
+                    pile,
+                ))
+                .observe(inspect_graveyard);
+        }
+    }
+}
+
+// Piles grow with their card count and disappear when empty
+pub(crate) fn update_piles(
+    game_state: ReactRes<GameState>,
+    mut piles: Query<(&Pile, &mut Transform, &mut Visibility)>,
+) {
+    for (pile, mut transform, mut visibility) in piles.iter_mut() {
+        let height = pile.count(&game_state) as f32 * CARD_THICKNESS;
+        *visibility = if height > 0.0 { Visibility::Inherited } else { Visibility::Hidden };
+        transform.scale.y = height.max(CARD_THICKNESS);
+        transform.translation.y = transform.scale.y / 2.0;
+    }
+}
+
+// Decks stay face down; graveyards are public, so clicking one asks the server for its cards
+fn inspect_graveyard(
+    trigger: Trigger<Pointer<Click>>,
+    piles: Query<&Pile>,
+    game_state: ReactRes<GameState>,
+    client: Res<Client>,
+) {
+    let Ok(pile) = piles.get(trigger.entity()) else { return };
+    if pile.kind != PileKind::Graveyard {
+        return;
+    }
+    let owner = match pile.side {
+        PileSide::Own => Some(client.id()),
+        PileSide::Opponent => game_state.match_profiles.iter()
+            .map(|profile| profile.player_id)
+            .find(|&id| id != client.id()),
+    };
+    if let Some(owner) = owner {
+        let _ = client.request(GameMessage::InspectGraveyard(owner));
+    }
+}
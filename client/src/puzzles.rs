@@ -0,0 +1,207 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::egui;
+use shared::channel::{CardData, CardType, GameMessage, PuzzleInfo, PuzzleMove};
+use shared::messages::MessageCode;
+use crate::client::Client;
+use crate::state::Language;
+
+// Puzzles from the server and the solution being put together for the open one
+#[derive(Resource, Default)]
+pub(crate) struct PuzzleBrowser {
+    puzzles: Vec<PuzzleInfo>,
+    requested: bool,
+    selected: Option<String>,
+    moves: Vec<PuzzleMove>,
+    // Own board slot picked to attack with, waiting for a target
+    attacker: Option<usize>,
+    // Answer to the last submission of the open puzzle; None inside means it was solved
+    result: Option<Option<MessageCode>>,
+}
+
+impl PuzzleBrowser {
+    pub(crate) fn receive_list(&mut self, puzzles: Vec<PuzzleInfo>) {
+        self.puzzles = puzzles;
+    }
+
+    pub(crate) fn receive_result(&mut self, puzzle_id: String, failure: Option<MessageCode>) {
+        if failure.is_none() {
+            if let Some(puzzle) = self.puzzles.iter_mut().find(|puzzle| puzzle.id == puzzle_id) {
+                puzzle.completed = true;
+            }
+        }
+        if self.selected.as_ref() == Some(&puzzle_id) {
+            self.result = Some(failure);
+        }
+    }
+
+    fn open(&mut self, puzzle_id: String) {
+        self.selected = Some(puzzle_id);
+        self.moves.clear();
+        self.attacker = None;
+        self.result = None;
+    }
+}
+
+// The board as it will be after the planned moves, counting creatures played from hand but not combat
+fn planned_board(puzzle: &PuzzleInfo, moves: &[PuzzleMove]) -> Vec<Option<CardData>> {
+    let mut board = puzzle.board.clone();
+    for puzzle_move in moves {
+        if let PuzzleMove::PlayCard { card_id, slot: Some(slot) } = *puzzle_move {
+            let card = puzzle.hand.iter().find(|card| card.card_id == card_id).cloned();
+            if let Some(free) = board.get_mut(slot) {
+                *free = card;
+            }
+        }
+    }
+    board
+}
+
+fn describe(puzzle: &PuzzleInfo, board: &[Option<CardData>], puzzle_move: &PuzzleMove) -> String {
+    let name = |card: Option<&CardData>| card.map_or("?".to_string(), |card| card.card_name.clone());
+    match *puzzle_move {
+        PuzzleMove::PlayCard { card_id, slot } => {
+            let card = name(puzzle.hand.iter().find(|card| card.card_id == card_id));
+            match slot {
+                Some(slot) => format!("Play {card} into slot {}", slot + 1),
+                None => format!("Play {card}"),
+            }
+        }
+        PuzzleMove::Attack { attacker, target } => {
+            let attacking = board.get(attacker).and_then(Option::as_ref).map_or(format!("slot {}", attacker + 1), |card| card.card_name.clone());
+            match target {
+                Some(target) => format!("{attacking} attacks {}", name(puzzle.opponent_board.get(target).and_then(Option::as_ref))),
+                None => format!("{attacking} attacks the opponent"),
+            }
+        }
+    }
+}
+
+pub(crate) fn render_puzzles(world: &mut World, ui: &mut egui::Ui) {
+    let language = *world.resource::<Language>();
+    let mut browser = world.resource_mut::<PuzzleBrowser>();
+    let mut request = None;
+    if !browser.requested {
+        browser.requested = true;
+        request = Some(GameMessage::ListPuzzles);
+    }
+
+    ui.horizontal(|ui| {
+        let solved = browser.puzzles.iter().filter(|puzzle| puzzle.completed).count();
+        ui.heading("Puzzles");
+        ui.label(format!("{solved}/{} solved", browser.puzzles.len()));
+        if ui.button("Refresh").clicked() {
+            request = Some(GameMessage::ListPuzzles);
+        }
+    });
+    ui.separator();
+
+    let mut open = None;
+    ui.horizontal_wrapped(|ui| {
+        for puzzle in &browser.puzzles {
+            let mark = if puzzle.completed { "✔ " } else { "" };
+            let selected = browser.selected.as_ref() == Some(&puzzle.id);
+            if ui.selectable_label(selected, format!("{mark}{}", puzzle.name)).clicked() && !selected {
+                open = Some(puzzle.id.clone());
+            }
+        }
+    });
+    if let Some(puzzle_id) = open {
+        browser.open(puzzle_id);
+    }
+
+    let Some(puzzle) = browser.selected.as_ref()
+        .and_then(|id| browser.puzzles.iter().find(|puzzle| &puzzle.id == id))
+        .cloned() else {
+        if let Some(request) = request {
+            let _ = world.resource::<Client>().request(request);
+        }
+        return;
+    };
+    let browser = &mut *browser;
+
+    ui.separator();
+    ui.label(&puzzle.description);
+    ui.strong(format!("{} in at most {} moves", puzzle.objective.text(), puzzle.move_limit));
+    ui.label(format!("Mana: {}   Your health: {}   Opponent health: {}", puzzle.mana, puzzle.health, puzzle.opponent_health));
+
+    ui.label("Opponent's board");
+    ui.horizontal_wrapped(|ui| {
+        for (slot, card) in puzzle.opponent_board.iter().enumerate() {
+            let Some(card) = card else { continue };
+            let button = ui.add_enabled(browser.attacker.is_some(), egui::Button::new(format!("[ {} {}/{} ]", card.card_name, card.power, card.health)));
+            if button.clicked() {
+                if let Some(attacker) = browser.attacker.take() {
+                    browser.moves.push(PuzzleMove::Attack { attacker, target: Some(slot) });
+                }
+            }
+        }
+        if ui.add_enabled(browser.attacker.is_some(), egui::Button::new("Attack the opponent")).clicked() {
+            if let Some(attacker) = browser.attacker.take() {
+                browser.moves.push(PuzzleMove::Attack { attacker, target: None });
+            }
+        }
+    });
+
+    ui.label("Your board, pick a creature to attack with");
+    let board = planned_board(&puzzle, &browser.moves);
+    ui.horizontal_wrapped(|ui| {
+        for (slot, card) in board.iter().enumerate() {
+            let Some(card) = card else { continue };
+            let selected = browser.attacker == Some(slot);
+            if ui.selectable_label(selected, format!("[ {} {}/{} ]", card.card_name, card.power, card.health)).clicked() {
+                browser.attacker = (!selected).then_some(slot);
+            }
+        }
+    });
+
+    ui.label("Your hand");
+    ui.horizontal_wrapped(|ui| {
+        let played: Vec<_> = browser.moves.iter()
+            .filter_map(|puzzle_move| match puzzle_move {
+                PuzzleMove::PlayCard { card_id, .. } => Some(*card_id),
+                _ => None,
+            })
+            .collect();
+        for card in puzzle.hand.iter().filter(|card| !played.contains(&card.card_id)) {
+            if ui.button(format!("Play {} ({})", card.card_name, card.cost)).on_hover_text(&card.card_text).clicked() {
+                // Creatures go into the leftmost free slot
+                let slot = matches!(card.card_type, CardType::Creature)
+                    .then(|| board.iter().position(Option::is_none))
+                    .flatten();
+                browser.moves.push(PuzzleMove::PlayCard { card_id: card.card_id, slot });
+                browser.result = None;
+            }
+        }
+    });
+
+    ui.separator();
+    ui.label(format!("Solution ({}/{} moves)", browser.moves.len(), puzzle.move_limit));
+    for (i, puzzle_move) in browser.moves.iter().enumerate() {
+        ui.label(format!("{}. {}", i + 1, describe(&puzzle, &board, puzzle_move)));
+    }
+    ui.horizontal(|ui| {
+        if ui.add_enabled(!browser.moves.is_empty(), egui::Button::new("Undo")).clicked() {
+            browser.moves.pop();
+            browser.result = None;
+        }
+        if ui.add_enabled(!browser.moves.is_empty(), egui::Button::new("Start over")).clicked() {
+            browser.open(puzzle.id.clone());
+        }
+        if ui.add_enabled(!browser.moves.is_empty(), egui::Button::new("Submit")).clicked() {
+            request = Some(GameMessage::SubmitPuzzle { puzzle_id: puzzle.id.clone(), moves: browser.moves.clone() });
+        }
+    });
+    match &browser.result {
+        Some(None) => {
+            ui.colored_label(egui::Color32::LIGHT_GREEN, "Solved!");
+        }
+        Some(Some(failure)) => {
+            ui.colored_label(egui::Color32::LIGHT_RED, language.message(failure));
+        }
+        None => {}
+    }
+
+    if let Some(request) = request {
+        let _ = world.resource::<Client>().request(request);
+    }
+}
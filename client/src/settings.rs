@@ -0,0 +1,50 @@
+use bevy::prelude::*;
+use bevy_cobweb::prelude::ReactRes;
+use serde::{Deserialize, Serialize};
+use shared::EntityID;
+use crate::state::GameState;
+
+// Relative to the working directory, like the asset folder
+const SETTINGS_FILE: &str = "client_settings.toml";
+
+// What the player set up locally, kept across restarts
+#[derive(Resource, Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+#[serde(default)]
+pub(crate) struct ClientSettings {
+    // The hand as the player last arranged it, so reconnecting into the same match keeps it
+    pub(crate) hand_order: Vec<EntityID>,
+}
+
+impl ClientSettings {
+    // Missing or unreadable files give the defaults
+    pub(crate) fn load() -> Self {
+        let Ok(contents) = std::fs::read_to_string(SETTINGS_FILE) else {
+            return Self::default();
+        };
+
+        match toml::from_str(&contents) {
+            Ok(settings) => settings,
+            Err(e) => {
+                warn!("Ignoring invalid settings {}: {}", SETTINGS_FILE, e);
+                Self::default()
+            }
+        }
+    }
+
+    fn save(&self) {
+        let result = toml::to_string(self).map_err(|e| e.to_string())
+            .and_then(|contents| std::fs::write(SETTINGS_FILE, contents).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            warn!("Failed to save settings to {}: {}", SETTINGS_FILE, e);
+        }
+    }
+}
+
+// Writes the file whenever the hand order changes, whether the player dragged a card or one left the hand
+pub(crate) fn save_hand_order(game_state: ReactRes<GameState>, mut settings: ResMut<ClientSettings>) {
+    if settings.hand_order == game_state.hand_order {
+        return;
+    }
+    settings.hand_order = game_state.hand_order.clone();
+    settings.save();
+}
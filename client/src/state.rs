@@ -1,18 +1,30 @@
 use std::any::TypeId;
-use bevy::prelude::Resource;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::{Res, Resource};
 use bevy_asset::UntypedAssetId;
 use bevy_cobweb::prelude::*;
 use bevy_inspector_egui::bevy_inspector::hierarchy::SelectedEntities;
 use egui_dock::DockState;
-use shared::channel::CardData;
+use shared::channel::{CardData, CreatureHistory, DeckSummary, Emote, GameMessage, LobbyRules, MatchListing, MatchSummary, PlayerProfile, PlayerStats, RevealedCards, BOARD_SLOTS};
+use shared::card_details::{card_language, load_cards_for, CardConfig, CardDefinition};
+use shared::card_filter::CardFilter;
+use shared::errors::GameError;
+use shared::messages::MessageCode;
+use shared::text::{game_error_text, message_text};
+use shared::keywords::Keyword;
 use shared::EntityID;
 use crate::client::{Client};
 
 pub(crate) fn setup_game_state(game_state: &mut GameState) {
     game_state.player_hand = vec![];
+    game_state.play_field = vec![None; BOARD_SLOTS];
+    game_state.opponent_field = vec![None; BOARD_SLOTS];
     game_state.player_health = 30;
     game_state.opponent_health = 30;
-    game_state.available_mana = 10;
+    game_state.available_mana = 0;
+    game_state.max_mana = 0;
 }
 
 #[derive(ReactResource, Copy, Clone, Eq, PartialEq, Debug)]
@@ -35,7 +47,9 @@ impl ConnectionStatus {
 #[derive(ReactResource, Default)]
 pub struct TurnPlayer {
     pub server_determined_player_id: Option<EntityID>,
-    pub predicted_player_id: Option<EntityID>
+    pub predicted_player_id: Option<EntityID>,
+    // Remaining total time of the turn player, only sent for rooms with a time bank
+    pub time_bank: Option<Duration>,
 }
 
 impl TurnPlayer {
@@ -65,6 +79,24 @@ impl EndTurn {
 
 impl Default for EndTurn { fn default() -> Self { Self(None) } }
 
+// A PlayCard applied ahead of the server, kept so it can be taken back if refused
+pub(crate) struct PredictedPlay {
+    pub(crate) signal: bevy_simplenet::RequestSignal,
+    pub(crate) card: CardData,
+    pub(crate) slot: Option<usize>,
+    pub(crate) cost: u32,
+    // Once the server reports our mana its figure either includes this play or never will, so there's nothing to refund
+    pub(crate) mana_settled: bool,
+}
+
+// Never sent until run; the creature is kept by card so the plan survives the board shifting around it
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum PlannedAction {
+    Play { card_id: EntityID, slot: usize },
+    // Target is an enemy slot, None for the opponent
+    Attack { attacker: EntityID, target: Option<usize> },
+}
+
 #[derive(Default, PartialEq, Clone)]
 pub(crate) enum Turn {
     #[default]
@@ -75,16 +107,195 @@ pub(crate) enum Turn {
 #[derive(Resource, Default)]
 pub(crate) struct SelectedCard {
     pub(crate) index: Option<usize>,
+    // Hand card under the pointer in the hand list, drawn at full detail
+    pub(crate) hovered: Option<usize>,
+}
+
+// The server's last word on the turn clock, counted down locally until the next one
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct TurnClock {
+    pub(crate) remaining: Duration,
+    pub(crate) total: Duration,
+    // Seconds since startup when it arrived
+    pub(crate) received_at: f32,
+}
+
+impl TurnClock {
+    pub(crate) fn left(&self, now: f32) -> Duration {
+        self.remaining.saturating_sub(Duration::from_secs_f32((now - self.received_at).max(0.0)))
+    }
 }
 
 #[derive(Resource, Default)]
 pub(crate) struct GameState {
     pub(crate) player_hand: Vec<CardData>,
-    pub(crate) play_field: Vec<CardData>,
+    // Card ids as the player arranged them; player_hand keeps the server's draw order for checksums
+    pub(crate) hand_order: Vec<EntityID>,
+    // One entry per board slot, mirrored from CardPlayed
+    pub(crate) play_field: Vec<Option<CardData>>,
+    // Own creatures that can't attack again this turn, as far as this client has seen
+    pub(crate) exhausted: HashSet<EntityID>,
+    // Moves queued during the opponent's turn, sent in order when we choose to run them
+    pub(crate) plan: Vec<PlannedAction>,
+    // A plan that's being run, sent one move at a time so the server never sees a burst faster than a person
+    pub(crate) running_plan: VecDeque<PlannedAction>,
+    // Seconds since startup when the next running move goes out
+    pub(crate) next_plan_step: f32,
+    // Plays already shown on the table while the server decides, oldest first
+    pub(crate) predicted_plays: Vec<PredictedPlay>,
+    pub(crate) opponent_field: Vec<Option<CardData>>,
     pub(crate) player_health: u32,
     pub(crate) opponent_health: u32,
     pub(crate) current_turn: Turn,
     pub(crate) available_mana: u32,
+    pub(crate) max_mana: u32,
+    pub(crate) deck_size: u32,
+    // From snapshots and OpponentHandSize
+    pub(crate) opponent_hand_count: u32,
+    pub(crate) opponent_deck_size: u32,
+    pub(crate) graveyard_size: u32,
+    pub(crate) opponent_graveyard_size: u32,
+    // Cards each side discarded this match, oldest first; creatures that died are only counted in the sizes
+    pub(crate) discarded: Vec<CardData>,
+    pub(crate) opponent_discarded: Vec<CardData>,
+    // Owner and contents of the graveyard opened from the board, until closed
+    pub(crate) graveyard_view: Option<(EntityID, Vec<CardData>)>,
+    // Owner and slot of the creature under the pointer, and its history once the server answers
+    pub(crate) hovered_creature: Option<(EntityID, usize)>,
+    pub(crate) creature_history: Option<(EntityID, usize, CreatureHistory)>,
+    // Glossary from the server, looked up by the ids on each card
+    pub(crate) keywords: Vec<Keyword>,
+    // Decks saved on the server for this account
+    pub(crate) saved_decks: Vec<DeckSummary>,
+    pub(crate) default_deck: Option<String>,
+    // Deck being built in the collection tab, as card ids
+    pub(crate) deck_draft: Vec<String>,
+    pub(crate) deck_draft_name: String,
+    // Search box state; results replace the full card list once the server answers
+    pub(crate) card_filter: CardFilter,
+    pub(crate) card_search_results: Option<Vec<(String, CardDefinition)>>,
+    // Onboarding messages still to show, front first
+    pub(crate) welcome_steps: VecDeque<String>,
+    pub(crate) profile: Option<PlayerProfile>,
+    // Both players' profiles for the current match
+    pub(crate) match_profiles: Vec<PlayerProfile>,
+    // Reference for the current or last match, to quote when reporting it
+    pub(crate) match_id: Option<String>,
+    // Time left in the current turn, while a match is in progress
+    pub(crate) turn_clock: Option<TurnClock>,
+    // Latest emote from each player at the table, with when it arrived in seconds since startup
+    pub(crate) emotes: HashMap<EntityID, (Emote, f32)>,
+    // Set by MatchFound until we answer with Ready, or the match is called off
+    pub(crate) ready_prompt: bool,
+    // Asking whether to really give up the game in progress
+    pub(crate) confirm_concede: bool,
+    // Shown on the game-over screen until dismissed
+    pub(crate) match_summary: Option<MatchSummary>,
+    // Who asked to play the finished match again, until the rematch starts
+    pub(crate) rematch_from: Option<EntityID>,
+    // Only sent by rooms that reveal leftover cards
+    pub(crate) final_reveal: Vec<RevealedCards>,
+    // Last answer to ListMatches
+    pub(crate) live_matches: Vec<MatchListing>,
+    // Last answer to RequestStats, asked for after logging in and after each match
+    pub(crate) stats: Option<PlayerStats>,
+    // Code of the private lobby we opened, shown until a friend joins
+    pub(crate) lobby_code: Option<String>,
+    pub(crate) lobby_code_input: String,
+    // Rules for the next lobby we open
+    pub(crate) lobby_rules: LobbyRules,
+    // Why the server refused our last action or request, cleared when the turn changes
+    pub(crate) last_rejection: Option<GameError>,
+}
+
+// Deck picked in the deck builder, sent with JoinGame whenever we connect
+#[derive(Resource, Default)]
+pub(crate) struct DeckResource {
+    pub(crate) name: Option<String>,
+    pub(crate) cards: Vec<String>,
+}
+
+// Overrides the system locale for card and server text, e.g. "fr"
+const LANGUAGE_ENV: &str = "GAME_LANGUAGE";
+
+// The player's language, picked once at startup from the environment. Falls back to English
+#[derive(Resource, Clone, Copy)]
+pub(crate) struct Language(pub(crate) &'static str);
+
+impl Language {
+    pub(crate) fn from_env() -> Self {
+        let locale = [LANGUAGE_ENV, "LC_ALL", "LANG"].iter()
+            .find_map(|var| std::env::var(var).ok().filter(|value| !value.is_empty()))
+            .unwrap_or_default();
+        Self(card_language(&locale))
+    }
+
+    pub(crate) fn error(&self, error: &GameError) -> String {
+        game_error_text(error, self.0)
+    }
+
+    pub(crate) fn message(&self, message: &MessageCode) -> String {
+        message_text(message, self.0)
+    }
+}
+
+// Card definitions compiled into the client, used by the deck editor and to word cards the server sends
+#[derive(Resource)]
+pub(crate) struct CardLibrary(pub(crate) CardConfig);
+
+impl CardLibrary {
+    // Names and text follow the player's language where a translation exists
+    pub(crate) fn for_language(language: Language) -> Self {
+        Self(load_cards_for(language.0).expect("Failed to load card definitions"))
+    }
+
+    // The server words cards in English; anything we know by key gets our own name and text instead
+    pub(crate) fn localize(&self, card: &mut CardData) {
+        if let Some(definition) = self.0.cards.get(&card.card_key) {
+            card.card_name.clone_from(&definition.name);
+            card.card_text.clone_from(&definition.text);
+        }
+    }
+
+    pub(crate) fn localized(&self, mut message: GameMessage) -> GameMessage {
+        let localize_all = |cards: &mut Vec<CardData>| cards.iter_mut().for_each(|card| self.localize(card));
+        let localize_board = |board: &mut Vec<Option<CardData>>| board.iter_mut().flatten().for_each(|card| self.localize(card));
+        match &mut message {
+            GameMessage::CardsDrawn(cards) | GameMessage::Graveyard { cards, .. } => localize_all(cards),
+            GameMessage::CardPlayed(_, card, _) | GameMessage::CardDiscarded(_, card) | GameMessage::CardTransformed { card, .. } => self.localize(card),
+            GameMessage::BoardUpdate(_, board) => localize_board(board),
+            GameMessage::GameStateSnapshot(snapshot) => {
+                localize_all(&mut snapshot.hand);
+                snapshot.players.iter_mut().for_each(|player| localize_board(&mut player.board));
+            }
+            GameMessage::FinalReveal(reveals) => reveals.iter_mut().for_each(|reveal| {
+                localize_all(&mut reveal.hand);
+                localize_all(&mut reveal.deck);
+            }),
+            GameMessage::PuzzleList(puzzles) => puzzles.iter_mut().for_each(|puzzle| {
+                localize_all(&mut puzzle.hand);
+                localize_board(&mut puzzle.board);
+                localize_board(&mut puzzle.opponent_board);
+            }),
+            GameMessage::CardSearchResults(results) => {
+                for (key, definition) in results.iter_mut() {
+                    if let Some(local) = self.0.cards.get(key) {
+                        definition.name.clone_from(&local.name);
+                        definition.text.clone_from(&local.text);
+                    }
+                }
+            }
+            _ => {}
+        }
+        message
+    }
+}
+
+// Everything shown in the player's language, for systems already at bevy's parameter limit
+#[derive(SystemParam)]
+pub(crate) struct Locale<'w> {
+    pub(crate) language: Res<'w, Language>,
+    pub(crate) cards: Res<'w, CardLibrary>,
 }
 
 #[derive(Resource)]
@@ -99,6 +310,8 @@ pub (crate) struct UiState {
 pub(crate) enum GameSelection {
     CardInHand(usize),
     CardInPlay(usize),
+    // Index into discarded, or opponent_discarded when not ours
+    Discarded { own: bool, index: usize },
     CardDetail(TypeId, String),
     InventoryItem(TypeId, String, UntypedAssetId),
 }
@@ -110,6 +323,101 @@ pub(crate) enum GameWindow {
     CardCollection, // Card collection/deck building
     Inventory,      // Player inventory
     CardDetail,     // Card details/inspector
+    LiveMatches,    // Running matches to spectate
+    Accessibility,  // Accessible mode settings and the table as text
+    Graveyard,      // Discarded cards on both sides
+    Chat,           // Room chat and whispers
+    Puzzles,        // Solve-in-one-turn challenges
 }
 
-impl ReactResource for GameState {}
\ No newline at end of file
+impl ReactResource for GameState {}
+
+impl GameState {
+    // The hand as shown: arranged cards in the player's order, then fresh draws in draw order
+    pub(crate) fn arranged_hand(&self) -> Vec<CardData> {
+        let mut hand = self.player_hand.clone();
+        hand.sort_by_key(|card| self.hand_order.iter().position(|&id| id == card.card_id).unwrap_or(usize::MAX));
+        hand
+    }
+
+    // Moves the card shown at `from` to `to`, pinning the order of everything currently shown
+    pub(crate) fn move_hand_card(&mut self, from: usize, to: usize) {
+        let mut order: Vec<EntityID> = self.arranged_hand().iter().map(|card| card.card_id).collect();
+        if from >= order.len() || to >= order.len() {
+            return;
+        }
+        let card_id = order.remove(from);
+        order.insert(to, card_id);
+        self.hand_order = order;
+    }
+
+    // Shows the play as made: out of the hand, onto the board and paid for. The card keeps its place in
+    // hand_order so a rollback puts it back where it was
+    pub(crate) fn predict_play(&mut self, signal: bevy_simplenet::RequestSignal, card: CardData, slot: Option<usize>, cost: u32) {
+        self.player_hand.retain(|held| held.card_id != card.card_id);
+        if let Some(entry) = slot.and_then(|slot| self.play_field.get_mut(slot)) {
+            *entry = Some(card.clone());
+        }
+        self.available_mana = self.available_mana.saturating_sub(cost);
+        self.predicted_plays.push(PredictedPlay { signal, card, slot, cost, mana_settled: false });
+    }
+
+    // The server played the card, so its prediction is settled. False when the play wasn't predicted here
+    pub(crate) fn confirm_play(&mut self, card_id: EntityID) -> bool {
+        let before = self.predicted_plays.len();
+        self.predicted_plays.retain(|play| play.card.card_id != card_id);
+        self.predicted_plays.len() != before
+    }
+
+    pub(crate) fn is_play_predicted(&self, request_id: u64) -> bool {
+        self.predicted_plays.iter().any(|play| play.signal.id() == request_id)
+    }
+
+    pub(crate) fn roll_back_play(&mut self, request_id: u64) {
+        if let Some(index) = self.predicted_plays.iter().position(|play| play.signal.id() == request_id) {
+            let play = self.predicted_plays.remove(index);
+            self.undo_play(play);
+        }
+    }
+
+    // Takes back every play still waiting. Refusals after the ack don't say which request they answer, and
+    // a play undone by mistake comes straight back with its CardPlayed
+    pub(crate) fn roll_back_plays(&mut self) {
+        while let Some(play) = self.predicted_plays.pop() {
+            self.undo_play(play);
+        }
+    }
+
+    // Mana reported by the server replaces the predicted figure
+    pub(crate) fn settle_mana(&mut self) {
+        for play in &mut self.predicted_plays {
+            play.mana_settled = true;
+        }
+    }
+
+    fn undo_play(&mut self, play: PredictedPlay) {
+        let card_id = play.card.card_id;
+        if let Some(entry) = play.slot.and_then(|slot| self.play_field.get_mut(slot)) {
+            if entry.as_ref().is_some_and(|card| card.card_id == card_id) {
+                *entry = None;
+            }
+        }
+        if !play.mana_settled {
+            self.available_mana += play.cost;
+        }
+        if !self.player_hand.iter().any(|held| held.card_id == card_id) {
+            self.player_hand.push(play.card);
+        }
+    }
+
+    // Seated at a table whose game hasn't ended yet
+    pub(crate) fn in_match(&self) -> bool {
+        !self.match_profiles.is_empty() && self.match_summary.is_none()
+    }
+
+    // A card is only planned once; planning it again moves it
+    pub(crate) fn plan_play(&mut self, card_id: EntityID, slot: usize) {
+        self.plan.retain(|action| !matches!(action, PlannedAction::Play { card_id: id, .. } if *id == card_id));
+        self.plan.push(PlannedAction::Play { card_id, slot });
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+use std::collections::HashMap;
 use bevy::prelude::*;
 use bevy::asset::Assets;
 use bevy::color::Color;
@@ -7,14 +9,111 @@ use bevy::render::camera::Viewport;
 use bevy_inspector_egui::bevy_egui::{EguiContext, EguiContextSettings};
 use bevy_inspector_egui::bevy_inspector::hierarchy::SelectedEntities;
 use bevy_inspector_egui::egui;
-use crate::state::{UiState, GameState, GameWindow, GameSelection, Turn, SelectedCard};
+use crate::accessibility::{Accessibility, TEXT_SCALE_RANGE};
+use crate::board::PLAY_FIELD_SIZE;
+use crate::chat::{self, ChatLog};
+use crate::puzzles;
+use crate::lethal::{self, LethalHint};
+use crate::camera::CameraRig;
+use crate::latency::LatencyStats;
+use crate::message_log;
+use crate::crash_report;
+use crate::login;
+use crate::perf_hud;
+use crate::client::{play_card, Client};
+use crate::state::{UiState, CardLibrary, DeckResource, GameState, GameWindow, GameSelection, Language, PlannedAction, Turn, TurnClock, SelectedCard};
 use bevy_window::{PrimaryWindow, Window};
 use egui_dock::{DockArea, DockState, NodeIndex, Style};
-use shared::channel::{CardData, CardType};
+use shared::channel::{BotDifficulty, CardData, CardType, CreatureChange, Emote, GameMessage, MatchOutcome, MatchSummary, PlayerProfile, TurnSummary, AVATARS, TITLES};
+use shared::card_details::{CardDefinition, CARD_TYPES};
+use shared::card_filter::CardFilter;
+use shared::errors::{ErrorCode, GameError};
+use shared::rules::{check_attack, check_play, check_turn};
+use shared::deck_rules::{card_counts, validate_deck, MAX_COPIES_PER_CARD, MAX_DECK_SIZE};
+use shared::deck_stats::{DeckStats, CURVE_MAX_COST};
+use shared::EntityID;
 
 #[derive(Component)]
 pub(crate) struct PlayerHandArea;
 
+// The client only knows whose turn it is relative to itself, which is all the shared turn check needs
+fn own_turn(world: &World) -> Result<(), GameError> {
+    let own_id = world.resource::<Client>().id();
+    let game_state = world.resource::<GameState>();
+    check_turn((game_state.current_turn == Turn::Player).then_some(own_id), own_id)
+}
+
+// Gap between the moves of a running plan. The server flags replies faster than a person could react and counts
+// every refusal against the player, so a plan goes out at a human pace
+const PLAN_STEP_SECS: f32 = 0.4;
+
+// Sends the next move of a running plan once it's due. Each move is checked against the table as it stands after
+// the ones before it, and the first that no longer holds stops the rest, the same as a refusal from the server
+pub(crate) fn step_plan(world: &mut World) {
+    let now = world.resource::<Time>().elapsed_secs();
+    let due = {
+        let game_state = world.resource::<GameState>();
+        !game_state.running_plan.is_empty() && now >= game_state.next_plan_step
+    };
+    if !due {
+        return;
+    }
+    let action = world.resource_mut::<GameState>().running_plan.pop_front().expect("checked above");
+    let sent = match action {
+        PlannedAction::Play { card_id, slot } => {
+            let card = world.resource::<GameState>().player_hand.iter().find(|card| card.card_id == card_id).cloned();
+            match card {
+                Some(card) => play_from_hand(world, card, slot),
+                None => Err(ErrorCode::CardNotInHand.into()),
+            }
+        }
+        PlannedAction::Attack { attacker, target } => plan_attack(world, attacker, target),
+    };
+    let mut game_state = world.resource_mut::<GameState>();
+    match sent {
+        Ok(()) => game_state.next_plan_step = now + PLAN_STEP_SECS,
+        Err(error) => {
+            game_state.running_plan.clear();
+            game_state.last_rejection = Some(error);
+        }
+    }
+}
+
+fn plan_attack(world: &mut World, attacker: EntityID, target: Option<usize>) -> Result<(), GameError> {
+    own_turn(world)?;
+    let (slot, card, ready, defender) = {
+        let game_state = world.resource::<GameState>();
+        let slot = game_state.play_field.iter().position(|card| card.as_ref().is_some_and(|card| card.card_id == attacker));
+        let card = slot.and_then(|slot| game_state.play_field[slot].clone());
+        let defender = target.map(|target| game_state.opponent_field.get(target).cloned().flatten());
+        (slot, card, !game_state.exhausted.contains(&attacker), defender)
+    };
+    check_attack(card.as_ref(), ready, defender.as_ref().map(Option::as_ref))?;
+    let slot = slot.expect("check_attack needs an attacker");
+    if world.resource::<Client>().request(GameMessage::Attack { attacker: slot, target }).is_ok() {
+        world.resource_mut::<GameState>().exhausted.insert(attacker);
+    }
+    Ok(())
+}
+
+// Same predicted play as dragging onto the 3D board
+fn play_from_hand(world: &mut World, card: CardData, slot: usize) -> Result<(), GameError> {
+    world.resource_scope::<Client, _>(|world, client| {
+        let own_id = client.id();
+        let mut state = world.resource_mut::<GameState>();
+        let turn_player = (state.current_turn == Turn::Player).then_some(own_id);
+        play_card(&client, &mut state, turn_player, card, Some(slot))
+    })
+}
+
+// Why a hand card can't be played right now, if it can't. Creatures only need some free slot here, the drop picks it
+fn play_blocked(world: &World, card: &CardData) -> Result<(), GameError> {
+    own_turn(world)?;
+    let game_state = world.resource::<GameState>();
+    let free = game_state.play_field.iter().position(Option::is_none);
+    check_play(card, card.play_cost(), game_state.available_mana, free, |_| true)
+}
+
 #[derive(Component)]
 pub(crate) struct PlayFieldArea;
 
@@ -22,9 +121,11 @@ pub(crate) struct PlayFieldArea;
 pub(crate) struct MainCamera;
 
 pub(crate) fn setup_camera(commands: &mut Commands) {
+    let rig = CameraRig::default();
     commands.spawn((
         Camera3d::default(),
-        Transform::from_xyz(0.0, 3.0, 20.0).looking_at(Vec3::new(0., 0., 10.), Vec3::Y),
+        rig.goal(),
+        rig,
         MainCamera,
     ));
 }
@@ -48,7 +149,7 @@ pub(crate) fn setup_play_field(
 ) {
     // Add a simple playing field in the center
     commands.spawn((
-        Mesh3d(meshes.add(Plane3d::default().mesh().size(15.0, 15.0))),
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(PLAY_FIELD_SIZE, PLAY_FIELD_SIZE))),
         MeshMaterial3d(materials.add(StandardMaterial {
             base_color: Color::rgb(0.1, 0.5, 0.1),
             ..default()
@@ -104,6 +205,112 @@ fn handle_play_card(
 //     }
 // }
 
+fn profile_label(profile: &PlayerProfile) -> String {
+    format!("[{}] {}", profile.avatar, profile.title)
+}
+
+// Under this much time left the turn clock turns red
+const TURN_CLOCK_WARNING: Duration = Duration::from_secs(5);
+
+fn render_turn_clock(ui: &mut egui::Ui, clock: &TurnClock, now: f32) {
+    let left = clock.left(now);
+    let fraction = left.as_secs_f32() / clock.total.as_secs_f32().max(f32::EPSILON);
+    let text = format!("{}s", left.as_secs_f32().ceil());
+    let mut bar = egui::ProgressBar::new(fraction.clamp(0.0, 1.0)).text(text);
+    if left < TURN_CLOCK_WARNING {
+        bar = bar.fill(egui::Color32::RED);
+    }
+    ui.add(bar);
+}
+
+// How long an emote stays over the sender's portrait
+const EMOTE_SECS: f32 = 3.0;
+// Muted green for attacks and cards that make up a lethal turn
+const LETHAL_COLOR: egui::Color32 = egui::Color32::from_rgb(150, 200, 140);
+
+// Drifts up from the portrait and fades out as it ages
+fn show_emote_bubble(ctx: &egui::Context, portrait: egui::Rect, player_id: EntityID, emote: Emote, age: f32) {
+    let progress = (age / EMOTE_SECS).clamp(0.0, 1.0);
+    egui::Area::new(egui::Id::new(("emote", player_id)))
+        .order(egui::Order::Foreground)
+        .interactable(false)
+        .pivot(egui::Align2::CENTER_BOTTOM)
+        .fixed_pos(portrait.center_top() - egui::vec2(0.0, 4.0 + 20.0 * progress))
+        .show(ctx, |ui| {
+            ui.multiply_opacity(1.0 - progress);
+            egui::Frame::popup(ui.style())
+                .rounding(10.0)
+                .show(ui, |ui| ui.label(emote.text()));
+        });
+    ctx.request_repaint();
+}
+
+// Empty fields mean "any"
+fn render_card_filter(ui: &mut egui::Ui, filter: &mut CardFilter) {
+    fn optional_text(ui: &mut egui::Ui, label: &str, value: &mut Option<String>) {
+        let mut text = value.clone().unwrap_or_default();
+        ui.label(label);
+        if ui.add(egui::TextEdit::singleline(&mut text).desired_width(80.0)).changed() {
+            *value = (!text.trim().is_empty()).then_some(text);
+        }
+    }
+
+    fn optional_cost(ui: &mut egui::Ui, label: &str, value: &mut Option<u32>) {
+        let mut enabled = value.is_some();
+        let mut cost = value.unwrap_or(0);
+        ui.checkbox(&mut enabled, label);
+        ui.add_enabled(enabled, egui::DragValue::new(&mut cost).range(0..=20));
+        *value = enabled.then_some(cost);
+    }
+
+    ui.horizontal_wrapped(|ui| {
+        optional_text(ui, "Search", &mut filter.name);
+        optional_cost(ui, "Min", &mut filter.min_cost);
+        optional_cost(ui, "Max", &mut filter.max_cost);
+
+        egui::ComboBox::from_id_salt("card_filter_type")
+            .selected_text(filter.c_type.as_deref().unwrap_or("Any type"))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut filter.c_type, None, "Any type");
+                for c_type in CARD_TYPES {
+                    ui.selectable_value(&mut filter.c_type, Some(c_type.to_string()), *c_type);
+                }
+            });
+
+        optional_text(ui, "Keyword", &mut filter.keyword);
+        optional_text(ui, "Set", &mut filter.set);
+    });
+}
+
+// One bar per cost bucket, scaled to the tallest
+fn render_mana_curve(ui: &mut egui::Ui, stats: &DeckStats) {
+    let bar_width = 24.0;
+    let height = 80.0;
+    let size = egui::vec2(bar_width * stats.mana_curve.len() as f32, height + 16.0);
+    let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let peak = stats.curve_peak().max(1) as f32;
+
+    for (cost, &count) in stats.mana_curve.iter().enumerate() {
+        let left = rect.left() + cost as f32 * bar_width;
+        let bar_height = height * count as f32 / peak;
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(left + 2.0, rect.top() + height - bar_height),
+            egui::pos2(left + bar_width - 2.0, rect.top() + height),
+        );
+        painter.rect_filled(bar, 2.0, egui::Color32::from_rgb(70, 130, 200));
+
+        let label = if cost == CURVE_MAX_COST as usize { format!("{cost}+") } else { cost.to_string() };
+        painter.text(
+            egui::pos2(left + bar_width / 2.0, rect.top() + height + 2.0),
+            egui::Align2::CENTER_TOP,
+            label,
+            egui::FontId::proportional(10.0),
+            ui.visuals().text_color(),
+        );
+    }
+}
+
 // UI Systems and Functions
 pub(crate) fn show_ui_system(world: &mut World) {
     let Ok(egui_context) = world
@@ -164,7 +371,7 @@ impl UiState {
             tree.split_right(NodeIndex::root(), 0.75, vec![GameWindow::CardDetail]);
         let [game, _player_hand] = tree.split_left(game, 0.2, vec![GameWindow::PlayerHand]);
         let [_game, _bottom] =
-            tree.split_below(game, 0.8, vec![GameWindow::CardCollection, GameWindow::Inventory]);
+            tree.split_below(game, 0.8, vec![GameWindow::CardCollection, GameWindow::Inventory, GameWindow::LiveMatches, GameWindow::Graveyard, GameWindow::Chat, GameWindow::Puzzles, GameWindow::Accessibility]);
 
         Self {
             state,
@@ -175,6 +382,15 @@ impl UiState {
     }
 
     fn ui(&mut self, world: &mut World, ctx: &mut egui::Context) {
+        world.resource_mut::<Accessibility>().apply(ctx);
+        show_status_bar(world, ctx);
+        if !world.resource::<Client>().is_logged_in() {
+            login::show_login(world, ctx);
+            message_log::show_message_log(world, ctx);
+            crash_report::show_crash_report_prompt(world, ctx);
+            return;
+        }
+
         let mut tab_viewer = GameTabViewer {
             world,
             viewport_rect: &mut self.viewport_rect,
@@ -184,9 +400,259 @@ impl UiState {
         DockArea::new(&mut self.state)
             .style(Style::from_egui(ctx.style().as_ref()))
             .show(ctx, &mut tab_viewer);
+
+        show_welcome(world, ctx);
+        show_ready_prompt(world, ctx);
+        show_concede_prompt(world, ctx);
+        show_match_summary(world, ctx);
+        show_graveyard(world, ctx);
+        show_creature_history(world, ctx);
+        message_log::show_message_log(world, ctx);
+        perf_hud::show_perf_hud(world, ctx);
+        crash_report::show_crash_report_prompt(world, ctx);
     }
 }
 
+// Next to the pointer while it rests on a creature whose history has arrived
+fn show_creature_history(world: &mut World, ctx: &egui::Context) {
+    let game_state = world.resource::<GameState>();
+    let Some((_, _, history)) = game_state.creature_history.as_ref() else { return };
+    let Some(pointer) = ctx.pointer_hover_pos() else { return };
+
+    egui::Area::new(egui::Id::new("creature_history"))
+        .order(egui::Order::Tooltip)
+        .fixed_pos(pointer + egui::vec2(16.0, 16.0))
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.strong(&history.card_name);
+                ui.label(format!("Printed {}/{}", history.printed_power, history.printed_health));
+                if history.changes.is_empty() {
+                    ui.weak("Unchanged since it was played");
+                }
+                for change in &history.changes {
+                    ui.label(match change {
+                        CreatureChange::Buffed { power, health, source } => format!("+{power}/+{health} from {source}"),
+                        CreatureChange::Damaged { amount, source } => format!("{amount} damage from {source}"),
+                        CreatureChange::Transformed { from, source } => format!("Was {from}, transformed by {source}"),
+                    });
+                }
+            });
+        });
+}
+
+fn show_graveyard(world: &mut World, ctx: &egui::Context) {
+    let own_id = world.resource::<Client>().id();
+    let mut game_state = world.resource_mut::<GameState>();
+    let Some((owner, cards)) = game_state.graveyard_view.clone() else { return };
+
+    let title = if owner == own_id { "Your graveyard" } else { "Opponent's graveyard" };
+    let mut open = true;
+    egui::Window::new(title)
+        .collapsible(false)
+        .open(&mut open)
+        .show(ctx, |ui| {
+            if cards.is_empty() {
+                ui.label("No cards yet");
+            }
+            egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                // Most recent first, the way the pile is stacked
+                for card in cards.iter().rev() {
+                    ui.label(format!("{} ({})", card.card_name, card.cost));
+                }
+            });
+        });
+
+    if !open {
+        game_state.graveyard_view = None;
+    }
+}
+
+fn show_status_bar(world: &mut World, ctx: &egui::Context) {
+    let latency = world.resource::<LatencyStats>();
+    egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            match (latency.last, latency.average) {
+                (Some(last), Some(average)) => {
+                    ui.label(format!("Latency: {} ms (average {} ms)", last.as_millis(), average.as_millis()));
+                }
+                _ => {
+                    ui.label("Latency: measuring...");
+                }
+            }
+            if latency.outstanding > 1 {
+                ui.colored_label(egui::Color32::YELLOW, format!("{} heartbeats unanswered", latency.outstanding));
+            }
+        });
+    });
+}
+
+// Giving up can't be taken back, so the button in the playing field only opens this
+fn show_concede_prompt(world: &mut World, ctx: &egui::Context) {
+    if !world.resource::<GameState>().confirm_concede {
+        return;
+    }
+    let (mut concede, mut keep_playing) = (false, false);
+    egui::Window::new("Concede")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            ui.label("Give up this game? Your opponent will be awarded the win");
+            ui.horizontal(|ui| {
+                concede = ui.button("Concede").clicked();
+                keep_playing = ui.button("Keep playing").clicked();
+            });
+        });
+    if concede {
+        let _ = world.resource::<Client>().request(GameMessage::Concede);
+    }
+    if concede || keep_playing {
+        world.resource_mut::<GameState>().confirm_concede = false;
+    }
+}
+
+// Matchmade games are called off if a player doesn't answer in time
+fn show_ready_prompt(world: &mut World, ctx: &egui::Context) {
+    if !world.resource::<GameState>().ready_prompt {
+        return;
+    }
+    let mut ready = false;
+    egui::Window::new("Match found")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_TOP, egui::Vec2::new(0.0, 40.0))
+        .show(ctx, |ui| {
+            ui.label("An opponent is waiting for you");
+            ready = ui.button("Ready").clicked();
+        });
+    if ready {
+        let _ = world.resource::<Client>().request(GameMessage::Ready);
+        world.resource_mut::<GameState>().ready_prompt = false;
+    }
+}
+
+fn show_match_summary(world: &mut World, ctx: &egui::Context) {
+    let own_id = world.resource::<Client>().id();
+    let mut game_state = world.resource_mut::<GameState>();
+    let Some(summary) = game_state.match_summary.clone() else { return };
+    let reveal = game_state.final_reveal.clone();
+    let match_id = game_state.match_id.clone();
+    let rematch_from = game_state.rematch_from;
+
+    let mut open = true;
+    let mut rematch = None;
+    egui::Window::new("Game Over")
+        .collapsible(false)
+        .open(&mut open)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            ui.heading(match summary.winner {
+                Some(winner) if winner == own_id => "Victory",
+                Some(_) => "Defeat",
+                None => "Draw",
+            });
+            match summary.conceded_by {
+                Some(player_id) if player_id == own_id => {
+                    ui.label("You conceded");
+                }
+                Some(_) => {
+                    ui.label("Your opponent conceded");
+                }
+                None => {}
+            }
+            if let Some(match_id) = &match_id {
+                ui.weak(format!("Match id: {match_id}")).on_hover_text("Quote this when reporting the match");
+            }
+            ui.horizontal(|ui| match rematch_from {
+                Some(from) if from == own_id => {
+                    ui.label("Waiting for your opponent to accept the rematch");
+                }
+                Some(_) => {
+                    ui.label("Your opponent wants a rematch");
+                    if ui.button("Accept").clicked() {
+                        rematch = Some(GameMessage::AcceptRematch);
+                    }
+                }
+                None => {
+                    if ui.button("Rematch").clicked() {
+                        rematch = Some(GameMessage::RequestRematch);
+                    }
+                }
+            });
+
+            ui.label("Cards played per turn");
+            render_turn_chart(ui, &summary, own_id, |turn| turn.cards_played.len() as i32);
+            ui.label("Damage per turn");
+            render_turn_chart(ui, &summary, own_id, |turn| turn.damage as i32);
+            ui.label("Board swing per turn");
+            render_turn_chart(ui, &summary, own_id, |turn| turn.swing);
+
+            if let Some(turn) = summary.biggest_swing.and_then(|index| summary.turns.get(index)) {
+                let who = if turn.player_id == own_id { "your" } else { "your opponent's" };
+                ui.label(format!("Biggest swing: {:+} on {} turn {}", turn.swing, who, turn.turn));
+            }
+
+            for revealed in &reveal {
+                let whose = if revealed.player_id == own_id { "Your" } else { "Opponent's" };
+                let names = |cards: &[CardData]| cards.iter().map(|card| card.card_name.as_str()).collect::<Vec<_>>().join(", ");
+                ui.collapsing(format!("{whose} leftover cards"), |ui| {
+                    ui.label(format!("Hand ({}): {}", revealed.hand.len(), names(&revealed.hand)));
+                    ui.label(format!("Deck ({}): {}", revealed.deck.len(), names(&revealed.deck)));
+                });
+            }
+        });
+
+    if !open {
+        game_state.match_summary = None;
+        game_state.final_reveal.clear();
+    }
+    if let Some(request) = rematch {
+        let _ = world.resource::<Client>().request(request);
+    }
+}
+
+// Bars above or below a baseline, coloured by whose turn it was
+fn render_turn_chart(ui: &mut egui::Ui, summary: &MatchSummary, own_id: u128, value: impl Fn(&TurnSummary) -> i32) {
+    let bar_width = 14.0;
+    let half_height = 30.0;
+    let size = egui::vec2((bar_width * summary.turns.len() as f32).max(bar_width), half_height * 2.0);
+    let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let peak = summary.turns.iter().map(|turn| value(turn).abs()).max().unwrap_or(0).max(1) as f32;
+    let baseline = rect.center().y;
+
+    painter.hline(rect.x_range(), baseline, ui.visuals().widgets.noninteractive.bg_stroke);
+    for (i, turn) in summary.turns.iter().enumerate() {
+        let left = rect.left() + i as f32 * bar_width;
+        let top = baseline - half_height * value(turn) as f32 / peak;
+        let bar = egui::Rect::from_x_y_ranges(left + 1.0..=left + bar_width - 1.0, top.min(baseline)..=top.max(baseline));
+        let color = if turn.player_id == own_id {
+            egui::Color32::from_rgb(70, 130, 200)
+        } else {
+            egui::Color32::from_rgb(200, 90, 70)
+        };
+        painter.rect_filled(bar, 1.0, color);
+    }
+}
+
+// Steps through the onboarding messages one at a time on top of the dock
+fn show_welcome(world: &mut World, ctx: &egui::Context) {
+    let mut game_state = world.resource_mut::<GameState>();
+    let Some(step) = game_state.welcome_steps.front().cloned() else { return };
+    let last = game_state.welcome_steps.len() == 1;
+
+    egui::Window::new("Welcome")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            ui.label(step);
+            if ui.button(if last { "Let's go" } else { "Next" }).clicked() {
+                game_state.welcome_steps.pop_front();
+            }
+        });
+}
+
 // Tab viewer for the UI
 struct GameTabViewer<'a> {
     world: &'a mut World,
@@ -205,6 +671,11 @@ impl egui_dock::TabViewer for GameTabViewer<'_> {
             GameWindow::CardCollection => self.render_card_collection(ui),
             GameWindow::Inventory => self.render_inventory(ui),
             GameWindow::CardDetail => self.render_card_detail(ui),
+            GameWindow::LiveMatches => self.render_live_matches(ui),
+            GameWindow::Accessibility => self.render_accessibility(ui),
+            GameWindow::Graveyard => self.render_graveyard(ui),
+            GameWindow::Chat => chat::render_chat(self.world, ui),
+            GameWindow::Puzzles => puzzles::render_puzzles(self.world, ui),
         }
     }
 
@@ -215,6 +686,14 @@ impl egui_dock::TabViewer for GameTabViewer<'_> {
             GameWindow::CardCollection => "Card Collection".into(),
             GameWindow::Inventory => "Inventory".into(),
             GameWindow::CardDetail => "Card Details".into(),
+            GameWindow::LiveMatches => "Live Matches".into(),
+            GameWindow::Accessibility => "Accessibility".into(),
+            GameWindow::Graveyard => "Graveyard".into(),
+            GameWindow::Chat => match self.world.resource::<ChatLog>().unread() {
+                0 => "Chat".into(),
+                unread => format!("Chat ({unread})").into(),
+            },
+            GameWindow::Puzzles => "Puzzles".into(),
         }
     }
 
@@ -230,21 +709,64 @@ impl GameTabViewer<'_> {
         *self.viewport_rect = ui.clip_rect();
 
         // Get game state data for this panel
-        let (player_health, opponent_health, available_mana, current_turn) = {
+        let own_id = self.world.resource::<Client>().id();
+        let now = self.world.resource::<Time>().elapsed_secs();
+        let language = *self.world.resource::<Language>();
+        let (player_health, opponent_health, available_mana, max_mana, deck_size, opponent_cards, current_turn, own_profile, opponent_profile, rejection, emotes, turn_clock) = {
             let game_state = self.world.resource::<GameState>();
+            let (own, opponent): (Vec<_>, Vec<_>) = game_state.match_profiles.iter()
+                .cloned()
+                .partition(|profile| profile.player_id == own_id);
             (
                 game_state.player_health,
                 game_state.opponent_health,
                 game_state.available_mana,
+                game_state.max_mana,
+                game_state.deck_size,
+                (game_state.opponent_hand_count, game_state.opponent_deck_size),
                 game_state.current_turn.clone(),
+                own.into_iter().next().or_else(|| game_state.profile.clone()),
+                opponent.into_iter().next(),
+                game_state.last_rejection.clone(),
+                game_state.emotes.clone(),
+                game_state.turn_clock,
             )
         };
+        let in_match = self.world.resource::<GameState>().in_match();
+        let mut sent_emote = None;
+        let mut concede = false;
 
         ui.vertical(|ui| {
             ui.horizontal(|ui| {
+                // Each portrait carries its player's latest emote until it fades
+                let portrait = |ui: &mut egui::Ui, profile: &PlayerProfile| {
+                    let rect = ui.label(profile_label(profile)).rect;
+                    if let Some(&(emote, at)) = emotes.get(&profile.player_id) {
+                        if now - at < EMOTE_SECS {
+                            show_emote_bubble(ui.ctx(), rect, profile.player_id, emote, now - at);
+                        }
+                    }
+                };
+                if let Some(profile) = &own_profile {
+                    portrait(ui, profile);
+                }
                 ui.label(format!("Player Health: {}", player_health));
-                ui.label(format!("Mana: {}/10", available_mana));
+                ui.label(format!("Mana: {}/{}", available_mana, max_mana));
+                ui.label(format!("Deck: {}", deck_size));
+                if let Some(profile) = &opponent_profile {
+                    portrait(ui, profile);
+                }
                 ui.label(format!("Opponent Health: {}", opponent_health));
+                ui.label(format!("Opponent Hand: {} / Deck: {}", opponent_cards.0, opponent_cards.1));
+                ui.menu_button("Emote", |ui| {
+                    for emote in Emote::ALL {
+                        if ui.button(emote.text()).clicked() {
+                            sent_emote = Some(emote);
+                            ui.close_menu();
+                        }
+                    }
+                });
+                concede = ui.add_enabled(in_match, egui::Button::new("Concede")).clicked();
             });
             ui.label(format!(
                 "Turn: {}",
@@ -254,27 +776,273 @@ impl GameTabViewer<'_> {
                     "Opponent Turn"
                 }
             ));
+            if let Some(clock) = turn_clock {
+                render_turn_clock(ui, &clock, now);
+            }
+            if let Some(error) = &rejection {
+                ui.colored_label(egui::Color32::LIGHT_RED, language.error(error));
+            }
+
+            ui.separator();
+            self.render_board(ui);
+        });
+        if let Some(emote) = sent_emote {
+            let _ = self.world.resource::<Client>().request(GameMessage::Emote { emote, sender: None });
+        }
+        if concede {
+            self.world.resource_mut::<GameState>().confirm_concede = true;
+        }
+    }
+
+    // Board slots double as drop zones for creatures dragged out of the hand
+    fn render_board(&mut self, ui: &mut egui_dock::egui::Ui) {
+        let (play_field, opponent_field, hand, exhausted, plan, in_match) = {
+            let game_state = self.world.resource::<GameState>();
+            (
+                game_state.play_field.clone(),
+                game_state.opponent_field.clone(),
+                game_state.arranged_hand(),
+                game_state.exhausted.clone(),
+                game_state.plan.clone(),
+                game_state.in_match(),
+            )
+        };
+        let turn = own_turn(self.world);
+        let language = *self.world.resource::<Language>();
+        // Out of turn the same gestures queue moves for later instead of being refused
+        let planning = turn.is_err() && in_match;
+        let planned_play = |slot: usize| plan.iter().find_map(|action| match action {
+            PlannedAction::Play { card_id, slot: planned } if *planned == slot => hand.iter().find(|card| card.card_id == *card_id),
+            _ => None,
+        });
+        let lethal = lethal::lethal_plan(self.world);
+        if let Some(plan) = &lethal {
+            ui.colored_label(LETHAL_COLOR, format!("Lethal: {} damage available", plan.damage));
+        }
+
+        // With one of our creatures selected, enemy creatures and the opponent become attack targets
+        let attacker = match &*self.selection {
+            GameSelection::CardInPlay(slot) if matches!(play_field.get(*slot), Some(Some(_))) => Some(*slot),
+            _ => None,
+        };
+        let attacker_card = attacker.and_then(|slot| play_field.get(slot).cloned().flatten());
+        let can_attack = |target: Option<Option<&CardData>>| {
+            if planning {
+                // Every creature is ready again by the time the plan runs
+                return check_attack(attacker_card.as_ref(), true, target);
+            }
+            turn.clone().and_then(|_| {
+                let ready = attacker_card.as_ref().is_some_and(|card| !exhausted.contains(&card.card_id));
+                check_attack(attacker_card.as_ref(), ready, target)
+            })
+        };
+        let mut attack = None;
+        ui.horizontal(|ui| {
+            for (slot, card) in opponent_field.iter().enumerate() {
+                match (card, attacker) {
+                    (Some(card), Some(_)) => {
+                        let allowed = can_attack(Some(Some(card)));
+                        let button = ui.add_enabled(
+                            allowed.is_ok(),
+                            egui::Button::new(format!("[ {} {}/{} ]", card.card_name, card.power, card.health)),
+                        );
+                        if button.clicked() {
+                            attack = Some(Some(slot));
+                        }
+                        if let Err(reason) = allowed {
+                            button.on_disabled_hover_text(language.error(&reason));
+                        }
+                    }
+                    _ => {
+                        ui.label(card.as_ref().map_or("[ empty ]".to_string(), |card| format!("[ {} ]", card.card_name)));
+                    }
+                }
+            }
+            if attacker.is_some() {
+                let suggested = attacker.is_some_and(|slot| lethal.as_ref().is_some_and(|plan| plan.attackers.contains(&slot)));
+                let text = if suggested { egui::RichText::new("Attack opponent").color(LETHAL_COLOR) } else { egui::RichText::new("Attack opponent") };
+                let allowed = can_attack(None);
+                let button = ui.add_enabled(allowed.is_ok(), egui::Button::new(text));
+                if button.clicked() {
+                    attack = Some(None);
+                }
+                if let Err(reason) = allowed {
+                    button.on_disabled_hover_text(language.error(&reason));
+                }
+            }
+        });
+        if let (Some(card), Some(target), true) = (&attacker_card, attack, planning) {
+            self.world.resource_mut::<GameState>().plan.push(PlannedAction::Attack { attacker: card.card_id, target });
+        } else if let (Some(attacker), Some(target)) = (attacker, attack) {
+            if self.world.resource::<Client>().request(GameMessage::Attack { attacker, target }).is_ok() {
+                let mut game_state = self.world.resource_mut::<GameState>();
+                if let Some(Some(card)) = game_state.play_field.get(attacker).cloned() {
+                    game_state.exhausted.insert(card.card_id);
+                }
+            }
+        }
+
+        let mut dropped = None;
+        ui.horizontal(|ui| {
+            for (slot, card) in play_field.iter().enumerate() {
+                match card {
+                    Some(card) => {
+                        let selected = matches!(self.selection, GameSelection::CardInPlay(idx) if *idx == slot);
+                        let name = match &lethal {
+                            Some(plan) if plan.attackers.contains(&slot) => egui::RichText::new(&card.card_name).color(LETHAL_COLOR),
+                            _ => egui::RichText::new(&card.card_name),
+                        };
+                        if ui.selectable_label(selected, name).clicked() {
+                            *self.selection = GameSelection::CardInPlay(slot);
+                        }
+                    }
+                    None => {
+                        let (_, payload) = ui.dnd_drop_zone::<usize, _>(egui::Frame::group(ui.style()), |ui| {
+                            match planned_play(slot) {
+                                Some(card) => ui.label(egui::RichText::new(format!("{} (planned)", card.card_name)).italics().weak()),
+                                None => ui.label(format!("Slot {}", slot + 1)),
+                            };
+                        });
+                        if let Some(hand_idx) = payload {
+                            dropped = Some((*hand_idx, slot));
+                        }
+                    }
+                }
+            }
         });
+
+        if let Some((hand_idx, slot)) = dropped {
+            if let Some(card) = hand.get(hand_idx).filter(|_| planning) {
+                // Mana for the coming turn isn't known yet, so only the slot is checked
+                let free = |s: usize| matches!(play_field.get(s), Some(None)) && planned_play(s).is_none_or(|planned| planned.card_id == card.card_id);
+                match check_play(card, 0, 0, Some(slot), free) {
+                    Ok(()) => self.world.resource_mut::<GameState>().plan_play(card.card_id, slot),
+                    Err(error) => self.world.resource_mut::<GameState>().last_rejection = Some(error),
+                }
+            } else if let Some(card) = hand.get(hand_idx) {
+                if let Err(error) = play_from_hand(self.world, card.clone(), slot) {
+                    self.world.resource_mut::<GameState>().last_rejection = Some(error);
+                }
+            }
+        }
+
+        if !plan.is_empty() {
+            self.render_plan(ui, &plan, &hand, &play_field, &opponent_field, turn.is_ok());
+        }
+    }
+
+    // Queued moves in the order they will be sent, shown as ghosts until run or dropped
+    fn render_plan(
+        &mut self,
+        ui: &mut egui_dock::egui::Ui,
+        plan: &[PlannedAction],
+        hand: &[CardData],
+        play_field: &[Option<CardData>],
+        opponent_field: &[Option<CardData>],
+        our_turn: bool,
+    ) {
+        let name_in_hand = |card_id: EntityID| hand.iter().find(|card| card.card_id == card_id).map(|card| card.card_name.clone());
+        let name_on_board = |card_id: EntityID| play_field.iter().flatten().find(|card| card.card_id == card_id).map(|card| card.card_name.clone());
+
+        ui.separator();
+        ui.label("Planned for your turn:");
+        let mut removed = None;
+        for (i, action) in plan.iter().enumerate() {
+            let text = match action {
+                PlannedAction::Play { card_id, slot } => match name_in_hand(*card_id) {
+                    Some(name) => format!("Play {} into slot {}", name, slot + 1),
+                    None => "Play a card no longer in hand".to_string(),
+                },
+                PlannedAction::Attack { attacker, target } => {
+                    let attacker = name_on_board(*attacker).unwrap_or_else(|| "a creature no longer in play".to_string());
+                    match target {
+                        Some(slot) => match opponent_field.get(*slot).cloned().flatten() {
+                            Some(defender) => format!("{} attacks {}", attacker, defender.card_name),
+                            None => format!("{} attacks enemy slot {}", attacker, slot + 1),
+                        },
+                        None => format!("{} attacks the opponent", attacker),
+                    }
+                }
+            };
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(text).italics().weak());
+                if ui.small_button("x").on_hover_text("Remove from the plan").clicked() {
+                    removed = Some(i);
+                }
+            });
+        }
+
+        let (run, clear) = ui.horizontal(|ui| {
+            let run = ui.add_enabled(our_turn, egui::Button::new("Run plan"))
+                .on_disabled_hover_text("Waiting for your turn")
+                .clicked();
+            (run, ui.button("Clear plan").clicked())
+        }).inner;
+
+        if let Some(i) = removed {
+            self.world.resource_mut::<GameState>().plan.remove(i);
+        }
+        if clear {
+            self.world.resource_mut::<GameState>().plan.clear();
+        }
+        if run {
+            let now = self.world.resource::<Time>().elapsed_secs();
+            let mut game_state = self.world.resource_mut::<GameState>();
+            game_state.running_plan = std::mem::take(&mut game_state.plan).into();
+            game_state.next_plan_step = now + PLAN_STEP_SECS;
+        }
     }
 
     fn render_player_hand(&mut self, ui: &mut egui_dock::egui::Ui) {
-        let cards = {
+        let (cards, planned) = {
             let game_state = self.world.resource::<GameState>();
-            game_state.player_hand.clone()
+            let planned: Vec<EntityID> = game_state.plan.iter().filter_map(|action| match action {
+                PlannedAction::Play { card_id, .. } => Some(*card_id),
+                PlannedAction::Attack { .. } => None,
+            }).collect();
+            (game_state.arranged_hand(), planned)
         };
 
         let card_count = cards.len();
+        let lethal = lethal::lethal_plan(self.world);
+        let blocked: Vec<_> = cards.iter().map(|card| play_blocked(self.world, card).err()).collect();
+        let language = *self.world.resource::<Language>();
 
         // Player's hand of cards
         ui.heading("Your Hand");
 
         // Provide information about the cards
+        let mut hovered = None;
+        let mut moved = None;
         for (i, card) in cards.iter().enumerate() {
             let selected = matches!(self.selection, GameSelection::CardInHand(idx) if *idx == i);
 
-            if ui.selectable_label(selected, format!("{} ({} mana)", card.card_name, card.cost))
-                .clicked()
-            {
+            let response = ui.dnd_drag_source(egui::Id::new(("hand_card", i)), i, |ui| {
+                let text = if planned.contains(&card.card_id) {
+                    egui::RichText::new(format!("{} ({} mana, planned)", card.card_name, card.play_cost())).italics()
+                } else {
+                    egui::RichText::new(format!("{} ({} mana)", card.card_name, card.play_cost()))
+                };
+                let suggested = lethal.as_ref().is_some_and(|plan| plan.cards.contains(&card.card_id));
+                let text = match &blocked[i] {
+                    _ if suggested => text.color(LETHAL_COLOR),
+                    Some(_) => text.weak(),
+                    None => text,
+                };
+                let label = ui.selectable_label(selected, text);
+                match &blocked[i] {
+                    Some(reason) => label.on_hover_text(language.error(reason)),
+                    None => label,
+                }
+            });
+            if response.inner.hovered() {
+                hovered = Some(i);
+            }
+            // Dropping a hand card onto another one moves it into that position
+            if let Some(from) = response.response.dnd_release_payload::<usize>() {
+                moved = Some((*from, i));
+            }
+            if response.inner.clicked() {
                 *self.selection = GameSelection::CardInHand(i);
 
                 // Set selected card
@@ -283,6 +1051,17 @@ impl GameTabViewer<'_> {
             }
         }
 
+        self.world.resource_mut::<SelectedCard>().hovered = hovered;
+
+        if let Some((from, to)) = moved.filter(|(from, to)| from != to) {
+            self.world.resource_mut::<GameState>().move_hand_card(from, to);
+            // Keep the selection on the card that moved
+            if matches!(self.selection, GameSelection::CardInHand(idx) if *idx == from) {
+                *self.selection = GameSelection::CardInHand(to);
+                self.world.resource_mut::<SelectedCard>().index = Some(to);
+            }
+        }
+
         ui.separator();
         ui.label(format!("Cards in hand: {}", card_count));
 
@@ -296,34 +1075,296 @@ impl GameTabViewer<'_> {
     fn render_card_collection(&mut self, ui: &mut egui_dock::egui::Ui) {
         // Player's card collection/deck building area
         ui.heading("Card Collection");
-        ui.label("Build your deck by selecting cards from your collection:");
-
-        // Example card categories
-        ui.collapsing("Creatures", |ui| {
-            ui.label("Fire Elemental (4 mana)");
-            ui.label("Water Spirit (3 mana)");
-            ui.label("Earth Golem (6 mana)");
-            ui.label("Air Wisp (2 mana)");
+
+        let (saved_decks, default_deck) = {
+            let game_state = self.world.resource::<GameState>();
+            (game_state.saved_decks.clone(), game_state.default_deck.clone())
+        };
+        ui.collapsing("Saved Decks", |ui| {
+            if saved_decks.is_empty() {
+                ui.label("No saved decks, the starter deck will be used");
+            }
+            for deck in &saved_decks {
+                ui.horizontal(|ui| {
+                    let is_default = default_deck.as_ref() == Some(&deck.name);
+                    ui.label(format!("{} ({} cards){}", deck.name, deck.card_count, if is_default { " - default" } else { "" }));
+                    // Replies come back as a fresh DeckList
+                    let client = self.world.resource::<Client>();
+                    if ui.button("Use next game").clicked() {
+                        let _ = client.request(GameMessage::SelectDeck(deck.name.clone()));
+                    }
+                    if !is_default && ui.button("Make default").clicked() {
+                        let _ = client.request(GameMessage::SetDefaultDeck(deck.name.clone()));
+                    }
+                    if ui.button("Delete").clicked() {
+                        let _ = client.request(GameMessage::DeleteDeck(deck.name.clone()));
+                    }
+                });
+            }
+            if ui.button("Refresh").clicked() {
+                let _ = self.world.resource::<Client>().request(GameMessage::ListDecks);
+            }
         });
 
-        ui.collapsing("Spells", |ui| {
-            ui.label("Fireball (3 mana)");
-            ui.label("Healing Rain (4 mana)");
-            ui.label("Stone Wall (2 mana)");
-            ui.label("Lightning Bolt (1 mana)");
+        match &self.world.resource::<DeckResource>().name {
+            Some(name) => ui.label(format!("Next game: {name}")),
+            None if !self.world.resource::<DeckResource>().cards.is_empty() => ui.label("Next game: unsaved deck"),
+            None => ui.label("Next game: saved default or starter deck"),
+        };
+
+        egui::CollapsingHeader::new("Deck Builder")
+            .default_open(true)
+            .show(ui, |ui| self.render_deck_editor(ui));
+    }
+
+    fn render_deck_editor(&mut self, ui: &mut egui_dock::egui::Ui) {
+        let language = *self.world.resource::<Language>();
+        self.world.resource_scope::<CardLibrary, _>(|world, library| {
+            let mut game_state = world.resource_mut::<GameState>();
+            let mut keys: Vec<_> = library.0.cards.iter()
+                .filter(|(_, card)| !card.token)
+                .map(|(key, _)| key.clone())
+                .collect();
+            keys.sort();
+
+            ui.horizontal(|ui| {
+                ui.label("Name");
+                ui.text_edit_singleline(&mut game_state.deck_draft_name);
+            });
+
+            let previous_filter = game_state.card_filter.clone();
+            render_card_filter(ui, &mut game_state.card_filter);
+            let search = (game_state.card_filter != previous_filter).then(|| game_state.card_filter.clone());
+
+            let listed: Vec<(String, CardDefinition)> = match &game_state.card_search_results {
+                Some(results) => results.clone(),
+                None => keys.iter().map(|key| (key.clone(), library.0.cards[key].clone())).collect(),
+            };
+
+            let counts: HashMap<String, usize> = card_counts(&game_state.deck_draft).into_iter()
+                .map(|(key, count)| (key.to_string(), count))
+                .collect();
+            let deck_full = game_state.deck_draft.len() >= MAX_DECK_SIZE;
+            ui.columns(2, |columns| {
+                columns[0].label("Cards");
+                for (heading, card_type) in [("Creatures", CardType::Creature), ("Spells", CardType::Spell), ("Artifacts", CardType::Artifact)] {
+                    columns[0].collapsing(heading, |ui| {
+                        let of_type = listed.iter()
+                            .filter(|(_, card)| std::mem::discriminant(&card.card_type()) == std::mem::discriminant(&card_type));
+                        for (key, card) in of_type {
+                            let copies = counts.get(key).copied().unwrap_or(0);
+                            let can_add = !deck_full && copies < MAX_COPIES_PER_CARD;
+                            let label = format!("+ {} ({} mana) {}/{}", card.name, card.cost, copies, MAX_COPIES_PER_CARD);
+                            if ui.add_enabled(can_add, egui::Button::new(label)).clicked() {
+                                game_state.deck_draft.push(key.clone());
+                            }
+                        }
+                    });
+                }
+
+                columns[1].label(format!("Deck ({}/{} cards)", game_state.deck_draft.len(), MAX_DECK_SIZE));
+                let mut removed = None;
+                for (i, key) in game_state.deck_draft.iter().enumerate() {
+                    let name = library.0.cards.get(key).map_or(key.as_str(), |card| card.name.as_str());
+                    if columns[1].button(format!("- {name}")).clicked() {
+                        removed = Some(i);
+                    }
+                }
+                if let Some(i) = removed {
+                    game_state.deck_draft.remove(i);
+                }
+            });
+
+            let stats = DeckStats::from_keys(&library.0, &game_state.deck_draft);
+            ui.separator();
+            ui.label(format!("Average cost: {:.2}", stats.average_cost));
+            ui.label(stats.type_breakdown.iter()
+                .map(|(c_type, count)| format!("{c_type}: {count}"))
+                .collect::<Vec<_>>()
+                .join("  "));
+            render_mana_curve(ui, &stats);
+
+            let validation = validate_deck(&library.0, &game_state.deck_draft);
+            if let Err(e) = &validation {
+                ui.colored_label(egui::Color32::YELLOW, language.message(e));
+            }
+            let (save, use_deck) = ui.horizontal(|ui| {
+                let can_save = validation.is_ok() && !game_state.deck_draft_name.trim().is_empty();
+                let save = ui.add_enabled(can_save, egui::Button::new("Save Deck")).clicked();
+                let use_deck = ui.add_enabled(validation.is_ok(), egui::Button::new("Use for next game")).clicked();
+                (save, use_deck)
+            }).inner;
+            let save = save.then(|| GameMessage::SaveDeck {
+                name: game_state.deck_draft_name.clone(),
+                cards: game_state.deck_draft.clone(),
+            });
+            let chosen = use_deck.then(|| {
+                let name = game_state.deck_draft_name.trim();
+                DeckResource {
+                    name: (!name.is_empty()).then(|| name.to_string()),
+                    cards: game_state.deck_draft.clone(),
+                }
+            });
+
+            let client = world.resource::<Client>();
+            if let Some(filter) = search {
+                let _ = client.request(GameMessage::SearchCards(filter));
+            }
+            if let Some(request) = save {
+                let _ = client.request(request);
+            }
+            if let Some(chosen) = chosen {
+                // Rooms deal decks when they start, so this only counts while we're still waiting for an opponent
+                let _ = client.request(GameMessage::JoinGame { deck: Some(chosen.cards.clone()) });
+                world.insert_resource(chosen);
+            }
         });
+    }
+
+    // Everything the board shows, as plain labels a screen reader can walk through
+    fn render_accessibility(&mut self, ui: &mut egui_dock::egui::Ui) {
+        let mut lethal_hint = self.world.resource_mut::<LethalHint>();
+        ui.checkbox(&mut lethal_hint.enabled, "Highlight lethal").on_hover_text("Point out attacks and cards that win this turn");
+        let mut accessibility = self.world.resource_mut::<Accessibility>();
+        ui.checkbox(&mut accessibility.enabled, "Accessible mode");
+        ui.add(egui::Slider::new(&mut accessibility.text_scale, TEXT_SCALE_RANGE).text("Text size"));
+        if !accessibility.enabled {
+            return;
+        }
+        let announcements: Vec<String> = accessibility.announcements().cloned().collect();
+
+        let game_state = self.world.resource::<GameState>();
+        let describe_board = |board: &[Option<CardData>]| {
+            let cards: Vec<String> = board.iter().enumerate()
+                .filter_map(|(slot, card)| card.as_ref().map(|card| format!("slot {}: {} {}/{}", slot + 1, card.card_name, card.power, card.health)))
+                .collect();
+            if cards.is_empty() { "empty".to_string() } else { cards.join(", ") }
+        };
 
-        ui.collapsing("Artifacts", |ui| {
-            ui.label("Mana Crystal (0 mana)");
-            ui.label("Ancient Tome (2 mana)");
-            ui.label("Enchanted Armor (3 mana)");
+        ui.separator();
+        ui.heading("Table");
+        ui.label(if game_state.current_turn == Turn::Player { "It's your turn" } else { "It's your opponent's turn" });
+        ui.label(format!("Your health: {}, mana: {} of {}, deck: {} cards", game_state.player_health, game_state.available_mana, game_state.max_mana, game_state.deck_size));
+        ui.label(format!("Opponent health: {}, hand: {} cards, deck: {} cards", game_state.opponent_health, game_state.opponent_hand_count, game_state.opponent_deck_size));
+        ui.label(format!("Your board: {}", describe_board(&game_state.play_field)));
+        ui.label(format!("Opponent board: {}", describe_board(&game_state.opponent_field)));
+        for (i, card) in game_state.arranged_hand().iter().enumerate() {
+            ui.label(format!("Hand card {}: {}, costs {} mana. {}", i + 1, card.card_name, card.play_cost(), card.card_text));
+        }
+
+        ui.separator();
+        ui.heading("Recent events");
+        egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+            for text in &announcements {
+                ui.label(text);
+            }
         });
     }
 
+    fn render_live_matches(&mut self, ui: &mut egui_dock::egui::Ui) {
+        ui.heading("Matchmaking");
+        // Seated players stay put; this queues us again after a match was called off
+        if ui.button("Find a match").clicked() {
+            let _ = self.world.resource::<Client>().request(GameMessage::JoinGame { deck: None });
+        }
+        ui.separator();
+
+        ui.heading("Play a Friend");
+        let lobby_code = self.world.resource::<GameState>().lobby_code.clone();
+        match lobby_code {
+            Some(code) => {
+                ui.label(format!("Waiting in lobby {code}, share the code with your friend"));
+            }
+            None => {
+                let rules = {
+                    let mut game_state = self.world.resource_mut::<GameState>();
+                    ui.checkbox(&mut game_state.lobby_rules.overkill_to_hero, "Excess combat damage hits the opponent");
+                    ui.checkbox(&mut game_state.lobby_rules.reveal_at_end, "Reveal leftover hands and decks at the end");
+                    game_state.lobby_rules
+                };
+                if ui.button("Create lobby").clicked() {
+                    let _ = self.world.resource::<Client>().request(GameMessage::CreateLobby(rules));
+                }
+            }
+        }
+        let join = ui.horizontal(|ui| {
+            let mut game_state = self.world.resource_mut::<GameState>();
+            ui.text_edit_singleline(&mut game_state.lobby_code_input);
+            let code = game_state.lobby_code_input.trim().to_string();
+            let clicked = ui.add_enabled(!code.is_empty(), egui::Button::new("Join")).clicked();
+            if clicked {
+                game_state.lobby_code_input.clear();
+            }
+            clicked.then_some(code)
+        }).inner;
+        if let Some(code) = join {
+            let _ = self.world.resource::<Client>().request(GameMessage::JoinLobby(code));
+        }
+        ui.horizontal(|ui| {
+            if ui.button("Practice against a bot").clicked() {
+                let _ = self.world.resource::<Client>().request(GameMessage::PlayVsBot(BotDifficulty::Normal));
+            }
+            if ui.button("Hard bot").clicked() {
+                let _ = self.world.resource::<Client>().request(GameMessage::PlayVsBot(BotDifficulty::Hard));
+            }
+        });
+        ui.separator();
+
+        ui.heading("Live Matches");
+        if ui.button("Refresh").clicked() {
+            let _ = self.world.resource::<Client>().request(GameMessage::ListMatches);
+        }
+        ui.separator();
+
+        let matches = self.world.resource::<GameState>().live_matches.clone();
+        if matches.is_empty() {
+            ui.label("No matches in progress");
+        }
+        for listing in &matches {
+            ui.horizontal(|ui| {
+                ui.label(&listing.room_id);
+                let players: Vec<_> = listing.players.iter().map(profile_label).collect();
+                ui.label(players.join(" vs "));
+                ui.label(format!("Turn {}", listing.turn));
+            });
+        }
+    }
+
     fn render_inventory(&mut self, ui: &mut egui_dock::egui::Ui) {
         // Player's inventory
         ui.heading("Inventory");
 
+        let profile = self.world.resource::<GameState>().profile.clone();
+        ui.collapsing("Profile", |ui| {
+            let Some(profile) = profile else {
+                ui.label("Not connected");
+                return;
+            };
+            let mut request = None;
+            egui::ComboBox::from_label("Avatar")
+                .selected_text(&profile.avatar)
+                .show_ui(ui, |ui| {
+                    for avatar in AVATARS {
+                        if ui.selectable_label(profile.avatar == *avatar, *avatar).clicked() {
+                            request = Some(GameMessage::SetProfile { avatar: Some(avatar.to_string()), title: None });
+                        }
+                    }
+                });
+            egui::ComboBox::from_label("Title")
+                .selected_text(&profile.title)
+                .show_ui(ui, |ui| {
+                    for title in TITLES {
+                        if ui.selectable_label(profile.title == *title, *title).clicked() {
+                            request = Some(GameMessage::SetProfile { avatar: None, title: Some(title.to_string()) });
+                        }
+                    }
+                });
+            // The server answers with the updated Profile
+            if let Some(request) = request {
+                let _ = self.world.resource::<Client>().request(request);
+            }
+        });
+
         ui.collapsing("Resources", |ui| {
             ui.label("Gold: 1250");
             ui.label("Dust: 350");
@@ -337,11 +1378,39 @@ impl GameTabViewer<'_> {
             ui.label("✗ Collect all rare cards");
         });
 
+        let stats = self.world.resource::<GameState>().stats.clone();
         ui.collapsing("Game Stats", |ui| {
-            ui.label("Games played: 15");
-            ui.label("Wins: 8");
-            ui.label("Losses: 7");
-            ui.label("Win rate: 53%");
+            if ui.button("Refresh").clicked() {
+                let _ = self.world.resource::<Client>().request(GameMessage::RequestStats);
+            }
+            let Some(stats) = stats else {
+                ui.label("No stats yet");
+                return;
+            };
+            ui.label(format!("Games played: {}", stats.games_played));
+            ui.label(format!("Wins: {}", stats.wins));
+            ui.label(format!("Losses: {}", stats.losses));
+            if stats.draws > 0 {
+                ui.label(format!("Draws: {}", stats.draws));
+            }
+            if stats.games_played > 0 {
+                ui.label(format!("Win rate: {}%", stats.wins * 100 / stats.games_played));
+            }
+
+            ui.separator();
+            ui.label("Recent matches");
+            if stats.recent.is_empty() {
+                ui.label("No matches played");
+            }
+            for recent in &stats.recent {
+                let outcome = match recent.outcome {
+                    MatchOutcome::Win => "Won",
+                    MatchOutcome::Loss => "Lost",
+                    MatchOutcome::Draw => "Drew",
+                };
+                let conceded = if recent.conceded { ", conceded" } else { "" };
+                ui.label(format!("{outcome} in {} turns{conceded}", recent.turns));
+            }
         });
     }
 
@@ -354,11 +1423,7 @@ impl GameTabViewer<'_> {
                 // Get play field card data
                 let card_data = {
                     let game_state = self.world.resource::<GameState>();
-                    if idx < game_state.play_field.len() {
-                        Some(game_state.play_field[idx].clone())
-                    } else {
-                        None
-                    }
+                    game_state.play_field.get(idx).cloned().flatten()
                 };
 
                 if let Some(card) = card_data {
@@ -367,6 +1432,23 @@ impl GameTabViewer<'_> {
                     // More card details would go here
                 }
             }
+            GameSelection::Discarded { own, index } => {
+                let card = {
+                    let game_state = self.world.resource::<GameState>();
+                    let discarded = if own { &game_state.discarded } else { &game_state.opponent_discarded };
+                    discarded.get(index).cloned()
+                };
+                match card {
+                    Some(card) => {
+                        ui.label(if own { "Discarded by you" } else { "Discarded by your opponent" });
+                        self.render_card_info(ui, &card);
+                        self.render_card_preview(ui, &card);
+                    }
+                    None => {
+                        ui.label("No card selected");
+                    }
+                }
+            }
             GameSelection::CardDetail(_, ref name) => {
                 ui.label(format!("Card Detail: {}", name));
             }
@@ -378,37 +1460,11 @@ impl GameTabViewer<'_> {
 
     fn render_hand_card_detail(&mut self, ui: &mut egui_dock::egui::Ui, idx: usize) {
         // Create a local copy of the card data we need to avoid the borrow conflict
-        let (card, can_play) = {
-            let game_state = self.world.resource::<GameState>();
-            if idx < game_state.player_hand.len() {
-                let card = game_state.player_hand[idx].clone(); // Clone to end the borrow
-                let can_play = card.cost <= game_state.available_mana;
-                (Some(card), can_play)
-            } else {
-                (None, false)
-            }
-        }; // game_state borrow ends here
+        let card = self.world.resource::<GameState>().arranged_hand().get(idx).cloned();
+        let can_play = card.as_ref().is_some_and(|card| play_blocked(self.world, card).is_ok());
 
         if let Some(card) = card {
-            ui.heading(&card.card_name);
-            ui.horizontal(|ui| {
-                ui.label(format!("Cost: {} mana", card.cost));
-                match card.card_type {
-                    CardType::Creature => {
-                        ui.label(format!("Power: {}", card.power));
-                        ui.label(format!("Health: {}", card.health));
-                    },
-                    CardType::Spell => {
-                        ui.label("Type: Spell");
-                    },
-                    CardType::Artifact => {
-                        ui.label("Type: Artifact");
-                    }
-                }
-            });
-
-            ui.separator();
-            ui.label(&card.card_text);
+            self.render_card_info(ui, &card);
 
             ui.separator();
             // Play card button
@@ -429,6 +1485,84 @@ impl GameTabViewer<'_> {
         }
     }
 
+    fn render_graveyard(&mut self, ui: &mut egui_dock::egui::Ui) {
+        let own_id = self.world.resource::<Client>().id();
+        let (sections, opponent_id) = {
+            let game_state = self.world.resource::<GameState>();
+            let opponent_id = game_state.match_profiles.iter()
+                .map(|profile| profile.player_id)
+                .find(|&player_id| player_id != own_id);
+            let sections = [
+                (true, "Your discards", game_state.graveyard_size, game_state.discarded.clone()),
+                (false, "Opponent's discards", game_state.opponent_graveyard_size, game_state.opponent_discarded.clone()),
+            ];
+            (sections, opponent_id)
+        };
+
+        for (own, heading, graveyard_size, cards) in sections {
+            ui.heading(heading);
+            if cards.is_empty() {
+                ui.label("Nothing discarded yet");
+            }
+            // Most recent first, the way the pile is stacked
+            for (index, card) in cards.iter().enumerate().rev() {
+                let selected = *self.selection == GameSelection::Discarded { own, index };
+                if ui.selectable_label(selected, format!("{} ({})", card.card_name, card.cost)).clicked() {
+                    *self.selection = GameSelection::Discarded { own, index };
+                }
+            }
+
+            // Destroyed creatures are only in the server's copy of the pile
+            let owner = if own { Some(own_id) } else { opponent_id };
+            let button = egui::Button::new(format!("Open whole graveyard ({graveyard_size} cards)"));
+            if ui.add_enabled(owner.is_some() && graveyard_size > 0, button).clicked() {
+                if let Some(owner) = owner {
+                    let _ = self.world.resource::<Client>().request(GameMessage::InspectGraveyard(owner));
+                }
+            }
+            ui.separator();
+        }
+    }
+
+    // Name, cost, stats, rules text and keywords, shared by every card detail view
+    fn render_card_info(&mut self, ui: &mut egui_dock::egui::Ui, card: &CardData) {
+        ui.heading(&card.card_name);
+        ui.horizontal(|ui| {
+            if card.play_cost() == card.cost {
+                ui.label(format!("Cost: {} mana", card.cost));
+            } else {
+                ui.label(format!("Cost: {} mana (printed {})", card.play_cost(), card.cost));
+            }
+            match card.card_type {
+                CardType::Creature => {
+                    ui.label(format!("Power: {}", card.power));
+                    ui.label(format!("Health: {}", card.health));
+                },
+                CardType::Spell => {
+                    ui.label("Type: Spell");
+                },
+                CardType::Artifact => {
+                    ui.label("Type: Artifact");
+                }
+            }
+        });
+
+        ui.separator();
+        ui.label(&card.card_text);
+        if !card.keywords.is_empty() {
+            let glossary = &self.world.resource::<GameState>().keywords;
+            ui.horizontal_wrapped(|ui| {
+                for id in &card.keywords {
+                    match glossary.iter().find(|k| &k.id == id) {
+                        Some(keyword) => ui.label(egui::RichText::new(&keyword.name).strong())
+                            .on_hover_text(&keyword.description),
+                        None => ui.label(id),
+                    };
+                }
+            });
+        }
+    }
+
     fn render_card_preview(&mut self, ui: &mut egui_dock::egui::Ui, card: &CardData) {
         // Card preview visualization
         let card_image_size = [120.0, 180.0];
@@ -476,7 +1610,7 @@ impl GameTabViewer<'_> {
         ui.painter().text(
             mana_pos,
             egui::Align2::CENTER_CENTER,
-            format!("{}", card.cost),
+            format!("{}", card.play_cost()),
             egui::FontId::proportional(14.0),
             egui::Color32::WHITE,
         );
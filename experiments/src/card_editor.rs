@@ -0,0 +1,314 @@
+use std::path::Path;
+use bevy::prelude::*;
+use shared::card_details::{load_cards_from, save_cards, AuraScope, CardConfig, CardDefinition, CardEffect, CostAura, EffectTarget, CARDS_PATH, CARD_TYPES};
+use shared::keywords::{load_keywords, Keyword};
+
+const EFFECT_KINDS: &[&str] = &[
+    "damage", "draw", "buff", "discount", "reduce_hand_cost", "transform",
+    "mana_crystal", "extra_turn", "skip_opponent_turn", "temporary_mana",
+];
+
+const EFFECT_TARGETS: &[EffectTarget] = &[
+    EffectTarget::Opponent,
+    EffectTarget::Source,
+    EffectTarget::AdjacentAllies,
+    EffectTarget::OtherAllies,
+    EffectTarget::LeftmostEnemy,
+    EffectTarget::RightmostEnemy,
+    EffectTarget::OpposingEnemy,
+];
+
+// Edits shared/assets/cards.toml in place; the game picks changes up on the next build
+#[derive(Resource)]
+pub struct CardEditorState {
+    config: CardConfig,
+    // Every keyword a card can list, sorted by id
+    keywords: Vec<Keyword>,
+    selected: Option<String>,
+    new_card_id: String,
+    status: String,
+}
+
+impl CardEditorState {
+    pub fn load() -> Self {
+        let (config, status) = match load_cards_from(Path::new(CARDS_PATH)) {
+            Ok(config) => (config, format!("Loaded {}", CARDS_PATH)),
+            Err(e) => (
+                CardConfig { cards: Default::default() },
+                format!("Failed to load {}: {}", CARDS_PATH, e),
+            ),
+        };
+
+        let keywords = load_keywords().map(|keywords| keywords.glossary()).unwrap_or_default();
+
+        Self {
+            config,
+            keywords,
+            selected: None,
+            new_card_id: String::new(),
+            status,
+        }
+    }
+
+    // Draws the editor and returns the selected card so the caller can preview it
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> Option<CardDefinition> {
+        ui.horizontal(|ui| {
+            if ui.button("Reload").clicked() {
+                *self = Self::load();
+            }
+            if ui.button("Save").clicked() {
+                self.save();
+            }
+        });
+        ui.label(&self.status);
+        ui.separator();
+
+        ui.columns(2, |columns| {
+            self.card_list_ui(&mut columns[0]);
+            self.card_fields_ui(&mut columns[1]);
+        });
+
+        let errors = self.config.validate();
+        if !errors.is_empty() {
+            ui.separator();
+            for error in errors {
+                ui.colored_label(egui::Color32::from_rgb(220, 80, 80), error);
+            }
+        }
+
+        self.selected.as_ref().and_then(|key| self.config.cards.get(key)).cloned()
+    }
+
+    fn card_list_ui(&mut self, ui: &mut egui::Ui) {
+        let mut keys: Vec<_> = self.config.cards.keys().cloned().collect();
+        keys.sort();
+
+        egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+            for key in keys {
+                let selected = self.selected.as_ref() == Some(&key);
+                if ui.selectable_label(selected, &key).clicked() {
+                    self.selected = Some(key);
+                }
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.new_card_id);
+            if ui.button("Add").clicked() && !self.new_card_id.is_empty() {
+                let key = std::mem::take(&mut self.new_card_id);
+                self.config.cards.entry(key.clone()).or_insert_with(|| CardDefinition {
+                    name: String::new(),
+                    text: String::new(),
+                    c_type: CARD_TYPES[0].to_string(),
+                    cost: 0,
+                    power: 0,
+                    keywords: Vec::new(),
+                    set: None,
+                    health: 0,
+                    effects: Vec::new(),
+                    aura: None,
+                    token: false,
+                });
+                self.selected = Some(key);
+            }
+        });
+    }
+
+    fn card_fields_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(key) = self.selected.clone() else {
+            ui.label("No card selected");
+            return;
+        };
+        let Some(card) = self.config.cards.get_mut(&key) else {
+            return;
+        };
+
+        egui::Grid::new("card_fields").num_columns(2).show(ui, |ui| {
+            ui.label("Name");
+            ui.text_edit_singleline(&mut card.name);
+            ui.end_row();
+
+            ui.label("Type");
+            egui::ComboBox::from_id_salt("c_type")
+                .selected_text(&card.c_type)
+                .show_ui(ui, |ui| {
+                    for c_type in CARD_TYPES {
+                        ui.selectable_value(&mut card.c_type, c_type.to_string(), *c_type);
+                    }
+                });
+            ui.end_row();
+
+            ui.label("Cost");
+            ui.add(egui::DragValue::new(&mut card.cost).range(0..=10));
+            ui.end_row();
+
+            ui.label("Power");
+            ui.add(egui::DragValue::new(&mut card.power).range(0..=20));
+            ui.end_row();
+
+            ui.label("Health");
+            ui.add(egui::DragValue::new(&mut card.health).range(0..=20));
+            ui.end_row();
+
+            // Left empty for cards outside any set
+            ui.label("Set");
+            let mut set = card.set.clone().unwrap_or_default();
+            if ui.text_edit_singleline(&mut set).changed() {
+                card.set = Some(set.trim().to_string()).filter(|set| !set.is_empty());
+            }
+            ui.end_row();
+
+            ui.label("Token");
+            ui.checkbox(&mut card.token, "Only made during a match");
+            ui.end_row();
+        });
+
+        ui.label("Text");
+        ui.text_edit_multiline(&mut card.text);
+
+        ui.label("Keywords");
+        ui.horizontal_wrapped(|ui| {
+            for keyword in &self.keywords {
+                let mut listed = card.keywords.contains(&keyword.id);
+                if ui.checkbox(&mut listed, &keyword.name).on_hover_text(&keyword.description).changed() {
+                    if listed {
+                        card.keywords.push(keyword.id.clone());
+                    } else {
+                        card.keywords.retain(|id| id != &keyword.id);
+                    }
+                }
+            }
+        });
+
+        aura_ui(ui, &mut card.aura);
+        effects_ui(ui, &mut card.effects);
+
+        if ui.button("Delete").clicked() {
+            self.config.cards.remove(&key);
+            self.selected = None;
+        }
+    }
+
+    fn save(&mut self) {
+        let errors = self.config.validate();
+        if !errors.is_empty() {
+            self.status = format!("Not saved: {} validation error(s)", errors.len());
+            return;
+        }
+
+        self.status = match save_cards(&self.config, Path::new(CARDS_PATH)) {
+            Ok(()) => format!("Saved {}", CARDS_PATH),
+            Err(e) => format!("Failed to save: {}", e),
+        };
+    }
+}
+
+fn aura_ui(ui: &mut egui::Ui, aura: &mut Option<CostAura>) {
+    ui.horizontal(|ui| {
+        let mut has_aura = aura.is_some();
+        if ui.checkbox(&mut has_aura, "Cost aura").changed() {
+            *aura = has_aura.then_some(CostAura { cost: -1, applies_to: AuraScope::Allies });
+        }
+        if let Some(aura) = aura {
+            ui.add(egui::DragValue::new(&mut aura.cost).range(-10..=10));
+            egui::ComboBox::from_id_salt("aura_scope")
+                .selected_text(format!("{:?}", aura.applies_to))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut aura.applies_to, AuraScope::Allies, "Allies");
+                    ui.selectable_value(&mut aura.applies_to, AuraScope::Enemies, "Enemies");
+                });
+        }
+    });
+}
+
+// One row per effect, in the order they resolve
+fn effects_ui(ui: &mut egui::Ui, effects: &mut Vec<CardEffect>) {
+    ui.label("Effects");
+    let mut removed = None;
+    for (index, effect) in effects.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            let kind = effect_kind(effect);
+            egui::ComboBox::from_id_salt(("effect_kind", index))
+                .selected_text(kind)
+                .show_ui(ui, |ui| {
+                    for &other in EFFECT_KINDS {
+                        if ui.selectable_label(other == kind, other).clicked() && other != kind {
+                            *effect = new_effect(other);
+                        }
+                    }
+                });
+
+            match effect {
+                CardEffect::Damage { amount, target } => {
+                    ui.add(egui::DragValue::new(amount).range(0..=20));
+                    target_ui(ui, index, target);
+                }
+                CardEffect::Buff { power, health, target } => {
+                    ui.add(egui::DragValue::new(power).range(0..=20).prefix("+"));
+                    ui.add(egui::DragValue::new(health).range(0..=20).prefix("+"));
+                    target_ui(ui, index, target);
+                }
+                CardEffect::Transform { into, target } => {
+                    ui.text_edit_singleline(into);
+                    target_ui(ui, index, target);
+                }
+                CardEffect::Draw { amount } | CardEffect::Discount { amount } | CardEffect::ReduceHandCost { amount }
+                | CardEffect::ManaCrystal { amount } | CardEffect::TemporaryMana { amount } => {
+                    ui.add(egui::DragValue::new(amount).range(0..=10));
+                }
+                CardEffect::ExtraTurn | CardEffect::SkipOpponentTurn => {}
+            }
+
+            if ui.button("Remove").clicked() {
+                removed = Some(index);
+            }
+        });
+    }
+    if let Some(index) = removed {
+        effects.remove(index);
+    }
+    if ui.button("Add effect").clicked() {
+        effects.push(new_effect(EFFECT_KINDS[0]));
+    }
+}
+
+fn target_ui(ui: &mut egui::Ui, index: usize, target: &mut EffectTarget) {
+    egui::ComboBox::from_id_salt(("effect_target", index))
+        .selected_text(format!("{target:?}"))
+        .show_ui(ui, |ui| {
+            for &option in EFFECT_TARGETS {
+                ui.selectable_value(target, option, format!("{option:?}"));
+            }
+        });
+}
+
+// The `kind` the effect is written with in cards.toml
+fn effect_kind(effect: &CardEffect) -> &'static str {
+    match effect {
+        CardEffect::Damage { .. } => "damage",
+        CardEffect::Draw { .. } => "draw",
+        CardEffect::Buff { .. } => "buff",
+        CardEffect::Discount { .. } => "discount",
+        CardEffect::ReduceHandCost { .. } => "reduce_hand_cost",
+        CardEffect::Transform { .. } => "transform",
+        CardEffect::ManaCrystal { .. } => "mana_crystal",
+        CardEffect::ExtraTurn => "extra_turn",
+        CardEffect::SkipOpponentTurn => "skip_opponent_turn",
+        CardEffect::TemporaryMana { .. } => "temporary_mana",
+    }
+}
+
+fn new_effect(kind: &str) -> CardEffect {
+    match kind {
+        "draw" => CardEffect::Draw { amount: 1 },
+        "buff" => CardEffect::Buff { power: 1, health: 1, target: EffectTarget::Source },
+        "discount" => CardEffect::Discount { amount: 1 },
+        "reduce_hand_cost" => CardEffect::ReduceHandCost { amount: 1 },
+        "transform" => CardEffect::Transform { into: String::new(), target: EffectTarget::OpposingEnemy },
+        "mana_crystal" => CardEffect::ManaCrystal { amount: 1 },
+        "extra_turn" => CardEffect::ExtraTurn,
+        "skip_opponent_turn" => CardEffect::SkipOpponentTurn,
+        "temporary_mana" => CardEffect::TemporaryMana { amount: 1 },
+        _ => CardEffect::Damage { amount: 1, target: EffectTarget::Opponent },
+    }
+}
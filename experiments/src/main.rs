@@ -9,6 +9,11 @@ use bevy_render::render_resource::{Extent3d, TextureDimension, TextureFormat};
 use bevy_window::{PrimaryWindow, Window};
 use egui_dock::{DockArea, DockState, NodeIndex, Style};
 use fontdue::Font;
+use serde::Serialize;
+use shared::card_details::CardDefinition;
+use crate::card_editor::CardEditorState;
+
+mod card_editor;
 
 // ----- Game State and Components -----
 
@@ -67,8 +72,9 @@ struct CardText;
 
 // ----- Hand Layout Parameters -----
 
-#[derive(Resource, Clone, Debug)]
+#[derive(Resource, Clone, Debug, Serialize)]
 struct HandLayoutParams {
+    #[serde(skip)]
     count: usize,
     ideal_spacing: f32,
     spread_width: f32,
@@ -118,6 +124,8 @@ enum GameWindow {
     CardCollection, // Card collection/deck building
     Inventory,      // Player inventory
     CardDetail,     // Card details/inspector
+    LayoutWorkbench, // Hand layout tuning
+    CardEditor,     // cards.toml authoring
 }
 
 #[derive(Resource)]
@@ -147,6 +155,7 @@ fn main() {
         .insert_resource(GameState::default())
         .init_resource::<HandLayoutParams>()
         .init_resource::<SelectedCard>()
+        .insert_resource(CardEditorState::load())
         .add_systems(Startup, setup)
         .add_systems(
             PostUpdate,
@@ -158,8 +167,9 @@ fn main() {
         .add_systems(PostUpdate, set_camera_viewport.after(show_ui_system))
         .add_systems(Update, (
             handle_play_card,
+            update_preview_card_count,
             update_card_layout,
-        ))
+        ).chain())
         .register_type::<Option<Handle<Image>>>()
         .register_type::<AlphaMode>()
         .run();
@@ -315,27 +325,113 @@ fn handle_play_card(
     }
 }
 
-// New system to update card layout in hand
-fn update_card_layout(
-    game_state: Res<GameState>,
+// Respawn the preview cards whenever the workbench changes the card count
+fn update_preview_card_count(
+    mut commands: Commands,
     layout_params: Res<HandLayoutParams>,
+    game_state: Res<GameState>,
+    card_query: Query<Entity, With<Card>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if !layout_params.is_changed() || card_query.iter().count() == layout_params.count {
+        return;
+    }
+
+    for entity in card_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    if game_state.player_hand.is_empty() {
+        return;
+    }
+
+    let debug_material = materials.add(StandardMaterial {
+        base_color_texture: Some(images.add(uv_debug_texture())),
+        ..default()
+    });
+
+    let font_data = include_bytes!("../assets/fonts/FiraMono-Medium.ttf");
+    let font = Font::from_bytes(font_data as &[u8], fontdue::FontSettings::default()).unwrap();
+
+    // Cycle through the sample cards to fill the requested count
+    for i in 0..layout_params.count {
+        let card = game_state.player_hand[i % game_state.player_hand.len()].clone();
+        spawn_card(
+            &mut commands,
+            &mut meshes,
+            &mut images,
+            &mut materials,
+            &debug_material,
+            &font,
+            i,
+            card,
+        );
+    }
+}
+
+// Same layout as the client's update_card_positions so the preview matches in game
+fn update_card_layout(
+    params: Res<HandLayoutParams>,
     mut card_transforms: Query<(&Card, &mut Transform)>,
 ) {
-    // Update card positions based on hand layout
+    let desired_total_width = if params.count <= 1 {
+        0.0
+    } else {
+        params.ideal_spacing * (params.count as f32 - 1.0)
+    };
+
+    let actual_total_width = desired_total_width.min(params.spread_width);
+    let card_spacing = if params.count <= 1 {
+        0.0
+    } else {
+        actual_total_width / (params.count as f32 - 1.0)
+    };
+
     for (card, mut transform) in card_transforms.iter_mut() {
-        // For simplicity, just offset each card in hand horizontally
-        let card_spacing = layout_params.spread_width / layout_params.count as f32;
-        let idx = card.index as f32;
-        let offset = idx * card_spacing - (layout_params.spread_width / 2.0);
+        let i = card.index as f32;
+
+        let x = if params.count <= 1 {
+            0.0
+        } else {
+            -(actual_total_width / 2.0) + (i * card_spacing)
+        };
+
+        let normalized_x = (x / (params.spread_width / 2.0)).abs();
+        let curved = params.count > params.card_curve_threshold;
 
-        // Simple card positioning in hand
-        transform.translation.x = offset;
+        let y = if curved {
+            params.base_height + (normalized_x * normalized_x * params.curve_height)
+        } else {
+            params.base_height
+        };
+
+        let z = if curved {
+            params.base_z - normalized_x * 0.5 + (x.signum() * params.z_overlap_factor)
+        } else {
+            params.base_z
+        };
 
-        // In a more complex implementation, you would apply the full layout parameters
-        // like the curve, rotation, etc.
+        transform.translation = Vec3::new(x, y, z);
+        transform.rotation = if curved {
+            Quat::from_rotation_y(normalized_x * params.rotation_y * x.signum())
+                * Quat::from_rotation_x(params.rotation_x)
+        } else {
+            Quat::from_rotation_x(params.rotation_x)
+        };
     }
 }
 
+// Writes the tuned values where the client picks them up at startup
+const LAYOUT_EXPORT_PATH: &str = "client/assets/hand_layout.toml";
+
+fn export_hand_layout(params: &HandLayoutParams) -> Result<String, Box<dyn std::error::Error>> {
+    let snippet = toml::to_string_pretty(params)?;
+    std::fs::write(LAYOUT_EXPORT_PATH, &snippet)?;
+    Ok(snippet)
+}
+
 // UI Systems and Functions
 fn show_ui_system(world: &mut World) {
     let Ok(egui_context) = world
@@ -395,8 +491,16 @@ impl UiState {
         let [game, _card_detail] =
             tree.split_right(NodeIndex::root(), 0.75, vec![GameWindow::CardDetail]);
         let [game, _player_hand] = tree.split_left(game, 0.2, vec![GameWindow::PlayerHand]);
-        let [_game, _bottom] =
-            tree.split_below(game, 0.8, vec![GameWindow::CardCollection, GameWindow::Inventory]);
+        let [_game, _bottom] = tree.split_below(
+            game,
+            0.8,
+            vec![
+                GameWindow::LayoutWorkbench,
+                GameWindow::CardEditor,
+                GameWindow::CardCollection,
+                GameWindow::Inventory,
+            ],
+        );
 
         Self {
             state,
@@ -437,6 +541,8 @@ impl egui_dock::TabViewer for GameTabViewer<'_> {
             GameWindow::CardCollection => self.render_card_collection(ui),
             GameWindow::Inventory => self.render_inventory(ui),
             GameWindow::CardDetail => self.render_card_detail(ui),
+            GameWindow::LayoutWorkbench => self.render_layout_workbench(ui),
+            GameWindow::CardEditor => self.render_card_editor(ui),
         }
     }
 
@@ -447,6 +553,8 @@ impl egui_dock::TabViewer for GameTabViewer<'_> {
             GameWindow::CardCollection => "Card Collection".into(),
             GameWindow::Inventory => "Inventory".into(),
             GameWindow::CardDetail => "Card Details".into(),
+            GameWindow::LayoutWorkbench => "Layout Workbench".into(),
+            GameWindow::CardEditor => "Card Editor".into(),
         }
     }
 
@@ -526,6 +634,66 @@ impl GameTabViewer<'_> {
         }
     }
 
+    fn render_layout_workbench(&mut self, ui: &mut egui_dock::egui::Ui) {
+        ui.heading("Hand Layout");
+
+        // Edit a copy so the resource is only marked changed when a value actually moves
+        let mut params = self.world.resource::<HandLayoutParams>().clone();
+        let mut changed = false;
+
+        egui::Grid::new("layout_params").num_columns(2).show(ui, |ui| {
+            let mut slider = |ui: &mut egui::Ui, label: &str, value: &mut f32, range: std::ops::RangeInclusive<f32>| {
+                ui.label(label);
+                changed |= ui.add(egui::Slider::new(value, range)).changed();
+                ui.end_row();
+            };
+            slider(ui, "Ideal spacing", &mut params.ideal_spacing, 0.0..=5.0);
+            slider(ui, "Spread width", &mut params.spread_width, 1.0..=30.0);
+            slider(ui, "Curve height", &mut params.curve_height, -5.0..=5.0);
+            slider(ui, "Base height", &mut params.base_height, -10.0..=10.0);
+            slider(ui, "Base z", &mut params.base_z, 0.0..=20.0);
+            slider(ui, "Rotation y", &mut params.rotation_y, -1.5..=1.5);
+            slider(ui, "Rotation x", &mut params.rotation_x, -1.5..=1.5);
+            slider(ui, "Z overlap factor", &mut params.z_overlap_factor, 0.0..=0.5);
+
+            ui.label("Curve threshold");
+            changed |= ui.add(egui::Slider::new(&mut params.card_curve_threshold, 0..=15)).changed();
+            ui.end_row();
+
+            ui.label("Preview cards");
+            changed |= ui.add(egui::Slider::new(&mut params.count, 0..=15)).changed();
+            ui.end_row();
+        });
+
+        if changed {
+            *self.world.resource_mut::<HandLayoutParams>() = params.clone();
+        }
+
+        ui.separator();
+        if ui.button("Export").clicked() {
+            match export_hand_layout(&params) {
+                Ok(_) => info!("Exported hand layout to {}", LAYOUT_EXPORT_PATH),
+                Err(e) => error!("Failed to export hand layout: {}", e),
+            }
+        }
+
+        // Show the snippet so it can be copied by hand as well
+        if let Ok(snippet) = toml::to_string_pretty(&params) {
+            ui.add(egui::TextEdit::multiline(&mut snippet.as_str()).code_editor());
+        }
+    }
+
+    fn render_card_editor(&mut self, ui: &mut egui_dock::egui::Ui) {
+        let selected = self.world.resource_scope::<CardEditorState, _>(|_, mut editor| {
+            editor.ui(ui)
+        });
+
+        if let Some(definition) = selected {
+            ui.separator();
+            self.render_card_preview(ui, &preview_card_data(&definition));
+        }
+    }
+
     fn render_card_collection(&mut self, ui: &mut egui_dock::egui::Ui) {
         // Player's card collection/deck building area
         ui.heading("Card Collection");
@@ -716,6 +884,22 @@ impl GameTabViewer<'_> {
     }
 }
 
+// Maps an authored definition onto the preview's card data
+fn preview_card_data(definition: &CardDefinition) -> CardData {
+    CardData {
+        name: definition.name.clone(),
+        cost: definition.cost,
+        power: definition.power,
+        health: definition.health,
+        description: definition.text.clone(),
+        card_type: match definition.card_type() {
+            shared::channel::CardType::Creature => CardType::Creature,
+            shared::channel::CardType::Spell => CardType::Spell,
+            shared::channel::CardType::Artifact => CardType::Artifact,
+        },
+    }
+}
+
 // Card creation and rendering functions
 fn spawn_card(
     commands: &mut Commands,
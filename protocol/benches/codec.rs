@@ -0,0 +1,84 @@
+use std::time::Duration;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use protocol::cards::{CardEffect, EffectTarget};
+use protocol::channel::{CardData, CardType, GameMessage, GameStateSnapshot, PlayerSnapshot, BOARD_SLOTS};
+use protocol::codec::{Codec, Frame};
+
+// Sized like a typical creature from cards.toml, with a keyword and an effect
+fn cards(count: usize) -> Vec<CardData> {
+    (0..count as u128).map(|card_id| CardData {
+        card_id,
+        card_key: format!("card_{card_id}"),
+        card_name: format!("Patrol Frigate {card_id}"),
+        card_text: "When played, deal 2 damage to the opposing enemy".to_string(),
+        card_type: CardType::Creature,
+        cost: 3,
+        power: 2,
+        health: 3,
+        keywords: vec!["shielded".to_string()],
+        effects: vec![CardEffect::Damage { amount: 2, target: EffectTarget::OpposingEnemy }],
+        aura: None,
+        effective_cost: None,
+        revealed: false,
+    }).collect()
+}
+
+fn player(player_id: u128) -> PlayerSnapshot {
+    let mut board = vec![None; BOARD_SLOTS];
+    for (slot, card) in cards(4).into_iter().enumerate() {
+        board[slot] = Some(card);
+    }
+    PlayerSnapshot {
+        player_id,
+        hand_count: 5,
+        deck_size: 20,
+        health: 30,
+        mana: 4,
+        max_mana: 7,
+        board,
+        graveyard_size: 3,
+    }
+}
+
+// The messages sent most often during a match, plus the largest one
+fn sample_messages() -> Vec<(&'static str, GameMessage)> {
+    vec![
+        ("current_turn", GameMessage::CurrentTurn(Some(1), Some(Duration::from_secs(75)))),
+        ("mana_update", GameMessage::ManaUpdate { player_id: 1, current: 3, max: 7 }),
+        ("cards_drawn", GameMessage::CardsDrawn(cards(3))),
+        ("board_update", GameMessage::BoardUpdate(1, player(1).board)),
+        ("snapshot", GameMessage::GameStateSnapshot(GameStateSnapshot {
+            current_turn: Some(1),
+            time_bank: Some(Duration::from_secs(75)),
+            hand: cards(5),
+            players: vec![player(1), player(2)],
+        })),
+    ]
+}
+
+// JSON is measured as the text frame the transport would send, MessagePack as the bytes inside the frame
+fn payload_sizes(message: &GameMessage) -> (usize, usize) {
+    let json = serde_json::to_vec(message).expect("messages serialize to JSON").len();
+    let packed = match Codec::MessagePack.encode(message.clone()) {
+        Frame::MessagePack(bytes) => bytes.len(),
+        Frame::Json(_) => panic!("MessagePack encoding fell back to JSON"),
+    };
+    (json, packed)
+}
+
+fn codec_benchmarks(c: &mut Criterion) {
+    for (name, message) in sample_messages() {
+        let (json, packed) = payload_sizes(&message);
+        println!("{name}: json {json} bytes, messagepack {packed} bytes ({:.0}%)", packed as f64 * 100.0 / json as f64);
+
+        let mut group = c.benchmark_group(name);
+        group.bench_function("json_encode", |b| b.iter(|| serde_json::to_vec(black_box(&message)).unwrap()));
+        group.bench_function("messagepack_encode", |b| b.iter(|| Codec::MessagePack.encode(black_box(message.clone()))));
+        let frame = Codec::MessagePack.encode(message.clone());
+        group.bench_function("messagepack_decode", |b| b.iter(|| black_box(frame.clone()).decode().unwrap()));
+        group.finish();
+    }
+}
+
+criterion_group!(benches, codec_benchmarks);
+criterion_main!(benches);
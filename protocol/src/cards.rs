@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+use crate::channel::{CardData, CardType};
+use crate::EntityID;
+
+// Cards as written in cards.toml and sent in search results and deck lists. Loading and validating the
+// card files is gameplay's business and lives in shared
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CardDefinition {
+    pub name: String,
+    pub text: String,
+    pub c_type: String,
+    pub cost: u32,
+    pub power: u32,
+    // Ids from keywords.toml, resolved by the client for tooltips
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+    // Release the card belongs to, for collection filters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub set: Option<String>,
+    // Creatures only; damage that brings it to zero destroys the creature
+    #[serde(default)]
+    pub health: u32,
+    // Resolved by the server in order when the card is played
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub effects: Vec<CardEffect>,
+    // Changes the cost of other cards while this creature is on the board
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aura: Option<CostAura>,
+    // Made by the game during a match, never collected or put in a deck
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub token: bool,
+}
+
+// Written as e.g. `aura = { cost = -1 }` for a cheaper hand, or `aura = { cost = 1, applies_to = "enemies" }` as a tax
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CostAura {
+    pub cost: i32,
+    #[serde(default)]
+    pub applies_to: AuraScope,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuraScope {
+    #[default]
+    Allies,
+    Enemies,
+}
+
+// Who an effect lands on. Everything except Opponent is a creature on one of the boards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EffectTarget {
+    // The enemy player themselves
+    #[default]
+    Opponent,
+    // The creature that was just played
+    Source,
+    AdjacentAllies,
+    OtherAllies,
+    LeftmostEnemy,
+    RightmostEnemy,
+    OpposingEnemy,
+}
+
+impl EffectTarget {
+    // These resolve relative to the played creature's slot, so spells can't use them
+    pub fn needs_source(&self) -> bool {
+        matches!(self, EffectTarget::Source | EffectTarget::AdjacentAllies | EffectTarget::OtherAllies | EffectTarget::OpposingEnemy)
+    }
+}
+
+// Written in cards.toml as e.g. `effects = [{ kind = "damage", amount = 2, target = "leftmost_enemy" }]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CardEffect {
+    Damage {
+        amount: u32,
+        #[serde(default)]
+        target: EffectTarget,
+    },
+    Draw {
+        amount: u32,
+    },
+    Buff {
+        power: u32,
+        health: u32,
+        #[serde(default = "buff_target")]
+        target: EffectTarget,
+    },
+    // The next card the owner plays this turn costs less
+    Discount {
+        amount: u32,
+    },
+    // Permanently lowers the cost of every card currently in the owner's hand
+    ReduceHandCost {
+        amount: u32,
+    },
+    // Turns each targeted creature into a fresh copy of another card, by id
+    Transform {
+        into: String,
+        target: EffectTarget,
+    },
+    // Permanent empty mana crystals for the owner, on top of the one gained each turn
+    ManaCrystal {
+        amount: u32,
+    },
+    // The owner takes another turn straight after this one
+    ExtraTurn,
+    // The opponent's next turn is passed straight back to the owner
+    SkipOpponentTurn,
+    // Spendable mana for this turn only, without gaining a crystal
+    TemporaryMana {
+        amount: u32,
+    },
+}
+
+// A bare "buff +1/+1" buffs the card itself
+fn buff_target() -> EffectTarget {
+    EffectTarget::Source
+}
+
+impl CardEffect {
+    pub fn target(&self) -> Option<EffectTarget> {
+        match self {
+            CardEffect::Damage { target, .. } | CardEffect::Buff { target, .. } | CardEffect::Transform { target, .. } => Some(*target),
+            CardEffect::Draw { .. } | CardEffect::Discount { .. } | CardEffect::ReduceHandCost { .. }
+            | CardEffect::ManaCrystal { .. } | CardEffect::ExtraTurn | CardEffect::SkipOpponentTurn
+            | CardEffect::TemporaryMana { .. } => None,
+        }
+    }
+}
+
+impl CardDefinition {
+    // A fresh in-game copy of the card, `key` being its id in cards.toml
+    pub fn to_card_data(&self, key: &str, card_id: EntityID) -> CardData {
+        CardData {
+            card_id,
+            card_key: key.to_string(),
+            card_type: self.card_type(),
+            card_name: self.name.clone(),
+            card_text: self.text.clone(),
+            cost: self.cost,
+            power: self.power,
+            health: self.health,
+            keywords: self.keywords.clone(),
+            effects: self.effects.clone(),
+            aura: self.aura,
+            effective_cost: None,
+            revealed: false,
+        }
+    }
+
+    pub fn card_type(&self) -> CardType {
+        match self.c_type.as_str() {
+            "Ship" | "Station" => CardType::Creature,
+            "Weapon" | "Event" => CardType::Spell,
+            _ => CardType::Artifact,
+        }
+    }
+}
+
+// Glossary entry as sent to clients, cards refer to it by id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Keyword {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+}
+
+// Every field that is set must match; the default filter matches every card
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct CardFilter {
+    // Case-insensitive substring of the card name
+    pub name: Option<String>,
+    pub min_cost: Option<u32>,
+    pub max_cost: Option<u32>,
+    pub c_type: Option<String>,
+    // Keyword id from keywords.toml
+    pub keyword: Option<String>,
+    pub set: Option<String>,
+}
+
+impl CardFilter {
+    pub fn matches(&self, card: &CardDefinition) -> bool {
+        let name_matches = self.name.as_ref().map_or(true, |name| {
+            card.name.to_lowercase().contains(&name.trim().to_lowercase())
+        });
+
+        name_matches
+            && self.min_cost.map_or(true, |min| card.cost >= min)
+            && self.max_cost.map_or(true, |max| card.cost <= max)
+            && self.c_type.as_ref().map_or(true, |c_type| &card.c_type == c_type)
+            && self.keyword.as_ref().map_or(true, |keyword| card.keywords.contains(keyword))
+            && self.set.as_ref().map_or(true, |set| card.set.as_ref() == Some(set))
+    }
+}
@@ -0,0 +1,464 @@
+use std::time::Duration;
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use crate::cards::{CardDefinition, CardEffect, CardFilter, CostAura, Keyword};
+use crate::checksum::MirroredState;
+use crate::codec::{Codec, ConnectOptions, Frame};
+use crate::errors::GameError;
+use crate::messages::MessageCode;
+use crate::EntityID;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum MessageType {
+    Room {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sender: Option<String>,
+        content: String,
+    },
+    Private {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sender: Option<String>,
+        recipient: String,
+        content: String
+    },
+    // Sent as a code so the client can word it
+    System(MessageCode),
+}
+
+// Longest chat message the server passes on, in characters
+pub const MAX_CHAT_LENGTH: usize = 200;
+
+// Quick chat lines, the only thing players can say to a stranger besides room chat
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Emote {
+    Greetings,
+    WellPlayed,
+    Thanks,
+    Wow,
+    Oops,
+    Threaten,
+}
+
+impl Emote {
+    pub const ALL: [Emote; 6] = [Emote::Greetings, Emote::WellPlayed, Emote::Thanks, Emote::Wow, Emote::Oops, Emote::Threaten];
+
+    pub fn text(self) -> &'static str {
+        match self {
+            Emote::Greetings => "Greetings, commander.",
+            Emote::WellPlayed => "Well played.",
+            Emote::Thanks => "Thanks.",
+            Emote::Wow => "Wow!",
+            Emote::Oops => "Oops.",
+            Emote::Threaten => "Your fleet is mine.",
+        }
+    }
+}
+
+// Creature slots per side of the board
+pub const BOARD_SLOTS: usize = 7;
+
+// How often clients send Ping; the server drops connections that stay silent for several intervals
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CardData {
+    pub card_id: EntityID,
+    // Id from cards.toml, so clients can show the name and text in their own language
+    #[serde(default)]
+    pub card_key: String,
+    pub card_name: String,
+    pub card_text: String,
+    pub card_type: CardType,
+    pub cost: u32,
+    pub power: u32,
+    pub health: u32,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    #[serde(default)]
+    pub effects: Vec<CardEffect>,
+    #[serde(default)]
+    pub aura: Option<CostAura>,
+    // Cost after auras, discounts and enchantments; None means the printed cost applies
+    #[serde(default)]
+    pub effective_cost: Option<u32>,
+    // Set once the creature has attacked, which breaks stealth
+    #[serde(default)]
+    pub revealed: bool,
+}
+
+impl CardData {
+    // What playing the card actually takes out of the mana pool
+    pub fn play_cost(&self) -> u32 {
+        self.effective_cost.unwrap_or(self.cost)
+    }
+
+    pub fn has_keyword(&self, keyword: &str) -> bool {
+        self.keywords.iter().any(|k| k == keyword)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DeckSummary {
+    pub name: String,
+    pub card_count: u32,
+}
+
+// Cosmetic choices a player can pick from
+pub const AVATARS: &[&str] = &["pilot", "engineer", "android", "admiral", "smuggler"];
+pub const TITLES: &[&str] = &["Cadet", "Navigator", "Captain", "Commodore", "Void Walker"];
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PlayerProfile {
+    pub player_id: EntityID,
+    pub avatar: String,
+    pub title: String,
+}
+
+// What solving a puzzle takes, written in puzzle files as e.g. `objective = "win_this_turn"`
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PuzzleObjective {
+    // Bring the opponent to zero health
+    WinThisTurn,
+    // Leave no enemy creature on the board
+    ClearEnemyBoard,
+}
+
+impl PuzzleObjective {
+    pub fn text(self) -> &'static str {
+        match self {
+            PuzzleObjective::WinThisTurn => "Win this turn",
+            PuzzleObjective::ClearEnemyBoard => "Destroy every enemy creature",
+        }
+    }
+}
+
+// One step of a puzzle solution, the same actions a player takes in a match
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PuzzleMove {
+    PlayCard {
+        card_id: EntityID,
+        slot: Option<usize>,
+    },
+    Attack {
+        attacker: usize,
+        target: Option<usize>,
+    },
+}
+
+// A puzzle's starting position, everything the client needs to show it and build a solution
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PuzzleInfo {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub objective: PuzzleObjective,
+    // Most moves a solution may take
+    pub move_limit: usize,
+    pub mana: u32,
+    pub health: u32,
+    pub opponent_health: u32,
+    pub hand: Vec<CardData>,
+    pub board: Vec<Option<CardData>>,
+    pub opponent_board: Vec<Option<CardData>>,
+    // Solved by the receiver before
+    pub completed: bool,
+}
+
+// Public facts about a running match, nothing a spectator could use to cheat for a player
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MatchListing {
+    pub room_id: String,
+    pub players: Vec<PlayerProfile>,
+    pub turn: u32,
+}
+
+// What everyone at the table can see about one player
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PlayerSnapshot {
+    pub player_id: EntityID,
+    pub hand_count: u32,
+    pub deck_size: u32,
+    pub health: u32,
+    pub mana: u32,
+    pub max_mana: u32,
+    pub board: Vec<Option<CardData>>,
+    pub graveyard_size: u32,
+}
+
+// Everything a client needs to draw the table, built for one recipient so only their own hand is included
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GameStateSnapshot {
+    pub current_turn: Option<EntityID>,
+    pub time_bank: Option<Duration>,
+    // The recipient's hand with effective costs filled in
+    pub hand: Vec<CardData>,
+    pub players: Vec<PlayerSnapshot>,
+}
+
+// A player's leftover cards, shown to both sides after friendly games
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RevealedCards {
+    pub player_id: EntityID,
+    pub hand: Vec<CardData>,
+    // In draw order
+    pub deck: Vec<CardData>,
+}
+
+// Something that changed a creature's stats while it was in play, named after the card that caused it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum CreatureChange {
+    Buffed { power: u32, health: u32, source: String },
+    Damaged { amount: u32, source: String },
+    // Stats start over from the new card's printed ones
+    Transformed { from: String, source: String },
+}
+
+// How a creature on the board got its current stats, oldest change first
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CreatureHistory {
+    pub card_id: EntityID,
+    pub card_name: String,
+    pub printed_power: u32,
+    pub printed_health: u32,
+    pub changes: Vec<CreatureChange>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct TurnSummary {
+    pub turn: u32,
+    pub player_id: EntityID,
+    pub cards_played: Vec<String>,
+    pub damage: u32,
+    // Change in board power advantage for the turn player over their turn
+    pub swing: i32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct MatchSummary {
+    pub winner: Option<EntityID>,
+    pub turns: Vec<TurnSummary>,
+    // Index into turns of the largest absolute swing
+    pub biggest_swing: Option<usize>,
+    // Set when the game ended because this player gave up
+    #[serde(default)]
+    pub conceded_by: Option<EntityID>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatchOutcome {
+    Win,
+    Loss,
+    Draw,
+}
+
+// One finished game, from the point of view of the player asking
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RecentMatch {
+    // None if the player somehow finished a game alone
+    pub opponent: Option<EntityID>,
+    pub outcome: MatchOutcome,
+    pub turns: u32,
+    // The loser gave up rather than being beaten
+    #[serde(default)]
+    pub conceded: bool,
+    // Seconds since the Unix epoch
+    pub finished_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PlayerStats {
+    pub games_played: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    // Newest first
+    pub recent: Vec<RecentMatch>,
+}
+
+// How hard a practice bot plays
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BotDifficulty {
+    // One step of lookahead scored by the heuristic evaluation
+    #[default]
+    Normal,
+    // Searches the rest of its turn within a time budget per action
+    Hard,
+}
+
+// Rule variations whoever opens a private lobby picks for it; other rooms play with none
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LobbyRules {
+    // Combat damage beyond what kills the defending creature carries through to its owner
+    pub overkill_to_hero: bool,
+    // Show both players what was left in each hand and deck once the game ends
+    pub reveal_at_end: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum CardType {
+    Creature,
+    Spell,
+    Artifact,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum GameMessage {
+    // Game state updates (server -> client)
+    CurrentTurn(Option<EntityID>, Option<Duration>), // Who's turn is it, and their time bank if the room has one
+    TurnTimeRemaining {                // Sent as turns start and every few seconds; clients count down in between
+        remaining: Duration,
+        total: Duration,               // Full length of a turn
+    },
+    CardsDrawn(Vec<CardData>),             // Cards drawn
+    CardPlayed(EntityID, CardData, Option<usize>), // Who played what card, and the board slot for creatures
+    CardDiscarded(EntityID, CardData),     // Who discarded what card
+    CardsInDeck(u32),                  // Current deck count
+    BoardUpdate(EntityID, Vec<Option<CardData>>), // A player's whole board after card effects resolved
+    CardTransformed {                  // A creature on the board became a different card
+        player_id: EntityID,
+        slot: usize,
+        card: CardData,
+    },
+    HealthUpdate {                     // A player took damage
+        player_id: EntityID,
+        health: u32,
+    },
+    LoggedIn(EntityID),                // The account's persistent player id, answer to Register or Login
+    Pong(u64),                         // Echo of a Ping's timestamp
+    CodecSelected(Codec),              // Encoding the server picked from ConnectOptions, use it for requests too
+    GameStateSnapshot(GameStateSnapshot), // Whole table on join or resume, replacing whatever the client had
+    HandCosts(Vec<(EntityID, u32)>),   // Effective cost of every card in your hand, sent whenever a modifier changes
+    OpponentHandSize(u32),             // Cards in the opponent's hand, after they draw or play
+    GraveyardSize {                    // A player's graveyard grew
+        player_id: EntityID,
+        size: u32,
+    },
+    Graveyard {                        // Every card in a player's graveyard, answer to InspectGraveyard
+        player_id: EntityID,
+        cards: Vec<CardData>,
+    },
+    CreatureHistory {                  // Answer to InspectCreature
+        player_id: EntityID,
+        slot: usize,
+        history: CreatureHistory,
+    },
+    ManaUpdate {                       // A player's mana pool changed
+        player_id: EntityID,
+        current: u32,
+        max: u32,
+    },
+    GameOver(Option<EntityID>),        // Game ended, optional winner
+    MatchSummary(MatchSummary),        // Turn-by-turn recap, sent right after GameOver
+    FinalReveal(Vec<RevealedCards>),   // Both players' remaining hands and decks, last message of rooms that reveal
+    StateChecksum(u64),                // Checksum of the receiver's mirrored state (desync detection)
+    KeywordGlossary(Vec<Keyword>),     // Keyword definitions, sent once on connect
+    DeckList(Vec<DeckSummary>, Option<String>), // Saved decks and the default, after any deck change
+    CardSearchResults(Vec<(String, CardDefinition)>), // Card ids and definitions matching a SearchCards filter
+    Profile(PlayerProfile),            // The receiver's own profile, after connecting or changing it
+    MatchFound(Vec<PlayerProfile>),    // Both players' profiles once a room is full
+    MatchId(String),                   // Id the match's log is saved under, to quote in reports
+    MatchList(Vec<MatchListing>),      // Matches in progress, answer to ListMatches
+    StatsResponse(PlayerStats),        // Record and recent matches, answer to RequestStats
+    LobbyCreated(String),              // Join code for the private lobby you just opened
+    StarterRewards {                   // Sent once, on an account's first login
+        cards: BTreeMap<String, u32>,  // Card id to copies granted
+        packs: u32,
+    },
+    RematchRequested(EntityID),        // Who asked to play again, sent to both players after a game
+    PuzzleList(Vec<PuzzleInfo>),       // Every puzzle and whether you solved it, answer to ListPuzzles
+    PuzzleResult {                     // Answer to SubmitPuzzle
+        puzzle_id: String,
+        failure: Option<MessageCode>,  // Why the solution didn't work, None when it solved the puzzle
+    },
+
+    // Player actions (client -> server)
+    EndTurn,                           // Player wants to end their turn
+    DrawCard(u32),                     // Player wants to draw N cards
+    PlayCard {
+        card_id: EntityID,
+        target: Option<EntityID>,      // Optional target for card effects
+        slot: Option<usize>,           // Board slot, required for creatures
+    },
+    Attack {
+        attacker: usize,               // Slot of one of your creatures
+        target: Option<usize>,         // Enemy creature's slot, or None to attack the opponent
+    },
+    Concede,                           // Give up the game in progress, the opponent wins
+    MirroredState(MirroredState),      // Full mirrored state, uploaded after a checksum mismatch
+    Ping(u64),                         // Heartbeat carrying the client's clock in milliseconds, answered with Pong
+
+    // Chat functionality (bidirectional)
+    Chat(MessageType),                 // Chat messages work both ways
+    Emote {                            // Quick chat, shown as a bubble over the sender's portrait
+        emote: Emote,
+        sender: Option<EntityID>,      // Left empty by clients, filled in by the server
+    },
+
+    // Accounts (client -> server), the only requests a connection may send before logging in
+    Register {                         // Create an account and log in to it
+        username: String,
+        password: String,
+    },
+    Login {
+        username: String,
+        password: String,
+    },
+
+    // Game setup and management
+    JoinGame {                         // Player is ready to play, optionally bringing their own deck
+        deck: Option<Vec<String>>,     // Card ids from cards.toml; None keeps the saved or starter deck
+    },
+    LeaveGame,                         // Leave the room for good, conceding a game in progress
+    Resume,                            // Reconnected player wants back into their room; logging in proved who they are
+    CreateLobby(LobbyRules),           // Open a private room that matchmaking never fills, with its own rules
+    JoinLobby(String),                 // Join a friend's private room by its code
+    PlayVsBot(BotDifficulty),          // Practice game against a server-controlled opponent
+    Ready,                             // Accept a match from matchmaking; any game action counts too
+    RequestRematch,                    // Play again in the same room once the game is over
+    AcceptRematch,                     // Agree to the opponent's RequestRematch
+
+    // Deck management (client -> server)
+    SaveDeck {
+        name: String,
+        cards: Vec<String>,            // Card ids from cards.toml
+    },
+    DeleteDeck(String),
+    ListDecks,
+    SetDefaultDeck(String),
+    SelectDeck(String),                // Deck to bring into the next room
+    SearchCards(CardFilter),           // Query the card database
+    ListMatches,                       // Ask for the matches that can be spectated
+    InspectGraveyard(EntityID),        // Ask for the cards in a graveyard at your table
+    InspectCreature {                  // Ask how a creature at your table got its current stats
+        player_id: EntityID,
+        slot: usize,
+    },
+    RequestStats,                      // Ask for your own win/loss record and recent matches
+    SetProfile {
+        avatar: Option<String>,        // Entry from AVATARS, None keeps the current one
+        title: Option<String>,         // Entry from TITLES, None keeps the current one
+    },
+
+    // Puzzles (client -> server), played out by the server outside of any room
+    ListPuzzles,
+    SubmitPuzzle {
+        puzzle_id: String,
+        moves: Vec<PuzzleMove>,        // In order, all on the one turn the puzzle gives
+    },
+
+    // Error handling
+    Error(GameError),                  // A request was refused
+    ActionRejected(GameError),         // A game action broke the rules and was not applied
+}
+
+#[derive(Debug, Clone)]
+pub struct GameChannel;
+impl bevy_simplenet::ChannelPack for GameChannel
+{
+    type ConnectMsg = ConnectOptions;
+    type ServerMsg = Frame;
+    type ServerResponse = ();
+    type ClientMsg = ();
+    type ClientRequest = Frame;
+}
\ No newline at end of file
@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use crate::EntityID;
+
+// FNV-1a so client and server agree regardless of platform or std hasher changes
+pub struct StateHasher(u64);
+
+impl StateHasher {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    pub fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    pub fn write_u128(&mut self, value: u128) {
+        self.write(&value.to_le_bytes());
+    }
+
+    pub fn write_option(&mut self, value: Option<EntityID>) {
+        match value {
+            Some(id) => {
+                self.write(&[1]);
+                self.write_u128(id);
+            }
+            None => self.write(&[0]),
+        }
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Default for StateHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// The state a client mirrors from the server, uploaded in full when checksums disagree
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct MirroredState {
+    pub current_turn: Option<EntityID>,
+    pub hand: Vec<EntityID>,
+}
+
+impl MirroredState {
+    pub fn checksum(&self) -> u64 {
+        player_state_checksum(self.current_turn, &self.hand)
+    }
+
+    // Describes the first field where this state differs from the authoritative one
+    pub fn first_divergence(&self, authority: &MirroredState) -> Option<String> {
+        if self.current_turn != authority.current_turn {
+            return Some(format!("current_turn: client {:?}, server {:?}", self.current_turn, authority.current_turn));
+        }
+        if let Some((i, (ours, theirs))) = self.hand.iter().zip(authority.hand.iter())
+            .enumerate()
+            .find(|(_, (ours, theirs))| ours != theirs)
+        {
+            return Some(format!("hand[{i}]: client {ours}, server {theirs}"));
+        }
+        if self.hand.len() != authority.hand.len() {
+            return Some(format!("hand.len: client {}, server {}", self.hand.len(), authority.hand.len()));
+        }
+        None
+    }
+}
+
+// Checksum of the state a client mirrors from the server: whose turn it is and its hand in draw order
+pub fn player_state_checksum(current_turn: Option<EntityID>, hand: &[EntityID]) -> u64 {
+    let mut hasher = StateHasher::new();
+    hasher.write_option(current_turn);
+    hasher.write_u32(hand.len() as u32);
+    for &card_id in hand {
+        hasher.write_u128(card_id);
+    }
+    hasher.finish()
+}
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use crate::channel::GameMessage;
+
+// Encodings a connection can carry GameMessages in
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Codec {
+    // Left to the transport's own text encoding, always available
+    Json,
+    // MessagePack with named fields. Bincode isn't an option, it can't decode the internally tagged CardEffect
+    MessagePack,
+}
+
+// Codecs this build can encode and decode, preferred first
+#[cfg(feature = "binary_codec")]
+pub const SUPPORTED_CODECS: &[Codec] = &[Codec::MessagePack, Codec::Json];
+#[cfg(not(feature = "binary_codec"))]
+pub const SUPPORTED_CODECS: &[Codec] = &[Codec::Json];
+
+impl Codec {
+    // First codec the client offered that this build supports, JSON when there's no overlap
+    pub fn negotiate(offered: &[Codec]) -> Codec {
+        offered.iter().copied().find(|codec| SUPPORTED_CODECS.contains(codec)).unwrap_or(Codec::Json)
+    }
+
+    pub fn encode(self, message: GameMessage) -> Frame {
+        match self {
+            Codec::Json => Frame::Json(message),
+            #[cfg(feature = "binary_codec")]
+            Codec::MessagePack => match rmp_serde::to_vec_named(&message) {
+                Ok(bytes) => Frame::MessagePack(bytes),
+                // Nothing in GameMessage should fail to encode, but a larger frame beats a lost message
+                Err(_) => Frame::Json(message),
+            },
+            #[cfg(not(feature = "binary_codec"))]
+            Codec::MessagePack => Frame::Json(message),
+        }
+    }
+}
+
+// What actually crosses the socket. Every frame names its codec, so the receiver never has to track
+// what was negotiated to read it
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum Frame {
+    Json(GameMessage),
+    MessagePack(#[serde(with = "serde_bytes")] Vec<u8>),
+}
+
+impl Frame {
+    pub fn decode(self) -> Result<GameMessage, String> {
+        match self {
+            Frame::Json(message) => Ok(message),
+            #[cfg(feature = "binary_codec")]
+            Frame::MessagePack(bytes) => rmp_serde::from_slice(&bytes).map_err(|e| e.to_string()),
+            #[cfg(not(feature = "binary_codec"))]
+            Frame::MessagePack(_) => Err("MessagePack frames need the binary_codec feature".to_string()),
+        }
+    }
+}
+
+impl From<GameMessage> for Frame {
+    fn from(message: GameMessage) -> Self {
+        Frame::Json(message)
+    }
+}
+
+// Sent once when connecting, the server answers with CodecSelected
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ConnectOptions {
+    pub codecs: Vec<Codec>,
+}
+
+impl Default for ConnectOptions {
+    fn default() -> Self {
+        Self { codecs: SUPPORTED_CODECS.to_vec() }
+    }
+}
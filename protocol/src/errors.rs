@@ -0,0 +1,80 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::messages::MessageCode;
+use crate::text;
+
+// Why the server refused a request. Clients pick their own wording from the code and react to it,
+// the detail only adds specifics like card names or costs, as a code of its own
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    // Game actions
+    NotYourTurn,
+    NotSeated,
+    GameOver,
+    CardNotInHand,
+    InsufficientMana,
+    InvalidSlot,
+    SlotOccupied,
+    InvalidTarget,
+    CannotAttack,
+    // Rooms and lobbies
+    RoomFull,
+    InGame,
+    LobbyNotFound,
+    AlreadyInLobby,
+    NotAtTable,
+    MatchTimedOut,
+    QueueCooldown,
+    RematchUnavailable,
+    EmoteCooldown,
+    ChatRejected,
+    // Decks and cards
+    DeckLocked,
+    DeckRejected,
+    SearchFailed,
+    PuzzleUnavailable,
+    // Sessions and accounts
+    NotInitialized,
+    SessionExpired,
+    AlreadyConnected,
+    VersionMismatch,
+    ProfileRejected,
+    NotLoggedIn,
+    InvalidCredentials,
+    UsernameTaken,
+    AccountRejected,
+    StatsUnavailable,
+    // Escalating answers to repeated invalid actions
+    InvalidActionWarning,
+    RequestCooldown,
+    ForcedConcede,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GameError {
+    pub code: ErrorCode,
+    pub detail: Option<MessageCode>,
+}
+
+impl GameError {
+    pub fn new(code: ErrorCode) -> Self {
+        Self { code, detail: None }
+    }
+
+    pub fn with_detail(code: ErrorCode, detail: MessageCode) -> Self {
+        Self { code, detail: Some(detail) }
+    }
+}
+
+impl From<ErrorCode> for GameError {
+    fn from(code: ErrorCode) -> Self {
+        Self::new(code)
+    }
+}
+
+// English text, for logs and tools; clients word it in the player's language through `text`
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&text::game_error_text(self, "en"))
+    }
+}
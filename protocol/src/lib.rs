@@ -0,0 +1,19 @@
+// Everything client and server exchange: the channel, its messages, errors and the card data they carry.
+// Kept free of gameplay so tools and the wasm client can depend on it alone
+pub mod channel;
+pub mod cards;
+pub mod checksum;
+pub mod errors;
+pub mod messages;
+pub mod text;
+pub mod codec;
+#[cfg(feature = "test_support")]
+pub mod test_support;
+
+pub type EntityID = u128;
+
+pub mod api {
+    // Checked at connect. Only the part of the protocol version that changes on breaking changes is included,
+    // so patch releases still talk to each other
+    pub const API_VERSION: &str = concat!("v", env!("CARGO_PKG_VERSION_MAJOR"), ".", env!("CARGO_PKG_VERSION_MINOR"));
+}
@@ -0,0 +1,64 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::errors::GameError;
+use crate::text;
+
+// Text the server wants shown to a player, sent as a code plus the values to fill in so each client can word it
+// in the player's language. Card names, deck names and usernames are passed through as they are
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum MessageCode {
+    // Matchmaking and rooms
+    QueueCooldownLeft { seconds: u64 },
+    CalledOffWithCooldown { seconds: u64 },
+    OpponentNeverReadied,
+    OpponentDisconnected { grace_seconds: u64 },
+    OpponentReconnected,
+    RoomClosedIdle,
+    FinishGameFirst,
+    NoLobbyWithCode { code: String },
+    PlayerNotAtTable { name: String },
+    // Accounts and profiles
+    UsernameRules { min: usize, max: usize },
+    PasswordTooShort { min: usize },
+    ConnectionLoggedIn,
+    LoggedInElsewhere,
+    UnknownAvatar { avatar: String },
+    UnknownTitle { title: String },
+    // Decks
+    UnknownCard { card: String },
+    TokenInDeck { card: String },
+    DeckSize { min: usize, max: usize, actual: usize },
+    TooManyCopies { max: usize, card: String },
+    EmptyDeckName,
+    TooManyDecks { max: usize },
+    NoDeckNamed { name: String },
+    CardsUnavailable,
+    StarterDeckInstead { reason: Box<MessageCode> },
+    // Game actions
+    ManaCost { cost: u32, available: u32 },
+    SlotOutOfRange { max_slot: usize },
+    SlotTaken { slot: usize },
+    NoCreatureInSlot,
+    NoEnemyInSlot,
+    TargetNotInPlay,
+    NotReadyToAttack { card: String },
+    Stealthed { card: String },
+    Elusive { card: String },
+    // Puzzles
+    UnknownPuzzle { id: String },
+    PuzzleMoveLimit { limit: usize },
+    PuzzleMoveRefused { step: usize, reason: Box<GameError> },
+    PuzzleObjectiveMissed,
+    // Chat and penalties
+    SystemChatReserved,
+    ChatTooLong { max: usize },
+    ChatCooldown,
+    RequestCooldownFor { seconds: u64 },
+}
+
+// English text, for logs and tools; clients word it in the player's language through `text`
+impl fmt::Display for MessageCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&text::message_text(self, "en"))
+    }
+}
@@ -0,0 +1,61 @@
+use crate::cards::{CardEffect, CostAura};
+use crate::channel::{CardData, CardType};
+use crate::EntityID;
+
+// Cards for tests: a free 1/1 creature named after its id, with only what a test cares about changed
+pub struct CardBuilder(CardData);
+
+impl CardBuilder {
+    pub fn new(card_id: EntityID) -> Self {
+        Self(CardData {
+            card_id,
+            card_key: String::new(),
+            card_name: format!("Card {card_id}"),
+            card_text: String::new(),
+            card_type: CardType::Creature,
+            cost: 0,
+            power: 1,
+            health: 1,
+            keywords: Vec::new(),
+            effects: Vec::new(),
+            aura: None,
+            effective_cost: None,
+            revealed: false,
+        })
+    }
+
+    pub fn card_type(mut self, card_type: CardType) -> Self {
+        self.0.card_type = card_type;
+        self
+    }
+
+    pub fn cost(mut self, cost: u32) -> Self {
+        self.0.cost = cost;
+        self
+    }
+
+    pub fn stats(mut self, power: u32, health: u32) -> Self {
+        self.0.power = power;
+        self.0.health = health;
+        self
+    }
+
+    pub fn keyword(mut self, keyword: &str) -> Self {
+        self.0.keywords.push(keyword.to_string());
+        self
+    }
+
+    pub fn effect(mut self, effect: CardEffect) -> Self {
+        self.0.effects.push(effect);
+        self
+    }
+
+    pub fn aura(mut self, aura: CostAura) -> Self {
+        self.0.aura = Some(aura);
+        self
+    }
+
+    pub fn build(self) -> CardData {
+        self.0
+    }
+}
@@ -0,0 +1,290 @@
+use crate::errors::{ErrorCode, GameError};
+use crate::messages::MessageCode;
+
+// Player-facing wording for every error and message code, one table per language. `language` is a two letter
+// code like "fr"; anything without a table of its own gets English, which is also what Display shows
+
+pub fn error_text(code: ErrorCode, language: &str) -> &'static str {
+    match language {
+        "fr" => french_error(code),
+        "de" => german_error(code),
+        _ => english_error(code),
+    }
+}
+
+pub fn message_text(code: &MessageCode, language: &str) -> String {
+    match language {
+        "fr" => french_message(code),
+        "de" => german_message(code),
+        _ => english_message(code),
+    }
+}
+
+// The detail already reads as a full sentence where there is one
+pub fn game_error_text(error: &GameError, language: &str) -> String {
+    match &error.detail {
+        Some(detail) => message_text(detail, language),
+        None => error_text(error.code, language).to_string(),
+    }
+}
+
+fn english_error(code: ErrorCode) -> &'static str {
+    match code {
+        ErrorCode::NotYourTurn => "It's not your turn",
+        ErrorCode::NotSeated => "You're not seated in this game",
+        ErrorCode::GameOver => "The game is over",
+        ErrorCode::CardNotInHand => "Card is not in your hand",
+        ErrorCode::InsufficientMana => "Not enough mana",
+        ErrorCode::InvalidSlot => "That board slot doesn't exist",
+        ErrorCode::SlotOccupied => "That board slot is already occupied",
+        ErrorCode::InvalidTarget => "That target can't be chosen",
+        ErrorCode::CannotAttack => "That creature can't attack",
+        ErrorCode::RoomFull => "That room is full",
+        ErrorCode::InGame => "Finish your current game first",
+        ErrorCode::LobbyNotFound => "No open lobby with that code",
+        ErrorCode::AlreadyInLobby => "You're already in that lobby",
+        ErrorCode::NotAtTable => "That player isn't at your table",
+        ErrorCode::MatchTimedOut => "The match was called off because you never readied up",
+        ErrorCode::QueueCooldown => "You let too many matches time out, wait before queueing again",
+        ErrorCode::RematchUnavailable => "There is no finished game with the same opponent to play again",
+        ErrorCode::EmoteCooldown => "Slow down, you're sending emotes too quickly",
+        ErrorCode::ChatRejected => "That chat message can't be sent",
+        ErrorCode::DeckLocked => "The game has already started, your deck is locked in",
+        ErrorCode::DeckRejected => "That deck can't be used",
+        ErrorCode::SearchFailed => "Card search failed",
+        ErrorCode::PuzzleUnavailable => "That puzzle isn't available",
+        ErrorCode::NotInitialized => "Cannot process request - player not initialized",
+        ErrorCode::SessionExpired => "Nothing to resume, the session may have expired",
+        ErrorCode::AlreadyConnected => "Already connected from another session",
+        ErrorCode::VersionMismatch => "Client and server versions don't match, please update",
+        ErrorCode::ProfileRejected => "That profile change isn't allowed",
+        ErrorCode::NotLoggedIn => "Log in first",
+        ErrorCode::InvalidCredentials => "Wrong username or password",
+        ErrorCode::UsernameTaken => "That username is already taken",
+        ErrorCode::AccountRejected => "That username or password can't be used",
+        ErrorCode::StatsUnavailable => "This server doesn't keep match history",
+        ErrorCode::InvalidActionWarning => "Invalid action. Repeated invalid actions will be penalised",
+        ErrorCode::RequestCooldown => "Too many invalid actions, requests are ignored for a while",
+        ErrorCode::ForcedConcede => "Too many invalid actions, you have conceded the game",
+    }
+}
+
+fn english_message(code: &MessageCode) -> String {
+    match code {
+        MessageCode::QueueCooldownLeft { seconds } => format!("You let too many matches time out, you can queue again in {seconds} seconds"),
+        MessageCode::CalledOffWithCooldown { seconds } => format!("The match was called off because you never readied up, you can queue again in {seconds} seconds"),
+        MessageCode::OpponentNeverReadied => "Your opponent never readied up, you're first in line for the next match".to_string(),
+        MessageCode::OpponentDisconnected { grace_seconds } => format!("Your opponent disconnected, waiting {grace_seconds} seconds for them to return"),
+        MessageCode::OpponentReconnected => "Your opponent reconnected".to_string(),
+        MessageCode::RoomClosedIdle => "Room closed due to inactivity".to_string(),
+        MessageCode::FinishGameFirst => "Finish your current game before moving to a lobby".to_string(),
+        MessageCode::NoLobbyWithCode { code } => format!("No open lobby with code {code}"),
+        MessageCode::PlayerNotAtTable { name } => format!("{name} isn't at your table"),
+        MessageCode::UsernameRules { min, max } => format!("Usernames are {min} to {max} letters, digits, '_' or '-'"),
+        MessageCode::PasswordTooShort { min } => format!("Passwords need at least {min} characters"),
+        MessageCode::ConnectionLoggedIn => "This connection is already logged in".to_string(),
+        MessageCode::LoggedInElsewhere => "Your account logged in from another connection".to_string(),
+        MessageCode::UnknownAvatar { avatar } => format!("Unknown avatar '{avatar}'"),
+        MessageCode::UnknownTitle { title } => format!("Unknown title '{title}'"),
+        MessageCode::UnknownCard { card } => format!("Unknown card '{card}'"),
+        MessageCode::TokenInDeck { card } => format!("{card} can't be put in a deck"),
+        MessageCode::DeckSize { min, max, actual } => format!("Decks need {min} to {max} cards, this one has {actual}"),
+        MessageCode::TooManyCopies { max, card } => format!("At most {max} copies of {card} are allowed"),
+        MessageCode::EmptyDeckName => "Deck name cannot be empty".to_string(),
+        MessageCode::TooManyDecks { max } => format!("Cannot save more than {max} decks"),
+        MessageCode::NoDeckNamed { name } => format!("No deck named '{name}'"),
+        MessageCode::CardsUnavailable => "The card list couldn't be loaded".to_string(),
+        MessageCode::StarterDeckInstead { reason } => format!("Your deck can't be used, playing the starter deck instead: {}", english_message(reason)),
+        MessageCode::ManaCost { cost, available } => format!("Not enough mana: costs {cost}, you have {available}"),
+        MessageCode::SlotOutOfRange { max_slot } => format!("Creatures must be played into a slot from 0 to {max_slot}"),
+        MessageCode::SlotTaken { slot } => format!("Board slot {slot} is already occupied"),
+        MessageCode::NoCreatureInSlot => "There is no creature in that slot".to_string(),
+        MessageCode::NoEnemyInSlot => "There is no enemy creature in that slot".to_string(),
+        MessageCode::TargetNotInPlay => "That target isn't in play".to_string(),
+        MessageCode::NotReadyToAttack { card } => format!("{card} can't attack yet this turn"),
+        MessageCode::Stealthed { card } => format!("{card} is in stealth and can't be targeted"),
+        MessageCode::Elusive { card } => format!("{card} is elusive and can't be targeted by card effects"),
+        MessageCode::UnknownPuzzle { id } => format!("No puzzle named '{id}'"),
+        MessageCode::PuzzleMoveLimit { limit } => format!("This puzzle must be solved in {limit} moves or fewer"),
+        MessageCode::PuzzleMoveRefused { step, reason } => format!("Move {step} isn't allowed: {}", game_error_text(reason, "en")),
+        MessageCode::PuzzleObjectiveMissed => "The objective wasn't reached".to_string(),
+        MessageCode::SystemChatReserved => "Only the server sends system messages".to_string(),
+        MessageCode::ChatTooLong { max } => format!("Chat messages are at most {max} characters"),
+        MessageCode::ChatCooldown => "Slow down, you're sending messages too quickly".to_string(),
+        MessageCode::RequestCooldownFor { seconds } => format!("Too many invalid actions, requests are ignored for {seconds} seconds"),
+    }
+}
+
+fn french_error(code: ErrorCode) -> &'static str {
+    match code {
+        ErrorCode::NotYourTurn => "Ce n'est pas votre tour",
+        ErrorCode::NotSeated => "Vous ne participez pas à cette partie",
+        ErrorCode::GameOver => "La partie est terminée",
+        ErrorCode::CardNotInHand => "Cette carte n'est pas dans votre main",
+        ErrorCode::InsufficientMana => "Pas assez de mana",
+        ErrorCode::InvalidSlot => "Cet emplacement n'existe pas",
+        ErrorCode::SlotOccupied => "Cet emplacement est déjà occupé",
+        ErrorCode::InvalidTarget => "Cette cible ne peut pas être choisie",
+        ErrorCode::CannotAttack => "Cette créature ne peut pas attaquer",
+        ErrorCode::RoomFull => "Cette salle est pleine",
+        ErrorCode::InGame => "Terminez d'abord votre partie en cours",
+        ErrorCode::LobbyNotFound => "Aucun salon ouvert avec ce code",
+        ErrorCode::AlreadyInLobby => "Vous êtes déjà dans ce salon",
+        ErrorCode::NotAtTable => "Ce joueur n'est pas à votre table",
+        ErrorCode::MatchTimedOut => "La partie a été annulée car vous n'avez jamais confirmé être prêt",
+        ErrorCode::QueueCooldown => "Trop de parties expirées, attendez avant de rejoindre la file",
+        ErrorCode::RematchUnavailable => "Aucune partie terminée contre le même adversaire à rejouer",
+        ErrorCode::EmoteCooldown => "Doucement, vous envoyez des emotes trop vite",
+        ErrorCode::ChatRejected => "Ce message ne peut pas être envoyé",
+        ErrorCode::DeckLocked => "La partie a déjà commencé, votre deck est verrouillé",
+        ErrorCode::DeckRejected => "Ce deck ne peut pas être utilisé",
+        ErrorCode::SearchFailed => "La recherche de cartes a échoué",
+        ErrorCode::PuzzleUnavailable => "Ce puzzle n'est pas disponible",
+        ErrorCode::NotInitialized => "Requête impossible, le joueur n'est pas initialisé",
+        ErrorCode::SessionExpired => "Rien à reprendre, la session a peut-être expiré",
+        ErrorCode::AlreadyConnected => "Déjà connecté depuis une autre session",
+        ErrorCode::VersionMismatch => "Les versions du client et du serveur diffèrent, veuillez mettre à jour",
+        ErrorCode::ProfileRejected => "Cette modification du profil n'est pas autorisée",
+        ErrorCode::NotLoggedIn => "Connectez-vous d'abord",
+        ErrorCode::InvalidCredentials => "Nom d'utilisateur ou mot de passe incorrect",
+        ErrorCode::UsernameTaken => "Ce nom d'utilisateur est déjà pris",
+        ErrorCode::AccountRejected => "Ce nom d'utilisateur ou ce mot de passe ne peut pas être utilisé",
+        ErrorCode::StatsUnavailable => "Ce serveur ne conserve pas l'historique des parties",
+        ErrorCode::InvalidActionWarning => "Action invalide. Les actions invalides répétées seront sanctionnées",
+        ErrorCode::RequestCooldown => "Trop d'actions invalides, vos requêtes sont ignorées pendant un moment",
+        ErrorCode::ForcedConcede => "Trop d'actions invalides, vous avez abandonné la partie",
+    }
+}
+
+fn french_message(code: &MessageCode) -> String {
+    match code {
+        MessageCode::QueueCooldownLeft { seconds } => format!("Trop de parties expirées, vous pourrez rejoindre la file dans {seconds} secondes"),
+        MessageCode::CalledOffWithCooldown { seconds } => format!("La partie a été annulée car vous n'avez jamais confirmé être prêt, vous pourrez rejoindre la file dans {seconds} secondes"),
+        MessageCode::OpponentNeverReadied => "Votre adversaire n'a jamais confirmé, vous êtes prioritaire pour la prochaine partie".to_string(),
+        MessageCode::OpponentDisconnected { grace_seconds } => format!("Votre adversaire s'est déconnecté, attente de son retour pendant {grace_seconds} secondes"),
+        MessageCode::OpponentReconnected => "Votre adversaire s'est reconnecté".to_string(),
+        MessageCode::RoomClosedIdle => "Salle fermée pour inactivité".to_string(),
+        MessageCode::FinishGameFirst => "Terminez votre partie en cours avant de rejoindre un salon".to_string(),
+        MessageCode::NoLobbyWithCode { code } => format!("Aucun salon ouvert avec le code {code}"),
+        MessageCode::PlayerNotAtTable { name } => format!("{name} n'est pas à votre table"),
+        MessageCode::UsernameRules { min, max } => format!("Les noms d'utilisateur font de {min} à {max} lettres, chiffres, '_' ou '-'"),
+        MessageCode::PasswordTooShort { min } => format!("Les mots de passe doivent faire au moins {min} caractères"),
+        MessageCode::ConnectionLoggedIn => "Cette connexion est déjà identifiée".to_string(),
+        MessageCode::LoggedInElsewhere => "Votre compte s'est connecté depuis une autre connexion".to_string(),
+        MessageCode::UnknownAvatar { avatar } => format!("Avatar inconnu '{avatar}'"),
+        MessageCode::UnknownTitle { title } => format!("Titre inconnu '{title}'"),
+        MessageCode::UnknownCard { card } => format!("Carte inconnue '{card}'"),
+        MessageCode::TokenInDeck { card } => format!("{card} ne peut pas être mis dans un deck"),
+        MessageCode::DeckSize { min, max, actual } => format!("Un deck doit avoir de {min} à {max} cartes, celui-ci en a {actual}"),
+        MessageCode::TooManyCopies { max, card } => format!("Au plus {max} exemplaires de {card} sont autorisés"),
+        MessageCode::EmptyDeckName => "Le nom du deck ne peut pas être vide".to_string(),
+        MessageCode::TooManyDecks { max } => format!("Impossible d'enregistrer plus de {max} decks"),
+        MessageCode::NoDeckNamed { name } => format!("Aucun deck nommé '{name}'"),
+        MessageCode::CardsUnavailable => "La liste des cartes n'a pas pu être chargée".to_string(),
+        MessageCode::StarterDeckInstead { reason } => format!("Votre deck ne peut pas être utilisé, vous jouez le deck de départ : {}", french_message(reason)),
+        MessageCode::ManaCost { cost, available } => format!("Pas assez de mana : coûte {cost}, vous avez {available}"),
+        MessageCode::SlotOutOfRange { max_slot } => format!("Les créatures se jouent sur un emplacement de 0 à {max_slot}"),
+        MessageCode::SlotTaken { slot } => format!("L'emplacement {slot} est déjà occupé"),
+        MessageCode::NoCreatureInSlot => "Il n'y a pas de créature à cet emplacement".to_string(),
+        MessageCode::NoEnemyInSlot => "Il n'y a pas de créature ennemie à cet emplacement".to_string(),
+        MessageCode::TargetNotInPlay => "Cette cible n'est pas en jeu".to_string(),
+        MessageCode::NotReadyToAttack { card } => format!("{card} ne peut pas encore attaquer ce tour-ci"),
+        MessageCode::Stealthed { card } => format!("{card} est camouflé et ne peut pas être ciblé"),
+        MessageCode::Elusive { card } => format!("{card} est insaisissable et ne peut pas être ciblé par des effets de carte"),
+        MessageCode::UnknownPuzzle { id } => format!("Aucun puzzle nommé '{id}'"),
+        MessageCode::PuzzleMoveLimit { limit } => format!("Ce puzzle doit être résolu en {limit} coups maximum"),
+        MessageCode::PuzzleMoveRefused { step, reason } => format!("Le coup {step} n'est pas autorisé : {}", game_error_text(reason, "fr")),
+        MessageCode::PuzzleObjectiveMissed => "L'objectif n'a pas été atteint".to_string(),
+        MessageCode::SystemChatReserved => "Seul le serveur envoie des messages système".to_string(),
+        MessageCode::ChatTooLong { max } => format!("Les messages font au plus {max} caractères"),
+        MessageCode::ChatCooldown => "Doucement, vous envoyez des messages trop vite".to_string(),
+        MessageCode::RequestCooldownFor { seconds } => format!("Trop d'actions invalides, vos requêtes sont ignorées pendant {seconds} secondes"),
+    }
+}
+
+fn german_error(code: ErrorCode) -> &'static str {
+    match code {
+        ErrorCode::NotYourTurn => "Du bist nicht am Zug",
+        ErrorCode::NotSeated => "Du spielst in dieser Partie nicht mit",
+        ErrorCode::GameOver => "Die Partie ist vorbei",
+        ErrorCode::CardNotInHand => "Die Karte ist nicht auf deiner Hand",
+        ErrorCode::InsufficientMana => "Nicht genug Mana",
+        ErrorCode::InvalidSlot => "Dieses Feld gibt es nicht",
+        ErrorCode::SlotOccupied => "Dieses Feld ist schon belegt",
+        ErrorCode::InvalidTarget => "Dieses Ziel kann nicht gewählt werden",
+        ErrorCode::CannotAttack => "Diese Kreatur kann nicht angreifen",
+        ErrorCode::RoomFull => "Dieser Raum ist voll",
+        ErrorCode::InGame => "Beende zuerst deine laufende Partie",
+        ErrorCode::LobbyNotFound => "Keine offene Lobby mit diesem Code",
+        ErrorCode::AlreadyInLobby => "Du bist schon in dieser Lobby",
+        ErrorCode::NotAtTable => "Dieser Spieler sitzt nicht an deinem Tisch",
+        ErrorCode::MatchTimedOut => "Die Partie wurde abgesagt, weil du nie bereit warst",
+        ErrorCode::QueueCooldown => "Zu viele Partien sind verfallen, warte bevor du dich wieder anstellst",
+        ErrorCode::RematchUnavailable => "Es gibt keine beendete Partie gegen denselben Gegner zum Wiederholen",
+        ErrorCode::EmoteCooldown => "Langsam, du sendest Emotes zu schnell",
+        ErrorCode::ChatRejected => "Diese Chatnachricht kann nicht gesendet werden",
+        ErrorCode::DeckLocked => "Die Partie hat schon begonnen, dein Deck steht fest",
+        ErrorCode::DeckRejected => "Dieses Deck kann nicht verwendet werden",
+        ErrorCode::SearchFailed => "Die Kartensuche ist fehlgeschlagen",
+        ErrorCode::PuzzleUnavailable => "Dieses Rätsel ist nicht verfügbar",
+        ErrorCode::NotInitialized => "Anfrage nicht möglich, der Spieler ist nicht initialisiert",
+        ErrorCode::SessionExpired => "Nichts fortzusetzen, die Sitzung ist wohl abgelaufen",
+        ErrorCode::AlreadyConnected => "Schon aus einer anderen Sitzung verbunden",
+        ErrorCode::VersionMismatch => "Client- und Serverversion passen nicht zusammen, bitte aktualisieren",
+        ErrorCode::ProfileRejected => "Diese Profiländerung ist nicht erlaubt",
+        ErrorCode::NotLoggedIn => "Melde dich zuerst an",
+        ErrorCode::InvalidCredentials => "Falscher Benutzername oder falsches Passwort",
+        ErrorCode::UsernameTaken => "Dieser Benutzername ist schon vergeben",
+        ErrorCode::AccountRejected => "Dieser Benutzername oder dieses Passwort kann nicht verwendet werden",
+        ErrorCode::StatsUnavailable => "Dieser Server speichert keinen Spielverlauf",
+        ErrorCode::InvalidActionWarning => "Ungültige Aktion. Wiederholte ungültige Aktionen werden bestraft",
+        ErrorCode::RequestCooldown => "Zu viele ungültige Aktionen, Anfragen werden eine Weile ignoriert",
+        ErrorCode::ForcedConcede => "Zu viele ungültige Aktionen, du hast die Partie aufgegeben",
+    }
+}
+
+fn german_message(code: &MessageCode) -> String {
+    match code {
+        MessageCode::QueueCooldownLeft { seconds } => format!("Zu viele Partien sind verfallen, du kannst dich in {seconds} Sekunden wieder anstellen"),
+        MessageCode::CalledOffWithCooldown { seconds } => format!("Die Partie wurde abgesagt, weil du nie bereit warst, du kannst dich in {seconds} Sekunden wieder anstellen"),
+        MessageCode::OpponentNeverReadied => "Dein Gegner war nie bereit, du bist als Nächstes an der Reihe".to_string(),
+        MessageCode::OpponentDisconnected { grace_seconds } => format!("Dein Gegner hat die Verbindung verloren, es wird {grace_seconds} Sekunden auf ihn gewartet"),
+        MessageCode::OpponentReconnected => "Dein Gegner ist wieder verbunden".to_string(),
+        MessageCode::RoomClosedIdle => "Raum wegen Inaktivität geschlossen".to_string(),
+        MessageCode::FinishGameFirst => "Beende deine laufende Partie, bevor du in eine Lobby wechselst".to_string(),
+        MessageCode::NoLobbyWithCode { code } => format!("Keine offene Lobby mit dem Code {code}"),
+        MessageCode::PlayerNotAtTable { name } => format!("{name} sitzt nicht an deinem Tisch"),
+        MessageCode::UsernameRules { min, max } => format!("Benutzernamen haben {min} bis {max} Buchstaben, Ziffern, '_' oder '-'"),
+        MessageCode::PasswordTooShort { min } => format!("Passwörter brauchen mindestens {min} Zeichen"),
+        MessageCode::ConnectionLoggedIn => "Diese Verbindung ist schon angemeldet".to_string(),
+        MessageCode::LoggedInElsewhere => "Dein Konto hat sich über eine andere Verbindung angemeldet".to_string(),
+        MessageCode::UnknownAvatar { avatar } => format!("Unbekannter Avatar '{avatar}'"),
+        MessageCode::UnknownTitle { title } => format!("Unbekannter Titel '{title}'"),
+        MessageCode::UnknownCard { card } => format!("Unbekannte Karte '{card}'"),
+        MessageCode::TokenInDeck { card } => format!("{card} kann nicht in ein Deck gelegt werden"),
+        MessageCode::DeckSize { min, max, actual } => format!("Decks brauchen {min} bis {max} Karten, dieses hat {actual}"),
+        MessageCode::TooManyCopies { max, card } => format!("Höchstens {max} Exemplare von {card} sind erlaubt"),
+        MessageCode::EmptyDeckName => "Der Deckname darf nicht leer sein".to_string(),
+        MessageCode::TooManyDecks { max } => format!("Mehr als {max} Decks können nicht gespeichert werden"),
+        MessageCode::NoDeckNamed { name } => format!("Kein Deck namens '{name}'"),
+        MessageCode::CardsUnavailable => "Die Kartenliste konnte nicht geladen werden".to_string(),
+        MessageCode::StarterDeckInstead { reason } => format!("Dein Deck kann nicht verwendet werden, du spielst stattdessen das Startdeck: {}", german_message(reason)),
+        MessageCode::ManaCost { cost, available } => format!("Nicht genug Mana: kostet {cost}, du hast {available}"),
+        MessageCode::SlotOutOfRange { max_slot } => format!("Kreaturen werden auf ein Feld von 0 bis {max_slot} gespielt"),
+        MessageCode::SlotTaken { slot } => format!("Feld {slot} ist schon belegt"),
+        MessageCode::NoCreatureInSlot => "Auf diesem Feld steht keine Kreatur".to_string(),
+        MessageCode::NoEnemyInSlot => "Auf diesem Feld steht keine gegnerische Kreatur".to_string(),
+        MessageCode::TargetNotInPlay => "Dieses Ziel ist nicht im Spiel".to_string(),
+        MessageCode::NotReadyToAttack { card } => format!("{card} kann in diesem Zug noch nicht angreifen"),
+        MessageCode::Stealthed { card } => format!("{card} ist getarnt und kann nicht anvisiert werden"),
+        MessageCode::Elusive { card } => format!("{card} ist flüchtig und kann nicht von Karteneffekten anvisiert werden"),
+        MessageCode::UnknownPuzzle { id } => format!("Kein Rätsel namens '{id}'"),
+        MessageCode::PuzzleMoveLimit { limit } => format!("Dieses Rätsel muss in höchstens {limit} Zügen gelöst werden"),
+        MessageCode::PuzzleMoveRefused { step, reason } => format!("Zug {step} ist nicht erlaubt: {}", game_error_text(reason, "de")),
+        MessageCode::PuzzleObjectiveMissed => "Das Ziel wurde nicht erreicht".to_string(),
+        MessageCode::SystemChatReserved => "Nur der Server sendet Systemnachrichten".to_string(),
+        MessageCode::ChatTooLong { max } => format!("Chatnachrichten haben höchstens {max} Zeichen"),
+        MessageCode::ChatCooldown => "Langsam, du sendest Nachrichten zu schnell".to_string(),
+        MessageCode::RequestCooldownFor { seconds } => format!("Zu viele ungültige Aktionen, Anfragen werden {seconds} Sekunden lang ignoriert"),
+    }
+}
@@ -0,0 +1,75 @@
+use std::time::Duration;
+use protocol::cards::{CardEffect, EffectTarget};
+use protocol::channel::{CardData, CardType, GameMessage};
+use protocol::codec::{Codec, ConnectOptions, Frame, SUPPORTED_CODECS};
+
+fn sample() -> GameMessage {
+    GameMessage::CardsDrawn(vec![CardData {
+        card_id: 7,
+        card_key: "patrol_frigate".to_string(),
+        card_name: "Patrol Frigate".to_string(),
+        card_text: "When played, deal 2 damage to the opposing enemy".to_string(),
+        card_type: CardType::Creature,
+        cost: 3,
+        power: 2,
+        health: 3,
+        keywords: vec!["shielded".to_string()],
+        // Internally tagged, the part binary formats trip over
+        effects: vec![CardEffect::Damage { amount: 2, target: EffectTarget::OpposingEnemy }],
+        aura: None,
+        effective_cost: Some(2),
+        revealed: false,
+    }])
+}
+
+// Through the transport's text encoding, the way simplenet sends every frame
+fn over_the_wire(frame: Frame) -> GameMessage {
+    let text = serde_json::to_string(&frame).unwrap();
+    serde_json::from_str::<Frame>(&text).unwrap().decode().unwrap()
+}
+
+#[test]
+fn clients_offering_nothing_known_get_json() {
+    assert_eq!(Codec::negotiate(&[]), Codec::Json);
+    assert_eq!(Codec::negotiate(&[Codec::Json]), Codec::Json);
+}
+
+#[test]
+fn the_default_offer_is_everything_this_build_supports() {
+    let offered = ConnectOptions::default().codecs;
+    assert_eq!(offered, SUPPORTED_CODECS);
+    assert_eq!(Codec::negotiate(&offered), SUPPORTED_CODECS[0]);
+}
+
+#[test]
+fn json_frames_round_trip() {
+    let frame = Codec::Json.encode(sample());
+    assert!(matches!(frame, Frame::Json(_)));
+    assert_eq!(format!("{:?}", over_the_wire(frame)), format!("{:?}", sample()));
+
+    let turn = GameMessage::CurrentTurn(Some(1), Some(Duration::from_secs(75)));
+    assert_eq!(format!("{:?}", over_the_wire(turn.clone().into())), format!("{turn:?}"));
+}
+
+#[cfg(feature = "binary_codec")]
+#[test]
+fn the_clients_order_decides_between_shared_codecs() {
+    assert_eq!(Codec::negotiate(&[Codec::Json, Codec::MessagePack]), Codec::Json);
+    assert_eq!(Codec::negotiate(&[Codec::MessagePack, Codec::Json]), Codec::MessagePack);
+}
+
+#[cfg(feature = "binary_codec")]
+#[test]
+fn message_pack_frames_round_trip() {
+    let frame = Codec::MessagePack.encode(sample());
+    assert!(matches!(frame, Frame::MessagePack(_)));
+    assert_eq!(format!("{:?}", over_the_wire(frame)), format!("{:?}", sample()));
+}
+
+#[cfg(not(feature = "binary_codec"))]
+#[test]
+fn message_pack_falls_back_to_json_without_the_feature() {
+    assert_eq!(Codec::negotiate(&[Codec::MessagePack]), Codec::Json);
+    assert!(matches!(Codec::MessagePack.encode(sample()), Frame::Json(_)));
+    assert!(Frame::MessagePack(Vec::new()).decode().is_err());
+}
@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+use std::time::Duration;
+use bevy::prelude::*;
+use bevy::tasks::{ComputeTaskPool, TaskPool};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use server_backend::action_history::InvalidAction;
+use server_backend::game::game_event_processing::process_game_events;
+use server_backend::game::game_event_structs::{CardLibrary, GameEvent, GameEventContext, GameEventQueue, GameEventWithContext, GameStateComponent};
+use server_backend::room::room_components::{CurrentTurn, GameRng, Players, TurnTimer};
+use server_backend::server::setup_server;
+
+const PLAYER_A: u128 = 1;
+const PLAYER_B: u128 = 2;
+
+// Upper bound on ticks so a queue that never drains fails loudly instead of hanging
+const MAX_TICKS: usize = 1_000_000;
+
+// Rooms with a started game followed by alternating draws and turn ends
+fn build_world(rooms: usize, events_per_room: usize) -> World {
+    // Rooms are processed with par_iter_mut, which needs the compute pool
+    ComputeTaskPool::get_or_init(TaskPool::default);
+
+    let mut world = World::new();
+    // Port 0 lets every benchmark iteration bind its own socket
+    world.insert_resource(setup_server("127.0.0.1:0"));
+    world.init_resource::<Events<InvalidAction>>();
+    world.init_resource::<CardLibrary>();
+
+    for _ in 0..rooms {
+        let room_entity = world
+            .spawn((
+                Players { set: HashSet::from([PLAYER_A, PLAYER_B]) },
+                CurrentTurn { player: Some(PLAYER_A) },
+                TurnTimer {
+                    timer: Timer::new(Duration::from_secs(30), TimerMode::Once)
+                },
+                GameStateComponent::default(),
+                GameRng::seeded(0),
+                GameEventQueue::default(),
+            ))
+            .id();
+
+        let context = GameEventContext { room_entity };
+        let mut queue = world.get_mut::<GameEventQueue>(room_entity).unwrap();
+        queue.push(GameEventWithContext {
+            context: context.clone(),
+            event: GameEvent::StartGame {},
+        });
+
+        for i in 0..events_per_room {
+            let player_id = if i % 2 == 0 { PLAYER_A } else { PLAYER_B };
+            let event = if i % 4 < 2 {
+                GameEvent::DrawCard { player_id, amount: 1 }
+            } else {
+                GameEvent::EndTurn { player_id }
+            };
+            queue.push(GameEventWithContext {
+                context: context.clone(),
+                event,
+            });
+        }
+    }
+
+    world
+}
+
+// Ticks process_game_events until every room queue is drained, returning the tick count
+fn drain_queues(world: &mut World) -> usize {
+    let mut schedule = Schedule::default();
+    schedule.add_systems(process_game_events);
+
+    let mut queues = world.query::<&GameEventQueue>();
+    for tick in 0..MAX_TICKS {
+        if queues.iter(world).all(|queue| queue.is_empty()) {
+            return tick;
+        }
+        schedule.run(world);
+    }
+    panic!("event queues did not drain within {MAX_TICKS} ticks");
+}
+
+fn bench_process_game_events(c: &mut Criterion) {
+    let mut group = c.benchmark_group("process_game_events");
+    group.sample_size(10);
+
+    for (rooms, events_per_room) in [(1, 1_000), (10, 1_000), (100, 100), (100, 1_000)] {
+        group.bench_with_input(
+            BenchmarkId::new("drain", format!("{rooms}x{events_per_room}")),
+            &(rooms, events_per_room),
+            |b, &(rooms, events_per_room)| {
+                b.iter_batched(
+                    || build_world(rooms, events_per_room),
+                    |mut world| drain_queues(&mut world),
+                    BatchSize::PerIteration,
+                );
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_process_game_events);
+criterion_main!(benches);
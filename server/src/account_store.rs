@@ -0,0 +1,129 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use bevy::prelude::*;
+use bevy_simplenet::ClientId;
+use serde::{Deserialize, Serialize};
+use shared::card_details::CardConfig;
+use shared::channel::{PlayerProfile, AVATARS, TITLES};
+use shared::messages::MessageCode;
+
+// Copies of every starter-set card a new account receives
+const STARTER_COPIES: u32 = 2;
+const STARTER_SET: &str = "core";
+const STARTER_PACKS: u32 = 1;
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct AccountRecord {
+    // Card id to number of copies owned
+    pub collection: BTreeMap<String, u32>,
+    pub unopened_packs: u32,
+    // Set once the starter rewards are granted so they can't be claimed twice
+    pub onboarded: bool,
+    // Cosmetics, None until the player picks something
+    #[serde(default)]
+    pub avatar: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    // Ids of the puzzles this account has solved
+    #[serde(default)]
+    pub completed_puzzles: BTreeSet<String>,
+}
+
+// Per-account progression, written back to disk after each change when a path is set
+#[derive(Resource, Default)]
+pub struct AccountStore {
+    accounts: HashMap<ClientId, AccountRecord>,
+    path: Option<PathBuf>,
+}
+
+impl AccountStore {
+    // A missing file just means nobody has logged in yet
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let accounts = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { accounts, path: Some(path.to_path_buf()) })
+    }
+
+    fn persist(&self) {
+        let Some(path) = &self.path else { return };
+        let result = serde_json::to_string_pretty(&self.accounts)
+            .map_err(std::io::Error::from)
+            .and_then(|json| std::fs::write(path, json));
+        if let Err(e) = result {
+            warn!("Failed to save accounts to {}: {}", path.display(), e);
+        }
+    }
+
+    pub fn account(&self, player_id: ClientId) -> Option<&AccountRecord> {
+        self.accounts.get(&player_id)
+    }
+
+    // Grants the starter collection and packs on first login; None if the account already has them
+    pub fn claim_starter_rewards(&mut self, player_id: ClientId, known: &CardConfig) -> Option<(BTreeMap<String, u32>, u32)> {
+        let account = self.accounts.entry(player_id).or_default();
+        if account.onboarded {
+            return None;
+        }
+
+        let cards: BTreeMap<String, u32> = known.cards.iter()
+            .filter(|(_, card)| card.set.as_deref() == Some(STARTER_SET))
+            .map(|(key, _)| (key.clone(), STARTER_COPIES))
+            .collect();
+
+        for (key, &copies) in &cards {
+            *account.collection.entry(key.clone()).or_default() += copies;
+        }
+        account.unopened_packs += STARTER_PACKS;
+        account.onboarded = true;
+        info!("Granted starter rewards to new account {}", player_id);
+        self.persist();
+        Some((cards, STARTER_PACKS))
+    }
+
+    // Accounts that never customised anything get the first avatar and title
+    pub fn profile(&self, player_id: ClientId) -> PlayerProfile {
+        let account = self.accounts.get(&player_id);
+        PlayerProfile {
+            player_id,
+            avatar: account.and_then(|a| a.avatar.clone()).unwrap_or_else(|| AVATARS[0].to_string()),
+            title: account.and_then(|a| a.title.clone()).unwrap_or_else(|| TITLES[0].to_string()),
+        }
+    }
+
+    pub fn completed_puzzle(&self, player_id: ClientId, puzzle_id: &str) -> bool {
+        self.accounts.get(&player_id).is_some_and(|account| account.completed_puzzles.contains(puzzle_id))
+    }
+
+    // Only saves when the puzzle wasn't already solved
+    pub fn complete_puzzle(&mut self, player_id: ClientId, puzzle_id: &str) {
+        if self.accounts.entry(player_id).or_default().completed_puzzles.insert(puzzle_id.to_string()) {
+            self.persist();
+        }
+    }
+
+    pub fn set_profile(&mut self, player_id: ClientId, avatar: Option<String>, title: Option<String>) -> Result<(), MessageCode> {
+        if let Some(avatar) = &avatar {
+            if !AVATARS.contains(&avatar.as_str()) {
+                return Err(MessageCode::UnknownAvatar { avatar: avatar.clone() });
+            }
+        }
+        if let Some(title) = &title {
+            if !TITLES.contains(&title.as_str()) {
+                return Err(MessageCode::UnknownTitle { title: title.clone() });
+            }
+        }
+
+        let account = self.accounts.entry(player_id).or_default();
+        if avatar.is_some() {
+            account.avatar = avatar;
+        }
+        if title.is_some() {
+            account.title = title;
+        }
+        self.persist();
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use bevy::prelude::*;
+use bevy_simplenet::ClientId;
+use shared::channel::GameMessage;
+use shared::errors::{ErrorCode, GameError};
+use shared::messages::MessageCode;
+use crate::game::game_event_structs::{GameEvent, GameEventContext, GameEventWithContext, GameState};
+use crate::player_component::PlayerLeaveEvent;
+use crate::room::room_components::Players;
+use crate::types::Server;
+
+// How many recent actions are kept per player
+const HISTORY_LEN: usize = 50;
+// Faster than this after the turn starts is beyond human reaction time
+const MIN_REACTION_SECS: f32 = 0.15;
+// A single hit can be a latency fluke, this many within the window is a pattern
+const FLAG_THRESHOLD: usize = 3;
+// Invalid requests only count against a player for this long
+const STRIKE_WINDOW_SECS: f32 = 60.0;
+// Strikes below this only earn a warning
+const COOLDOWN_STRIKES: usize = 3;
+// At this many the player concedes and the account is flagged
+const CONCEDE_STRIKES: usize = 6;
+const COOLDOWN_SECS: f32 = 5.0;
+
+#[derive(Clone, Debug)]
+pub struct ActionRecord {
+    // Seconds since startup when the request arrived
+    pub at: f32,
+    pub action: &'static str,
+    pub own_turn: bool,
+    // Seconds into the player's turn, None when it wasn't their turn
+    pub reaction_secs: Option<f32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SuspicionFlag {
+    InstantResponses,
+    OutOfTurnActions,
+    ForcedConcession,
+}
+
+// A request the game rejected, e.g. playing a card that isn't in hand
+#[derive(Event, Clone, Debug)]
+pub struct InvalidAction {
+    pub player_id: ClientId,
+    pub room_entity: Entity,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Escalation {
+    Warn,
+    Cooldown,
+    ForceConcede,
+}
+
+// Rolling per-player log of game requests, kept for anti-cheat review
+#[derive(Resource, Default)]
+pub struct ActionHistory {
+    players: HashMap<ClientId, VecDeque<ActionRecord>>,
+    // Times of recent invalid requests
+    strikes: HashMap<ClientId, VecDeque<f32>>,
+    cooldowns: HashMap<ClientId, f32>,
+    forced_concessions: HashSet<ClientId>,
+}
+
+impl ActionHistory {
+    pub fn record(&mut self, player_id: ClientId, record: ActionRecord) {
+        let before = self.flags(player_id);
+        let history = self.players.entry(player_id).or_default();
+        if history.len() == HISTORY_LEN {
+            history.pop_front();
+        }
+        history.push_back(record);
+
+        let flags = self.flags(player_id);
+        if flags != before && !flags.is_empty() {
+            warn!("Player {} flagged for review: {:?}", player_id, flags);
+        }
+    }
+
+    pub fn history(&self, player_id: ClientId) -> impl Iterator<Item = &ActionRecord> {
+        self.players.get(&player_id).into_iter().flatten()
+    }
+
+    pub fn flags(&self, player_id: ClientId) -> Vec<SuspicionFlag> {
+        let instant = self.history(player_id)
+            .filter(|r| r.reaction_secs.is_some_and(|secs| secs < MIN_REACTION_SECS))
+            .count();
+        let out_of_turn = self.history(player_id).filter(|r| !r.own_turn).count();
+
+        let mut flags = Vec::new();
+        if instant >= FLAG_THRESHOLD {
+            flags.push(SuspicionFlag::InstantResponses);
+        }
+        if out_of_turn >= FLAG_THRESHOLD {
+            flags.push(SuspicionFlag::OutOfTurnActions);
+        }
+        if self.forced_concessions.contains(&player_id) {
+            flags.push(SuspicionFlag::ForcedConcession);
+        }
+        flags
+    }
+
+    // Counts an invalid request and decides how hard to push back
+    pub fn record_invalid(&mut self, player_id: ClientId, now: f32) -> Escalation {
+        let strikes = self.strikes.entry(player_id).or_default();
+        while strikes.front().is_some_and(|&at| now - at > STRIKE_WINDOW_SECS) {
+            strikes.pop_front();
+        }
+        strikes.push_back(now);
+
+        match strikes.len() {
+            n if n >= CONCEDE_STRIKES => {
+                strikes.clear();
+                self.forced_concessions.insert(player_id);
+                warn!("Player {} flagged for review: {:?}", player_id, self.flags(player_id));
+                Escalation::ForceConcede
+            }
+            n if n >= COOLDOWN_STRIKES => {
+                self.cooldowns.insert(player_id, now + COOLDOWN_SECS);
+                Escalation::Cooldown
+            }
+            _ => Escalation::Warn,
+        }
+    }
+
+    // Requests from a player in cooldown are rejected without being processed
+    pub fn in_cooldown(&self, player_id: ClientId, now: f32) -> bool {
+        self.cooldowns.get(&player_id).is_some_and(|&until| now < until)
+    }
+
+    // Every player with at least one flag, for admin tooling
+    pub fn flagged(&self) -> Vec<(ClientId, Vec<SuspicionFlag>)> {
+        let players: HashSet<_> = self.players.keys().chain(self.forced_concessions.iter()).copied().collect();
+        players.into_iter()
+            .map(|&player_id| (player_id, self.flags(player_id)))
+            .filter(|(_, flags)| !flags.is_empty())
+            .collect()
+    }
+}
+
+// Names the requests that count as game actions; chat and housekeeping are ignored
+pub fn action_name(message: &GameMessage) -> Option<&'static str> {
+    match message {
+        GameMessage::EndTurn => Some("EndTurn"),
+        GameMessage::DrawCard(_) => Some("DrawCard"),
+        GameMessage::PlayCard { .. } => Some("PlayCard"),
+        GameMessage::Attack { .. } => Some("Attack"),
+        GameMessage::Concede => Some("Concede"),
+        _ => None,
+    }
+}
+
+// Clean sessions are dropped on leave so the map doesn't grow forever; flagged ones stay for review
+pub fn forget_unflagged_players(
+    mut leave_events: EventReader<PlayerLeaveEvent>,
+    mut history: ResMut<ActionHistory>,
+) {
+    for event in leave_events.read() {
+        if history.flags(event.player_id).is_empty() {
+            history.players.remove(&event.player_id);
+        }
+        history.strikes.remove(&event.player_id);
+        history.cooldowns.remove(&event.player_id);
+    }
+}
+
+// Warn first, then ignore the player for a while, then end their game for them
+pub fn escalate_invalid_actions(
+    mut invalid_actions: EventReader<InvalidAction>,
+    mut history: ResMut<ActionHistory>,
+    mut game_events: EventWriter<GameEventWithContext>,
+    rooms: Query<&Players>,
+    server: Res<Server>,
+    time: Res<Time>,
+) {
+    for action in invalid_actions.read() {
+        match history.record_invalid(action.player_id, time.elapsed_secs()) {
+            Escalation::Warn => {
+                server.send(action.player_id, GameMessage::Error(ErrorCode::InvalidActionWarning.into()));
+            }
+            Escalation::Cooldown => {
+                server.send(action.player_id, GameMessage::Error(GameError::with_detail(
+                    ErrorCode::RequestCooldown,
+                    MessageCode::RequestCooldownFor { seconds: COOLDOWN_SECS as u64 },
+                )));
+            }
+            Escalation::ForceConcede => {
+                let Ok(players) = rooms.get(action.room_entity) else { continue };
+                let winner = players.set.iter().find(|&&p| p != action.player_id).copied();
+                server.send(action.player_id, GameMessage::Error(ErrorCode::ForcedConcede.into()));
+                game_events.send(GameEventWithContext {
+                    context: GameEventContext { room_entity: action.room_entity },
+                    event: GameEvent::GameStateChange { new_state: GameState::Finished(winner) },
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::io::BufRead;
+use std::sync::{mpsc, Mutex};
+use bevy::prelude::*;
+use crate::logging::LogControl;
+use crate::storage::Storage;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdminCommand {
+    // Prints the current log levels
+    ShowLogLevels,
+    // Replaces the log levels, in RUST_LOG syntax
+    SetLogLevels(String),
+    // Prints the saved log of a finished match
+    ShowMatch(String),
+    Help,
+}
+
+impl AdminCommand {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut words = line.split_whitespace();
+        match (words.next(), words.next(), words.next()) {
+            (Some("log"), None, _) => Ok(AdminCommand::ShowLogLevels),
+            (Some("log"), Some(directives), None) => Ok(AdminCommand::SetLogLevels(directives.to_string())),
+            (Some("match"), Some(match_id), None) => Ok(AdminCommand::ShowMatch(match_id.to_string())),
+            (Some("help"), None, _) => Ok(AdminCommand::Help),
+            _ => Err(format!("Unknown command '{}', try 'help'", line.trim())),
+        }
+    }
+}
+
+// Lines typed into the server's terminal, read on their own thread so waiting for input never blocks an update
+#[derive(Resource)]
+pub struct AdminConsole {
+    lines: Mutex<mpsc::Receiver<String>>,
+}
+
+impl AdminConsole {
+    pub fn from_stdin() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let spawned = std::thread::Builder::new()
+            .name("admin-console".to_string())
+            .spawn(move || {
+                for line in std::io::stdin().lock().lines().map_while(Result::ok) {
+                    if sender.send(line).is_err() {
+                        break;
+                    }
+                }
+            });
+        if let Err(e) = spawned {
+            warn!("Admin console unavailable: {}", e);
+        }
+        Self { lines: Mutex::new(receiver) }
+    }
+}
+
+pub fn run_admin_commands(console: Res<AdminConsole>, mut log: Option<ResMut<LogControl>>, storage: Option<Res<Storage>>) {
+    let lines: Vec<String> = console.lines.lock()
+        .map(|receiver| receiver.try_iter().collect())
+        .unwrap_or_default();
+    for line in lines.iter().filter(|line| !line.trim().is_empty()) {
+        match (AdminCommand::parse(line), log.as_mut()) {
+            (Ok(AdminCommand::Help), _) => info!("Commands: 'log' shows log levels, 'log <levels>' sets them, e.g. 'log info,server_backend::game=debug', 'match <id>' shows a finished match"),
+            (Ok(AdminCommand::ShowMatch(match_id)), _) => show_match(storage.as_deref(), &match_id),
+            (Ok(AdminCommand::ShowLogLevels), Some(log)) => info!("Log levels: {}", log.directives()),
+            (Ok(AdminCommand::SetLogLevels(directives)), Some(log)) => match log.set_directives(&directives) {
+                Ok(()) => info!("Log levels set to {}", directives),
+                Err(e) => warn!("Invalid log levels '{}': {}", directives, e),
+            },
+            (Ok(_), None) => warn!("Log levels can't be changed, logging wasn't set up by the server"),
+            (Err(e), _) => warn!("{}", e),
+        }
+    }
+}
+
+fn show_match(storage: Option<&Storage>, match_id: &str) {
+    let Some(storage) = storage else {
+        warn!("Matches aren't saved, the server was started without storage");
+        return;
+    };
+    match storage.room_log(match_id) {
+        Ok(Some(log)) => {
+            info!("Match {} started at {} (Unix time)", log.match_id, log.started_at);
+            for entry in &log.entries {
+                info!("  {:>7.1}s {:?}", entry.at, entry.entry);
+            }
+        }
+        Ok(None) => warn!("No finished match with id {}", match_id),
+        Err(e) => warn!("Couldn't read match {}: {}", match_id, e),
+    }
+}
@@ -0,0 +1,145 @@
+// Pits greedy bots against each other over seeded games and writes per-card win rates to CSV.
+//
+// Games are played out with the server's own rules through resolve_all, so combat, effects and keywords
+// all count. Both sides get random legal decks and the first deck always starts; games still going after
+// MAX_TURNS turns are draws.
+//
+// Usage: balance_sim [games] [seed] [output.csv]
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use server_backend::bot::choose_action;
+use server_backend::game::game_event_structs::{GameEvent, GameState, GameStateComponent};
+use server_backend::game::simulation::{resolve_all, Outbox, Table};
+use server_backend::room::room_components::{CurrentTurn, Players};
+use shared::card_details::{load_cards, CardConfig};
+use shared::deck_rules::MAX_COPIES_PER_CARD;
+use shared::EntityID;
+
+const DECK_SIZE: usize = 30;
+const MAX_TURNS: u32 = 100;
+// A bot whose pick keeps being refused would otherwise never end its turn
+const MAX_ACTIONS_PER_TURN: u32 = 50;
+const SEATS: [EntityID; 2] = [1, 2];
+
+#[derive(Default)]
+struct CardStats {
+    games: u32,
+    wins: u32,
+}
+
+// Random legal deck: up to MAX_COPIES_PER_CARD of each card until DECK_SIZE is reached
+fn random_deck<'a>(card_ids: &[&'a str], rng: &mut StdRng) -> Vec<&'a str> {
+    let mut pool: Vec<&str> = card_ids.iter()
+        .flat_map(|id| std::iter::repeat(*id).take(MAX_COPIES_PER_CARD))
+        .collect();
+    pool.shuffle(rng);
+    pool.truncate(DECK_SIZE);
+    pool
+}
+
+// The bot whose turn it is acts until it ends its turn or is refused, which ends the turn for it
+fn take_turn(table: &mut Table, player_id: EntityID) {
+    for _ in 0..MAX_ACTIONS_PER_TURN {
+        let event = choose_action(table.players, table.game_state, player_id);
+        let ends_turn = matches!(event, GameEvent::EndTurn { .. });
+        let refused = !resolve_all(table, event, &mut Outbox::default()).is_empty();
+        if ends_turn || matches!(table.game_state.state, GameState::Finished(_)) {
+            return;
+        }
+        if refused {
+            break;
+        }
+    }
+    resolve_all(table, GameEvent::EndTurn { player_id }, &mut Outbox::default());
+}
+
+// Returns the index of the winning deck, or None on a draw
+fn play_game(decks: [Vec<&str>; 2], cards: &CardConfig, rng: &mut StdRng) -> Option<usize> {
+    let players = Players { set: HashSet::from(SEATS) };
+    let mut current_turn = CurrentTurn { player: Some(SEATS[0]) };
+    let mut game_state = GameStateComponent::default();
+    for (player_id, deck) in SEATS.into_iter().zip(decks) {
+        game_state.deck_choices.insert(player_id, deck.into_iter().map(str::to_string).collect());
+    }
+    // Each game gets its own generator, as each room does
+    let mut game_rng = StdRng::seed_from_u64(rng.gen());
+    let mut table = Table {
+        players: &players,
+        current_turn: &mut current_turn,
+        game_state: &mut game_state,
+        rules: None,
+        time_bank: None,
+        cards,
+        rng: &mut game_rng,
+    };
+    resolve_all(&mut table, GameEvent::StartGame {}, &mut Outbox::default());
+
+    for _ in 0..MAX_TURNS {
+        let Some(player_id) = table.current_turn.player else { break };
+        take_turn(&mut table, player_id);
+        if let GameState::Finished(winner) = table.game_state.state {
+            return winner.and_then(|winner| SEATS.iter().position(|&seat| seat == winner));
+        }
+    }
+    None
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let games: u32 = args.next().map(|a| a.parse()).transpose()?.unwrap_or(10_000);
+    let seed: u64 = args.next().map(|a| a.parse()).transpose()?.unwrap_or(0);
+    let output = args.next().unwrap_or_else(|| "balance.csv".to_string());
+
+    let config = load_cards()?;
+    let mut card_ids: Vec<&str> = config.cards.iter()
+        .filter(|(_, card)| !card.token)
+        .map(|(k, _)| k.as_str())
+        .collect();
+    card_ids.sort();
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut stats: HashMap<&str, CardStats> = HashMap::new();
+    let mut draws = 0;
+
+    for _ in 0..games {
+        let decks = [random_deck(&card_ids, &mut rng), random_deck(&card_ids, &mut rng)];
+
+        // Count each card once per deck it appears in
+        let mut unique_decks = decks.clone();
+        for deck in unique_decks.iter_mut() {
+            deck.sort();
+            deck.dedup();
+        }
+
+        let Some(winner) = play_game(decks, &config, &mut rng) else {
+            draws += 1;
+            continue;
+        };
+
+        for (player, deck) in unique_decks.iter().enumerate() {
+            for id in deck {
+                let entry = stats.entry(*id).or_default();
+                entry.games += 1;
+                if player == winner {
+                    entry.wins += 1;
+                }
+            }
+        }
+    }
+
+    let mut writer = BufWriter::new(File::create(&output)?);
+    writeln!(writer, "card_id,name,cost,power,games,wins,win_rate")?;
+    for id in card_ids {
+        let card = &config.cards[id];
+        let entry = stats.get(id).map_or((0, 0), |s| (s.games, s.wins));
+        let win_rate = if entry.0 == 0 { 0.0 } else { entry.1 as f64 / entry.0 as f64 };
+        writeln!(writer, "{},\"{}\",{},{},{},{},{:.4}", id, card.name, card.cost, card.power, entry.0, entry.1, win_rate)?;
+    }
+
+    println!("Simulated {games} games with seed {seed} ({draws} draws), wrote {output}");
+    Ok(())
+}
@@ -0,0 +1,208 @@
+use bevy::prelude::*;
+use bevy::tasks::{block_on, AsyncComputeTaskPool, Task};
+use std::sync::Arc;
+use std::time::Duration;
+use std::path::Path;
+use bots::heuristic::{load_weights_from, EvalWeights, HeuristicBot, WEIGHTS_PATH};
+use bots::mcts::{ForwardModel, MctsBot};
+use bots::{Bot, BotAction, BotView, Creature, GreedyBot};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use shared::card_details::CardConfig;
+use shared::channel::{BotDifficulty, CardData, BOARD_SLOTS};
+use shared::EntityID;
+use crate::game::costs::effective_cost;
+use crate::game::game_event_structs::{CardLibrary, GameEvent, GameEventContext, GameEventQueue, GameEventWithContext, GameState, GameStateComponent, STARTING_HEALTH};
+use crate::game::simulation::{resolve_all, Outbox, Table};
+use crate::room::room_components::{CurrentTurn, Players, RoomRules};
+
+// Bot ids live at the top of the id space, far from anything a client picks
+pub const BOT_ID_BASE: EntityID = 1 << 127;
+
+// Pause between bot actions so the human can follow along
+const BOT_THINK_TIME: f32 = 1.0;
+
+// Search time for a hard bot's action, spent on the compute pool so the server's frame never waits on it
+const HARD_BOT_BUDGET: Duration = Duration::from_millis(300);
+
+pub enum BotBrain {
+    // Cheap enough to decide inside the frame
+    Quick(Box<dyn Bot>),
+    // Searches with the server's own rules on the compute pool
+    Search(MctsBot),
+}
+
+// Read once at startup and handed to every bot; main may load another file with `--bot-weights`
+#[derive(Resource, Clone)]
+pub struct BotWeights(pub EvalWeights);
+
+impl Default for BotWeights {
+    fn default() -> Self {
+        Self(load_weights_from(Path::new(WEIGHTS_PATH)).expect("Failed to load bot weights"))
+    }
+}
+
+// Sits on a room whose second seat is played by the server
+#[derive(Component)]
+pub struct BotPlayer {
+    pub id: EntityID,
+    // Seconds since startup when the bot may act next
+    pub next_action_at: f32,
+    pub brain: BotBrain,
+    // A search still running for the next action
+    thinking: Option<Task<BotAction>>,
+}
+
+impl BotPlayer {
+    pub fn new(id: EntityID, now: f32, difficulty: BotDifficulty, weights: &BotWeights) -> Self {
+        let weights = weights.0.clone();
+        let brain = match difficulty {
+            BotDifficulty::Normal => BotBrain::Quick(Box::new(HeuristicBot::new(weights))),
+            BotDifficulty::Hard => BotBrain::Search(MctsBot::new(weights, HARD_BOT_BUDGET)),
+        };
+        Self { id, next_action_at: now + BOT_THINK_TIME, brain, thinking: None }
+    }
+}
+
+// What a player in the bot's seat would be able to see. The opponent is whoever holds the other seat,
+// since they have no board until they play a creature
+pub fn visible_state(players: &Players, game_state: &GameStateComponent, bot_id: EntityID) -> BotView {
+    let opponent = players.set.iter().copied().find(|&player_id| player_id != bot_id);
+    let health = |player_id: Option<EntityID>| player_id
+        .and_then(|player_id| game_state.player_health.get(&player_id).copied())
+        .unwrap_or(STARTING_HEALTH);
+
+    BotView {
+        hand: game_state.player_hands.get(&bot_id)
+            .map(|hand| hand.cards.iter().map(|card| CardData {
+                effective_cost: Some(effective_cost(game_state, bot_id, card)),
+                ..card.as_card()
+            }).collect())
+            .unwrap_or_default(),
+        mana: game_state.player_mana.get(&bot_id).map_or(0, |mana| mana.current),
+        health: health(Some(bot_id)),
+        board: game_state.player_boards.get(&bot_id)
+            .map(|board| board.slots.iter()
+                .map(|slot| slot.as_ref().map(|card| Creature { card: card.as_card(), can_attack: card.can_attack() }))
+                .collect())
+            .unwrap_or_else(|| vec![None; BOARD_SLOTS]),
+        opponent_health: health(opponent),
+        opponent_hand_count: opponent
+            .and_then(|opponent| game_state.player_hands.get(&opponent))
+            .map_or(0, |hand| hand.cards.len() as u32),
+        opponent_board: opponent
+            .and_then(|opponent| game_state.player_boards.get(&opponent))
+            .map(|board| board.slots.iter().map(|slot| slot.as_ref().map(|card| card.as_card())).collect())
+            .unwrap_or_else(|| vec![None; BOARD_SLOTS]),
+    }
+}
+
+// Rollouts through the same resolve_all the room runs, on a copy of the room. The server may read the whole
+// table, but the search only ever plays the bot's own turn
+pub struct RulesModel {
+    pub bot_id: EntityID,
+    pub players: Players,
+    pub rules: Option<RoomRules>,
+    pub cards: Arc<CardConfig>,
+}
+
+impl RulesModel {
+    // Rollouts get their own generator rather than a copy of the room's, which would tell the bot what's coming
+    pub fn root(current_turn: &CurrentTurn, game_state: &GameStateComponent) -> (CurrentTurn, GameStateComponent, StdRng) {
+        (current_turn.clone(), game_state.clone(), StdRng::seed_from_u64(0))
+    }
+}
+
+impl ForwardModel for RulesModel {
+    type State = (CurrentTurn, GameStateComponent, StdRng);
+
+    fn view(&self, (_, game_state, _): &Self::State) -> BotView {
+        visible_state(&self.players, game_state, self.bot_id)
+    }
+
+    fn apply(&self, (current_turn, game_state, rng): &Self::State, action: BotAction) -> Self::State {
+        let (mut current_turn, mut game_state, mut rng) = (current_turn.clone(), game_state.clone(), rng.clone());
+        // The search stops at the end of the turn, so the opponent's never has to be played
+        if action != BotAction::EndTurn {
+            let mut table = Table {
+                players: &self.players,
+                current_turn: &mut current_turn,
+                game_state: &mut game_state,
+                rules: self.rules.as_ref(),
+                time_bank: None,
+                cards: &self.cards,
+                rng: &mut rng,
+            };
+            resolve_all(&mut table, to_event(action, self.bot_id), &mut Outbox::default());
+        }
+        (current_turn, game_state, rng)
+    }
+}
+
+pub fn to_event(action: BotAction, bot_id: EntityID) -> GameEvent {
+    match action {
+        BotAction::PlayCard { card_id, target, slot } => GameEvent::PlayCard { player_id: bot_id, card_id, target, slot },
+        BotAction::Attack { attacker, target } => GameEvent::Attack { player_id: bot_id, attacker, target },
+        BotAction::EndTurn => GameEvent::EndTurn { player_id: bot_id },
+    }
+}
+
+// What GreedyBot would do next, the simple baseline the heuristic bot is measured against
+pub fn choose_action(players: &Players, game_state: &GameStateComponent, bot_id: EntityID) -> GameEvent {
+    to_event(GreedyBot.choose_action(&visible_state(players, game_state, bot_id)), bot_id)
+}
+
+// Bots act through the same event queue as players, one action at a time once earlier events have resolved
+#[allow(clippy::type_complexity)]
+pub fn run_bots(
+    mut rooms: Query<(Entity, &mut BotPlayer, &Players, &CurrentTurn, &GameStateComponent, &GameEventQueue, Option<&RoomRules>)>,
+    mut game_events: EventWriter<GameEventWithContext>,
+    cards: Res<CardLibrary>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+    for (room_entity, bot, players, current_turn, game_state, event_queue, rules) in rooms.iter_mut() {
+        let bot = bot.into_inner();
+        let bot_to_act = matches!(game_state.state, GameState::InProgress)
+            && current_turn.player == Some(bot.id)
+            && event_queue.is_empty();
+        if !bot_to_act {
+            bot.next_action_at = now + BOT_THINK_TIME;
+            // Whatever it was searching no longer applies
+            bot.thinking = None;
+            continue;
+        }
+
+        let action = match bot.thinking.take() {
+            Some(task) if task.is_finished() => block_on(task),
+            Some(task) => {
+                bot.thinking = Some(task);
+                continue;
+            }
+            None if now < bot.next_action_at => continue,
+            None => {
+                let bot_id = bot.id;
+                let view = visible_state(players, game_state, bot_id);
+                match &mut bot.brain {
+                    BotBrain::Quick(brain) => {
+                        brain.observe(&view);
+                        brain.choose_action(&view)
+                    }
+                    BotBrain::Search(search) => {
+                        let mut search = search.clone();
+                        let model = RulesModel { bot_id, players: players.clone(), rules: rules.cloned(), cards: cards.0.clone() };
+                        let root = RulesModel::root(current_turn, game_state);
+                        bot.thinking = Some(AsyncComputeTaskPool::get().spawn(async move { search.search(&model, root) }));
+                        continue;
+                    }
+                }
+            }
+        };
+
+        bot.next_action_at = now + BOT_THINK_TIME;
+        game_events.send(GameEventWithContext {
+            context: GameEventContext { room_entity },
+            event: to_event(action, bot.id),
+        });
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, OnceLock};
+use std::thread::JoinHandle;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::SaltString;
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use shared::errors::{ErrorCode, GameError};
+use shared::messages::MessageCode;
+use shared::EntityID;
+
+pub const USERNAME_LENGTH: std::ops::RangeInclusive<usize> = 3..=20;
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Credential {
+    // Persistent id the account plays, saves decks and earns progress under
+    pub player_id: EntityID,
+    // Username as typed at registration; the map key is its lowercase form
+    pub username: String,
+    // Argon2 PHC string, salt and parameters included
+    pub password_hash: String,
+}
+
+// Checked and ready to store once the password hash is done, which is too slow for the main loop
+pub struct Registration {
+    key: String,
+    username: String,
+}
+
+// Login details for every account, written back to disk after each registration when a path is set
+#[derive(Resource, Default)]
+pub struct CredentialStore {
+    credentials: HashMap<String, Credential>,
+    // Map key for each player id, so chat can look up names without going through every account
+    keys: HashMap<EntityID, String>,
+    writer: Option<CredentialWriter>,
+}
+
+// Writes the whole file from its own thread, latest contents last, so registering never waits on the disk
+struct CredentialWriter {
+    sender: Option<mpsc::Sender<String>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl CredentialWriter {
+    fn spawn(path: PathBuf) -> std::io::Result<Self> {
+        let (sender, receiver) = mpsc::channel::<String>();
+        let thread = std::thread::Builder::new()
+            .name("credentials".to_string())
+            .spawn(move || {
+                while let Ok(mut json) = receiver.recv() {
+                    // Only the newest contents matter when several registrations queued up
+                    while let Ok(newer) = receiver.try_recv() {
+                        json = newer;
+                    }
+                    if let Err(e) = std::fs::write(&path, json) {
+                        warn!("Failed to save credentials to {}: {}", path.display(), e);
+                    }
+                }
+            })?;
+        Ok(Self { sender: Some(sender), thread: Some(thread) })
+    }
+}
+
+// Pending writes finish before the store goes away
+impl Drop for CredentialWriter {
+    fn drop(&mut self) {
+        self.sender.take();
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                warn!("Credential writer thread panicked");
+            }
+        }
+    }
+}
+
+pub fn hash_password(password: &str) -> Result<String, GameError> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default().hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| {
+            warn!("Could not hash a password: {}", e);
+            GameError::from(ErrorCode::AccountRejected)
+        })
+}
+
+pub fn verify_password(password_hash: &str, password: &str) -> bool {
+    PasswordHash::new(password_hash)
+        .is_ok_and(|hash| Argon2::default().verify_password(password.as_bytes(), &hash).is_ok())
+}
+
+// Checked against when the username doesn't exist, so unknown names take as long as wrong passwords
+fn dummy_hash() -> &'static str {
+    static DUMMY: OnceLock<String> = OnceLock::new();
+    DUMMY.get_or_init(|| hash_password("no account has this password").unwrap_or_default())
+}
+
+impl CredentialStore {
+    // A missing file just means nobody has registered yet
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let credentials: HashMap<String, Credential> = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let keys = credentials.iter().map(|(key, credential)| (credential.player_id, key.clone())).collect();
+        Ok(Self { credentials, keys, writer: Some(CredentialWriter::spawn(path.to_path_buf())?) })
+    }
+
+    fn persist(&self) {
+        let Some(sender) = self.writer.as_ref().and_then(|writer| writer.sender.as_ref()) else { return };
+        match serde_json::to_string_pretty(&self.credentials) {
+            Ok(json) => {
+                if sender.send(json).is_err() {
+                    warn!("Credential writer is gone, registration not saved");
+                }
+            }
+            Err(e) => warn!("Failed to serialize credentials: {}", e),
+        }
+    }
+
+    // Creates the account and returns its new player id. Hashes on the calling thread, so the server
+    // goes through check_registration and complete_registration instead
+    pub fn register(&mut self, username: &str, password: &str) -> Result<EntityID, GameError> {
+        let registration = self.check_registration(username, password)?;
+        let password_hash = hash_password(password)?;
+        self.complete_registration(registration, password_hash)
+    }
+
+    // Everything about a registration that doesn't need the hash
+    pub fn check_registration(&self, username: &str, password: &str) -> Result<Registration, GameError> {
+        let username = username.trim();
+        if !USERNAME_LENGTH.contains(&username.chars().count())
+            || !username.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(GameError::with_detail(
+                ErrorCode::AccountRejected,
+                MessageCode::UsernameRules { min: *USERNAME_LENGTH.start(), max: *USERNAME_LENGTH.end() },
+            ));
+        }
+        if password.chars().count() < MIN_PASSWORD_LENGTH {
+            return Err(GameError::with_detail(
+                ErrorCode::AccountRejected,
+                MessageCode::PasswordTooShort { min: MIN_PASSWORD_LENGTH },
+            ));
+        }
+        let key = username.to_lowercase();
+        if self.credentials.contains_key(&key) {
+            return Err(ErrorCode::UsernameTaken.into());
+        }
+        Ok(Registration { key, username: username.to_string() })
+    }
+
+    // The name is checked again, since another registration for it may have finished while hashing
+    pub fn complete_registration(&mut self, registration: Registration, password_hash: String) -> Result<EntityID, GameError> {
+        let Registration { key, username } = registration;
+        if self.credentials.contains_key(&key) {
+            return Err(ErrorCode::UsernameTaken.into());
+        }
+        // v4 UUIDs never land in the bot id range, which has the version bits clear
+        let player_id = uuid::Uuid::new_v4().as_u128();
+        info!("Registered account {} as player {}", username, player_id);
+        self.keys.insert(player_id, key.clone());
+        self.credentials.insert(key, Credential { player_id, username, password_hash });
+        self.persist();
+        Ok(player_id)
+    }
+
+    // Username as typed at registration, for showing to other players
+    pub fn username(&self, player_id: EntityID) -> Option<&str> {
+        self.keys.get(&player_id)
+            .and_then(|key| self.credentials.get(key))
+            .map(|credential| credential.username.as_str())
+    }
+
+    // Verifies on the calling thread; the server runs check_login on what login_target hands it off the main loop
+    pub fn log_in(&self, username: &str, password: &str) -> Result<EntityID, GameError> {
+        check_login(self.login_target(username), password)
+    }
+
+    // The account and the hash its password has to match, if the username exists
+    pub fn login_target(&self, username: &str) -> Option<(EntityID, String)> {
+        self.credentials.get(&username.trim().to_lowercase())
+            .map(|credential| (credential.player_id, credential.password_hash.clone()))
+    }
+}
+
+// Unknown usernames are checked against a dummy hash, so they take as long and get the same answer as wrong passwords
+pub fn check_login(target: Option<(EntityID, String)>, password: &str) -> Result<EntityID, GameError> {
+    let verified = match &target {
+        Some((_, password_hash)) => verify_password(password_hash, password),
+        None => {
+            let _ = verify_password(dummy_hash(), password);
+            false
+        }
+    };
+    target.filter(|_| verified).map(|(player_id, _)| player_id).ok_or(ErrorCode::InvalidCredentials.into())
+}
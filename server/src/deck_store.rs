@@ -0,0 +1,113 @@
+use std::collections::{BTreeMap, HashMap};
+use bevy::prelude::*;
+use bevy_simplenet::ClientId;
+use serde::{Deserialize, Serialize};
+use shared::card_details::CardConfig;
+use shared::channel::DeckSummary;
+use shared::deck_rules::validate_deck;
+use shared::deck_stats::DeckStats;
+use shared::messages::MessageCode;
+use crate::storage::{Storage, StorageWriter};
+
+pub const MAX_DECKS_PER_ACCOUNT: usize = 10;
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct AccountDecks {
+    // Deck name to card ids from cards.toml
+    pub decks: BTreeMap<String, Vec<String>>,
+    pub default: Option<String>,
+    // Picked for the next queue; only lasts for the session
+    #[serde(skip)]
+    pub selected: Option<String>,
+}
+
+// Saved decks for every account, handed to storage after each change when there is one
+#[derive(Resource, Default)]
+pub struct DeckStore {
+    accounts: HashMap<ClientId, AccountDecks>,
+    storage: Option<StorageWriter>,
+}
+
+impl DeckStore {
+    pub fn with_storage(storage: &Storage) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self { accounts: storage.load_decks()?, storage: Some(storage.writer()) })
+    }
+
+    // Only the changed account is written, off the main loop
+    fn persist(&self, player_id: ClientId) {
+        let (Some(storage), Some(account)) = (&self.storage, self.accounts.get(&player_id)) else { return };
+        storage.save_decks(player_id, account.clone());
+    }
+
+    pub fn save_deck(&mut self, player_id: ClientId, name: String, cards: Vec<String>, known: &CardConfig) -> Result<(), MessageCode> {
+        if name.trim().is_empty() {
+            return Err(MessageCode::EmptyDeckName);
+        }
+        validate_deck(known, &cards)?;
+
+        let account = self.accounts.entry(player_id).or_default();
+        if !account.decks.contains_key(&name) && account.decks.len() >= MAX_DECKS_PER_ACCOUNT {
+            return Err(MessageCode::TooManyDecks { max: MAX_DECKS_PER_ACCOUNT });
+        }
+        // The first deck becomes the default so queueing always has something to use
+        if account.default.is_none() {
+            account.default = Some(name.clone());
+        }
+        let stats = DeckStats::from_keys(known, &cards);
+        info!(
+            "Player {} saved deck '{}': {} cards, average cost {:.2}, curve {:?}, types {:?}",
+            player_id, name, stats.card_count, stats.average_cost, stats.mana_curve, stats.type_breakdown
+        );
+        account.decks.insert(name, cards);
+        self.persist(player_id);
+        Ok(())
+    }
+
+    pub fn delete_deck(&mut self, player_id: ClientId, name: &str) -> Result<(), MessageCode> {
+        let account = self.account_with_deck(player_id, name)?;
+        account.decks.remove(name);
+        if account.default.as_deref() == Some(name) {
+            account.default = account.decks.keys().next().cloned();
+        }
+        if account.selected.as_deref() == Some(name) {
+            account.selected = None;
+        }
+        self.persist(player_id);
+        Ok(())
+    }
+
+    pub fn set_default(&mut self, player_id: ClientId, name: &str) -> Result<(), MessageCode> {
+        self.account_with_deck(player_id, name)?.default = Some(name.to_string());
+        self.persist(player_id);
+        Ok(())
+    }
+
+    pub fn select(&mut self, player_id: ClientId, name: &str) -> Result<(), MessageCode> {
+        self.account_with_deck(player_id, name)?.selected = Some(name.to_string());
+        Ok(())
+    }
+
+    // Names and sizes in name order, plus the default
+    pub fn list(&self, player_id: ClientId) -> (Vec<DeckSummary>, Option<String>) {
+        let Some(account) = self.accounts.get(&player_id) else {
+            return (Vec::new(), None);
+        };
+        let decks = account.decks.iter()
+            .map(|(name, cards)| DeckSummary { name: name.clone(), card_count: cards.len() as u32 })
+            .collect();
+        (decks, account.default.clone())
+    }
+
+    // The deck a player brings into their next room; None falls back to the starter deck
+    pub fn queued_deck(&self, player_id: ClientId) -> Option<Vec<String>> {
+        let account = self.accounts.get(&player_id)?;
+        let name = account.selected.as_ref().or(account.default.as_ref())?;
+        account.decks.get(name).cloned()
+    }
+
+    fn account_with_deck(&mut self, player_id: ClientId, name: &str) -> Result<&mut AccountDecks, MessageCode> {
+        self.accounts.get_mut(&player_id)
+            .filter(|account| account.decks.contains_key(name))
+            .ok_or_else(|| MessageCode::NoDeckNamed { name: name.to_string() })
+    }
+}
@@ -0,0 +1,32 @@
+use shared::card_details::AuraScope;
+use shared::EntityID;
+use crate::game::game_event_structs::{CardComponent, GameStateComponent};
+
+// The one place a card's play cost is worked out: printed cost, then enchantments on the card,
+// then auras from creatures on either board, then the owner's discount for their next card. Never below zero
+pub fn effective_cost(game_state: &GameStateComponent, owner: EntityID, card: &CardComponent) -> u32 {
+    let mut cost = card.get_cost() as i32;
+    cost += card.cost_delta();
+    cost += aura_delta(game_state, owner);
+    cost -= game_state.discounts.get(&owner).copied().unwrap_or(0) as i32;
+    cost.max(0) as u32
+}
+
+// Effective cost of each card in the player's hand, in hand order
+pub fn hand_costs(game_state: &GameStateComponent, owner: EntityID) -> Vec<(EntityID, u32)> {
+    game_state.player_hands.get(&owner)
+        .map(|hand| hand.cards.iter().map(|card| (card.get_id(), effective_cost(game_state, owner, card))).collect())
+        .unwrap_or_default()
+}
+
+fn aura_delta(game_state: &GameStateComponent, owner: EntityID) -> i32 {
+    game_state.player_boards.iter()
+        .flat_map(|(&board_owner, board)| board.slots.iter().flatten().map(move |card| (board_owner, card)))
+        .filter_map(|(board_owner, card)| card.aura().map(|aura| (board_owner, aura)))
+        .filter(|(board_owner, aura)| match aura.applies_to {
+            AuraScope::Allies => *board_owner == owner,
+            AuraScope::Enemies => *board_owner != owner,
+        })
+        .map(|(_, aura)| aura.cost)
+        .sum()
+}
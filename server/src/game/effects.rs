@@ -0,0 +1,223 @@
+use bevy::log::warn;
+use shared::card_details::{CardConfig, CardEffect, EffectTarget};
+use shared::targeting::TargetSource;
+use shared::EntityID;
+use crate::game::game_event_structs::{BoardComponent, CardComponent, GameEvent, GameState, GameStateComponent, STARTING_HEALTH};
+use crate::room::room_components::Players;
+
+// Which creatures a positional effect applies to, relative to the card that triggered it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetPredicate {
+    // Creatures directly left and right of the source on its owner's board
+    AdjacentAllies,
+    // Every creature on the owner's board except the source
+    OtherAllies,
+    // Slots are numbered the same way on both boards, so leftmost is the lowest targetable index
+    LeftmostEnemy,
+    RightmostEnemy,
+    // The enemy creature in the same slot index as the source
+    OpposingEnemy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SlotRef {
+    pub player_id: EntityID,
+    pub slot: usize,
+}
+
+// Occupied slots matching the predicate, in slot order; empty when nothing qualifies
+pub fn resolve_targets(
+    game_state: &GameStateComponent,
+    players: &Players,
+    owner: EntityID,
+    source_slot: Option<usize>,
+    predicate: TargetPredicate,
+) -> Vec<SlotRef> {
+    let own_board = game_state.player_boards.get(&owner);
+    let enemy = players.set.iter().find(|&&p| p != owner).copied();
+    let enemy_board = enemy.and_then(|enemy| game_state.player_boards.get(&enemy));
+
+    let slots: Vec<usize> = match predicate {
+        TargetPredicate::AdjacentAllies => match (own_board, source_slot) {
+            (Some(board), Some(source)) => [source.checked_sub(1), source.checked_add(1)]
+                .into_iter()
+                .flatten()
+                .filter(|&slot| is_occupied(board, slot))
+                .collect(),
+            _ => Vec::new(),
+        },
+        TargetPredicate::OtherAllies => own_board
+            .map(|board| occupied(board).filter(|&slot| Some(slot) != source_slot).collect())
+            .unwrap_or_default(),
+        TargetPredicate::LeftmostEnemy => enemy_board
+            .and_then(|board| targetable(board).next())
+            .into_iter()
+            .collect(),
+        TargetPredicate::RightmostEnemy => enemy_board
+            .and_then(|board| targetable(board).last())
+            .into_iter()
+            .collect(),
+        TargetPredicate::OpposingEnemy => match (enemy_board, source_slot) {
+            (Some(board), Some(source)) if targetable(board).any(|slot| slot == source) => vec![source],
+            _ => Vec::new(),
+        },
+    };
+
+    let target_owner = match predicate {
+        TargetPredicate::AdjacentAllies | TargetPredicate::OtherAllies => Some(owner),
+        _ => enemy,
+    };
+    match target_owner {
+        Some(player_id) => slots.into_iter().map(|slot| SlotRef { player_id, slot }).collect(),
+        None => Vec::new(),
+    }
+}
+
+fn is_occupied(board: &BoardComponent, slot: usize) -> bool {
+    matches!(board.slots.get(slot), Some(Some(_)))
+}
+
+fn occupied(board: &BoardComponent) -> impl Iterator<Item = usize> + '_ {
+    board.slots.iter().enumerate().filter(|(_, card)| card.is_some()).map(|(slot, _)| slot)
+}
+
+// Enemy creatures that card effects may land on; stealthed and elusive ones are skipped
+fn targetable(board: &BoardComponent) -> impl Iterator<Item = usize> + '_ {
+    board.slots.iter().enumerate()
+        .filter(|(_, card)| card.as_ref().is_some_and(|card| card.targetable_by(TargetSource::Effect).is_ok()))
+        .map(|(slot, _)| slot)
+}
+
+#[derive(Debug, Default)]
+pub struct EffectOutcome {
+    pub next_events: Vec<GameEvent>,
+    // Creatures that became a different card, so clients can swap what they render
+    pub transformed: Vec<SlotRef>,
+}
+
+// Runs a played card's effects in order. Draws are returned as follow-up events so they go through the normal
+// draw rules; creatures left at zero health are removed once everything has resolved. Creatures the effects
+// touch record the source card's name in their history
+pub fn apply_effects(
+    game_state: &mut GameStateComponent,
+    players: &Players,
+    cards: &CardConfig,
+    owner: EntityID,
+    source_slot: Option<usize>,
+    source: &str,
+    effects: &[CardEffect],
+) -> EffectOutcome {
+    let mut next_events = Vec::new();
+    let mut transformed = Vec::new();
+    let enemy = players.set.iter().find(|&&p| p != owner).copied();
+
+    for effect in effects {
+        match *effect {
+            CardEffect::Draw { amount } => next_events.push(GameEvent::DrawCard { player_id: owner, amount }),
+            CardEffect::Damage { amount, target: EffectTarget::Opponent } => {
+                let Some(enemy) = enemy else { continue };
+                let health = game_state.player_health.entry(enemy).or_insert(STARTING_HEALTH);
+                *health = health.saturating_sub(amount);
+                game_state.match_log.record_damage(amount);
+            }
+            CardEffect::Damage { amount, target } => {
+                for slot_ref in creature_targets(game_state, players, owner, source_slot, target) {
+                    if let Some(card) = creature_mut(game_state, slot_ref) {
+                        let dealt = amount.min(card.health());
+                        card.take_damage(amount, source);
+                        game_state.match_log.record_damage(dealt);
+                    }
+                }
+            }
+            CardEffect::Discount { amount } => *game_state.discounts.entry(owner).or_insert(0) += amount,
+            CardEffect::ManaCrystal { amount } => {
+                if let Some(mana) = game_state.player_mana.get_mut(&owner) {
+                    mana.gain_crystals(amount);
+                }
+            }
+            CardEffect::TemporaryMana { amount } => {
+                if let Some(mana) = game_state.player_mana.get_mut(&owner) {
+                    mana.gain_temporary(amount);
+                }
+            }
+            CardEffect::ExtraTurn => *game_state.extra_turns.entry(owner).or_insert(0) += 1,
+            CardEffect::SkipOpponentTurn => {
+                let Some(enemy) = enemy else { continue };
+                *game_state.skipped_turns.entry(enemy).or_insert(0) += 1;
+            }
+            CardEffect::ReduceHandCost { amount } => {
+                if let Some(hand) = game_state.player_hands.get_mut(&owner) {
+                    for card in hand.cards.iter_mut() {
+                        card.enchant_cost(-(amount as i32));
+                    }
+                }
+            }
+            CardEffect::Buff { power, health, target } => {
+                for slot_ref in creature_targets(game_state, players, owner, source_slot, target) {
+                    if let Some(card) = creature_mut(game_state, slot_ref) {
+                        card.buff(power, health, source);
+                    }
+                }
+            }
+            CardEffect::Transform { ref into, target } => {
+                // cards.toml validation guarantees the id exists, but a bad file shouldn't take the room down
+                let Some(definition) = cards.cards.get(into) else {
+                    warn!("Cannot transform into unknown card '{}'", into);
+                    continue;
+                };
+                for slot_ref in creature_targets(game_state, players, owner, source_slot, target) {
+                    if let Some(card) = creature_mut(game_state, slot_ref) {
+                        card.transform(definition.to_card_data(into, card.get_id()), source);
+                        transformed.push(slot_ref);
+                    }
+                }
+            }
+        }
+    }
+
+    remove_destroyed(game_state);
+    next_events.extend(lethal_check(game_state, players, owner));
+    EffectOutcome { next_events, transformed }
+}
+
+pub(crate) fn remove_destroyed(game_state: &mut GameStateComponent) {
+    for (&owner, board) in game_state.player_boards.iter_mut() {
+        for slot in board.slots.iter_mut() {
+            if let Some(card) = slot.take_if(|card| card.health() == 0) {
+                game_state.graveyards.entry(owner).or_default().push(card.as_card());
+            }
+        }
+    }
+}
+
+// Ends the game in the owner's favour once their opponent is out of health
+pub(crate) fn lethal_check(game_state: &GameStateComponent, players: &Players, owner: EntityID) -> Option<GameEvent> {
+    let enemy = players.set.iter().find(|&&p| p != owner)?;
+    (game_state.player_health.get(enemy) == Some(&0))
+        .then(|| GameEvent::GameStateChange { new_state: GameState::Finished(Some(owner)) })
+}
+
+fn creature_targets(
+    game_state: &GameStateComponent,
+    players: &Players,
+    owner: EntityID,
+    source_slot: Option<usize>,
+    target: EffectTarget,
+) -> Vec<SlotRef> {
+    let predicate = match target {
+        EffectTarget::Opponent => return Vec::new(),
+        EffectTarget::Source => {
+            return source_slot.map(|slot| SlotRef { player_id: owner, slot }).into_iter().collect();
+        }
+        EffectTarget::AdjacentAllies => TargetPredicate::AdjacentAllies,
+        EffectTarget::OtherAllies => TargetPredicate::OtherAllies,
+        EffectTarget::LeftmostEnemy => TargetPredicate::LeftmostEnemy,
+        EffectTarget::RightmostEnemy => TargetPredicate::RightmostEnemy,
+        EffectTarget::OpposingEnemy => TargetPredicate::OpposingEnemy,
+    };
+    resolve_targets(game_state, players, owner, source_slot, predicate)
+}
+
+fn creature_mut(game_state: &mut GameStateComponent, slot_ref: SlotRef) -> Option<&mut CardComponent> {
+    game_state.player_boards.get_mut(&slot_ref.player_id)?.slots.get_mut(slot_ref.slot)?.as_mut()
+}
@@ -1,10 +1,41 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
 use bevy::prelude::*;
-use crate::game::game_event_structs::{CardComponent, EventResult, GameEvent, GameEventQueue, GameEventWithContext, GameStateComponent};
-use crate::game::game_events;
-use crate::room::room_components::{CurrentTurn, Players, TurnTimer};
+use bevy::utils::Parallel;
+use crate::action_history::InvalidAction;
+use crate::game::game_event_structs::{CardLibrary, DesyncDetection, GameEventQueue, GameEventWithContext, GameStateComponent};
+use crate::game::simulation::{self, Outbox, Table};
+use crate::room::room_components::{CurrentTurn, GameRng, Players, RoomRules, TimeBank, TurnTimer};
 use crate::types::Server;
+use shared::channel::GameMessage;
 
+// Caps on how many game events run per update, so a room that keeps queueing follow-ups can't stall the others.
+// Whatever doesn't fit stays queued and carries over to the next update
+#[derive(Resource, Clone, Debug)]
+pub struct EventBudget {
+    // Most events one room may process in an update
+    pub per_room: usize,
+    // Events shared by every room in an update; each room with work still gets its first event
+    pub per_tick: usize,
+}
+
+impl Default for EventBudget {
+    fn default() -> Self {
+        Self { per_room: 16, per_tick: 512 }
+    }
+}
 
+// Counts of how often the budgets ran out, for spotting pathological games
+#[derive(Resource, Debug, Default)]
+pub struct EventBudgetMetrics {
+    // Times a room stopped with events left over, once per room per update
+    pub room_caps: u64,
+    // Updates in which the shared per-tick budget ran out
+    pub tick_caps: u64,
+    // Rooms that hit a cap in the last update, with the events they carried over
+    pub last_capped: Vec<(Entity, usize)>,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn process_game_events(
     mut rooms: Query<(
         Entity,
@@ -12,68 +43,99 @@ pub fn process_game_events(
         &mut CurrentTurn,
         &mut TurnTimer,
         &mut GameStateComponent,
-        &mut GameEventQueue
+        &mut GameEventQueue,
+        &mut GameRng,
+        Option<&TimeBank>,
+        Option<&RoomRules>,
     )>,
     server: Res<Server>,
-    mut commands: Commands,
-    mut card_query: Query<&mut CardComponent>
+    cards: Res<CardLibrary>,
+    desync_detection: Option<Res<DesyncDetection>>,
+    budget: Res<EventBudget>,
+    mut metrics: ResMut<EventBudgetMetrics>,
+    mut invalid_actions: EventWriter<InvalidAction>,
+    mut rejected: Local<Parallel<Vec<InvalidAction>>>,
+    mut capped: Local<Parallel<Vec<(Entity, usize)>>>,
 ) {
-    for (room_entity, players, mut current_turn, mut timer, mut game_state, mut event_queue) in rooms.iter_mut() {
+    let send_checksums = desync_detection.is_some();
+    let tick_budget = AtomicUsize::new(budget.per_tick);
+    let tick_capped = AtomicUsize::new(0);
+
+    // Rooms share nothing mutable, so each one is processed on its own task
+    rooms.par_iter_mut().for_each(|(room_entity, players, mut current_turn, mut timer, mut game_state, mut event_queue, mut rng, time_bank, rules)| {
         if !event_queue.current_events.is_empty() {
             println!("Processing events for room {:?}, events: {:?}", room_entity, event_queue.current_events.len());
         }
-        let mut events_to_queue = Vec::new();
 
-        if let Some(event) = event_queue.current_events.pop_front() {
-            println!("Processing queued game event: {:?}", event);
-            let context = event.context.clone();
-            let result: EventResult = match event.event {
-                GameEvent::StartGame {} => {
-                    game_events::game_event_start_game(&mut game_state, players) }
-                GameEvent::EndGame { player_id } => {
-                    game_events::game_event_end_game()
-                }
-                GameEvent::StartTurn { player_id } => {
-                    game_events::game_event_start_turn(&mut current_turn, players, player_id, &server)
-                }
-                GameEvent::EndTurn { player_id } => {
-                    game_events::game_event_end_turn(players, &mut current_turn, player_id)
-                }
-                GameEvent::AddCardsToDeck { player_id, amount} => {
-                    game_events::game_event_add_cards_to_decks(&mut commands, &server, &mut game_state, player_id, amount)
-                }
-                GameEvent::DrawCard { player_id, amount } => {
-                    game_events::game_event_draw_card(&server, &card_query, &mut game_state, player_id, amount)
-                }
-                GameEvent::PlayCard { player_id, card_id, target } => {
-                    game_events::game_event_play_card(players, player_id, card_id, &mut game_state)
+        let mut processed = 0;
+        loop {
+            // Follow-ups queued by earlier events run once everything before them has
+            if event_queue.current_events.is_empty() {
+                if event_queue.next_events.is_empty() {
+                    break;
                 }
-                GameEvent::GameStateChange { new_state } => {
-                    game_events::game_event_game_state_change(&server, players, &mut game_state, new_state)
-                }
-                GameEvent::SpecialAction { player_id, action_type, targets } => {
-                    game_events::game_event_special_action(&server, players, &player_id, &action_type, &targets)
+                event_queue.swap_queues();
+            }
+            let within_room = processed < budget.per_room;
+            let within_tick = within_room
+                && tick_budget.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |left| left.checked_sub(1)).is_ok();
+            if !within_room || (processed > 0 && !within_tick) {
+                if within_room {
+                    tick_capped.fetch_add(1, Ordering::Relaxed);
                 }
+                let carried = event_queue.current_events.len() + event_queue.next_events.len();
+                capped.scope(|rooms| rooms.push((room_entity, carried)));
+                break;
+            }
+            let Some(event) = event_queue.current_events.pop_front() else { break };
+            processed += 1;
+
+            println!("Processing queued game event: {:?}", event);
+            let context = event.context.clone();
+            let mut table = Table {
+                players,
+                current_turn: &mut current_turn,
+                game_state: &mut game_state,
+                rules,
+                time_bank,
+                cards: &cards.0,
+                rng: &mut rng.rng,
             };
+            let mut outbox = Outbox::default();
+            let result = simulation::resolve(&mut table, event.event, &mut outbox);
+            for (player_id, message) in outbox.messages {
+                server.send(player_id, message);
+            }
+            if send_checksums {
+                for &player_id in &players.set {
+                    let checksum = game_state.player_checksum(player_id, current_turn.player);
+                    server.send(player_id, GameMessage::StateChecksum(checksum));
+                }
+            }
             if result.reset_timer {
                 timer.timer.reset();
             }
+            if let Some(player_id) = result.invalid_by {
+                rejected.scope(|actions| actions.push(InvalidAction { player_id, room_entity }));
+            }
             // Convert the result events into GameEventWithContext using the original context
-            events_to_queue.extend(
+            event_queue.next_events.extend(
                 result.next_events.into_iter().map(|event| GameEventWithContext {
                     context: context.clone(),
                     event,
                 })
             );
         }
+    });
 
-        // Queue up all the new events
-        event_queue.next_events.extend(events_to_queue);
+    invalid_actions.send_batch(rejected.drain());
 
-
-        // Only swap queues if current_events is empty
-        if event_queue.current_events.is_empty() {
-            event_queue.swap_queues();
-        }
+    metrics.last_capped = capped.drain().collect();
+    for &(room_entity, carried) in &metrics.last_capped {
+        warn!("Room {:?} hit its event budget, carrying {} events over", room_entity, carried);
+    }
+    metrics.room_caps += metrics.last_capped.len() as u64;
+    if tick_capped.into_inner() > 0 {
+        metrics.tick_caps += 1;
     }
 }
@@ -1,7 +1,16 @@
 use std::collections::{HashMap, HashSet, VecDeque};
-use bevy::prelude::{Component, Entity, Event};
-use shared::channel::{CardData, GameMessage};
+use std::sync::Arc;
+use std::time::Duration;
+use bevy::prelude::{Component, Entity, Event, Resource};
+use shared::card_details::{load_cards, CardConfig, CardEffect, CostAura};
+use shared::channel::{CardData, CardType, CreatureChange, CreatureHistory, GameMessage, GameStateSnapshot, MatchSummary, PlayerSnapshot, RevealedCards, TurnSummary, BOARD_SLOTS};
+use shared::checksum::MirroredState;
+use shared::lethal::RUSH;
+use shared::messages::MessageCode;
+use shared::targeting::{check_enemy_target, TargetSource};
 use shared::EntityID;
+use crate::game::costs::effective_cost;
+use crate::room::room_components::Players;
 
 // Context that every game event must have
 #[derive(Debug, Clone)]
@@ -32,10 +41,20 @@ pub enum GameEvent {
         player_id: EntityID,
         card_id: EntityID,
         target: Option<EntityID>,  // Optional target
+        slot: Option<usize>,       // Board slot for creatures
     },
     EndTurn {
         player_id: EntityID, // Player ending their turn
     },
+    ChooseDeck {
+        player_id: EntityID,
+        cards: Vec<String>,        // Card ids from cards.toml
+    },
+    Attack {
+        player_id: EntityID,
+        attacker: usize,           // Slot on the attacker's own board
+        target: Option<usize>,     // Enemy slot, or None to hit the opponent directly
+    },
     StartTurn {
         player_id: EntityID,
     },
@@ -49,6 +68,33 @@ pub enum GameEvent {
     },
     EndGame {
         player_id: EntityID,
+    },
+    ReportDesync {
+        player_id: EntityID,
+        state: MirroredState,
+    },
+    // A card made by the game straight into a hand, like the second player's compensation
+    CreateCard {
+        player_id: EntityID,
+        card_key: String,          // Card id from cards.toml
+    },
+    // Ends the game at once in the opponent's favour, on or off turn
+    Concede {
+        player_id: EntityID,
+    },
+}
+
+// When present, players receive a state checksum after every processed event
+#[derive(Resource)]
+pub struct DesyncDetection;
+
+// cards.toml, parsed once and shared by every room
+#[derive(Resource, Clone)]
+pub struct CardLibrary(pub Arc<CardConfig>);
+
+impl Default for CardLibrary {
+    fn default() -> Self {
+        Self(Arc::new(load_cards().expect("Failed to load card definitions")))
     }
 }
 
@@ -72,10 +118,27 @@ impl IntoGameEvent for GameMessage {
                 player_id: context.client_id,
                 amount,
             }),
-            GameMessage::PlayCard { card_id, target } => Some(GameEvent::PlayCard {
+            GameMessage::PlayCard { card_id, target, slot } => Some(GameEvent::PlayCard {
                 player_id: context.client_id,
                 card_id,
                 target,
+                slot,
+            }),
+            GameMessage::JoinGame { deck: Some(cards) } => Some(GameEvent::ChooseDeck {
+                player_id: context.client_id,
+                cards,
+            }),
+            GameMessage::Attack { attacker, target } => Some(GameEvent::Attack {
+                player_id: context.client_id,
+                attacker,
+                target,
+            }),
+            GameMessage::Concede => Some(GameEvent::Concede {
+                player_id: context.client_id,
+            }),
+            GameMessage::MirroredState(state) => Some(GameEvent::ReportDesync {
+                player_id: context.client_id,
+                state,
             }),
             // Messages that don't convert to game events return None
             _ => None
@@ -105,24 +168,150 @@ pub enum GameState {
 }
 
 // Component to track game state
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct GameStateComponent {
     pub state: GameState,
     pub player_decks: HashMap<EntityID, DeckComponent>,
     pub player_hands: HashMap<EntityID, HandComponent>,
-    pub discard_pile: Vec<EntityID>,
+    pub player_boards: HashMap<EntityID, BoardComponent>,
+    pub player_mana: HashMap<EntityID, ManaComponent>,
+    pub player_health: HashMap<EntityID, u32>,
+    // Temporary discounts for the owner's next card, used up by playing it or dropped when the next turn starts
+    pub discounts: HashMap<EntityID, u32>,
+    // Turns owed by extra-turn effects, and turns players will lose to skip effects
+    pub extra_turns: HashMap<EntityID, u32>,
+    pub skipped_turns: HashMap<EntityID, u32>,
+    // Turns the current player has started in a row
+    pub consecutive_turns: u32,
+    pub match_log: MatchLog,
+    // Resolved spells and destroyed creatures per owner, oldest first
+    pub graveyards: HashMap<EntityID, Vec<CardData>>,
+    // Saved deck each player queued with; players without one get the starter deck
+    pub deck_choices: HashMap<EntityID, Vec<String>>,
 }
 
-#[derive(Component)]
+// Decks and hands own their card data so handlers never need to look cards up in the World
+#[derive(Component, Clone)]
 pub struct DeckComponent {
     pub player_id: EntityID,
-    pub cards: Vec<Entity>
+    pub cards: Vec<CardComponent>
 }
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct HandComponent {
     pub player_id: EntityID,
-    pub cards: Vec<Entity>,
+    pub cards: Vec<CardComponent>,
+}
+
+// Fixed row of creature slots, empty slots stay in place so positions are stable
+#[derive(Component, Clone)]
+pub struct BoardComponent {
+    pub player_id: EntityID,
+    pub slots: Vec<Option<CardComponent>>,
+}
+
+impl BoardComponent {
+    pub fn new(player_id: EntityID) -> Self {
+        Self { player_id, slots: vec![None; BOARD_SLOTS] }
+    }
+
+    pub fn is_free(&self, slot: usize) -> bool {
+        matches!(self.slots.get(slot), Some(None))
+    }
+}
+
+// What happened each turn, turned into a MatchSummary when the game ends
+#[derive(Default, Debug, Clone)]
+pub struct MatchLog {
+    pub turns: Vec<TurnSummary>,
+    pub conceded_by: Option<EntityID>,
+    // Turn player's board advantage when their current turn began
+    advantage_at_start: i32,
+}
+
+impl MatchLog {
+    pub fn start_turn(&mut self, player_id: EntityID, advantage: i32) {
+        self.turns.push(TurnSummary {
+            turn: self.turns.len() as u32 + 1,
+            player_id,
+            ..Default::default()
+        });
+        self.advantage_at_start = advantage;
+    }
+
+    pub fn record_play(&mut self, card_name: String) {
+        if let Some(turn) = self.turns.last_mut() {
+            turn.cards_played.push(card_name);
+        }
+    }
+
+    pub fn record_damage(&mut self, amount: u32) {
+        if let Some(turn) = self.turns.last_mut() {
+            turn.damage += amount;
+        }
+    }
+
+    pub fn end_turn(&mut self, advantage: i32) {
+        if let Some(turn) = self.turns.last_mut() {
+            turn.swing = advantage - self.advantage_at_start;
+        }
+    }
+
+    pub fn current_player(&self) -> Option<EntityID> {
+        self.turns.last().map(|turn| turn.player_id)
+    }
+
+    pub fn summary(&self, winner: Option<EntityID>) -> MatchSummary {
+        let biggest_swing = self.turns.iter()
+            .enumerate()
+            .filter(|(_, turn)| turn.swing != 0)
+            .max_by_key(|(_, turn)| turn.swing.abs())
+            .map(|(index, _)| index);
+        MatchSummary { winner, turns: self.turns.clone(), biggest_swing, conceded_by: self.conceded_by }
+    }
+}
+
+// Mana pool cap, reached after this many turns
+pub const MAX_MANA: u32 = 10;
+
+pub const STARTING_HEALTH: u32 = 30;
+
+pub const OPENING_HAND: u32 = 5;
+// Token the player going second starts with, on top of one extra card in their opening hand
+pub const SECOND_PLAYER_TOKEN: &str = "power_cell";
+
+// Turns one player may take in a row, however many extra turns or skips they stack up
+pub const MAX_CONSECUTIVE_TURNS: u32 = 3;
+
+// Grows by one each turn up to MAX_MANA and refills at the start of the owner's turn
+#[derive(Component, Debug, Clone)]
+pub struct ManaComponent {
+    pub player_id: EntityID,
+    // Spendable this turn
+    pub current: u32,
+    // Crystals owned, what current refills to
+    pub max: u32,
+}
+
+impl ManaComponent {
+    pub fn new(player_id: EntityID) -> Self {
+        Self { player_id, current: 0, max: 0 }
+    }
+
+    pub fn start_turn(&mut self) {
+        self.gain_crystals(1);
+        self.current = self.max;
+    }
+
+    // New crystals arrive empty and fill up at the start of the owner's next turn
+    pub fn gain_crystals(&mut self, amount: u32) {
+        self.max = (self.max + amount).min(MAX_MANA);
+    }
+
+    // Can go above max; whatever isn't spent is lost when the pool refills
+    pub fn gain_temporary(&mut self, amount: u32) {
+        self.current = (self.current + amount).min(MAX_MANA);
+    }
 }
 
 impl DeckComponent {
@@ -141,30 +330,141 @@ impl HandComponent {
 }
 
 // Card component
-#[derive(Component)]
-pub struct CardComponent(CardData);
+#[derive(Component, Clone, Debug)]
+pub struct CardComponent {
+    card: CardData,
+    // Permanent cost changes applied to this copy, e.g. by hand enchantments
+    cost_delta: i32,
+    // Cleared at the start of the owner's turn
+    played_this_turn: bool,
+    attacked_this_turn: bool,
+    // Everything that changed this copy's stats; a transform starts it over from the Transformed entry
+    changes: Vec<CreatureChange>,
+    printed_power: u32,
+    printed_health: u32,
+}
 
 impl CardComponent {
 
     pub fn new(card: CardData) -> CardComponent {
-        CardComponent(card)
+        let (printed_power, printed_health) = (card.power, card.health);
+        CardComponent { card, cost_delta: 0, played_this_turn: false, attacked_this_turn: false, changes: Vec::new(), printed_power, printed_health }
     }
 
     pub(crate) fn as_card(&self) -> CardData {
-        self.0.clone()
+        self.card.clone()
+    }
+
+    // For the shared rules, which work on what clients can see
+    pub(crate) fn data(&self) -> &CardData {
+        &self.card
     }
 
     // using a separate ID to the Entity ID of bevy
-    pub(crate) fn get_id(&self) -> EntityID {
-        self.0.card_id
+    pub fn get_id(&self) -> EntityID {
+        self.card.card_id
+    }
+
+    // Printed cost, before any modifiers
+    pub(crate) fn get_cost(&self) -> u32 {
+        self.card.cost
+    }
+
+    pub fn cost_delta(&self) -> i32 {
+        self.cost_delta
+    }
+
+    pub(crate) fn enchant_cost(&mut self, delta: i32) {
+        self.cost_delta += delta;
+    }
+
+    pub fn aura(&self) -> Option<CostAura> {
+        self.card.aura
+    }
+
+    pub(crate) fn card_type(&self) -> &CardType {
+        &self.card.card_type
     }
 
     pub(crate) fn get_name(&self) -> String {
-        self.0.card_name.clone()
+        self.card.card_name.clone()
     }
 
     pub(crate) fn get_text(&self) -> String {
-        self.0.card_text.clone()
+        self.card.card_text.clone()
+    }
+
+    pub fn effects(&self) -> &[CardEffect] {
+        &self.card.effects
+    }
+
+    pub fn health(&self) -> u32 {
+        self.card.health
+    }
+
+    pub fn power(&self) -> u32 {
+        self.card.power
+    }
+
+    pub fn has_keyword(&self, keyword: &str) -> bool {
+        self.card.has_keyword(keyword)
+    }
+
+    pub fn targetable_by(&self, source: TargetSource) -> Result<(), MessageCode> {
+        check_enemy_target(&self.card, source)
+    }
+
+    // Summoning sickness: fresh creatures wait a turn unless they have Rush, and everything attacks once per turn
+    pub fn can_attack(&self) -> bool {
+        !self.attacked_this_turn && (!self.played_this_turn || self.has_keyword(RUSH))
+    }
+
+    pub(crate) fn mark_played(&mut self) {
+        self.played_this_turn = true;
+    }
+
+    pub(crate) fn mark_attacked(&mut self) {
+        self.attacked_this_turn = true;
+        self.card.revealed = true;
+    }
+
+    pub(crate) fn ready(&mut self) {
+        self.played_this_turn = false;
+        self.attacked_this_turn = false;
+    }
+
+    // Becomes a different card in place; modifiers from the old card don't carry over, so its history starts
+    // again from the transformation
+    pub(crate) fn transform(&mut self, card: CardData, source: &str) {
+        let attacked_this_turn = self.attacked_this_turn;
+        let transformed = CreatureChange::Transformed { from: self.get_name(), source: source.to_string() };
+        *self = CardComponent::new(card);
+        self.attacked_this_turn = attacked_this_turn;
+        self.changes = vec![transformed];
+    }
+
+    pub(crate) fn take_damage(&mut self, amount: u32, source: &str) {
+        let dealt = amount.min(self.card.health);
+        self.card.health -= dealt;
+        if dealt > 0 {
+            self.changes.push(CreatureChange::Damaged { amount: dealt, source: source.to_string() });
+        }
+    }
+
+    pub(crate) fn buff(&mut self, power: u32, health: u32, source: &str) {
+        self.card.power += power;
+        self.card.health += health;
+        self.changes.push(CreatureChange::Buffed { power, health, source: source.to_string() });
+    }
+
+    pub fn history(&self) -> CreatureHistory {
+        CreatureHistory {
+            card_id: self.card.card_id,
+            card_name: self.card.card_name.clone(),
+            printed_power: self.printed_power,
+            printed_health: self.printed_health,
+            changes: self.changes.clone(),
+        }
     }
 }
 
@@ -174,11 +474,132 @@ impl Default for GameStateComponent {
             state: GameState::Starting,
             player_decks: HashMap::new(),
             player_hands: HashMap::new(),
-            discard_pile: Vec::new(),
+            player_boards: HashMap::new(),
+            player_mana: HashMap::new(),
+            player_health: HashMap::new(),
+            discounts: HashMap::new(),
+            extra_turns: HashMap::new(),
+            skipped_turns: HashMap::new(),
+            consecutive_turns: 0,
+            match_log: MatchLog::default(),
+            graveyards: HashMap::new(),
+            deck_choices: HashMap::new(),
         }
     }
 }
 
+impl GameStateComponent {
+    // Authoritative version of the state a client mirrors
+    pub fn mirrored_state(&self, player_id: EntityID, current_turn: Option<EntityID>) -> MirroredState {
+        let hand = self.player_hands.get(&player_id)
+            .map(|hand| hand.cards.iter().map(|card| card.get_id()).collect())
+            .unwrap_or_default();
+        MirroredState { current_turn, hand }
+    }
+
+    // Table as seen by one player; the other hands are only counted
+    pub fn snapshot(&self, viewer: EntityID, players: &Players, current_turn: Option<EntityID>, time_bank: Option<Duration>) -> GameStateSnapshot {
+        let hand = self.player_hands.get(&viewer)
+            .map(|hand| hand.cards.iter().map(|card| CardData {
+                effective_cost: Some(effective_cost(self, viewer, card)),
+                ..card.as_card()
+            }).collect())
+            .unwrap_or_default();
+
+        let players = players.set.iter().map(|&player_id| {
+            let mana = self.player_mana.get(&player_id);
+            PlayerSnapshot {
+                player_id,
+                hand_count: self.player_hands.get(&player_id).map_or(0, |hand| hand.cards.len() as u32),
+                deck_size: self.player_decks.get(&player_id).map_or(0, |deck| deck.cards.len() as u32),
+                health: self.player_health.get(&player_id).copied().unwrap_or(STARTING_HEALTH),
+                graveyard_size: self.graveyards.get(&player_id).map_or(0, |cards| cards.len() as u32),
+                mana: mana.map_or(0, |mana| mana.current),
+                max_mana: mana.map_or(0, |mana| mana.max),
+                board: self.player_boards.get(&player_id)
+                    .map(|board| board.slots.iter().map(|slot| slot.as_ref().map(|card| card.as_card())).collect())
+                    .unwrap_or_else(|| vec![None; BOARD_SLOTS]),
+            }
+        }).collect();
+
+        GameStateSnapshot { current_turn, time_bank, hand, players }
+    }
+
+    pub fn revealed_cards(&self, players: &Players) -> Vec<RevealedCards> {
+        let cards = |cards: Option<&Vec<CardComponent>>| -> Vec<CardData> {
+            cards.map(|cards| cards.iter().map(|card| card.as_card()).collect()).unwrap_or_default()
+        };
+        players.set.iter().map(|&player_id| RevealedCards {
+            player_id,
+            hand: cards(self.player_hands.get(&player_id).map(|hand| &hand.cards)),
+            deck: cards(self.player_decks.get(&player_id).map(|deck| &deck.cards)),
+        }).collect()
+    }
+
+    // Total power on the player's board minus the opponent's
+    pub fn board_advantage(&self, player_id: EntityID) -> i32 {
+        let power = |board: &BoardComponent| -> i32 {
+            board.slots.iter().flatten().map(|card| card.as_card().power as i32).sum()
+        };
+        self.player_boards.iter()
+            .map(|(&owner, board)| if owner == player_id { power(board) } else { -power(board) })
+            .sum()
+    }
+
+    // Card ids only need to be unique per owner, so new cards take the next one after everything they have
+    pub fn next_card_id(&self, player_id: EntityID) -> EntityID {
+        let deck = self.player_decks.get(&player_id).into_iter().flat_map(|deck| deck.cards.iter().map(|card| card.get_id()));
+        let hand = self.player_hands.get(&player_id).into_iter().flat_map(|hand| hand.cards.iter().map(|card| card.get_id()));
+        let board = self.player_boards.get(&player_id).into_iter().flat_map(|board| board.slots.iter().flatten().map(|card| card.get_id()));
+        let graveyard = self.graveyards.get(&player_id).into_iter().flat_map(|cards| cards.iter().map(|card| card.card_id));
+        deck.chain(hand).chain(board).chain(graveyard).max().map_or(0, |id| id + 1)
+    }
+
+    // Moves a card from the player's hand to their graveyard, returning it as it was discarded
+    pub fn discard(&mut self, player_id: EntityID, card_id: EntityID) -> Option<CardData> {
+        let hand = self.player_hands.get_mut(&player_id)?;
+        let index = hand.cards.iter().position(|card| card.get_id() == card_id)?;
+        let card = hand.cards.remove(index).as_card();
+        self.graveyards.entry(player_id).or_default().push(card.clone());
+        Some(card)
+    }
+
+    // Whether the player ending their turn goes again, spending one of their extra turns or one of the opponent's skips
+    pub fn take_bonus_turn(&mut self, player_id: EntityID, opponent: EntityID) -> bool {
+        if self.consecutive_turns >= MAX_CONSECUTIVE_TURNS {
+            // Anything still owed is dropped so the opponent always gets to play eventually
+            self.extra_turns.remove(&player_id);
+            self.skipped_turns.remove(&opponent);
+            return false;
+        }
+        let owed = match self.extra_turns.get_mut(&player_id) {
+            Some(turns) if *turns > 0 => turns,
+            _ => match self.skipped_turns.get_mut(&opponent) {
+                Some(turns) if *turns > 0 => turns,
+                _ => return false,
+            },
+        };
+        *owed -= 1;
+        true
+    }
+
+    // Closes the log entry of whoever's turn it was and opens one for the next player
+    pub fn log_turn_change(&mut self, next_player: Option<EntityID>) {
+        if let Some(previous) = self.match_log.current_player() {
+            let advantage = self.board_advantage(previous);
+            self.match_log.end_turn(advantage);
+        }
+        if let Some(next_player) = next_player {
+            let advantage = self.board_advantage(next_player);
+            self.match_log.start_turn(next_player, advantage);
+        }
+    }
+
+    pub fn player_checksum(&self, player_id: EntityID, current_turn: Option<EntityID>) -> u64 {
+        self.mirrored_state(player_id, current_turn).checksum()
+    }
+}
+
 #[derive(Component)]
 pub struct GameEventQueue {
     pub(crate) current_events: VecDeque<GameEventWithContext>,
@@ -189,6 +610,14 @@ pub struct GameEventQueue {
 pub struct EventResult {
     pub next_events: Vec<GameEvent>,
     pub reset_timer: bool,
+    // Set when the event came from a request the rules don't allow
+    pub invalid_by: Option<EntityID>,
+}
+
+impl EventResult {
+    pub fn invalid(player_id: EntityID) -> Self {
+        Self { invalid_by: Some(player_id), ..Default::default() }
+    }
 }
 
 impl Default for GameEventQueue {
@@ -201,6 +630,14 @@ impl Default for GameEventQueue {
 }
 
 impl GameEventQueue {
+    pub fn push(&mut self, event: GameEventWithContext) {
+        self.next_events.push_back(event);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.current_events.is_empty() && self.next_events.is_empty()
+    }
+
     pub fn swap_queues(&mut self) {
         // Take ownership of next_events temporarily
         let next = std::mem::take(&mut self.next_events);
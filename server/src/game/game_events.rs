@@ -1,23 +1,62 @@
 use std::fmt::format;
-use bevy::prelude::{Commands, Entity, Mut, Query, Res};
 use bevy::reflect::Set;
-use tracing::warn;
-use shared::card_details::build_default_deck;
-use shared::channel::{CardData, CardType, GameMessage};
+use tracing::{info, warn};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use shared::card_details::{build_deck, build_default_deck, CardConfig, CardEffect};
+use shared::deck_rules::validate_deck;
+use shared::rules::check_turn;
+use shared::channel::{CardData, CardType, GameMessage, BOARD_SLOTS};
+use shared::checksum::MirroredState;
+use shared::errors::{ErrorCode, GameError};
+use shared::messages::MessageCode;
 use shared::EntityID;
-use crate::game::game_event_structs::{CardComponent, DeckComponent, EventResult, GameEvent, GameState, GameStateComponent, HandComponent, SpecialActionType};
-use crate::room::room_components::{CurrentTurn, Players};
-use crate::types::Server;
+use crate::game::costs::{effective_cost, hand_costs};
+use crate::game::effects::{apply_effects, lethal_check, remove_destroyed};
+use crate::game::simulation::Outbox;
+use crate::game::game_event_structs::{BoardComponent, CardComponent, DeckComponent, EventResult, GameEvent, GameState, GameStateComponent, HandComponent, ManaComponent, SpecialActionType, OPENING_HAND, SECOND_PLAYER_TOKEN, STARTING_HEALTH};
+use crate::room::room_components::{CurrentTurn, Players, RoomRules, TimeBank};
+use crate::validation::{validate_attack, validate_play};
 
-pub fn game_event_start_game(game_state: &mut GameStateComponent, players: &Players) -> EventResult {
+pub fn game_event_start_game(outbox: &mut Outbox, game_state: &mut GameStateComponent, players: &Players, current_turn: &mut CurrentTurn, time_bank: Option<&TimeBank>, rng: &mut StdRng) -> EventResult {
     // Verify we have exactly 2 players
     assert_eq!(players.set.len(), 2, "Must have exactly 2 players to initialize game");
     let mut result = EventResult::default();
 
-    // Initialize decks and hands for both players
-    for &player_id in players.set.iter() {
+    // Sorted since set order is arbitrary, so the same seed flips the same coin and deals the same decks
+    let mut seats: Vec<_> = players.set.iter().copied().collect();
+    seats.sort();
+
+    // The coin flip for the first turn
+    if current_turn.player.is_none() {
+        let first_player = seats[rng.gen_range(0..seats.len())];
+        current_turn.player = Some(first_player);
+        let remaining = time_bank.map(|bank| bank.remaining(first_player));
+        for &player_id in &players.set {
+            outbox.send(player_id, GameMessage::CurrentTurn(Some(first_player), remaining));
+        }
+    }
+
+    // Initialize decks and hands for both players; going second is made up for with an extra card and a token
+    for &player_id in &seats {
+        let second = current_turn.player != Some(player_id);
         result.next_events.push(GameEvent::AddCardsToDeck { player_id, amount:30});
-        result.next_events.push(GameEvent::DrawCard { player_id, amount:5});
+        result.next_events.push(GameEvent::DrawCard { player_id, amount: OPENING_HAND + second as u32 });
+        if second {
+            result.next_events.push(GameEvent::CreateCard { player_id, card_key: SECOND_PLAYER_TOKEN.to_string() });
+        }
+        game_state.player_mana.insert(player_id, ManaComponent::new(player_id));
+        game_state.player_health.insert(player_id, STARTING_HEALTH);
+    }
+
+    // The first turn is handed out above rather than through StartTurn
+    game_state.log_turn_change(current_turn.player);
+    if let Some(first_player) = current_turn.player {
+        if let Some(mana) = game_state.player_mana.get_mut(&first_player) {
+            mana.start_turn();
+            send_mana_update(outbox, players, mana);
+        }
     }
 
     game_state.state = GameState::InProgress;
@@ -30,54 +69,54 @@ pub fn game_event_end_game() -> EventResult {
     EventResult::default()
 }
 
-pub fn game_event_add_cards_to_decks(mut commands: &mut Commands, server: &Res<Server>, game_state: &mut GameStateComponent, player_id: EntityID, amount: u32) -> EventResult {
-    let deck = game_state.player_decks.entry(player_id).or_insert_with(|| DeckComponent::new(player_id));
-    let mut new_card_entities: Vec< Entity> = Vec::with_capacity(amount as usize); // Store Entity IDs
-
-    // Get the default deck configuration
-    let deck_cards = build_default_deck();
-
-    // Create entities for each card
-    for (card_id, card_name, card_text) in deck_cards {
-        let new_card = CardComponent::new(
-            CardData {
-                card_id,
-                card_name,
-                card_text,
-                card_type: CardType::Creature,
-                cost: 0,
-                power: 0,
-                health: 0,
+pub fn game_event_add_cards_to_decks(outbox: &mut Outbox, game_state: &mut GameStateComponent, cards: &CardConfig, rng: &mut StdRng, player_id: EntityID, amount: u32) -> EventResult {
+    let mut new_cards: Vec<CardComponent> = Vec::with_capacity(amount as usize);
+
+    // Use the deck the player submitted or queued with, checked again since saved decks can predate card changes
+    let deck_cards = match game_state.deck_choices.get(&player_id) {
+        Some(card_keys) => match validate_deck(cards, card_keys) {
+            Ok(()) => build_deck(cards, card_keys),
+            Err(reason) => {
+                outbox.send(player_id, GameMessage::Error(GameError::with_detail(
+                    ErrorCode::DeckRejected,
+                    MessageCode::StarterDeckInstead { reason: Box::new(reason) },
+                )));
+                build_default_deck(cards)
             }
-        );
-        let entity = commands.spawn(new_card).id();
-        new_card_entities.push(entity);
+        },
+        None => build_default_deck(cards),
+    };
+    let deck = game_state.player_decks.entry(player_id).or_insert_with(|| DeckComponent::new(player_id));
+
+    // Create each card
+    for card in deck_cards {
+        new_cards.push(CardComponent::new(card));
     }
 
-    // Shuffle the new cards using rand
-    use rand::seq::SliceRandom;
-    let mut rng = rand::thread_rng();
-    new_card_entities.shuffle(&mut rng);
+    new_cards.shuffle(rng);
 
     // Add shuffled cards to deck
-    deck.cards.append(&mut new_card_entities);
+    deck.cards.append(&mut new_cards);
 
-    server.send(player_id, GameMessage::CardsInDeck(deck.cards.len() as u32));
+    outbox.send(player_id, GameMessage::CardsInDeck(deck.cards.len() as u32));
     EventResult::default()
 }
 
-pub fn game_event_special_action(server: &Res<Server>, players: &Players, player_id: &EntityID, action_type: &SpecialActionType, targets: &Vec<EntityID>) -> EventResult {
+pub fn game_event_special_action(outbox: &mut Outbox, players: &Players, game_state: &mut GameStateComponent, player_id: &EntityID, action_type: &SpecialActionType, targets: &Vec<EntityID>) -> EventResult {
     // Handle special actions
     if players.set.contains(player_id) {
         match action_type {
             SpecialActionType::DiscardCard => {
-                // Handle discard action
                 for &target in targets {
-                    // Notify players of discarded cards
+                    // Discards are public, so both players get the whole card
+                    let Some(card) = game_state.discard(*player_id, target) else { continue };
                     for &p in &players.set {
-                        server.send(p, GameMessage::CardDiscarded(*player_id, target));
+                        outbox.send(p, GameMessage::CardDiscarded(*player_id, card.clone()));
                     }
                 }
+                outbox.send(*player_id, GameMessage::HandCosts(hand_costs(game_state, *player_id)));
+                send_graveyard_sizes(outbox, players, game_state);
+                send_opponent_hand_size(outbox, players, game_state, *player_id);
             }
             // Handle other special actions
             _ => {}
@@ -86,85 +125,378 @@ pub fn game_event_special_action(server: &Res<Server>, players: &Players, player
     EventResult::default()
 }
 
-pub fn game_event_game_state_change(server: &Res<Server>, players: &Players, game_state: &mut GameStateComponent, new_state: GameState) -> EventResult {
+pub fn game_event_game_state_change(outbox: &mut Outbox, players: &Players, rules: Option<&RoomRules>, game_state: &mut GameStateComponent, new_state: GameState) -> EventResult {
+    // A game can only end once, e.g. a timeout and a concession in the same turn
+    if matches!(game_state.state, GameState::Finished(_)) {
+        return EventResult::default();
+    }
     // Handle game state changes
     game_state.state = new_state.clone();
     // Notify all players of the state change
     if let GameState::Finished(winner) = new_state {
+        game_state.log_turn_change(None);
+        let summary = game_state.match_log.summary(winner);
         for &p in &players.set {
-            server.send(p, GameMessage::GameOver(winner));
+            outbox.send(p, GameMessage::GameOver(winner));
+            outbox.send(p, GameMessage::MatchSummary(summary.clone()));
+        }
+        if rules.is_some_and(|rules| rules.reveal_at_end) {
+            let reveal = game_state.revealed_cards(players);
+            for &p in &players.set {
+                outbox.send(p, GameMessage::FinalReveal(reveal.clone()));
+            }
         }
     }
     EventResult::default()
 }
 
-pub fn game_event_play_card(players: &Players, player_id: EntityID, card_id: EntityID, mut game_state: &mut GameStateComponent) -> EventResult {
-    // Handle playing a card
-    if players.set.contains(&player_id) {
-        // Add card to discard pile
-        game_state.discard_pile.push(card_id);
-        // Notify all players in the room
+// Plays and attacks queued behind the hit that ended the game are refused rather than resolved
+fn check_in_progress(game_state: &GameStateComponent) -> Result<(), GameError> {
+    match game_state.state {
+        GameState::InProgress => Ok(()),
+        _ => Err(ErrorCode::GameOver.into()),
+    }
+}
+
+pub fn game_event_concede(outbox: &mut Outbox, players: &Players, game_state: &mut GameStateComponent, player_id: EntityID) -> EventResult {
+    if !players.set.contains(&player_id) {
+        return EventResult::default();
+    }
+    if !matches!(game_state.state, GameState::InProgress) {
+        outbox.send(player_id, GameMessage::ActionRejected(ErrorCode::GameOver.into()));
+        return EventResult::invalid(player_id);
+    }
+    info!("Player {} conceded", player_id);
+    game_state.match_log.conceded_by = Some(player_id);
+    let winner = players.set.iter().find(|&&p| p != player_id).copied();
+    EventResult {
+        next_events: vec![GameEvent::GameStateChange { new_state: GameState::Finished(winner) }],
+        ..Default::default()
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn game_event_play_card(
+    outbox: &mut Outbox,
+    players: &Players,
+    current_turn: &CurrentTurn,
+    cards: &CardConfig,
+    player_id: EntityID,
+    card_id: EntityID,
+    target: Option<EntityID>,
+    slot: Option<usize>,
+    game_state: &mut GameStateComponent,
+) -> EventResult {
+    if !players.set.contains(&player_id) {
+        return EventResult::default();
+    }
+    // Earlier events in the queue can have changed things since the request was validated
+    if let Err(error) = check_in_progress(game_state)
+        .and_then(|_| check_turn(current_turn.player, player_id))
+        .and_then(|_| validate_play(game_state, players, player_id, card_id, target, slot)) {
+        outbox.send(player_id, GameMessage::ActionRejected(error));
+        return EventResult::invalid(player_id);
+    }
+    let hand = &game_state.player_hands[&player_id];
+    let index = hand.cards.iter().position(|card| card.get_id() == card_id).expect("validate_play found the card");
+    let cost = effective_cost(game_state, player_id, &hand.cards[index]);
+    // Creatures take the free slot they were checked against, everything else goes straight to the discard pile
+    let is_creature = matches!(hand.cards[index].card_type(), CardType::Creature);
+    if is_creature {
+        game_state.player_boards.entry(player_id).or_insert_with(|| BoardComponent::new(player_id));
+    }
+
+    if let Some(mana) = game_state.player_mana.get_mut(&player_id) {
+        mana.current -= cost;
+        send_mana_update(outbox, players, mana);
+    }
+    game_state.discounts.remove(&player_id);
+
+    let mut card = game_state.player_hands.get_mut(&player_id).unwrap().cards.remove(index);
+    card.mark_played();
+    let played = card.as_card();
+    game_state.match_log.record_play(played.card_name.clone());
+    let slot = slot.filter(|_| is_creature);
+    match slot {
+        Some(slot) => game_state.player_boards.get_mut(&player_id).unwrap().slots[slot] = Some(card),
+        None => game_state.graveyards.entry(player_id).or_default().push(played.clone()),
+    }
+
+    // Notify all players in the room
+    for &p in &players.set {
+        outbox.send(p, GameMessage::CardPlayed(player_id, played.clone(), slot));
+    }
+
+    let mut result = EventResult::default();
+    if !played.effects.is_empty() {
+        let outcome = apply_effects(game_state, players, cards, player_id, slot, &played.card_name, &played.effects);
+        for slot_ref in outcome.transformed {
+            // A transformed creature can also have died to a later effect
+            let Some(card) = game_state.player_boards.get(&slot_ref.player_id).and_then(|board| board.slots[slot_ref.slot].as_ref()) else {
+                continue;
+            };
+            for &p in &players.set {
+                outbox.send(p, GameMessage::CardTransformed {
+                    player_id: slot_ref.player_id,
+                    slot: slot_ref.slot,
+                    card: card.as_card(),
+                });
+            }
+        }
+        result.next_events = outcome.next_events;
+        send_effect_results(outbox, players, game_state);
+        if played.effects.iter().any(|effect| matches!(effect, CardEffect::ManaCrystal { .. } | CardEffect::TemporaryMana { .. })) {
+            if let Some(mana) = game_state.player_mana.get(&player_id) {
+                send_mana_update(outbox, players, mana);
+            }
+        }
+    }
+    // Auras may have entered or left the board, so both hands can change
+    send_hand_costs(outbox, players, game_state);
+    send_graveyard_sizes(outbox, players, game_state);
+    send_opponent_hand_size(outbox, players, game_state, player_id);
+    result
+}
+
+// Everyone else at the table only learns how many cards the player holds
+fn send_opponent_hand_size(outbox: &mut Outbox, players: &Players, game_state: &GameStateComponent, player_id: EntityID) {
+    let size = game_state.player_hands.get(&player_id).map_or(0, |hand| hand.cards.len() as u32);
+    for &p in players.set.iter().filter(|&&p| p != player_id) {
+        outbox.send(p, GameMessage::OpponentHandSize(size));
+    }
+}
+
+// Graveyards are public, so both players see every pile grow
+fn send_graveyard_sizes(outbox: &mut Outbox, players: &Players, game_state: &GameStateComponent) {
+    for &owner in &players.set {
+        let size = game_state.graveyards.get(&owner).map_or(0, |cards| cards.len() as u32);
         for &p in &players.set {
-            // TODO
-            // server.send(p, GameMessage::CardPlayed(*player_id, *card_id));
+            outbox.send(p, GameMessage::GraveyardSize { player_id: owner, size });
         }
     }
+}
+
+fn send_hand_costs(outbox: &mut Outbox, players: &Players, game_state: &GameStateComponent) {
+    for &p in &players.set {
+        outbox.send(p, GameMessage::HandCosts(hand_costs(game_state, p)));
+    }
+}
+
+// Effects can touch any creature or player, so both boards and health totals are resent whole
+fn send_effect_results(outbox: &mut Outbox, players: &Players, game_state: &GameStateComponent) {
+    for &owner in &players.set {
+        let board = game_state.player_boards.get(&owner)
+            .map(|board| board.slots.iter().map(|slot| slot.as_ref().map(|card| card.as_card())).collect())
+            .unwrap_or_else(|| vec![None; BOARD_SLOTS]);
+        let health = game_state.player_health.get(&owner).copied().unwrap_or(STARTING_HEALTH);
+        for &p in &players.set {
+            outbox.send(p, GameMessage::BoardUpdate(owner, board.clone()));
+            outbox.send(p, GameMessage::HealthUpdate { player_id: owner, health });
+        }
+    }
+}
+
+// Decks are dealt when the game starts, so the choice can change until then
+pub fn game_event_choose_deck(outbox: &mut Outbox, players: &Players, game_state: &mut GameStateComponent, cards: &CardConfig, player_id: EntityID, deck: Vec<String>) -> EventResult {
+    if !players.set.contains(&player_id) {
+        return EventResult::default();
+    }
+    if !matches!(game_state.state, GameState::Starting) {
+        outbox.send(player_id, GameMessage::Error(ErrorCode::DeckLocked.into()));
+        return EventResult::default();
+    }
+    // Saved decks can predate card changes, so every deck is checked against the current card list
+    match validate_deck(cards, &deck) {
+        Ok(()) => {
+            game_state.deck_choices.insert(player_id, deck);
+        }
+        Err(e) => outbox.send(player_id, GameMessage::Error(GameError::with_detail(ErrorCode::DeckRejected, e))),
+    }
     EventResult::default()
 }
 
+#[allow(clippy::too_many_arguments)]
+pub fn game_event_attack(
+    outbox: &mut Outbox,
+    players: &Players,
+    current_turn: &CurrentTurn,
+    rules: Option<&RoomRules>,
+    game_state: &mut GameStateComponent,
+    player_id: EntityID,
+    attacker: usize,
+    target: Option<usize>,
+) -> EventResult {
+    if !players.set.contains(&player_id) {
+        return EventResult::default();
+    }
+    if let Err(error) = check_in_progress(game_state)
+        .and_then(|_| check_turn(current_turn.player, player_id))
+        .and_then(|_| validate_attack(game_state, players, player_id, attacker, target)) {
+        outbox.send(player_id, GameMessage::ActionRejected(error));
+        return EventResult::invalid(player_id);
+    }
+    let Some(enemy) = players.set.iter().find(|&&p| p != player_id).copied() else {
+        return EventResult::default();
+    };
+    let attacking = game_state.player_boards[&player_id].slots[attacker].as_ref().expect("validate_attack found the attacker");
+    let power = attacking.power();
+    let attacker_name = attacking.get_name();
+
+    match target {
+        Some(slot) => {
+            let defending = game_state.player_boards.get_mut(&enemy)
+                .and_then(|board| board.slots[slot].as_mut())
+                .expect("validate_attack found the defender");
+            // Both creatures deal their power to each other
+            let counter = defending.power();
+            let defender_name = defending.get_name();
+            let dealt = power.min(defending.health());
+            defending.take_damage(power, &attacker_name);
+            game_state.match_log.record_damage(dealt);
+            let excess = power - dealt;
+            if excess > 0 && rules.is_some_and(|rules| rules.overkill_to_hero) {
+                let health = game_state.player_health.entry(enemy).or_insert(STARTING_HEALTH);
+                *health = health.saturating_sub(excess);
+                game_state.match_log.record_damage(excess);
+            }
+            if let Some(attacking) = game_state.player_boards.get_mut(&player_id).and_then(|board| board.slots[attacker].as_mut()) {
+                attacking.take_damage(counter, &defender_name);
+            }
+        }
+        None => {
+            let health = game_state.player_health.entry(enemy).or_insert(STARTING_HEALTH);
+            *health = health.saturating_sub(power);
+            game_state.match_log.record_damage(power);
+        }
+    }
+    if let Some(attacking) = game_state.player_boards.get_mut(&player_id).and_then(|board| board.slots[attacker].as_mut()) {
+        attacking.mark_attacked();
+    }
+
+    remove_destroyed(game_state);
+    send_effect_results(outbox, players, game_state);
+    send_graveyard_sizes(outbox, players, game_state);
+    EventResult {
+        next_events: lethal_check(game_state, players, player_id).into_iter().collect(),
+        ..Default::default()
+    }
+}
+
 pub fn game_event_start_turn(
     current_turn: &mut CurrentTurn,
+    game_state: &mut GameStateComponent,
     players: &Players,
     player_id: EntityID,
-    server: &Res<Server>,
+    time_bank: Option<&TimeBank>,
+    outbox: &mut Outbox,
 ) -> EventResult {
+    // Turns queued behind the end of the game don't happen
+    if matches!(game_state.state, GameState::Finished(_)) {
+        return EventResult::default();
+    }
     println!("Switching turn to player: {:?}", player_id);
     let mut result = EventResult {
         reset_timer: true,
-        next_events: Vec::new(),
+        ..Default::default()
     };
+    game_state.consecutive_turns = if current_turn.player == Some(player_id) { game_state.consecutive_turns + 1 } else { 1 };
     if let turn_player = Some(player_id) {
         current_turn.player = turn_player;
+        game_state.log_turn_change(turn_player);
+        let remaining = time_bank.map(|bank| bank.remaining(player_id));
         // Notify all players
         for &player_id in &players.set {
-            server.send(player_id, GameMessage::CurrentTurn(turn_player));
+            outbox.send(player_id, GameMessage::CurrentTurn(turn_player, remaining));
         }
         result.next_events.push(GameEvent::DrawCard { player_id: turn_player.unwrap(), amount: 1 });
     }
+    if let Some(mana) = game_state.player_mana.get_mut(&player_id) {
+        mana.start_turn();
+        send_mana_update(outbox, players, mana);
+    }
+    if let Some(board) = game_state.player_boards.get_mut(&player_id) {
+        board.slots.iter_mut().flatten().for_each(|card| card.ready());
+    }
+    // Discounts only last for the turn they were gained in, so whatever is left goes when the next one starts
+    if !game_state.discounts.is_empty() {
+        game_state.discounts.clear();
+        send_hand_costs(outbox, players, game_state);
+    }
     result
 }
 
-pub fn game_event_end_turn(players: &Players, current_turn: &mut Mut<CurrentTurn>, player_id: EntityID) -> EventResult {
+// Both players see each other's pools
+fn send_mana_update(outbox: &mut Outbox, players: &Players, mana: &ManaComponent) {
+    for &p in &players.set {
+        outbox.send(p, GameMessage::ManaUpdate {
+            player_id: mana.player_id,
+            current: mana.current,
+            max: mana.max,
+        });
+    }
+}
+
+pub fn game_event_end_turn(players: &Players, current_turn: &mut CurrentTurn, game_state: &mut GameStateComponent, player_id: EntityID) -> EventResult {
     let mut result = EventResult::default();
+    if matches!(game_state.state, GameState::Finished(_)) {
+        return result;
+    }
     if players.set.contains(&player_id) && current_turn.player == Some(player_id) {
-        if let Some(&next_player) = players.set.iter()
+        if let Some(&opponent) = players.set.iter()
             .find(|&&p| p != player_id) {
+            let next_player = if game_state.take_bonus_turn(player_id, opponent) { player_id } else { opponent };
             result.next_events.push(GameEvent::StartTurn { player_id: next_player });
         }
+    } else if players.set.contains(&player_id) {
+        // Ending somebody else's turn
+        result.invalid_by = Some(player_id);
     }
     result
 }
 
-pub fn game_event_draw_card(server: &Res<Server>, query: &Query<&mut CardComponent>, game_state: &mut Mut<GameStateComponent>, player_id: EntityID, amount: u32) -> EventResult {
+pub fn game_event_report_desync(current_turn: &CurrentTurn, game_state: &GameStateComponent, player_id: EntityID, state: &MirroredState) -> EventResult {
+    let authority = game_state.mirrored_state(player_id, current_turn.player);
+
+    match state.first_divergence(&authority) {
+        Some(field) => warn!("Desync for player {}: first divergence at {}", player_id, field),
+        None => info!("Player {} reported a desync but now matches the server", player_id),
+    }
+    EventResult::default()
+}
+
+pub fn game_event_create_card(outbox: &mut Outbox, players: &Players, game_state: &mut GameStateComponent, cards: &CardConfig, player_id: EntityID, card_key: &str) -> EventResult {
+    let Some(definition) = cards.cards.get(card_key) else {
+        warn!("Cannot create unknown card '{}'", card_key);
+        return EventResult::default();
+    };
+    let card = CardComponent::new(definition.to_card_data(card_key, game_state.next_card_id(player_id)));
+    let created = card.as_card();
+    game_state.player_hands.entry(player_id)
+        .or_insert(HandComponent::default(player_id))
+        .cards.push(card);
+
+    // Arrives like a draw, so clients don't need to tell the two apart
+    outbox.send(player_id, GameMessage::CardsDrawn(vec![created]));
+    outbox.send(player_id, GameMessage::HandCosts(hand_costs(game_state, player_id)));
+    send_opponent_hand_size(outbox, players, game_state, player_id);
+    EventResult::default()
+}
+
+// Players without enough cards left simply don't draw
+pub fn game_event_draw_card(outbox: &mut Outbox, players: &Players, game_state: &mut GameStateComponent, player_id: EntityID, amount: u32) -> EventResult {
     if let Some(deck) = game_state.player_decks.get_mut(&player_id) {
         if deck.cards.len() >= amount as usize {
-            let mut drawn_card_entities = deck.cards.drain(..amount as usize).collect::<Vec<_>>();
-
-            let mut drawn_cards: Vec<CardData> = Vec::with_capacity(drawn_card_entities.len());
-            for entity in &drawn_card_entities {
-                if let Ok(card_component) = query.get(*entity) { // Use the passed-in query
-                    drawn_cards.push(card_component.as_card());
-                } else {
-                    warn!("Entity {:?} does not have a CardComponent", entity);
-                }
-            }
+            let mut drawn = deck.cards.drain(..amount as usize).collect::<Vec<_>>();
+            let drawn_cards: Vec<CardData> = drawn.iter().map(|card| card.as_card()).collect();
 
             let hand = game_state.player_hands.entry(player_id)
                 .or_insert(HandComponent::default(player_id));
 
-            hand.cards.append(&mut drawn_card_entities);
+            hand.cards.append(&mut drawn);
 
-            server.send(player_id, GameMessage::CardsDrawn(drawn_cards));
+            outbox.send(player_id, GameMessage::CardsDrawn(drawn_cards));
+            outbox.send(player_id, GameMessage::HandCosts(hand_costs(game_state, player_id)));
+            send_opponent_hand_size(outbox, players, game_state, player_id);
         }
     }
     EventResult::default()
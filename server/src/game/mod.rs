@@ -1,3 +1,6 @@
 pub mod game_event_processing;
 mod game_events;
-pub(crate) mod game_event_structs;
\ No newline at end of file
+pub mod game_event_structs;
+pub mod effects;
+pub mod costs;
+pub mod simulation;
@@ -0,0 +1,103 @@
+use std::collections::VecDeque;
+use rand::rngs::StdRng;
+use shared::card_details::CardConfig;
+use shared::channel::GameMessage;
+use shared::EntityID;
+use crate::game::game_event_structs::{EventResult, GameEvent, GameStateComponent};
+use crate::game::game_events;
+use crate::room::room_components::{CurrentTurn, Players, RoomRules, TimeBank};
+
+// Messages produced while resolving events, in the order the rules sent them. Whoever runs the rules
+// decides what to do with them: the server forwards them to clients, tests and rollouts can inspect or drop them
+#[derive(Debug, Default)]
+pub struct Outbox {
+    pub messages: Vec<(EntityID, GameMessage)>,
+}
+
+impl Outbox {
+    pub fn send(&mut self, player_id: EntityID, message: GameMessage) {
+        self.messages.push((player_id, message));
+    }
+
+    // Everything one player was sent
+    pub fn to(&self, player_id: EntityID) -> impl Iterator<Item = &GameMessage> {
+        self.messages.iter().filter(move |(p, _)| *p == player_id).map(|(_, message)| message)
+    }
+}
+
+// Everything the rules read or change about one room. Borrowed from the room's components by the Bevy systems,
+// or from plain values anywhere else, so the same rules run with or without a World
+pub struct Table<'a> {
+    pub players: &'a Players,
+    pub current_turn: &'a mut CurrentTurn,
+    pub game_state: &'a mut GameStateComponent,
+    pub rules: Option<&'a RoomRules>,
+    pub time_bank: Option<&'a TimeBank>,
+    pub cards: &'a CardConfig,
+    // The room's seeded generator; the rules never reach for any other randomness
+    pub rng: &'a mut StdRng,
+}
+
+// Applies one event to the table. Follow-up events come back in the result rather than being queued,
+// so the caller controls when they run
+pub fn resolve(table: &mut Table, event: GameEvent, outbox: &mut Outbox) -> EventResult {
+    let Table { players, current_turn, game_state, rules, time_bank, cards, rng } = table;
+    let (players, rules, time_bank, cards) = (*players, *rules, *time_bank, *cards);
+    match event {
+        GameEvent::StartGame {} => {
+            game_events::game_event_start_game(outbox, game_state, players, current_turn, time_bank, rng)
+        }
+        GameEvent::EndGame { .. } => {
+            game_events::game_event_end_game()
+        }
+        GameEvent::StartTurn { player_id } => {
+            game_events::game_event_start_turn(current_turn, game_state, players, player_id, time_bank, outbox)
+        }
+        GameEvent::EndTurn { player_id } => {
+            game_events::game_event_end_turn(players, current_turn, game_state, player_id)
+        }
+        GameEvent::ChooseDeck { player_id, cards: deck } => {
+            game_events::game_event_choose_deck(outbox, players, game_state, cards, player_id, deck)
+        }
+        GameEvent::Attack { player_id, attacker, target } => {
+            game_events::game_event_attack(outbox, players, current_turn, rules, game_state, player_id, attacker, target)
+        }
+        GameEvent::AddCardsToDeck { player_id, amount } => {
+            game_events::game_event_add_cards_to_decks(outbox, game_state, cards, rng, player_id, amount)
+        }
+        GameEvent::DrawCard { player_id, amount } => {
+            game_events::game_event_draw_card(outbox, players, game_state, player_id, amount)
+        }
+        GameEvent::PlayCard { player_id, card_id, target, slot } => {
+            game_events::game_event_play_card(outbox, players, current_turn, cards, player_id, card_id, target, slot, game_state)
+        }
+        GameEvent::GameStateChange { new_state } => {
+            game_events::game_event_game_state_change(outbox, players, rules, game_state, new_state)
+        }
+        GameEvent::SpecialAction { player_id, action_type, targets } => {
+            game_events::game_event_special_action(outbox, players, game_state, &player_id, &action_type, &targets)
+        }
+        GameEvent::ReportDesync { player_id, state } => {
+            game_events::game_event_report_desync(current_turn, game_state, player_id, &state)
+        }
+        GameEvent::CreateCard { player_id, card_key } => {
+            game_events::game_event_create_card(outbox, players, game_state, cards, player_id, &card_key)
+        }
+        GameEvent::Concede { player_id } => {
+            game_events::game_event_concede(outbox, players, game_state, player_id)
+        }
+    }
+}
+
+// Resolves an event and every follow-up it causes, first in first out like a room's queue, for callers
+// that have no queue of their own. Returns the players whose actions were refused along the way
+pub fn resolve_all(table: &mut Table, event: GameEvent, outbox: &mut Outbox) -> Vec<EntityID> {
+    let mut queue = VecDeque::from([event]);
+    let mut refused = Vec::new();
+    while let Some(event) = queue.pop_front() {
+        let result = resolve(table, event, outbox);
+        refused.extend(result.invalid_by);
+        queue.extend(result.next_events);
+    }
+    refused
+}
@@ -0,0 +1,106 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiPlugin};
+use crate::action_history::ActionHistory;
+use crate::game::game_event_structs::{GameEvent, GameEventContext, GameEventQueue, GameEventWithContext, GameState, GameStateComponent};
+use crate::room::room_components::{CurrentTurn, Players, Room, TurnTimer};
+
+// Headful debug view of every room, enabled with the `inspector` feature
+pub struct InspectorPlugin;
+
+impl Plugin for InspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugins((DefaultPlugins, EguiPlugin))
+            .add_systems(Startup, |mut commands: Commands| {
+                commands.spawn(Camera2d);
+            })
+            .add_systems(Update, inspector_ui);
+    }
+}
+
+fn inspector_ui(
+    mut contexts: EguiContexts,
+    rooms: Query<(Entity, &Room, &Players, &CurrentTurn, &TurnTimer, &GameStateComponent, &GameEventQueue)>,
+    mut game_events: EventWriter<GameEventWithContext>,
+    history: Res<ActionHistory>,
+) {
+    let mut injected = Vec::new();
+
+    egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+        let flagged = history.flagged();
+        ui.collapsing(format!("Flagged players ({})", flagged.len()), |ui| {
+            for (player_id, flags) in flagged {
+                ui.collapsing(format!("Player {player_id}: {flags:?}"), |ui| {
+                    for record in history.history(player_id) {
+                        ui.monospace(format!(
+                            "{:>8.2}s {:<8} own turn: {:<5} reaction: {:?}",
+                            record.at, record.action, record.own_turn, record.reaction_secs
+                        ));
+                    }
+                });
+            }
+        });
+        ui.separator();
+
+        ui.heading(format!("Rooms ({})", rooms.iter().count()));
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (room_entity, room, players, current_turn, timer, game_state, event_queue) in rooms.iter() {
+                egui::CollapsingHeader::new(format!("{} ({:?})", room.room_id, room_entity))
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        let mut inject = |event: GameEvent| injected.push(GameEventWithContext {
+                            context: GameEventContext { room_entity },
+                            event,
+                        });
+
+                        ui.label(format!("State: {:?}", game_state.state));
+                        ui.label(format!("Current turn: {:?}", current_turn.player));
+                        ui.label(format!(
+                            "Turn timer: {:.1}s remaining",
+                            timer.timer.remaining_secs()
+                        ));
+
+                        for &player_id in &players.set {
+                            ui.horizontal(|ui| {
+                                let deck = game_state.player_decks.get(&player_id).map_or(0, |d| d.cards.len());
+                                let hand = game_state.player_hands.get(&player_id).map_or(0, |h| h.cards.len());
+                                ui.label(format!("Player {player_id}: {hand} in hand, {deck} in deck"));
+
+                                if ui.button("Draw").clicked() {
+                                    inject(GameEvent::DrawCard { player_id, amount: 1 });
+                                }
+                                if ui.button("End turn").clicked() {
+                                    inject(GameEvent::EndTurn { player_id });
+                                }
+                                if ui.button("Win").clicked() {
+                                    inject(GameEvent::GameStateChange {
+                                        new_state: GameState::Finished(Some(player_id)),
+                                    });
+                                }
+                            });
+                        }
+
+                        if players.set.len() == 2 && ui.button("Start game").clicked() {
+                            inject(GameEvent::StartGame {});
+                        }
+
+                        ui.collapsing(
+                            format!(
+                                "Queued events ({} current, {} next)",
+                                event_queue.current_events.len(),
+                                event_queue.next_events.len()
+                            ),
+                            |ui| {
+                                for event in event_queue.current_events.iter().chain(event_queue.next_events.iter()) {
+                                    ui.monospace(format!("{:?}", event.event));
+                                }
+                            },
+                        );
+                    });
+            }
+        });
+    });
+
+    game_events.send_batch(injected);
+}
@@ -0,0 +1,20 @@
+pub mod server;
+pub mod types;
+pub mod player_component;
+pub mod server_plugin;
+pub mod room;
+pub mod game;
+pub mod replay;
+pub mod action_history;
+pub mod room_log;
+pub mod deck_store;
+pub mod account_store;
+pub mod credential_store;
+pub mod storage;
+pub mod bot;
+pub mod puzzle;
+pub mod validation;
+pub mod logging;
+pub mod admin;
+#[cfg(feature = "inspector")]
+pub mod inspector;
@@ -0,0 +1,124 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use bevy::prelude::*;
+use serde::Deserialize;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    Minutely,
+    Hourly,
+    #[default]
+    Daily,
+    Never,
+}
+
+impl From<LogRotation> for Rotation {
+    fn from(rotation: LogRotation) -> Self {
+        match rotation {
+            LogRotation::Minutely => Rotation::MINUTELY,
+            LogRotation::Hourly => Rotation::HOURLY,
+            LogRotation::Daily => Rotation::DAILY,
+            LogRotation::Never => Rotation::NEVER,
+        }
+    }
+}
+
+// Where logs go and how much each module says
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct LogConfig {
+    pub level: String,
+    // Module path to level, e.g. "server_backend::game" = "debug"
+    pub modules: BTreeMap<String, String>,
+    // Rolling log files are written here as well as to stdout; None keeps logs on stdout only
+    pub directory: Option<PathBuf>,
+    pub rotation: LogRotation,
+    // Older files are deleted once there are more than this
+    pub max_files: usize,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            modules: BTreeMap::new(),
+            directory: None,
+            rotation: LogRotation::default(),
+            max_files: 7,
+        }
+    }
+}
+
+impl LogConfig {
+    // A missing file just means the defaults
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => Ok(serde_json::from_str(&contents)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    // The same syntax as RUST_LOG, e.g. "info,server_backend::game=debug"
+    pub fn directives(&self) -> String {
+        std::iter::once(self.level.clone())
+            .chain(self.modules.iter().map(|(module, level)| format!("{module}={level}")))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+// Changes log levels while the server runs
+#[derive(Resource)]
+pub struct LogControl {
+    filter: reload::Handle<EnvFilter, Registry>,
+    directives: String,
+    // Dropping this stops the file writer, so it lives as long as the app
+    _file_writer: Option<WorkerGuard>,
+}
+
+impl LogControl {
+    pub fn directives(&self) -> &str {
+        &self.directives
+    }
+
+    pub fn set_directives(&mut self, directives: &str) -> Result<(), String> {
+        let filter = EnvFilter::try_new(directives).map_err(|e| e.to_string())?;
+        self.filter.reload(filter).map_err(|e| e.to_string())?;
+        self.directives = directives.to_string();
+        Ok(())
+    }
+}
+
+// Installs the global subscriber. RUST_LOG, when set, wins over the configured levels
+pub fn init_logging(config: &LogConfig) -> Result<LogControl, Box<dyn std::error::Error>> {
+    let directives = std::env::var("RUST_LOG").unwrap_or_else(|_| config.directives());
+    let (filter, handle) = reload::Layer::new(EnvFilter::try_new(&directives)?);
+
+    let (file_layer, file_writer) = match &config.directory {
+        Some(directory) => {
+            let appender = RollingFileAppender::builder()
+                .rotation(config.rotation.into())
+                .filename_prefix("server")
+                .filename_suffix("log")
+                .max_log_files(config.max_files.max(1))
+                .build(directory)?;
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            (Some(fmt::layer().with_ansi(false).with_writer(writer)), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    Registry::default()
+        .with(filter)
+        .with(fmt::layer())
+        .with(file_layer)
+        .try_init()?;
+    Ok(LogControl { filter: handle, directives, _file_writer: file_writer })
+}
@@ -1,35 +1,105 @@
 use bevy::app::*;
-use bevy::log::tracing_subscriber;
+use bevy::core::TaskPoolPlugin;
 use bevy::time::TimePlugin;
 use bevy_cobweb::prelude::ReactPlugin;
-use crate::room::room_plugin::RoomPlugin;
-use crate::server::setup_server;
-use crate::server_plugin::handle_server_events;
-
-mod server;
-mod types;
-mod player_component;
-mod server_plugin;
-mod room;
-mod game;
+use server_backend::room::room_components::{TimeBankSettings};
+use server_backend::room::room_plugin::RoomPlugin;
+use server_backend::server::setup_server;
+use bots::heuristic::load_weights_from;
+use server_backend::account_store::AccountStore;
+use server_backend::bot::BotWeights;
+use server_backend::admin::{run_admin_commands, AdminConsole};
+use server_backend::logging::{init_logging, LogConfig};
+use server_backend::credential_store::CredentialStore;
+use server_backend::deck_store::DeckStore;
+use server_backend::game::game_event_structs::DesyncDetection;
+use server_backend::puzzle::{PuzzleBook, DEFAULT_PUZZLES_DIR};
+use server_backend::storage::Storage;
+use server_backend::replay::{EventRecorder, EventReplay};
+use server_backend::server_plugin::{drop_silent_connections, handle_server_events};
 
 fn main() {
-    let subscriber = tracing_subscriber::FmtSubscriber::builder()
-        .with_max_level(tracing::Level::INFO)
-        .finish();
-    tracing::subscriber::set_global_default(subscriber)
-        .expect("setting default subscriber failed");
+    let args: Vec<String> = std::env::args().collect();
+    let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1));
+
+    // --log-config <file> sets log levels per module and where rolling log files go
+    let log_config_path = flag("--log-config").map(String::as_str).unwrap_or("logging.json");
+    let log_config = LogConfig::load(log_config_path.as_ref()).expect("failed to load logging config");
+    let log_control = init_logging(&log_config).expect("setting up logging failed");
+
+    // --record <file> logs every received event, --replay <file> feeds a recording into a fresh server
+    let record_path = flag("--record");
+    let replay_path = flag("--replay");
+
+    // Replays get their own port so they can run next to a live server
+    let server = setup_server(if replay_path.is_some() { "127.0.0.1:0" } else { "127.0.0.1:48888" });
 
-    let server = setup_server();
+    let mut app = App::new();
 
-    App::new()
+    // The inspector brings its own window and runner
+    #[cfg(not(feature = "inspector"))]
+    app.add_plugins((
+        ScheduleRunnerPlugin::run_loop(std::time::Duration::from_millis(100)),
+        TaskPoolPlugin::default(),
+        TimePlugin::default(),
+    ));
+    #[cfg(feature = "inspector")]
+    app.add_plugins(server_backend::inspector::InspectorPlugin);
+
+    // --bot-weights <file> tunes practice bots; inserted before RoomPlugin so the bundled file isn't needed
+    if let Some(path) = flag("--bot-weights") {
+        let weights = load_weights_from(path.as_ref()).expect("failed to load bot weights");
+        app.insert_resource(BotWeights(weights));
+    }
+
+    app
         .add_plugins((
-            ScheduleRunnerPlugin::run_loop(std::time::Duration::from_millis(100)),
             ReactPlugin,
             RoomPlugin,
-            TimePlugin::default(),
         ))
         .insert_resource(server)
-        .add_systems(Update, handle_server_events)
-        .run();
+        .insert_resource(log_control)
+        .insert_resource(AdminConsole::from_stdin())
+        .add_systems(Update, (handle_server_events, drop_silent_connections, run_admin_commands));
+
+    if let Some(path) = record_path {
+        let recorder = EventRecorder::create(path.as_ref()).expect("failed to create recording file");
+        app.insert_resource(recorder);
+    }
+    if let Some(path) = replay_path {
+        let replay = EventReplay::load(path.as_ref()).expect("failed to load recording");
+        app.insert_resource(replay);
+    }
+
+    // --storage <dir> picks where saved decks, match results and win/loss records are kept
+    let storage_path = flag("--storage").map(String::as_str).unwrap_or("storage");
+    let storage = Storage::open(storage_path.as_ref()).expect("failed to open storage");
+    let decks = DeckStore::with_storage(&storage).expect("failed to load saved decks");
+    app.insert_resource(decks).insert_resource(storage);
+
+    // --accounts <file> picks where account progression is kept
+    let account_path = flag("--accounts").map(String::as_str).unwrap_or("accounts.json");
+    let accounts = AccountStore::load(account_path.as_ref()).expect("failed to load accounts");
+    app.insert_resource(accounts);
+
+    // --credentials <file> picks where usernames and password hashes are kept
+    let credential_path = flag("--credentials").map(String::as_str).unwrap_or("credentials.json");
+    let credentials = CredentialStore::load(credential_path.as_ref()).expect("failed to load credentials");
+    app.insert_resource(credentials);
+
+    // --puzzles <dir> picks where puzzle files are read from
+    let puzzles_path = flag("--puzzles").map(String::as_str).unwrap_or(DEFAULT_PUZZLES_DIR);
+    let puzzles = PuzzleBook::load(puzzles_path.as_ref()).expect("failed to load puzzles");
+    app.insert_resource(puzzles);
+
+    if args.iter().any(|a| a == "--desync-check") {
+        app.insert_resource(DesyncDetection);
+    }
+
+    // --time-bank <secs> gives each player a chess-style total clock for competitive play
+    if let Some(secs) = flag("--time-bank").and_then(|s| s.parse().ok()) {
+        app.insert_resource(TimeBankSettings { total: std::time::Duration::from_secs(secs) });
+    }
+
+    app.run();
 }
\ No newline at end of file
@@ -1,4 +1,9 @@
-use bevy::prelude::{Component, Entity, Event};
+use std::collections::HashMap;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::{Component, Entity, Event, EventWriter, Resource};
+use shared::channel::{BotDifficulty, LobbyRules};
+use crate::room::room_components::SendSnapshot;
+use crate::room_log::RoomLogEvent;
 
 #[derive(Component)]
 pub struct Player {
@@ -6,11 +11,132 @@ pub struct Player {
     pub room: Entity,
 }
 
+// Sent LeaveGame; the room lets them go once the game they gave up has finished
+#[derive(Component)]
+pub struct Leaving;
+
 #[derive(Event)]
 pub struct PlayerJoinEvent(pub u128);
 
+// What to do when a player who already has a Player logs in again
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicateConnectionPolicy {
+    // Hand the existing Player's seat to the new connection
+    #[default]
+    KickOlder,
+    // Keep the existing Player and refuse to set up another one
+    RejectNewer,
+}
+
+// Open sockets per player, since the same account can be logged in from more than one connection
+#[derive(Resource, Default)]
+pub struct PlayerSessions {
+    pub policy: DuplicateConnectionPolicy,
+    connections: HashMap<u128, u32>,
+    // Seconds since startup of each client's last Ping. Clients that never ping aren't tracked
+    heartbeats: HashMap<u128, f32>,
+}
+
+impl PlayerSessions {
+    pub fn connect(&mut self, player_id: u128) {
+        *self.connections.entry(player_id).or_default() += 1;
+    }
+
+    // Returns how many sessions are still open for the player
+    pub fn disconnect(&mut self, player_id: u128) -> u32 {
+        let Some(count) = self.connections.get_mut(&player_id) else {
+            return 0;
+        };
+        *count = count.saturating_sub(1);
+        let remaining = *count;
+        if remaining == 0 {
+            self.connections.remove(&player_id);
+            self.heartbeats.remove(&player_id);
+        }
+        remaining
+    }
+
+    pub fn is_connected(&self, player_id: u128) -> bool {
+        self.connections.contains_key(&player_id)
+    }
+
+    pub fn heartbeat(&mut self, player_id: u128, now: f32) {
+        self.heartbeats.insert(player_id, now);
+    }
+
+    // Clients silent for longer than `timeout`, forgotten so each is only reported once
+    pub fn take_silent(&mut self, now: f32, timeout: f32) -> Vec<u128> {
+        let silent: Vec<u128> = self.heartbeats.iter()
+            .filter(|&(_, &last)| now - last > timeout)
+            .map(|(&player_id, _)| player_id)
+            .collect();
+        for player_id in &silent {
+            self.heartbeats.remove(player_id);
+        }
+        silent
+    }
+}
+
 #[derive(Event)]
 pub struct PlayerLeaveEvent {
     pub player_id: u128,
     pub room_entity: Entity,
-}
\ No newline at end of file
+}
+// The last socket closed; the room decides whether to hold the seat or let the player go
+#[derive(Event)]
+pub struct PlayerDisconnectEvent {
+    pub player_id: u128,
+    pub room_entity: Entity,
+}
+
+// A player logged back in to the same account within the reconnect grace period and wants their seat back
+#[derive(Event)]
+pub struct PlayerResumeEvent {
+    pub player_id: u128,
+    pub room_entity: Entity,
+}
+
+// The player sent Ready or any other request from their room, so they're at the keyboard
+#[derive(Event)]
+pub struct PlayerReadyEvent {
+    pub player_id: u128,
+    pub room_entity: Entity,
+}
+
+#[derive(Debug, Clone)]
+pub enum LobbyAction {
+    Create(LobbyRules),
+    Join(String),
+    // A room of its own with a bot in the second seat
+    Bot(BotDifficulty),
+}
+
+// A player wants to move from their current room into a private lobby or bot game
+#[derive(Event)]
+pub struct LobbyRequest {
+    pub player_id: u128,
+    pub room_entity: Entity,
+    pub action: LobbyAction,
+}
+
+// A player asked to play again in the room they just finished a game in, or agreed to their opponent's ask
+#[derive(Event)]
+pub struct RematchRequest {
+    pub player_id: u128,
+    pub room_entity: Entity,
+    pub accept: bool,
+}
+
+// Grouped so the server event handler stays within Bevy's system parameter limit
+#[derive(SystemParam)]
+pub struct PlayerEventWriters<'w> {
+    pub join: EventWriter<'w, PlayerJoinEvent>,
+    pub leave: EventWriter<'w, PlayerLeaveEvent>,
+    pub disconnect: EventWriter<'w, PlayerDisconnectEvent>,
+    pub resume: EventWriter<'w, PlayerResumeEvent>,
+    pub lobby: EventWriter<'w, LobbyRequest>,
+    pub rematch: EventWriter<'w, RematchRequest>,
+    pub ready: EventWriter<'w, PlayerReadyEvent>,
+    pub room_log: EventWriter<'w, RoomLogEvent>,
+    pub snapshot: EventWriter<'w, SendSnapshot>,
+}
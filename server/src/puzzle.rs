@@ -0,0 +1,221 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use bevy::prelude::Resource;
+use serde::Deserialize;
+use shared::card_details::CardConfig;
+use shared::channel::{CardData, GameMessage, PuzzleInfo, PuzzleMove, PuzzleObjective, BOARD_SLOTS};
+use shared::errors::ErrorCode;
+use shared::messages::MessageCode;
+use shared::EntityID;
+use crate::game::game_event_structs::{BoardComponent, CardComponent, GameEvent, GameState, GameStateComponent, HandComponent, ManaComponent, STARTING_HEALTH};
+use crate::game::simulation::{resolve_all, Outbox, Table};
+use crate::room::room_components::{CurrentTurn, Players};
+use crate::validation::validate_action;
+
+// Where the server looks for puzzles unless started with `--puzzles <dir>`, relative to where it runs like its
+// other files. Every .toml file in it is one puzzle, named by the file
+pub const DEFAULT_PUZZLES_DIR: &str = "server/assets/puzzles";
+
+// Seats in a puzzle's table; the solver always has the turn and the opponent never acts
+pub const PUZZLE_PLAYER: EntityID = 1;
+pub const PUZZLE_OPPONENT: EntityID = 2;
+
+// Written as e.g. `board = [{ slot = 0, card = "stellar_cruiser" }]`
+#[derive(Deserialize, Clone, Debug)]
+pub struct PlacedCard {
+    pub slot: usize,
+    // Card id from cards.toml
+    pub card: String,
+}
+
+// A fixed position to solve in one turn, read from a puzzle file
+#[derive(Deserialize, Clone, Debug)]
+pub struct Puzzle {
+    pub name: String,
+    pub description: String,
+    pub objective: PuzzleObjective,
+    pub move_limit: usize,
+    pub mana: u32,
+    #[serde(default = "starting_health")]
+    pub health: u32,
+    #[serde(default = "starting_health")]
+    pub opponent_health: u32,
+    // Card ids from cards.toml
+    #[serde(default)]
+    pub hand: Vec<String>,
+    #[serde(default)]
+    pub board: Vec<PlacedCard>,
+    #[serde(default)]
+    pub opponent_board: Vec<PlacedCard>,
+}
+
+fn starting_health() -> u32 {
+    STARTING_HEALTH
+}
+
+// The cards of a puzzle with their ids, hand first, so the client and the check agree on what each id is
+struct PuzzleCards {
+    hand: Vec<CardData>,
+    board: Vec<Option<CardData>>,
+    opponent_board: Vec<Option<CardData>>,
+}
+
+impl Puzzle {
+    fn cards(&self, known: &CardConfig) -> Result<PuzzleCards, MessageCode> {
+        let mut next_id = 0;
+        let mut card = |key: &str| -> Result<CardData, MessageCode> {
+            let definition = known.cards.get(key).ok_or_else(|| MessageCode::UnknownCard { card: key.to_string() })?;
+            next_id += 1;
+            Ok(definition.to_card_data(key, next_id - 1))
+        };
+        let hand = self.hand.iter().map(|key| card(key)).collect::<Result<_, _>>()?;
+        let board = place(&self.board, &mut card)?;
+        let opponent_board = place(&self.opponent_board, &mut card)?;
+        Ok(PuzzleCards { hand, board, opponent_board })
+    }
+
+    pub fn info(&self, id: &str, completed: bool, known: &CardConfig) -> Result<PuzzleInfo, MessageCode> {
+        let cards = self.cards(known)?;
+        Ok(PuzzleInfo {
+            id: id.to_string(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            objective: self.objective,
+            move_limit: self.move_limit,
+            mana: self.mana,
+            health: self.health,
+            opponent_health: self.opponent_health,
+            hand: cards.hand,
+            board: cards.board,
+            opponent_board: cards.opponent_board,
+            completed,
+        })
+    }
+
+    // A game already in progress on the solver's turn, with nothing in either deck
+    pub fn setup(&self, known: &CardConfig) -> Result<(Players, CurrentTurn, GameStateComponent), MessageCode> {
+        let cards = self.cards(known)?;
+        let mut game_state = GameStateComponent {
+            state: GameState::InProgress,
+            ..Default::default()
+        };
+        let mut hand = HandComponent::default(PUZZLE_PLAYER);
+        hand.cards = cards.hand.into_iter().map(CardComponent::new).collect();
+        game_state.player_hands.insert(PUZZLE_PLAYER, hand);
+        game_state.player_hands.insert(PUZZLE_OPPONENT, HandComponent::default(PUZZLE_OPPONENT));
+        for (player_id, slots) in [(PUZZLE_PLAYER, cards.board), (PUZZLE_OPPONENT, cards.opponent_board)] {
+            let mut board = BoardComponent::new(player_id);
+            board.slots = slots.into_iter().map(|slot| slot.map(CardComponent::new)).collect();
+            game_state.player_boards.insert(player_id, board);
+        }
+        game_state.player_mana.insert(PUZZLE_PLAYER, ManaComponent { player_id: PUZZLE_PLAYER, current: self.mana, max: self.mana });
+        game_state.player_mana.insert(PUZZLE_OPPONENT, ManaComponent::new(PUZZLE_OPPONENT));
+        game_state.player_health.insert(PUZZLE_PLAYER, self.health);
+        game_state.player_health.insert(PUZZLE_OPPONENT, self.opponent_health);
+        game_state.log_turn_change(Some(PUZZLE_PLAYER));
+
+        let players = Players { set: HashSet::from([PUZZLE_PLAYER, PUZZLE_OPPONENT]) };
+        Ok((players, CurrentTurn { player: Some(PUZZLE_PLAYER) }, game_state))
+    }
+
+    // Plays the moves out under the normal rules; solved as soon as the objective is reached
+    pub fn check(&self, moves: &[PuzzleMove], known: &CardConfig) -> Result<(), MessageCode> {
+        if moves.len() > self.move_limit {
+            return Err(MessageCode::PuzzleMoveLimit { limit: self.move_limit });
+        }
+        let (players, mut current_turn, mut game_state) = self.setup(known)?;
+        // Nothing in a puzzle is random, but the rules still need a generator to hand around
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut outbox = Outbox::default();
+        for (step, &puzzle_move) in moves.iter().enumerate() {
+            let (message, event) = match puzzle_move {
+                PuzzleMove::PlayCard { card_id, slot } => (
+                    GameMessage::PlayCard { card_id, target: None, slot },
+                    GameEvent::PlayCard { player_id: PUZZLE_PLAYER, card_id, target: None, slot },
+                ),
+                PuzzleMove::Attack { attacker, target } => (
+                    GameMessage::Attack { attacker, target },
+                    GameEvent::Attack { player_id: PUZZLE_PLAYER, attacker, target },
+                ),
+            };
+            let refused = |reason| MessageCode::PuzzleMoveRefused { step: step + 1, reason: Box::new(reason) };
+            validate_action(&message, PUZZLE_PLAYER, &players, &current_turn, &game_state).map_err(refused)?;
+            let mut table = Table {
+                players: &players,
+                current_turn: &mut current_turn,
+                game_state: &mut game_state,
+                rules: None,
+                time_bank: None,
+                cards: known,
+                rng: &mut rng,
+            };
+            if !resolve_all(&mut table, event, &mut outbox).is_empty() {
+                let reason = outbox.to(PUZZLE_PLAYER)
+                    .filter_map(|message| match message {
+                        GameMessage::ActionRejected(error) => Some(error.clone()),
+                        _ => None,
+                    })
+                    .last()
+                    .unwrap_or_else(|| ErrorCode::InvalidTarget.into());
+                return Err(refused(reason));
+            }
+            if self.reached(&game_state) {
+                return Ok(());
+            }
+        }
+        Err(MessageCode::PuzzleObjectiveMissed)
+    }
+
+    fn reached(&self, game_state: &GameStateComponent) -> bool {
+        match self.objective {
+            PuzzleObjective::WinThisTurn => matches!(game_state.state, GameState::Finished(Some(PUZZLE_PLAYER))),
+            PuzzleObjective::ClearEnemyBoard => game_state.player_boards.get(&PUZZLE_OPPONENT)
+                .is_none_or(|board| board.slots.iter().all(Option::is_none)),
+        }
+    }
+}
+
+fn place(
+    placed: &[PlacedCard],
+    card: &mut impl FnMut(&str) -> Result<CardData, MessageCode>,
+) -> Result<Vec<Option<CardData>>, MessageCode> {
+    let mut slots = vec![None; BOARD_SLOTS];
+    for PlacedCard { slot, card: key } in placed {
+        let Some(free) = slots.get_mut(*slot) else {
+            return Err(MessageCode::SlotOutOfRange { max_slot: BOARD_SLOTS - 1 });
+        };
+        if free.is_some() {
+            return Err(MessageCode::SlotTaken { slot: *slot });
+        }
+        *free = Some(card(key)?);
+    }
+    Ok(slots)
+}
+
+// Every puzzle the server offers, read once at startup. Empty until main loads it
+#[derive(Resource, Default)]
+pub struct PuzzleBook(pub BTreeMap<String, Puzzle>);
+
+impl PuzzleBook {
+    pub fn load(dir: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        load_puzzles_from(dir).map(Self)
+    }
+}
+
+// Puzzle ids are the file names without .toml, listed in id order
+pub fn load_puzzles_from(dir: &Path) -> Result<BTreeMap<String, Puzzle>, Box<dyn std::error::Error>> {
+    let mut puzzles = BTreeMap::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|extension| extension != "toml") {
+            continue;
+        }
+        let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) else { continue };
+        let puzzle: Puzzle = toml::from_str(&std::fs::read_to_string(&path)?)
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        puzzles.insert(id.to_string(), puzzle);
+    }
+    Ok(puzzles)
+}
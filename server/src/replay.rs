@@ -0,0 +1,94 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use bevy::prelude::*;
+use bevy_simplenet::ClientId;
+use serde::{Deserialize, Serialize};
+use shared::channel::GameMessage;
+
+// The parts of a ServerEvent that matter for reproducing a session
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum RecordedEvent {
+    Connected,
+    Disconnected,
+    Request(GameMessage),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RecordedFrame {
+    pub tick: u64,
+    pub client_id: ClientId,
+    pub event: RecordedEvent,
+}
+
+// Appends every received event to a JSON lines file
+#[derive(Resource)]
+pub struct EventRecorder {
+    writer: BufWriter<File>,
+    tick: u64,
+}
+
+impl EventRecorder {
+    pub fn create(path: &Path) -> std::io::Result<Self> {
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+            tick: 0,
+        })
+    }
+
+    pub fn record(&mut self, client_id: ClientId, event: &RecordedEvent) {
+        let frame = RecordedFrame {
+            tick: self.tick,
+            client_id,
+            event: event.clone(),
+        };
+        let result = serde_json::to_string(&frame)
+            .map_err(std::io::Error::from)
+            .and_then(|line| writeln!(self.writer, "{line}"));
+        if let Err(e) = result {
+            warn!("Failed to record server event: {}", e);
+        }
+    }
+
+    // Called once per update so replay can reproduce the original batching
+    pub fn end_tick(&mut self) {
+        self.tick += 1;
+        if let Err(e) = self.writer.flush() {
+            warn!("Failed to flush event recording: {}", e);
+        }
+    }
+}
+
+// Feeds a recording back in, releasing each frame on the tick it was originally received
+#[derive(Resource)]
+pub struct EventReplay {
+    frames: VecDeque<RecordedFrame>,
+    tick: u64,
+}
+
+impl EventReplay {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut frames = VecDeque::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                frames.push_back(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(Self { frames, tick: 0 })
+    }
+
+    pub fn next_tick(&mut self) -> Vec<RecordedFrame> {
+        let mut due = Vec::new();
+        while self.frames.front().is_some_and(|frame| frame.tick <= self.tick) {
+            due.extend(self.frames.pop_front());
+        }
+        self.tick += 1;
+        due
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
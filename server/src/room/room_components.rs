@@ -1,5 +1,9 @@
-use std::collections::HashSet;
-use bevy::prelude::{Component, Timer};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use bevy::prelude::{Component, Entity, Event, Resource, Timer};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use shared::channel::LobbyRules;
 use shared::EntityID;
 
 #[derive(Component)]
@@ -7,26 +11,224 @@ pub struct Room {
     pub room_id: String,
 }
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct Players {
     pub set: HashSet<EntityID>
 }
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct CurrentTurn {
     pub player: Option<EntityID>
 }
 
+// Every coin flip and shuffle in a room comes from here, so a game replays exactly from its seed
+#[derive(Component)]
+pub struct GameRng {
+    pub seed: u64,
+    pub rng: StdRng,
+}
+
+impl GameRng {
+    pub fn seeded(seed: u64) -> Self {
+        Self { seed, rng: StdRng::seed_from_u64(seed) }
+    }
+}
+
 #[derive(Component)]
 pub struct TurnTimer {
     pub timer: Timer,
 }
 
+// Chess-style total clock per player, only attached to rooms when TimeBankSettings is present
+#[derive(Component, Debug)]
+pub struct TimeBank {
+    pub total: Duration,
+    pub remaining: HashMap<EntityID, Duration>,
+}
+
+impl TimeBank {
+    pub fn new(total: Duration) -> Self {
+        Self { total, remaining: HashMap::new() }
+    }
+
+    pub fn remaining(&self, player_id: EntityID) -> Duration {
+        self.remaining.get(&player_id).copied().unwrap_or(self.total)
+    }
+
+    // Returns true on the tick that runs the player's clock out
+    pub fn spend(&mut self, player_id: EntityID, delta: Duration) -> bool {
+        let remaining = self.remaining.entry(player_id).or_insert(self.total);
+        if remaining.is_zero() {
+            return false;
+        }
+        *remaining = remaining.saturating_sub(delta);
+        remaining.is_zero()
+    }
+}
+
+// Inserting this enables time banks for competitive play
+#[derive(Resource, Clone, Debug)]
+pub struct TimeBankSettings {
+    pub total: Duration,
+}
+
+// Optional rule variations for a room, chosen when its lobby is opened; rooms without one play by the base rules
+#[derive(Component, Clone, Debug, Default)]
+pub struct RoomRules {
+    // Combat damage beyond what kills the defending creature carries through to its owner
+    pub overkill_to_hero: bool,
+    // Show both players what was left in each hand and deck once the game ends
+    pub reveal_at_end: bool,
+}
+
+impl From<LobbyRules> for RoomRules {
+    fn from(rules: LobbyRules) -> Self {
+        Self { overkill_to_hero: rules.overkill_to_hero, reveal_at_end: rules.reveal_at_end }
+    }
+}
+
+// Private rooms are only reachable through their join code, never through matchmaking
+#[derive(Component)]
+pub struct PrivateLobby {
+    pub code: String,
+}
+
 #[derive(Component)]
 pub struct NextTurn;
 
+// Players who dropped mid-game, with the time in seconds since startup when their seat is given up
+#[derive(Component, Default)]
+pub struct ReconnectGrace {
+    pub deadlines: HashMap<EntityID, f32>,
+}
+
 #[derive(Component)]
 pub struct RoomState {
     pub is_active: bool,
+    // Seconds since startup of the last player action or join
     pub last_update: f32,
 }
+
+impl RoomState {
+    pub fn touch(&mut self, now: f32) {
+        self.last_update = now;
+    }
+
+    pub fn idle_for(&self, now: f32) -> Duration {
+        Duration::from_secs_f32((now - self.last_update).max(0.0))
+    }
+}
+
+// How long rooms may sit without activity before they are closed
+#[derive(Resource, Clone, Debug)]
+pub struct RoomTimeouts {
+    pub idle: Duration,
+    // Applies to rooms with fewer than two players, e.g. after an opponent disconnects
+    pub half_empty: Duration,
+    // How long a dropped player's seat is held in a game that's underway
+    pub reconnect_grace: Duration,
+    // Private lobbies still waiting on the friend to enter the code
+    pub lobby: Duration,
+    // Matchmade players have this long to send Ready or act before the match is called off
+    pub ready_check: Duration,
+    // Queue ban for letting a second match time out, growing with each one after
+    pub queue_cooldown: Duration,
+}
+
+impl Default for RoomTimeouts {
+    fn default() -> Self {
+        Self {
+            idle: Duration::from_secs(10 * 60),
+            half_empty: Duration::from_secs(2 * 60),
+            reconnect_grace: Duration::from_secs(60),
+            lobby: Duration::from_secs(10 * 60),
+            ready_check: Duration::from_secs(20),
+            queue_cooldown: Duration::from_secs(60),
+        }
+    }
+}
+
+// On matchmade rooms until both players have shown they're there
+#[derive(Component, Debug)]
+pub struct ReadyCheck {
+    // Seconds since startup
+    pub deadline: f32,
+    pub pending: HashSet<EntityID>,
+}
+
+// A room holding a player whose last match fell through; matchmaking fills these first
+#[derive(Component)]
+pub struct QueuePriority;
+
+// Players who let matchmade games time out. The first time is forgiven, after that each one means a wait
+#[derive(Resource, Default)]
+pub struct QueuePenalties {
+    offenses: HashMap<EntityID, u32>,
+    // Seconds since startup
+    banned_until: HashMap<EntityID, f32>,
+}
+
+impl QueuePenalties {
+    // Returns the cooldown handed out, if any
+    pub fn offend(&mut self, player_id: EntityID, now: f32, cooldown: Duration) -> Option<Duration> {
+        let offenses = self.offenses.entry(player_id).or_default();
+        *offenses += 1;
+        if *offenses < 2 {
+            return None;
+        }
+        let ban = cooldown * (*offenses - 1);
+        self.banned_until.insert(player_id, now + ban.as_secs_f32());
+        Some(ban)
+    }
+
+    pub fn cooldown_left(&self, player_id: EntityID, now: f32) -> Option<Duration> {
+        self.banned_until.get(&player_id)
+            .filter(|&&until| until > now)
+            .map(|&until| Duration::from_secs_f32(until - now))
+    }
+}
+
+// Lifecycle hooks so other plugins can react to rooms without touching the room plugin
+#[derive(Event, Clone, Debug)]
+pub struct RoomCreated {
+    pub room_entity: Entity,
+    pub room_id: String,
+}
+
+#[derive(Event, Clone, Debug)]
+pub struct RoomClosed {
+    pub room_entity: Entity,
+    pub room_id: String,
+}
+
+// Sent once when a room's game ends, so results can be recorded
+#[derive(Event, Clone, Debug)]
+pub struct MatchFinished {
+    pub room_entity: Entity,
+    pub players: Vec<EntityID>,
+    // None for a draw
+    pub winner: Option<EntityID>,
+    pub turns: u32,
+    pub conceded_by: Option<EntityID>,
+}
+
+// On a finished room once one player has asked for a rematch
+#[derive(Component, Debug)]
+pub struct RematchOffer {
+    pub from: EntityID,
+}
+
+// Marks a room restarted by a rematch, whose players have both already shown they're there
+#[derive(Component)]
+pub struct RematchAccepted;
+
+// Marks a room whose finished game has already been announced
+#[derive(Component)]
+pub struct MatchAnnounced;
+
+// Asks for a full GameStateSnapshot to go to one player once this frame's room changes are applied
+#[derive(Event, Clone, Debug)]
+pub struct SendSnapshot {
+    pub player_id: EntityID,
+    pub room_entity: Entity,
+}
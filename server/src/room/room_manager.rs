@@ -1,17 +1,27 @@
 use bevy::prelude::*;
-use std::collections::HashSet;
+use shared::channel::BotDifficulty;
+use shared::EntityID;
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
-use crate::game::game_event_structs::{GameEvent, GameEventContext, GameEventQueue, GameEventWithContext, GameStateComponent};
-use crate::room::room_components::{CurrentTurn, Players, Room, RoomState, TurnTimer};
+use crate::bot::{BotPlayer, BotWeights, BOT_ID_BASE};
+use crate::game::game_event_structs::{GameEventQueue, GameStateComponent};
+use crate::room::room_components::{CurrentTurn, GameRng, Players, PrivateLobby, QueuePriority, ReconnectGrace, Room, RoomCreated, RoomRules, RoomState, TurnTimer};
+
+// Codes skip 0/O and 1/I so they survive being read out loud
+const LOBBY_CODE_CHARS: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const LOBBY_CODE_LENGTH: usize = 5;
 
 #[derive(Resource)]
 pub struct RoomManager {
     next_room_id: usize,
+    // Join codes of private lobbies still waiting for their second player
+    lobbies: HashMap<String, Entity>,
+    next_bot_id: EntityID,
 }
 
 impl Default for RoomManager {
     fn default() -> Self {
-        Self { next_room_id: 0 }
+        Self { next_room_id: 0, lobbies: HashMap::new(), next_bot_id: BOT_ID_BASE }
     }
 }
 
@@ -20,34 +30,110 @@ impl RoomManager {
         &mut self,
         commands: &mut Commands,
         player_id: u128,
-        rooms: &mut Query<(Entity, &mut Players, &mut GameStateComponent)>,
-        event_queue: &mut EventWriter<GameEventWithContext>
+        deck: Option<Vec<String>>,
+        now: f32,
+        rooms: &mut Query<(Entity, &mut Players, &mut GameStateComponent, &mut RoomState, Has<QueuePriority>), (Without<PrivateLobby>, Without<BotPlayer>)>,
+        room_created: &mut EventWriter<RoomCreated>,
     ) -> Entity {
-        // Try to find existing room with space
-        for (entity, mut players, _game_state) in rooms.iter_mut() {
-            if players.set.len() < 2 {
-                players.set.insert(player_id);
+        // Try to find existing room with space, players whose match fell through first
+        let open = rooms.iter()
+            .filter(|(_, players, _, _, _)| players.set.len() < 2)
+            .max_by_key(|&(_, _, _, _, priority)| priority)
+            .map(|(entity, ..)| entity);
+        if let Some(Ok((entity, mut players, mut game_state, mut room_state, priority))) = open.map(|entity| rooms.get_mut(entity)) {
+            players.set.insert(player_id);
+            if let Some(deck) = deck {
+                game_state.deck_choices.insert(player_id, deck);
+            }
+            room_state.touch(now);
+            if priority {
+                commands.entity(entity).remove::<QueuePriority>();
+            }
+            return entity;
+        }
+
+        self.spawn_room(commands, player_id, deck, now, room_created)
+    }
+
+    // Private rooms skip the matchmaking pool; returns the room and its join code
+    pub fn create_lobby(
+        &mut self,
+        commands: &mut Commands,
+        player_id: u128,
+        deck: Option<Vec<String>>,
+        now: f32,
+        rules: RoomRules,
+        room_created: &mut EventWriter<RoomCreated>,
+    ) -> (Entity, String) {
+        let code = self.new_lobby_code();
+        let room_entity = self.spawn_room(commands, player_id, deck, now, room_created);
+        commands.entity(room_entity).insert((PrivateLobby { code: code.clone() }, rules));
+        self.lobbies.insert(code.clone(), room_entity);
+        (room_entity, code)
+    }
 
-                // If we now have exactly 2 players, start the game
-                if players.set.len() == 2 {
-                    event_queue.send(GameEventWithContext {
-                        context: GameEventContext {
-                            room_entity: entity,
-                        },
-                        event: GameEvent::StartGame {},
-                    });
-                }
-                return entity;
+    // Seats the player against a fresh bot; returns the room and the bot's id
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_bot_room(
+        &mut self,
+        commands: &mut Commands,
+        player_id: u128,
+        deck: Option<Vec<String>>,
+        now: f32,
+        difficulty: BotDifficulty,
+        weights: &BotWeights,
+        room_created: &mut EventWriter<RoomCreated>,
+    ) -> (Entity, EntityID) {
+        let bot_id = self.next_bot_id;
+        self.next_bot_id += 1;
+        let room_entity = self.spawn_room(commands, player_id, deck, now, room_created);
+        commands.entity(room_entity).insert((
+            Players { set: HashSet::from([player_id, bot_id]) },
+            BotPlayer::new(bot_id, now, difficulty, weights),
+        ));
+        (room_entity, bot_id)
+    }
+
+    // Codes are case-insensitive so players can type them however they like
+    pub fn lobby(&self, code: &str) -> Option<Entity> {
+        self.lobbies.get(&code.trim().to_ascii_uppercase()).copied()
+    }
+
+    // Called once a lobby fills up or closes, so its code stops working
+    pub fn close_lobby(&mut self, room_entity: Entity) {
+        self.lobbies.retain(|_, &mut entity| entity != room_entity);
+    }
+
+    fn new_lobby_code(&self) -> String {
+        loop {
+            let code: String = (0..LOBBY_CODE_LENGTH)
+                .map(|_| LOBBY_CODE_CHARS[rand::random::<usize>() % LOBBY_CODE_CHARS.len()] as char)
+                .collect();
+            if !self.lobbies.contains_key(&code) {
+                return code;
             }
         }
+    }
 
-        // Create new room
+    fn spawn_room(
+        &mut self,
+        commands: &mut Commands,
+        player_id: u128,
+        deck: Option<Vec<String>>,
+        now: f32,
+        room_created: &mut EventWriter<RoomCreated>,
+    ) -> Entity {
         let room_id = format!("room_{}", self.next_room_id);
         self.next_room_id += 1;
 
+        let mut game_state = GameStateComponent::default();
+        if let Some(deck) = deck {
+            game_state.deck_choices.insert(player_id, deck);
+        }
+
         let room_entity = commands
             .spawn((
-                Room { room_id },
+                Room { room_id: room_id.clone() },
                 Players { set: HashSet::from([player_id]) },
                 CurrentTurn { player: None },
                 TurnTimer {
@@ -55,13 +141,16 @@ impl RoomManager {
                 },
                 RoomState {
                     is_active: true,
-                    last_update: 0.0,
+                    last_update: now,
                 },
-                GameStateComponent::default(),
-                GameEventQueue::default()
+                game_state,
+                GameRng::seeded(rand::random()),
+                GameEventQueue::default(),
+                ReconnectGrace::default(),
             ))
             .id();
 
+        room_created.send(RoomCreated { room_entity, room_id });
         room_entity
     }
 }
\ No newline at end of file
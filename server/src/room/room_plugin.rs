@@ -1,10 +1,24 @@
+use std::collections::HashMap;
+use std::time::Duration;
 use bevy::prelude::*;
-use shared::channel::GameMessage;
-use crate::game::game_event_processing::process_game_events;
-use crate::game::game_event_structs::{GameEvent, GameEventQueue, GameEventWithContext, GameStateComponent};
-use crate::player_component::{Player, PlayerJoinEvent, PlayerLeaveEvent};
-use crate::room::room_components::{CurrentTurn, Players, Room, RoomState, TurnTimer};
+use shared::channel::{GameMessage, MessageType};
+use shared::errors::{ErrorCode, GameError};
+use shared::EntityID;
+use shared::messages::MessageCode;
+use crate::action_history::{escalate_invalid_actions, forget_unflagged_players, ActionHistory, InvalidAction};
+use crate::account_store::AccountStore;
+use crate::credential_store::CredentialStore;
+use crate::bot::{run_bots, BotPlayer, BotWeights};
+use crate::deck_store::DeckStore;
+use crate::game::game_event_processing::{process_game_events, EventBudget, EventBudgetMetrics};
+use crate::game::game_event_structs::{CardLibrary, GameEvent, GameEventContext, GameEventQueue, GameEventWithContext, GameState, GameStateComponent};
+use crate::player_component::{Leaving, LobbyAction, LobbyRequest, Player, PlayerDisconnectEvent, PlayerJoinEvent, PlayerLeaveEvent, PlayerReadyEvent, PlayerResumeEvent, PlayerSessions, RematchRequest};
+use crate::room::room_components::{CurrentTurn, MatchAnnounced, MatchFinished, Players, PrivateLobby, QueuePenalties, QueuePriority, ReadyCheck, ReconnectGrace, RematchAccepted, RematchOffer, Room, RoomClosed, RoomCreated, RoomRules, RoomState, RoomTimeouts, SendSnapshot, TimeBank, TimeBankSettings, TurnTimer};
 use crate::room::room_manager::RoomManager;
+use crate::server_plugin::{ChatLimiter, EmoteLimiter, KeywordGlossary, PendingLogins};
+use crate::puzzle::PuzzleBook;
+use crate::storage::{record_match_results, Storage};
+use crate::room_log::{close_room_log, record_room_logs, save_room_logs, RoomLog, RoomLogEntry, RoomLogEvent};
 use crate::types::Server;
 
 pub struct RoomPlugin;
@@ -13,137 +27,735 @@ impl Plugin for RoomPlugin {
     fn build(&self, app: &mut App) {
         app
             .init_resource::<RoomManager>()
+            .init_resource::<RoomTimeouts>()
+            .init_resource::<PlayerSessions>()
+            .init_resource::<KeywordGlossary>()
+            .init_resource::<DeckStore>()
+            .init_resource::<AccountStore>()
+            .init_resource::<CredentialStore>()
+            .init_resource::<ActionHistory>()
+            .init_resource::<EventBudget>()
+            .init_resource::<CardLibrary>()
+            .init_resource::<EventBudgetMetrics>()
+            .init_resource::<QueuePenalties>()
+            .init_resource::<EmoteLimiter>()
+            .init_resource::<ChatLimiter>()
+            .init_resource::<PendingLogins>()
+            .init_resource::<PuzzleBook>()
+            .init_resource::<BotWeights>()
             .add_event::<PlayerJoinEvent>()
             .add_event::<PlayerLeaveEvent>()
+            .add_event::<PlayerDisconnectEvent>()
+            .add_event::<PlayerResumeEvent>()
+            .add_event::<PlayerReadyEvent>()
+            .add_event::<LobbyRequest>()
+            .add_event::<RematchRequest>()
             .add_event::<GameEventWithContext>()
+            .add_event::<RoomCreated>()
+            .add_event::<RoomClosed>()
+            .add_event::<SendSnapshot>()
+            .add_event::<InvalidAction>()
+            .add_event::<MatchFinished>()
+            .add_event::<RoomLogEvent>()
             .add_systems(Update, (
                 // First handle player management
                 (
                     handle_player_join,
-                    handle_player_leave,
+                    attach_time_banks,
+                    (handle_player_disconnect, expire_reconnect_grace, handle_player_leave).chain(),
+                    handle_player_resume,
+                    handle_lobby_requests,
+                    handle_rematch_requests,
+                    (handle_ready_players, expire_ready_checks).chain(),
+                    run_bots,
+                    forget_unflagged_players,
                 ),
-                // Then route any generated events to room queues
+                // Then start rooms that just filled and route any generated events to room queues
+                start_full_rooms,
                 route_game_events,
                 // Then process room state
                 (
-                    handle_room_turns,
                     update_room_timer,
+                    tick_time_banks,
                     process_game_events,
-                ),
+                    escalate_invalid_actions,
+                    send_state_snapshots,
+                    sync_turn_clocks,
+                    announce_finished_matches,
+                    release_leaving_players,
+                    record_match_results,
+                    (record_room_logs, save_room_logs).chain(),
+                ).chain(),
                 // Finally cleanup
-                cleanup_inactive_rooms,
+                (
+                    expire_idle_rooms,
+                    cleanup_inactive_rooms,
+                    forget_closed_lobbies,
+                ).chain(),
             ).chain());
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_player_join(
     mut commands: Commands,
     mut room_manager: ResMut<RoomManager>,
     mut join_events: EventReader<PlayerJoinEvent>,
-    mut rooms: Query<(Entity, &mut Players, &mut GameStateComponent)>,
-    mut game_events: EventWriter<GameEventWithContext>,
+    mut rooms: Query<(Entity, &mut Players, &mut GameStateComponent, &mut RoomState, Has<QueuePriority>), (Without<PrivateLobby>, Without<BotPlayer>)>,
+    mut room_created: EventWriter<RoomCreated>,
+    mut snapshots: EventWriter<SendSnapshot>,
+    decks: Res<DeckStore>,
+    penalties: Res<QueuePenalties>,
+    server: Res<Server>,
+    time: Res<Time>,
 ) {
     for PlayerJoinEvent(player_id) in join_events.read() {
+        if let Some(left) = penalties.cooldown_left(*player_id, time.elapsed_secs()) {
+            server.send(*player_id, GameMessage::Error(GameError::with_detail(
+                ErrorCode::QueueCooldown,
+                MessageCode::QueueCooldownLeft { seconds: left.as_secs() + 1 },
+            )));
+            continue;
+        }
         let room_entity = room_manager.find_or_create_room(
             &mut commands,
             *player_id,
+            decks.queued_deck(*player_id),
+            time.elapsed_secs(),
             &mut rooms,
-            &mut game_events
+            &mut room_created,
         );
         commands.spawn(Player {
             id: *player_id,
             room: room_entity,
         });
+        snapshots.send(SendSnapshot { player_id: *player_id, room_entity });
+    }
+}
+
+fn handle_ready_players(
+    mut commands: Commands,
+    mut ready_events: EventReader<PlayerReadyEvent>,
+    mut rooms: Query<&mut ReadyCheck>,
+) {
+    for event in ready_events.read() {
+        let Ok(mut check) = rooms.get_mut(event.room_entity) else { continue };
+        check.pending.remove(&event.player_id);
+        if check.pending.is_empty() {
+            commands.entity(event.room_entity).remove::<ReadyCheck>();
+        }
     }
 }
 
-fn handle_room_turns(
-    mut query: Query<(Entity, &Room, &Players, &mut CurrentTurn, &mut TurnTimer)>,
+// Calls off matches a player never showed up for. Whoever was there goes back to the front of the queue
+// in the same room, and the no-shows lose their seat and may be barred from queueing for a while
+#[allow(clippy::too_many_arguments)]
+fn expire_ready_checks(
+    mut commands: Commands,
+    mut rooms: Query<(Entity, &ReadyCheck, &mut Players, &mut CurrentTurn, &mut TurnTimer, &mut GameStateComponent, &mut GameEventQueue, Option<&RoomLog>)>,
+    player_query: Query<(Entity, &Player)>,
+    mut snapshots: EventWriter<SendSnapshot>,
+    mut penalties: ResMut<QueuePenalties>,
+    timeouts: Res<RoomTimeouts>,
+    bank_settings: Option<Res<TimeBankSettings>>,
+    storage: Option<Res<Storage>>,
     server: Res<Server>,
+    time: Res<Time>,
 ) {
-    for (_entity, _room, players, mut current_turn, _timer) in query.iter_mut() {
-        if players.set.len() != 2 {
+    let now = time.elapsed_secs();
+    for (room_entity, check, mut players, mut current_turn, mut timer, mut game_state, mut event_queue, log) in rooms.iter_mut() {
+        if check.deadline > now {
             continue;
         }
+        commands.entity(room_entity).remove::<ReadyCheck>();
+        // The next match in this room gets a log of its own
+        if let Some(log) = log {
+            close_room_log(&mut commands, storage.as_deref(), room_entity, log, RoomLogEntry::CalledOff, now);
+        }
 
-        if current_turn.player.is_none() {
-            let players_vec: Vec<_> = players.set.iter().collect();
-            let first_player = *players_vec[rand::random::<usize>() % 2];
-            current_turn.player = Some(first_player);
-
-            // Notify players
-            for &player_id in &players.set {
-                server.send(player_id, GameMessage::CurrentTurn(Some(first_player)));
+        for &player_id in &check.pending {
+            players.set.remove(&player_id);
+            if let Some((player_entity, _)) = player_query.iter().find(|(_, p)| p.id == player_id) {
+                commands.entity(player_entity).despawn();
             }
+            let error = match penalties.offend(player_id, now, timeouts.queue_cooldown) {
+                Some(ban) => GameError::with_detail(ErrorCode::MatchTimedOut, MessageCode::CalledOffWithCooldown { seconds: ban.as_secs() }),
+                None => ErrorCode::MatchTimedOut.into(),
+            };
+            info!("Player {} never readied up", player_id);
+            server.send(player_id, GameMessage::Error(error));
+        }
+
+        if players.set.is_empty() {
+            commands.entity(room_entity).insert(RoomCleanup);
+            continue;
+        }
+        // Keeping the decks the remaining players queued with
+        let deck_choices = std::mem::take(&mut game_state.deck_choices).into_iter().filter(|(p, _)| players.set.contains(p)).collect();
+        let mut room = commands.entity(room_entity);
+        room.insert(QueuePriority);
+        reset_table(&mut room, &mut game_state, &mut event_queue, &mut current_turn, &mut timer, bank_settings.as_deref(), deck_choices);
+        for &player_id in &players.set {
+            server.send(player_id, GameMessage::Chat(MessageType::System(MessageCode::OpponentNeverReadied)));
+            snapshots.send(SendSnapshot { player_id, room_entity });
         }
     }
 }
 
+// Back to an empty table with a fresh clock; start_full_rooms deals the next game from the given decks
+fn reset_table(
+    room: &mut EntityCommands,
+    game_state: &mut GameStateComponent,
+    event_queue: &mut GameEventQueue,
+    current_turn: &mut CurrentTurn,
+    timer: &mut TurnTimer,
+    bank_settings: Option<&TimeBankSettings>,
+    deck_choices: HashMap<EntityID, Vec<String>>,
+) {
+    *game_state = GameStateComponent { deck_choices, ..Default::default() };
+    *event_queue = GameEventQueue::default();
+    current_turn.player = None;
+    timer.timer.reset();
+    if let Some(settings) = bank_settings {
+        room.insert(TimeBank::new(settings.total));
+    }
+}
+
+// Competitive rooms get their clocks as soon as they exist
+fn attach_time_banks(
+    mut commands: Commands,
+    mut room_created: EventReader<RoomCreated>,
+    settings: Option<Res<TimeBankSettings>>,
+) {
+    let Some(settings) = settings else {
+        room_created.clear();
+        return;
+    };
+    for event in room_created.read() {
+        commands.entity(event.room_entity).insert(TimeBank::new(settings.total));
+    }
+}
+
+// Matchmaking, lobby codes and bot games all fill rooms differently, but every game starts here with one StartGame
+#[allow(clippy::type_complexity)]
+fn start_full_rooms(
+    mut commands: Commands,
+    rooms: Query<(Entity, &Players, &GameStateComponent, Has<PrivateLobby>, Has<BotPlayer>, Has<RematchAccepted>), Changed<Players>>,
+    mut game_events: EventWriter<GameEventWithContext>,
+    accounts: Res<AccountStore>,
+    timeouts: Res<RoomTimeouts>,
+    server: Res<Server>,
+    time: Res<Time>,
+) {
+    for (room_entity, players, game_state, lobby, bot, rematch) in &rooms {
+        if players.set.len() != 2 || !matches!(game_state.state, GameState::Starting) {
+            continue;
+        }
+        // Friends, bots and rematches are already waiting on the player, only strangers from the queue might not be there
+        if rematch {
+            commands.entity(room_entity).remove::<RematchAccepted>();
+        } else if !lobby && !bot {
+            commands.entity(room_entity).insert(ReadyCheck {
+                deadline: time.elapsed_secs() + timeouts.ready_check.as_secs_f32(),
+                pending: players.set.clone(),
+            });
+        }
+        let profiles: Vec<_> = players.set.iter().map(|&p| accounts.profile(p)).collect();
+        let log = RoomLog::new(players.set.iter().copied(), time.elapsed_secs());
+        for &p in &players.set {
+            server.send(p, GameMessage::MatchFound(profiles.clone()));
+            server.send(p, GameMessage::MatchId(log.match_id.clone()));
+        }
+        commands.entity(room_entity).insert(log);
+        game_events.send(GameEventWithContext {
+            context: GameEventContext { room_entity },
+            event: GameEvent::StartGame {},
+        });
+    }
+}
+
 pub fn route_game_events(
     mut game_events: EventReader<GameEventWithContext>,
-    mut rooms: Query<(Entity, &mut GameEventQueue)>,
+    mut rooms: Query<(Entity, &mut GameEventQueue, &mut RoomState)>,
+    time: Res<Time>,
 ) {
     for event in game_events.read() {
         // Find the room using the context and add the event to its queue
-        if let Ok((_, mut event_queue)) = rooms.get_mut(event.context.room_entity) {
+        if let Ok((_, mut event_queue, mut room_state)) = rooms.get_mut(event.context.room_entity) {
             event_queue.next_events.push_back(event.clone());
+            room_state.touch(time.elapsed_secs());
         } else {
             warn!("Attempted to route event to non-existent room: {:?}", event.context.room_entity);
         }
     }
 }
 
+// A timed out turn ends like any other, so the next player still refills mana and draws. Only games underway have a clock
 fn update_room_timer(
     time: Res<Time>,
-    mut query: Query<(Entity, &Room, &Players, &mut CurrentTurn, &mut TurnTimer)>,
-    server: Res<Server>,
+    mut query: Query<(Entity, &CurrentTurn, &GameStateComponent, &mut TurnTimer)>,
+    mut game_events: EventWriter<GameEventWithContext>,
 ) {
-    for (_entity, _room, players, mut current_turn, mut timer) in query.iter_mut() {
+    for (entity, current_turn, game_state, mut timer) in query.iter_mut() {
+        if !matches!(game_state.state, GameState::InProgress) {
+            continue;
+        }
         timer.timer.tick(time.delta());
 
         if timer.timer.finished() {
             if let Some(current_player) = current_turn.player {
-                let next_player = *players.set.iter()
-                    .find(|&&p| p != current_player)
-                    .unwrap();
-
-                current_turn.player = Some(next_player);
+                // Reset now so the event is only sent once while it waits in the queue
                 timer.timer.reset();
-
-                // Notify players
-                for &player_id in &players.set {
-                    server.send(player_id, GameMessage::CurrentTurn(Some(next_player)));
-                }
+                game_events.send(GameEventWithContext {
+                    context: GameEventContext { room_entity: entity },
+                    event: GameEvent::EndTurn { player_id: current_player },
+                });
             }
         }
     }
 }
 
+// Burns the turn player's bank alongside the turn timer; running out loses the game
+fn tick_time_banks(
+    time: Res<Time>,
+    mut query: Query<(Entity, &Players, &CurrentTurn, &GameStateComponent, &mut TimeBank)>,
+    mut game_events: EventWriter<GameEventWithContext>,
+) {
+    for (entity, players, current_turn, game_state, mut time_bank) in query.iter_mut() {
+        if !matches!(game_state.state, GameState::InProgress) {
+            continue;
+        }
+        let Some(current_player) = current_turn.player else { continue };
+
+        if time_bank.spend(current_player, time.delta()) {
+            info!("Player {} ran out of time", current_player);
+            let winner = players.set.iter().find(|&&p| p != current_player).copied();
+            game_events.send(GameEventWithContext {
+                context: GameEventContext { room_entity: entity },
+                event: GameEvent::GameStateChange { new_state: GameState::Finished(winner) },
+            });
+        }
+    }
+}
+
 fn handle_player_leave(
     mut commands: Commands,
     mut leave_events: EventReader<PlayerLeaveEvent>,
-    mut rooms: Query<(Entity, &mut Players, &mut CurrentTurn)>,
+    mut rooms: Query<(Entity, &mut Players, &mut CurrentTurn, Option<&BotPlayer>)>,
 ) {
     for event in leave_events.read() {
-        if let Ok((entity, mut players, mut current_turn)) = rooms.get_mut(event.room_entity) {
+        if let Ok((entity, mut players, mut current_turn, bot)) = rooms.get_mut(event.room_entity) {
             players.set.remove(&event.player_id);
             current_turn.player = None;
 
-            if players.set.is_empty() {
+            // A bot doesn't keep a room open on its own
+            if players.set.iter().all(|&p| bot.is_some_and(|bot| bot.id == p)) {
                 commands.entity(entity).insert(RoomCleanup);
             }
         }
     }
 }
 
+// Seats in a game that's underway are held for a while; anywhere else the player just leaves
+#[allow(clippy::too_many_arguments)]
+fn handle_player_disconnect(
+    mut commands: Commands,
+    mut disconnect_events: EventReader<PlayerDisconnectEvent>,
+    mut leave_events: EventWriter<PlayerLeaveEvent>,
+    mut rooms: Query<(&Players, &GameStateComponent, &mut ReconnectGrace)>,
+    player_query: Query<(Entity, &Player)>,
+    timeouts: Res<RoomTimeouts>,
+    server: Res<Server>,
+    time: Res<Time>,
+) {
+    for event in disconnect_events.read() {
+        if let Ok((players, game_state, mut grace)) = rooms.get_mut(event.room_entity) {
+            if matches!(game_state.state, GameState::InProgress) {
+                let deadline = time.elapsed_secs() + timeouts.reconnect_grace.as_secs_f32();
+                grace.deadlines.insert(event.player_id, deadline);
+                info!("Player {} disconnected, holding their seat for {:?}", event.player_id, timeouts.reconnect_grace);
+                for &p in players.set.iter().filter(|&&p| p != event.player_id) {
+                    server.send(p, GameMessage::Chat(MessageType::System(MessageCode::OpponentDisconnected {
+                        grace_seconds: timeouts.reconnect_grace.as_secs(),
+                    })));
+                }
+                continue;
+            }
+        }
+        release_player(&mut commands, &mut leave_events, &player_query, event.player_id, event.room_entity);
+    }
+}
+
+// Not coming back concedes a game that's still underway, like leaving it does, so the opponent gets the win
+fn expire_reconnect_grace(
+    mut commands: Commands,
+    mut leave_events: EventWriter<PlayerLeaveEvent>,
+    mut game_events: EventWriter<GameEventWithContext>,
+    mut rooms: Query<(Entity, &GameStateComponent, &mut ReconnectGrace)>,
+    player_query: Query<(Entity, &Player)>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+    for (room_entity, game_state, mut grace) in rooms.iter_mut() {
+        let expired: Vec<_> = grace.deadlines.iter()
+            .filter(|(_, &deadline)| deadline <= now)
+            .map(|(&player_id, _)| player_id)
+            .collect();
+        for player_id in expired {
+            grace.deadlines.remove(&player_id);
+            info!("Player {} did not reconnect in time", player_id);
+            let player_entity = player_query.iter().find(|(_, p)| p.id == player_id).map(|(entity, _)| entity);
+            match player_entity {
+                // Released by release_leaving_players once the concession has finished the game
+                Some(player_entity) if matches!(game_state.state, GameState::InProgress) => {
+                    commands.entity(player_entity).insert(Leaving);
+                    game_events.send(GameEventWithContext {
+                        context: GameEventContext { room_entity },
+                        event: GameEvent::Concede { player_id },
+                    });
+                }
+                _ => release_player(&mut commands, &mut leave_events, &player_query, player_id, room_entity),
+            }
+        }
+    }
+}
+
+// Waits for a conceded game to finish first, so the result is still recorded against the player who left
+fn release_leaving_players(
+    mut commands: Commands,
+    mut leave_events: EventWriter<PlayerLeaveEvent>,
+    leaving: Query<&Player, With<Leaving>>,
+    rooms: Query<(&Players, &GameStateComponent)>,
+    player_query: Query<(Entity, &Player)>,
+) {
+    for player in &leaving {
+        let conceding = rooms.get(player.room)
+            .is_ok_and(|(players, game_state)| players.set.contains(&player.id) && matches!(game_state.state, GameState::InProgress));
+        if conceding {
+            continue;
+        }
+        info!("Player {} left room {:?}", player.id, player.room);
+        release_player(&mut commands, &mut leave_events, &player_query, player.id, player.room);
+    }
+}
+
+fn release_player(
+    commands: &mut Commands,
+    leave_events: &mut EventWriter<PlayerLeaveEvent>,
+    player_query: &Query<(Entity, &Player)>,
+    player_id: u128,
+    room_entity: Entity,
+) {
+    if let Some((player_entity, _)) = player_query.iter().find(|(_, p)| p.id == player_id) {
+        commands.entity(player_entity).despawn();
+    }
+    leave_events.send(PlayerLeaveEvent { player_id, room_entity });
+}
+
+fn handle_player_resume(
+    mut resume_events: EventReader<PlayerResumeEvent>,
+    mut snapshots: EventWriter<SendSnapshot>,
+    mut rooms: Query<(&Players, &mut ReconnectGrace)>,
+    server: Res<Server>,
+) {
+    for event in resume_events.read() {
+        let Ok((players, mut grace)) = rooms.get_mut(event.room_entity) else {
+            continue;
+        };
+        if grace.deadlines.remove(&event.player_id).is_none() {
+            continue;
+        }
+        info!("Player {} resumed their game", event.player_id);
+        snapshots.send(SendSnapshot { player_id: event.player_id, room_entity: event.room_entity });
+        for &p in players.set.iter().filter(|&&p| p != event.player_id) {
+            server.send(p, GameMessage::Chat(MessageType::System(MessageCode::OpponentReconnected)));
+        }
+    }
+}
+
+// Moves a player out of a room that hasn't started into a private lobby, creating it or filling it by code, or into a bot game
+#[allow(clippy::too_many_arguments)]
+fn handle_lobby_requests(
+    mut commands: Commands,
+    mut room_manager: ResMut<RoomManager>,
+    mut lobby_requests: EventReader<LobbyRequest>,
+    mut rooms: Query<(&mut Players, &mut GameStateComponent, &mut RoomState, Option<&BotPlayer>)>,
+    mut player_query: Query<&mut Player>,
+    mut room_created: EventWriter<RoomCreated>,
+    mut snapshots: EventWriter<SendSnapshot>,
+    decks: Res<DeckStore>,
+    bot_weights: Res<BotWeights>,
+    server: Res<Server>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+    for request in lobby_requests.read() {
+        // A full room is about to start or already playing, unless its game is over
+        let in_game = rooms.get(request.room_entity)
+            .is_ok_and(|(players, game_state, _, _)| players.set.len() == 2 && !matches!(game_state.state, GameState::Finished(_)));
+        if in_game {
+            server.send(request.player_id, GameMessage::Error(GameError::with_detail(
+                ErrorCode::InGame,
+                MessageCode::FinishGameFirst,
+            )));
+            continue;
+        }
+
+        let room_entity = match &request.action {
+            LobbyAction::Create(rules) => {
+                let (room_entity, code) = room_manager.create_lobby(
+                    &mut commands,
+                    request.player_id,
+                    decks.queued_deck(request.player_id),
+                    now,
+                    RoomRules::from(*rules),
+                    &mut room_created,
+                );
+                info!("Player {} opened lobby {}", request.player_id, code);
+                server.send(request.player_id, GameMessage::LobbyCreated(code));
+                room_entity
+            }
+            LobbyAction::Bot(difficulty) => {
+                let (room_entity, bot_id) = room_manager.create_bot_room(
+                    &mut commands,
+                    request.player_id,
+                    decks.queued_deck(request.player_id),
+                    now,
+                    *difficulty,
+                    &bot_weights,
+                    &mut room_created,
+                );
+                info!("Player {} started a {:?} practice game against bot {}", request.player_id, difficulty, bot_id);
+                room_entity
+            }
+            LobbyAction::Join(code) => {
+                let Some(room_entity) = room_manager.lobby(code) else {
+                    server.send(request.player_id, GameMessage::Error(GameError::with_detail(ErrorCode::LobbyNotFound, MessageCode::NoLobbyWithCode { code: code.clone() })));
+                    continue;
+                };
+                if room_entity == request.room_entity {
+                    server.send(request.player_id, GameMessage::Error(ErrorCode::AlreadyInLobby.into()));
+                    continue;
+                }
+                let Ok((mut players, mut game_state, mut room_state, _)) = rooms.get_mut(room_entity) else {
+                    continue;
+                };
+                if players.set.len() >= 2 {
+                    server.send(request.player_id, GameMessage::Error(ErrorCode::RoomFull.into()));
+                    continue;
+                }
+                players.set.insert(request.player_id);
+                if let Some(deck) = decks.queued_deck(request.player_id) {
+                    game_state.deck_choices.insert(request.player_id, deck);
+                }
+                room_state.touch(now);
+                room_manager.close_lobby(room_entity);
+                room_entity
+            }
+        };
+
+        // Nothing to forfeit in the room they came from
+        if let Ok((mut players, _, _, bot)) = rooms.get_mut(request.room_entity) {
+            players.set.remove(&request.player_id);
+            if players.set.iter().all(|&p| bot.is_some_and(|bot| bot.id == p)) {
+                commands.entity(request.room_entity).insert(RoomCleanup);
+            }
+        }
+        if let Some(mut player) = player_query.iter_mut().find(|p| p.id == request.player_id) {
+            player.room = room_entity;
+        }
+        snapshots.send(SendSnapshot { player_id: request.player_id, room_entity });
+    }
+}
+
+// Restarts a finished room with the same two players once both want to, with decks built fresh from each
+// player's current selection. Bots always agree
+#[allow(clippy::type_complexity)]
+fn handle_rematch_requests(
+    mut commands: Commands,
+    mut requests: EventReader<RematchRequest>,
+    mut rooms: Query<(&mut Players, &mut CurrentTurn, &mut TurnTimer, &mut GameStateComponent, &mut GameEventQueue, Option<&RematchOffer>, Has<BotPlayer>)>,
+    decks: Res<DeckStore>,
+    bank_settings: Option<Res<TimeBankSettings>>,
+    server: Res<Server>,
+) {
+    for request in requests.read() {
+        let Ok((mut players, mut current_turn, mut timer, mut game_state, mut event_queue, offer, bot)) = rooms.get_mut(request.room_entity) else {
+            continue;
+        };
+        let finished = players.set.len() == 2 && matches!(game_state.state, GameState::Finished(_));
+        let offered_by_opponent = offer.is_some_and(|offer| offer.from != request.player_id && players.set.contains(&offer.from));
+        if !finished || (request.accept && !offered_by_opponent) {
+            server.send(request.player_id, GameMessage::Error(ErrorCode::RematchUnavailable.into()));
+            continue;
+        }
+        // Asking after the opponent already asked is the same as accepting
+        if !offered_by_opponent && !bot {
+            commands.entity(request.room_entity).insert(RematchOffer { from: request.player_id });
+            for &player_id in &players.set {
+                server.send(player_id, GameMessage::RematchRequested(request.player_id));
+            }
+            continue;
+        }
+
+        info!("Rematch starting in room {:?}", request.room_entity);
+        let deck_choices = players.set.iter()
+            .filter_map(|&player_id| decks.queued_deck(player_id).map(|deck| (player_id, deck)))
+            .collect();
+        let mut room = commands.entity(request.room_entity);
+        room.remove::<RematchOffer>().insert(RematchAccepted);
+        reset_table(&mut room, &mut game_state, &mut event_queue, &mut current_turn, &mut timer, bank_settings.as_deref(), deck_choices);
+        // start_full_rooms deals the new game like any other
+        players.set_changed();
+    }
+}
+
+// Seconds between turn clock updates while nothing else about the clock changes
+const TURN_CLOCK_SYNC_SECS: f32 = 5.0;
+
+// What each room's players were last told about the turn clock
+#[derive(Default)]
+struct TurnClockSync {
+    sent_at: f32,
+    turn: Option<EntityID>,
+    elapsed: Duration,
+}
+
+// Runs after the room's events so a new turn is announced the frame its timer resets
+fn sync_turn_clocks(
+    time: Res<Time>,
+    rooms: Query<(Entity, &Players, &CurrentTurn, &TurnTimer, &GameStateComponent)>,
+    server: Res<Server>,
+    mut synced: Local<HashMap<Entity, TurnClockSync>>,
+) {
+    let now = time.elapsed_secs();
+    synced.retain(|&room_entity, _| rooms.contains(room_entity));
+    for (room_entity, players, current_turn, timer, game_state) in rooms.iter() {
+        if !matches!(game_state.state, GameState::InProgress) || current_turn.player.is_none() {
+            continue;
+        }
+        let last = synced.entry(room_entity).or_default();
+        let elapsed = timer.timer.elapsed();
+        let changed = last.turn != current_turn.player || elapsed < last.elapsed;
+        if !changed && now - last.sent_at < TURN_CLOCK_SYNC_SECS {
+            last.elapsed = elapsed;
+            continue;
+        }
+        *last = TurnClockSync { sent_at: now, turn: current_turn.player, elapsed };
+        for &player_id in &players.set {
+            server.send(player_id, GameMessage::TurnTimeRemaining {
+                remaining: timer.timer.remaining(),
+                total: timer.timer.duration(),
+            });
+        }
+    }
+}
+
+// Runs after the room's events so the snapshot includes anything that happened this frame
+fn send_state_snapshots(
+    mut snapshot_events: EventReader<SendSnapshot>,
+    rooms: Query<(&Players, &CurrentTurn, &GameStateComponent, Option<&TimeBank>)>,
+    server: Res<Server>,
+) {
+    for event in snapshot_events.read() {
+        let Ok((players, current_turn, game_state, time_bank)) = rooms.get(event.room_entity) else {
+            continue;
+        };
+        let remaining = current_turn.player.zip(time_bank).map(|(turn_player, bank)| bank.remaining(turn_player));
+        let snapshot = game_state.snapshot(event.player_id, players, current_turn.player, remaining);
+        server.send(event.player_id, GameMessage::GameStateSnapshot(snapshot));
+    }
+}
+
+// Rooms only finish once per game, but a lobby that starts over gets announced again
+fn announce_finished_matches(
+    mut commands: Commands,
+    rooms: Query<(Entity, &Players, &GameStateComponent, Has<MatchAnnounced>), Changed<GameStateComponent>>,
+    mut finished: EventWriter<MatchFinished>,
+) {
+    for (room_entity, players, game_state, announced) in &rooms {
+        match (&game_state.state, announced) {
+            (GameState::Finished(winner), false) => {
+                let mut players: Vec<_> = players.set.iter().copied().collect();
+                players.sort();
+                finished.send(MatchFinished {
+                    room_entity,
+                    players,
+                    winner: *winner,
+                    turns: game_state.match_log.turns.len() as u32,
+                    conceded_by: game_state.match_log.conceded_by,
+                });
+                commands.entity(room_entity).insert(MatchAnnounced);
+            }
+            (GameState::Finished(_), true) => {}
+            (_, true) => {
+                commands.entity(room_entity).remove::<MatchAnnounced>();
+            }
+            (_, false) => {}
+        }
+    }
+}
+
+// Closes rooms nobody has touched for too long and tells whoever is left
+fn expire_idle_rooms(
+    mut commands: Commands,
+    time: Res<Time>,
+    timeouts: Res<RoomTimeouts>,
+    rooms: Query<(Entity, &Room, &Players, &RoomState, Option<&PrivateLobby>), Without<RoomCleanup>>,
+    player_query: Query<(Entity, &Player)>,
+    mut leave_events: EventWriter<PlayerLeaveEvent>,
+    server: Res<Server>,
+) {
+    let now = time.elapsed_secs();
+    for (entity, room, players, room_state, lobby) in rooms.iter() {
+        let timeout = match (players.set.len() < 2, lobby.is_some()) {
+            (true, true) => timeouts.lobby,
+            (true, false) => timeouts.half_empty,
+            (false, _) => timeouts.idle,
+        };
+        if room_state.idle_for(now) < timeout {
+            continue;
+        }
+
+        info!("Closing {} after {:?} without activity", room.room_id, timeout);
+        for &player_id in &players.set {
+            server.send(player_id, GameMessage::Chat(MessageType::System(MessageCode::RoomClosedIdle)));
+        }
+        for &player_id in &players.set {
+            release_player(&mut commands, &mut leave_events, &player_query, player_id, entity);
+        }
+        commands.entity(entity).insert(RoomCleanup);
+    }
+}
+
 fn cleanup_inactive_rooms(
     mut commands: Commands,
-    rooms: Query<(Entity, &RoomState), With<RoomCleanup>>,
+    rooms: Query<(Entity, &Room, Option<&RoomLog>), (With<RoomState>, With<RoomCleanup>)>,
+    mut room_closed: EventWriter<RoomClosed>,
+    storage: Option<Res<Storage>>,
+    time: Res<Time>,
 ) {
-    for (entity, _) in rooms.iter() {
+    for (entity, room, log) in rooms.iter() {
+        // Still here only if the match never finished
+        if let Some(log) = log {
+            close_room_log(&mut commands, storage.as_deref(), entity, log, RoomLogEntry::Closed, time.elapsed_secs());
+        }
+        room_closed.send(RoomClosed {
+            room_entity: entity,
+            room_id: room.room_id.clone(),
+        });
         commands.entity(entity).despawn_recursive();
     }
 }
 
+fn forget_closed_lobbies(
+    mut room_closed: EventReader<RoomClosed>,
+    mut room_manager: ResMut<RoomManager>,
+) {
+    for event in room_closed.read() {
+        room_manager.close_lobby(event.room_entity);
+    }
+}
+
 #[derive(Component)]
 struct RoomCleanup;
\ No newline at end of file
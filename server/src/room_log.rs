@@ -0,0 +1,127 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use shared::channel::{GameMessage, MessageType};
+use shared::EntityID;
+use crate::action_history::action_name;
+use crate::player_component::{PlayerDisconnectEvent, PlayerLeaveEvent, PlayerResumeEvent};
+use crate::room::room_components::MatchFinished;
+use crate::storage::Storage;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum RoomLogEntry {
+    Joined(EntityID),
+    Disconnected(EntityID),
+    Resumed(EntityID),
+    Left(EntityID),
+    // The request as received, whether or not it was allowed
+    Action { player_id: EntityID, action: String },
+    Chat { player_id: EntityID, content: String },
+    Result { winner: Option<EntityID>, turns: u32 },
+    // A player never readied up
+    CalledOff,
+    // The room closed before the match finished
+    Closed,
+}
+
+impl RoomLogEntry {
+    // Game actions and chat; everything else a player sends is left out
+    pub fn from_request(player_id: EntityID, request: &GameMessage) -> Option<Self> {
+        match request {
+            GameMessage::Chat(MessageType::Room { content, .. }) => Some(RoomLogEntry::Chat { player_id, content: content.clone() }),
+            GameMessage::Chat(MessageType::Private { recipient, content, .. }) => {
+                Some(RoomLogEntry::Chat { player_id, content: format!("(to {recipient}) {content}") })
+            }
+            _ => action_name(request).map(|_| RoomLogEntry::Action { player_id, action: format!("{request:?}") }),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TimedEntry {
+    // Seconds into the match
+    pub at: f32,
+    pub entry: RoomLogEntry,
+}
+
+// Who joined, what they did and said, and how it ended, saved under a match id both players are given
+#[derive(Component, Serialize, Deserialize, Clone, Debug)]
+pub struct RoomLog {
+    pub match_id: String,
+    // Seconds since the Unix epoch
+    pub started_at: u64,
+    pub entries: Vec<TimedEntry>,
+    // Server uptime at the start, which entry times count from
+    #[serde(skip)]
+    started: f32,
+}
+
+impl RoomLog {
+    pub fn new(players: impl IntoIterator<Item = EntityID>, now: f32) -> Self {
+        let started_at = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+        // Random rather than counted, so ids stay unique across restarts
+        let match_id = format!("{:016x}", rand::random::<u64>());
+        let mut log = Self { match_id, started_at, entries: Vec::new(), started: now };
+        for player_id in players {
+            log.push(now, RoomLogEntry::Joined(player_id));
+        }
+        log
+    }
+
+    pub fn push(&mut self, now: f32, entry: RoomLogEntry) {
+        self.entries.push(TimedEntry { at: now - self.started, entry });
+    }
+}
+
+#[derive(Event, Clone, Debug)]
+pub struct RoomLogEvent {
+    pub room_entity: Entity,
+    pub entry: RoomLogEntry,
+}
+
+// Rooms only have a log once their match has started; anything before that isn't kept
+pub fn record_room_logs(
+    mut log_events: EventReader<RoomLogEvent>,
+    mut disconnects: EventReader<PlayerDisconnectEvent>,
+    mut resumes: EventReader<PlayerResumeEvent>,
+    mut leaves: EventReader<PlayerLeaveEvent>,
+    mut logs: Query<&mut RoomLog>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+    let entries = log_events.read().map(|event| (event.room_entity, event.entry.clone()))
+        .chain(disconnects.read().map(|event| (event.room_entity, RoomLogEntry::Disconnected(event.player_id))))
+        .chain(resumes.read().map(|event| (event.room_entity, RoomLogEntry::Resumed(event.player_id))))
+        .chain(leaves.read().map(|event| (event.room_entity, RoomLogEntry::Left(event.player_id))));
+    for (room_entity, entry) in entries {
+        if let Ok(mut log) = logs.get_mut(room_entity) {
+            log.push(now, entry);
+        }
+    }
+}
+
+// Saves the log with how the match ended, when the server was started with storage, and takes it off the room
+// so it's saved only once
+pub fn close_room_log(commands: &mut Commands, storage: Option<&Storage>, room_entity: Entity, log: &RoomLog, ending: RoomLogEntry, now: f32) {
+    let mut log = log.clone();
+    log.push(now, ending);
+    if let Some(storage) = storage {
+        storage.writer().save_room_log(log);
+    }
+    commands.entity(room_entity).remove::<RoomLog>();
+}
+
+// Finished matches are saved with their result; rooms save called off and abandoned ones themselves
+pub fn save_room_logs(
+    mut commands: Commands,
+    mut finished: EventReader<MatchFinished>,
+    logs: Query<&RoomLog>,
+    storage: Option<Res<Storage>>,
+    time: Res<Time>,
+) {
+    for event in finished.read() {
+        let Ok(log) = logs.get(event.room_entity) else { continue };
+        let ending = RoomLogEntry::Result { winner: event.winner, turns: event.turns };
+        close_room_log(&mut commands, storage.as_deref(), event.room_entity, log, ending, time.elapsed_secs());
+    }
+}
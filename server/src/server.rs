@@ -3,16 +3,17 @@ use shared::api::API_VERSION;
 use bevy_simplenet::{ServerFactory, AcceptorConfig, Authenticator, ServerConfig};
 use shared::channel::GameChannel;
 
-pub fn setup_server() -> Server {
-    ServerFactory::<GameChannel>::new(API_VERSION)
+pub fn setup_server(address: &str) -> Server {
+    let server = ServerFactory::<GameChannel>::new(API_VERSION)
         .new_server(
             enfync::builtin::native::TokioHandle::default(),
-            "127.0.0.1:48888",
+            address,
             AcceptorConfig::Default,
             Authenticator::None,
             ServerConfig {
                 heartbeat_interval: std::time::Duration::from_secs(6),
                 ..Default::default()
             },
-        )
+        );
+    Server::new(server)
 }
\ No newline at end of file
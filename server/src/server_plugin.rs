@@ -1,52 +1,638 @@
+use std::collections::HashMap;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
+use bevy::tasks::{block_on, AsyncComputeTaskPool, Task};
 use bevy_simplenet::{ClientId, RequestToken, ServerReport};
-use shared::channel::GameMessage;
-use crate::game::game_event_structs::{GameEventWithContext, IntoGameEvent, MessageContext};
-use crate::player_component::{Player, PlayerJoinEvent, PlayerLeaveEvent};
-use crate::room::room_components::Players;
+use shared::channel::{Emote, GameMessage, MatchListing, MessageType, HEARTBEAT_INTERVAL, MAX_CHAT_LENGTH};
+use shared::card_details::CardConfig;
+use shared::card_filter;
+use shared::codec::Codec;
+use shared::errors::{ErrorCode, GameError};
+use shared::messages::MessageCode;
+use shared::keywords::{load_keywords, Keyword};
+use shared::EntityID;
+use crate::account_store::AccountStore;
+use crate::credential_store::{check_login, hash_password, CredentialStore, Registration};
+use crate::action_history::{action_name, ActionHistory, ActionRecord, InvalidAction};
+use crate::deck_store::DeckStore;
+use crate::storage::Storage;
+use crate::game::game_event_structs::{CardLibrary, GameEvent, GameEventContext, GameEventWithContext, GameState, GameStateComponent, IntoGameEvent, MessageContext};
+use crate::player_component::{DuplicateConnectionPolicy, Leaving, LobbyAction, LobbyRequest, Player, PlayerDisconnectEvent, PlayerEventWriters, PlayerJoinEvent, PlayerReadyEvent, PlayerResumeEvent, PlayerSessions, RematchRequest};
+use crate::replay::{EventRecorder, EventReplay, RecordedEvent};
+use crate::room_log::{RoomLogEntry, RoomLogEvent};
+use crate::puzzle::PuzzleBook;
+use crate::bot::BotPlayer;
+use crate::room::room_components::{CurrentTurn, Players, PrivateLobby, Room, SendSnapshot, TurnTimer};
 use crate::types::{Server, ServerEvent};
+use crate::validation::validate_action;
 
-#[allow(clippy::type_complexity)]
+// Heartbeats a pinging client may miss in a row before its connection is closed
+pub const MAX_MISSED_HEARTBEATS: u32 = 3;
+
+// Sent to every client on connect so card tooltips always match the server's rules text
+#[derive(Resource)]
+pub struct KeywordGlossary(pub Vec<Keyword>);
+
+impl Default for KeywordGlossary {
+    fn default() -> Self {
+        Self(load_keywords().expect("Failed to load keyword definitions").glossary())
+    }
+}
+
+// Read once at startup and shared by every request
+#[derive(SystemParam)]
+pub struct GameData<'w> {
+    pub glossary: Res<'w, KeywordGlossary>,
+    pub puzzles: Res<'w, PuzzleBook>,
+    pub cards: Res<'w, CardLibrary>,
+}
+
+// Allows each player or connection `burst` actions within `window` seconds and refuses the rest
+pub struct BurstLimiter {
+    burst: usize,
+    window: f32,
+    sent: HashMap<u128, Vec<f32>>,
+}
+
+impl BurstLimiter {
+    pub fn new(burst: usize, window: f32) -> Self {
+        Self { burst, window, sent: HashMap::new() }
+    }
+
+    // Records the action if there is still room for it
+    pub fn allow(&mut self, id: u128, now: f32) -> bool {
+        // Dropping stale entries here keeps ids that went away from piling up
+        let window = self.window;
+        self.sent.retain(|_, times| {
+            times.retain(|&at| now - at < window);
+            !times.is_empty()
+        });
+        let times = self.sent.entry(id).or_default();
+        if times.len() >= self.burst {
+            return false;
+        }
+        times.push(now);
+        true
+    }
+}
+
+// Emotes a player may send within EMOTE_WINDOW_SECS before the rest are refused
+pub const EMOTE_BURST: usize = 3;
+pub const EMOTE_WINDOW_SECS: f32 = 10.0;
+
+// When each player's recent emotes were sent, so spamming them can't drown out the table
+#[derive(Resource)]
+pub struct EmoteLimiter(BurstLimiter);
+
+impl Default for EmoteLimiter {
+    fn default() -> Self {
+        Self(BurstLimiter::new(EMOTE_BURST, EMOTE_WINDOW_SECS))
+    }
+}
+
+impl EmoteLimiter {
+    pub fn allow(&mut self, player_id: EntityID, now: f32) -> bool {
+        self.0.allow(player_id, now)
+    }
+}
+
+// Chat messages a player may send within CHAT_WINDOW_SECS before the rest are refused
+pub const CHAT_BURST: usize = 5;
+pub const CHAT_WINDOW_SECS: f32 = 10.0;
+
+// When each player's recent chat messages were sent, so one player can't flood the table
+#[derive(Resource)]
+pub struct ChatLimiter(BurstLimiter);
+
+impl Default for ChatLimiter {
+    fn default() -> Self {
+        Self(BurstLimiter::new(CHAT_BURST, CHAT_WINDOW_SECS))
+    }
+}
+
+impl ChatLimiter {
+    pub fn allow(&mut self, player_id: EntityID, now: f32) -> bool {
+        self.0.allow(player_id, now)
+    }
+}
+
+// Login and registration attempts a connection may make within LOGIN_WINDOW_SECS
+pub const LOGIN_BURST: usize = 5;
+pub const LOGIN_WINDOW_SECS: f32 = 60.0;
+
+enum LoginOutcome {
+    Registered(Registration, Result<String, GameError>),
+    LoggedIn(Result<EntityID, GameError>),
+}
+
+// Password hashing is too slow for the main loop, so it runs on the async compute pool and the answer
+// is picked up on a later update. One attempt per connection at a time
+#[derive(Resource)]
+pub struct PendingLogins {
+    attempts: BurstLimiter,
+    tasks: Vec<(ClientId, RequestToken, Task<LoginOutcome>)>,
+}
+
+impl Default for PendingLogins {
+    fn default() -> Self {
+        Self { attempts: BurstLimiter::new(LOGIN_BURST, LOGIN_WINDOW_SECS), tasks: Vec::new() }
+    }
+}
+
+impl PendingLogins {
+    fn start(&mut self, client_id: ClientId, token: RequestToken, task: Task<LoginOutcome>) {
+        self.tasks.push((client_id, token, task));
+    }
+
+    fn in_flight(&self, client_id: ClientId) -> bool {
+        self.tasks.iter().any(|(pending, _, _)| *pending == client_id)
+    }
+
+    // Dropping the task cancels the hashing for a connection that went away
+    fn forget(&mut self, client_id: ClientId) {
+        self.tasks.retain(|(pending, _, _)| *pending != client_id);
+    }
+
+    fn finished(&mut self) -> Vec<(ClientId, RequestToken, LoginOutcome)> {
+        let (done, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut self.tasks).into_iter().partition(|(_, _, task)| task.is_finished());
+        self.tasks = pending;
+        done.into_iter().map(|(client_id, token, task)| (client_id, token, block_on(task))).collect()
+    }
+}
+
+// Closing the session produces a normal Disconnected report, so the room's reconnect grace still applies
+pub fn drop_silent_connections(server: Res<Server>, mut sessions: ResMut<PlayerSessions>, time: Res<Time>) {
+    let timeout = HEARTBEAT_INTERVAL.as_secs_f32() * MAX_MISSED_HEARTBEATS as f32;
+    for player_id in sessions.take_silent(time.elapsed_secs(), timeout) {
+        let Some(client_id) = server.connection(player_id) else { continue };
+        warn!("Player {} missed {} heartbeats, closing the connection", player_id, MAX_MISSED_HEARTBEATS);
+        let _ = server.close_session(client_id, None);
+    }
+}
+
+// Grouped so the server event handler stays within Bevy's system parameter limit
+#[derive(SystemParam)]
+pub struct AccountStores<'w> {
+    pub decks: ResMut<'w, DeckStore>,
+    pub accounts: ResMut<'w, AccountStore>,
+    pub credentials: ResMut<'w, CredentialStore>,
+    pub logins: ResMut<'w, PendingLogins>,
+    // Only there when the server was started with storage
+    pub storage: Option<Res<'w, Storage>>,
+}
+
+// Per-player allowances for what is passed straight on to the table
+#[derive(SystemParam)]
+pub struct RateLimits<'w> {
+    pub emotes: ResMut<'w, EmoteLimiter>,
+    pub chat: ResMut<'w, ChatLimiter>,
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 pub fn handle_server_events(
     mut commands: Commands,
     mut server: ResMut<Server>,
-    mut join_events: EventWriter<PlayerJoinEvent>,
-    mut leave_events: EventWriter<PlayerLeaveEvent>,
+    mut player_events: PlayerEventWriters,
     mut game_events: EventWriter<GameEventWithContext>,
     player_query: Query<(Entity, &Player)>,
-    rooms: Query<(Entity, &Players)>,
+    rooms: Query<(Entity, &Players, &Room, &GameStateComponent, Has<PrivateLobby>, Has<BotPlayer>)>,
+    mut recorder: Option<ResMut<EventRecorder>>,
+    mut replay: Option<ResMut<EventReplay>>,
+    mut sessions: ResMut<PlayerSessions>,
+    mut history: ResMut<ActionHistory>,
+    turns: Query<(&CurrentTurn, &TurnTimer)>,
+    time: Res<Time>,
+    data: GameData,
+    mut stores: AccountStores,
+    mut invalid_actions: EventWriter<InvalidAction>,
+    mut limits: RateLimits,
 ) {
+    // Connections become players once they log in; everything past this loop only sees player ids
+    let mut incoming = Vec::new();
     while let Some((client_id, event)) = server.next() {
         match event {
-            ServerEvent::Report(report) => handle_report(
-                &mut commands,
-                &mut join_events,
-                &mut leave_events,
+            ServerEvent::Report(ServerReport::Connected(_, options)) => {
+                let codec = Codec::negotiate(&options.codecs);
+                server.set_codec(client_id, codec);
+                server.send_to_connection(client_id, GameMessage::CodecSelected(codec));
+            }
+            ServerEvent::Report(ServerReport::Disconnected) => {
+                server.forget_codec(client_id);
+                stores.logins.forget(client_id);
+                if let Some(player_id) = server.log_out(client_id) {
+                    incoming.push((player_id, RecordedEvent::Disconnected, None));
+                }
+            }
+            ServerEvent::Request(token, frame) => match frame.decode() {
+                // Answered right away so heartbeats stay out of recordings and action history
+                Ok(GameMessage::Ping(sent)) => {
+                    if let Some(player_id) = server.player(client_id) {
+                        sessions.heartbeat(player_id, time.elapsed_secs());
+                    }
+                    server.send_to_connection(client_id, GameMessage::Pong(sent));
+                    server.ack(token);
+                }
+                // Credentials stay out of recordings too; a successful login is recorded as the player connecting
+                Ok(request @ (GameMessage::Register { .. } | GameMessage::Login { .. })) => {
+                    start_login(&server, &mut stores, time.elapsed_secs(), client_id, token, request);
+                }
+                Ok(request) => match server.player(client_id) {
+                    Some(player_id) => incoming.push((player_id, RecordedEvent::Request(request), Some(token))),
+                    None => {
+                        server.send_to_connection(client_id, GameMessage::Error(ErrorCode::NotLoggedIn.into()));
+                        server.reject(token);
+                    }
+                },
+                Err(e) => {
+                    warn!("Dropping undecodable request from {}: {}", client_id, e);
+                    server.reject(token);
+                }
+            },
+            ServerEvent::Msg(..) => {}
+        }
+    }
+
+    for (client_id, token, outcome) in stores.logins.finished() {
+        match finish_login(&mut server, &mut stores.credentials, &sessions, client_id, outcome) {
+            Some(player_id) => {
+                server.ack(token);
+                incoming.push((player_id, RecordedEvent::Connected, None));
+            }
+            None => server.reject(token),
+        }
+    }
+
+    // Replayed requests have no live token to ack or reject
+    if let Some(replay) = replay.as_mut() {
+        incoming.extend(replay.next_tick().into_iter().map(|frame| (frame.client_id, frame.event, None)));
+    }
+
+    for (client_id, event, token) in incoming {
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.record(client_id, &event);
+        }
+
+        // Resuming isn't a game action, so cooldowns and action history don't apply
+        if let RecordedEvent::Request(GameMessage::Resume) = &event {
+            let resumed = handle_resume(&mut player_events.resume, &server, &player_query, client_id);
+            if let Some(token) = token {
+                if resumed {
+                    server.ack(token);
+                } else {
+                    server.reject(token);
+                }
+            }
+            continue;
+        }
+
+        // Switching rooms goes through the room plugin, which owns matchmaking
+        if let RecordedEvent::Request(request @ (GameMessage::CreateLobby(_) | GameMessage::JoinLobby(_) | GameMessage::PlayVsBot(_))) = &event {
+            let accepted = handle_lobby_request(&mut player_events.lobby, &server, &player_query, client_id, request);
+            if let Some(token) = token {
+                if accepted {
+                    server.ack(token);
+                } else {
+                    server.reject(token);
+                }
+            }
+            continue;
+        }
+
+        // Leaving on purpose gives the game up, where a dropped connection gets its seat held
+        if let RecordedEvent::Request(GameMessage::LeaveGame) = &event {
+            let leaving = handle_leave_game(&mut commands, &mut game_events, &server, &player_query, &rooms, client_id);
+            if let Some(token) = token {
+                if leaving {
+                    server.ack(token);
+                } else {
+                    server.reject(token);
+                }
+            }
+            continue;
+        }
+
+        // Playing again restarts the room in place, which the room plugin does once both players agree
+        if let RecordedEvent::Request(request @ (GameMessage::RequestRematch | GameMessage::AcceptRematch)) = &event {
+            let sent = handle_rematch_request(&mut player_events.rematch, &server, &player_query, client_id, request);
+            if let Some(token) = token {
+                if sent {
+                    server.ack(token);
+                } else {
+                    server.reject(token);
+                }
+            }
+            continue;
+        }
+
+        // Emotes are cosmetic, so they stay out of action history and don't count as readying up
+        if let RecordedEvent::Request(GameMessage::Emote { emote, .. }) = &event {
+            let sent = handle_emote(&mut limits.emotes, &server, &player_query, &rooms, time.elapsed_secs(), client_id, *emote);
+            if let Some(token) = token {
+                if sent {
+                    server.ack(token);
+                } else {
+                    server.reject(token);
+                }
+            }
+            continue;
+        }
+
+        // Players without a seat, e.g. after a match was called off, queue again by asking to join
+        if let RecordedEvent::Request(GameMessage::JoinGame { .. }) = &event {
+            if !player_query.iter().any(|(_, p)| p.id == client_id) {
+                player_events.join.send(PlayerJoinEvent(client_id));
+                if let Some(token) = token {
+                    server.ack(token);
+                }
+                continue;
+            }
+        }
+
+        if let RecordedEvent::Request(request) = &event {
+            // Players penalised for invalid actions are ignored until their cooldown ends
+            if history.in_cooldown(client_id, time.elapsed_secs()) {
+                if let Some(token) = token {
+                    server.reject(token);
+                }
+                continue;
+            }
+            record_action(&mut history, &player_query, &turns, time.elapsed_secs(), client_id, request);
+            if let Some((_, player)) = player_query.iter().find(|(_, p)| p.id == client_id) {
+                player_events.ready.send(PlayerReadyEvent { player_id: client_id, room_entity: player.room });
+                if let Some(entry) = RoomLogEntry::from_request(client_id, request) {
+                    player_events.room_log.send(RoomLogEvent { room_entity: player.room, entry });
+                }
+            }
+        }
+
+        match event {
+            RecordedEvent::Connected | RecordedEvent::Disconnected => handle_report(
+                &mut player_events,
+                &mut sessions,
+                &mut stores.accounts,
+                &server,
+                &data.glossary,
+                &data.cards.0,
                 &player_query,
                 client_id,
-                report,
+                event,
             ),
-            ServerEvent::Request(token, request) => handle_request(
+            RecordedEvent::Request(request) => handle_request(
                 &mut game_events,
+                &mut invalid_actions,
                 &mut server,
+                &mut stores.decks,
+                &mut stores.accounts,
+                &stores.credentials,
+                stores.storage.as_deref(),
+                &data.puzzles,
+                &data.cards.0,
+                &mut limits.chat,
                 &player_query,
                 &rooms,
+                &turns,
+                time.elapsed_secs(),
                 client_id,
                 token,
                 request,
             ),
-            ServerEvent::Msg(..) => {}
         }
     }
+
+    if let Some(recorder) = recorder.as_mut() {
+        recorder.end_tick();
+    }
+}
+
+// Checks what can be checked right away and hands the password off to be hashed or verified
+fn start_login(
+    server: &Server,
+    stores: &mut AccountStores,
+    now: f32,
+    client_id: ClientId,
+    token: RequestToken,
+    request: GameMessage,
+) {
+    let refused = if server.player(client_id).is_some() {
+        Some(GameError::with_detail(ErrorCode::AlreadyConnected, MessageCode::ConnectionLoggedIn))
+    } else if stores.logins.in_flight(client_id) || !stores.logins.attempts.allow(client_id, now) {
+        Some(ErrorCode::RequestCooldown.into())
+    } else {
+        None
+    };
+    if let Some(error) = refused {
+        server.send_to_connection(client_id, GameMessage::Error(error));
+        server.reject(token);
+        return;
+    }
+
+    let pool = AsyncComputeTaskPool::get();
+    let task = match request {
+        GameMessage::Register { username, password } => match stores.credentials.check_registration(&username, &password) {
+            Ok(registration) => pool.spawn(async move { LoginOutcome::Registered(registration, hash_password(&password)) }),
+            Err(error) => {
+                server.send_to_connection(client_id, GameMessage::Error(error));
+                server.reject(token);
+                return;
+            }
+        },
+        GameMessage::Login { username, password } => {
+            let target = stores.credentials.login_target(&username);
+            pool.spawn(async move { LoginOutcome::LoggedIn(check_login(target, &password)) })
+        }
+        _ => return,
+    };
+    stores.logins.start(client_id, token, task);
+}
+
+// Ties the connection to the account's player id once its password checked out
+fn finish_login(
+    server: &mut Server,
+    credentials: &mut CredentialStore,
+    sessions: &PlayerSessions,
+    client_id: ClientId,
+    outcome: LoginOutcome,
+) -> Option<EntityID> {
+    let result = match outcome {
+        LoginOutcome::Registered(registration, password_hash) => {
+            password_hash.and_then(|password_hash| credentials.complete_registration(registration, password_hash))
+        }
+        LoginOutcome::LoggedIn(result) => result,
+    };
+    let result = result.and_then(|player_id| match (server.connection(player_id), sessions.policy) {
+        (Some(_), DuplicateConnectionPolicy::RejectNewer) => Err(ErrorCode::AlreadyConnected.into()),
+        _ => Ok(player_id),
+    });
+
+    match result {
+        Ok(player_id) => {
+            server.log_in(client_id, player_id);
+            server.send(player_id, GameMessage::LoggedIn(player_id));
+            Some(player_id)
+        }
+        Err(error) => {
+            server.send_to_connection(client_id, GameMessage::Error(error));
+            None
+        }
+    }
+}
+
+// Only logged-in connections get this far, so the player is who they say they are
+fn handle_resume(
+    resume_events: &mut EventWriter<PlayerResumeEvent>,
+    server: &Server,
+    player_query: &Query<(Entity, &Player)>,
+    client_id: ClientId,
+) -> bool {
+    match player_query.iter().find(|(_, p)| p.id == client_id) {
+        Some((_, player)) => {
+            resume_events.send(PlayerResumeEvent {
+                player_id: client_id,
+                room_entity: player.room,
+            });
+            true
+        }
+        _ => {
+            server.send(client_id, GameMessage::Error(ErrorCode::SessionExpired.into()));
+            false
+        }
+    }
+}
+
+fn handle_lobby_request(
+    lobby_events: &mut EventWriter<LobbyRequest>,
+    server: &Server,
+    player_query: &Query<(Entity, &Player)>,
+    client_id: ClientId,
+    request: &GameMessage,
+) -> bool {
+    let Some((_, player)) = player_query.iter().find(|(_, p)| p.id == client_id) else {
+        server.send(client_id, GameMessage::Error(ErrorCode::NotInitialized.into()));
+        return false;
+    };
+    let action = match request {
+        GameMessage::CreateLobby(rules) => LobbyAction::Create(*rules),
+        GameMessage::JoinLobby(code) => LobbyAction::Join(code.clone()),
+        GameMessage::PlayVsBot(difficulty) => LobbyAction::Bot(*difficulty),
+        _ => return false,
+    };
+    lobby_events.send(LobbyRequest {
+        player_id: client_id,
+        room_entity: player.room,
+        action,
+    });
+    true
+}
+
+fn handle_leave_game(
+    commands: &mut Commands,
+    game_events: &mut EventWriter<GameEventWithContext>,
+    server: &Server,
+    player_query: &Query<(Entity, &Player)>,
+    rooms: &Query<(Entity, &Players, &Room, &GameStateComponent, Has<PrivateLobby>, Has<BotPlayer>)>,
+    client_id: ClientId,
+) -> bool {
+    let Some((player_entity, player)) = player_query.iter().find(|(_, p)| p.id == client_id) else {
+        server.send(client_id, GameMessage::Error(ErrorCode::NotSeated.into()));
+        return false;
+    };
+    info!("Player {} is leaving room {:?}", client_id, player.room);
+    commands.entity(player_entity).insert(Leaving);
+    if let Ok((_, players, _, game_state, ..)) = rooms.get(player.room) {
+        if players.set.contains(&client_id) && matches!(game_state.state, GameState::InProgress) {
+            game_events.send(GameEventWithContext {
+                context: GameEventContext { room_entity: player.room },
+                event: GameEvent::Concede { player_id: client_id },
+            });
+        }
+    }
+    true
 }
 
+fn handle_rematch_request(
+    rematch_events: &mut EventWriter<RematchRequest>,
+    server: &Server,
+    player_query: &Query<(Entity, &Player)>,
+    client_id: ClientId,
+    request: &GameMessage,
+) -> bool {
+    let Some((_, player)) = player_query.iter().find(|(_, p)| p.id == client_id) else {
+        server.send(client_id, GameMessage::Error(ErrorCode::RematchUnavailable.into()));
+        return false;
+    };
+    rematch_events.send(RematchRequest {
+        player_id: client_id,
+        room_entity: player.room,
+        accept: matches!(request, GameMessage::AcceptRematch),
+    });
+    true
+}
+
+// Passed on to everyone at the sender's table, the sender included
+fn handle_emote(
+    emotes: &mut EmoteLimiter,
+    server: &Server,
+    player_query: &Query<(Entity, &Player)>,
+    rooms: &Query<(Entity, &Players, &Room, &GameStateComponent, Has<PrivateLobby>, Has<BotPlayer>)>,
+    now: f32,
+    client_id: ClientId,
+    emote: Emote,
+) -> bool {
+    let Some((_, players, ..)) = player_query.iter()
+        .find(|(_, p)| p.id == client_id)
+        .and_then(|(_, player)| rooms.get(player.room).ok()) else {
+        server.send(client_id, GameMessage::Error(ErrorCode::NotAtTable.into()));
+        return false;
+    };
+    if !emotes.allow(client_id, now) {
+        server.send(client_id, GameMessage::Error(ErrorCode::EmoteCooldown.into()));
+        return false;
+    }
+    for &player_id in &players.set {
+        server.send(player_id, GameMessage::Emote { emote, sender: Some(client_id) });
+    }
+    true
+}
+
+fn record_action(
+    history: &mut ActionHistory,
+    player_query: &Query<(Entity, &Player)>,
+    turns: &Query<(&CurrentTurn, &TurnTimer)>,
+    now: f32,
+    client_id: ClientId,
+    request: &GameMessage,
+) {
+    let Some(action) = action_name(request) else { return };
+    let Some((_, player)) = player_query.iter().find(|(_, p)| p.id == client_id) else { return };
+    let Ok((current_turn, timer)) = turns.get(player.room) else { return };
+
+    let own_turn = current_turn.player == Some(client_id);
+    history.record(client_id, ActionRecord {
+        at: now,
+        action,
+        own_turn,
+        reaction_secs: own_turn.then(|| timer.timer.elapsed_secs()),
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
 fn handle_request(
     game_events: &mut EventWriter<GameEventWithContext>,
+    invalid_actions: &mut EventWriter<InvalidAction>,
     server: &mut ResMut<Server>,
+    decks: &mut DeckStore,
+    accounts: &mut AccountStore,
+    credentials: &CredentialStore,
+    storage: Option<&Storage>,
+    puzzles: &PuzzleBook,
+    cards: &CardConfig,
+    chat: &mut ChatLimiter,
     player_query: &Query<(Entity, &Player)>,
-    rooms: &Query<(Entity, &Players)>,
+    rooms: &Query<(Entity, &Players, &Room, &GameStateComponent, Has<PrivateLobby>, Has<BotPlayer>)>,
+    turns: &Query<(&CurrentTurn, &TurnTimer)>,
+    now: f32,
     client_id: ClientId,
-    token: RequestToken,
+    token: Option<RequestToken>,
     message: GameMessage,
 ) {
     // Try to convert the message to a game event
@@ -56,66 +642,218 @@ fn handle_request(
             room_entity: player.room,
         };
 
+        let checked = match (rooms.get(player.room), turns.get(player.room)) {
+            (Ok((_, players, _, game_state, ..)), Ok((current_turn, _))) => validate_action(&message, client_id, players, current_turn, game_state),
+            _ => Ok(()),
+        };
+        if let Err(error) = checked {
+            server.send(client_id, GameMessage::ActionRejected(error));
+            invalid_actions.send(InvalidAction { player_id: client_id, room_entity: player.room });
+            if let Some(token) = token {
+                server.reject(token);
+            }
+            return;
+        }
+
         match message.clone().into_game_event(&context) {
             Some(event) => {
                 println!("Processing game event: {:?}", event);
                 game_events.send(event);
-                server.ack(token);
+                if let Some(token) = token {
+                    server.ack(token);
+                }
             }
             None => {
-                handle_non_event_message(message, player.room, rooms, server);
-                server.ack(token);
+                handle_non_event_message(message, client_id, player.room, rooms, decks, accounts, credentials, storage, puzzles, cards, chat, now, server);
+                if let Some(token) = token {
+                    server.ack(token);
+                }
             }
         }
     } else {
         // Player not found - might be in the process of joining
         server.send(
             client_id,
-            GameMessage::Error("Cannot process request - player not initialized".to_string()),
+            GameMessage::Error(ErrorCode::NotInitialized.into()),
         );
-        server.reject(token);
+        if let Some(token) = token {
+            server.reject(token);
+        }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_report(
-    commands: &mut Commands,
-    join_events: &mut EventWriter<PlayerJoinEvent>,
-    leave_events: &mut EventWriter<PlayerLeaveEvent>,
+    player_events: &mut PlayerEventWriters,
+    sessions: &mut PlayerSessions,
+    accounts: &mut AccountStore,
+    server: &Server,
+    glossary: &KeywordGlossary,
+    cards: &CardConfig,
     player_query: &Query<(Entity, &Player)>,
     client_id: ClientId,
-    report: ServerReport<()>,
+    report: RecordedEvent,
 ) {
     match report {
-        ServerReport::Connected(_, _) => {
-            join_events.send(PlayerJoinEvent(client_id));
+        RecordedEvent::Connected => {
+            // A player with no open sockets but still in the world is in their room's reconnect grace period
+            let reconnecting = !sessions.is_connected(client_id);
+            // Still counted as connected means logging in closed an older connection, whose own report is ignored
+            if !reconnecting {
+                sessions.disconnect(client_id);
+            }
+            sessions.connect(client_id);
+            if let Some((cards, packs)) = accounts.claim_starter_rewards(client_id, cards) {
+                server.send(client_id, GameMessage::StarterRewards { cards, packs });
+            }
+            server.send(client_id, GameMessage::Profile(accounts.profile(client_id)));
+            let existing = player_query.iter().find(|(_, p)| p.id == client_id);
+
+            match (existing, sessions.policy) {
+                (None, _) => {
+                    server.send(client_id, GameMessage::KeywordGlossary(glossary.0.clone()));
+                    player_events.join.send(PlayerJoinEvent(client_id));
+                }
+                // Their seat is held until they send Resume or the grace period runs out
+                (Some(_), _) if reconnecting => {
+                    server.send(client_id, GameMessage::KeywordGlossary(glossary.0.clone()));
+                }
+                // Logging in already moved their messages to the new connection, so it only needs to catch up
+                (Some((_, player)), DuplicateConnectionPolicy::KickOlder) => {
+                    warn!("Client {} connected again, moving their seat to the new session", client_id);
+                    server.send(client_id, GameMessage::KeywordGlossary(glossary.0.clone()));
+                    player_events.snapshot.send(SendSnapshot { player_id: client_id, room_entity: player.room });
+                }
+                (Some(_), DuplicateConnectionPolicy::RejectNewer) => {
+                    warn!("Client {} connected again, keeping their existing session", client_id);
+                    server.send(
+                        client_id,
+                        GameMessage::Error(ErrorCode::AlreadyConnected.into()),
+                    );
+                }
+            }
         }
-        ServerReport::Disconnected => {
-            if let Some((player_entity, player)) = player_query.iter().find(|(_, p)| p.id == client_id)
-            {
-                leave_events.send(PlayerLeaveEvent {
+        RecordedEvent::Disconnected => {
+            // Another session under the same id is still open
+            if sessions.disconnect(client_id) > 0 {
+                return;
+            }
+
+            // The room decides whether to hold the seat, so the Player stays for now
+            if let Some((_, player)) = player_query.iter().find(|(_, p)| p.id == client_id) {
+                player_events.disconnect.send(PlayerDisconnectEvent {
                     player_id: client_id,
                     room_entity: player.room,
                 });
-                commands.entity(player_entity).despawn();
             }
         }
+        RecordedEvent::Request(_) => {}
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_non_event_message(
     message: GameMessage,
+    client_id: ClientId,
     room_entity: Entity,
-    rooms: &Query<(Entity, &Players)>,
+    rooms: &Query<(Entity, &Players, &Room, &GameStateComponent, Has<PrivateLobby>, Has<BotPlayer>)>,
+    decks: &mut DeckStore,
+    accounts: &mut AccountStore,
+    credentials: &CredentialStore,
+    storage: Option<&Storage>,
+    puzzles: &PuzzleBook,
+    cards: &CardConfig,
+    chat: &mut ChatLimiter,
+    now: f32,
     server: &Server,
 ) {
     match message {
         GameMessage::Chat(msg) => {
             // We can directly query the room using its Entity
-            if let Ok((_, players)) = rooms.get(room_entity) {
-                for &player_id in &players.set {
-                    server.send(player_id, GameMessage::Chat(msg.clone()));
+            if let Ok((_, players, ..)) = rooms.get(room_entity) {
+                route_chat(server, players, credentials, chat, now, client_id, msg);
+            }
+        }
+        GameMessage::SaveDeck { name, cards: deck } => {
+            let result = decks.save_deck(client_id, name, deck, cards);
+            send_deck_result(server, decks, client_id, result);
+        }
+        GameMessage::DeleteDeck(name) => {
+            let result = decks.delete_deck(client_id, &name);
+            send_deck_result(server, decks, client_id, result);
+        }
+        GameMessage::SetDefaultDeck(name) => {
+            let result = decks.set_default(client_id, &name);
+            send_deck_result(server, decks, client_id, result);
+        }
+        GameMessage::SelectDeck(name) => {
+            let result = decks.select(client_id, &name);
+            send_deck_result(server, decks, client_id, result);
+        }
+        GameMessage::ListDecks => send_deck_result(server, decks, client_id, Ok(())),
+        GameMessage::InspectGraveyard(player_id) => {
+            match rooms.get(room_entity) {
+                Ok((_, players, _, game_state, ..)) if players.set.contains(&player_id) => {
+                    let cards = game_state.graveyards.get(&player_id).cloned().unwrap_or_default();
+                    server.send(client_id, GameMessage::Graveyard { player_id, cards });
+                }
+                _ => server.send(client_id, GameMessage::Error(ErrorCode::NotAtTable.into())),
+            }
+        }
+        GameMessage::InspectCreature { player_id, slot } => {
+            match rooms.get(room_entity) {
+                Ok((_, players, _, game_state, ..)) if players.set.contains(&player_id) => {
+                    let creature = game_state.player_boards.get(&player_id).and_then(|board| board.slots.get(slot)?.as_ref());
+                    match creature {
+                        Some(creature) => server.send(client_id, GameMessage::CreatureHistory { player_id, slot, history: creature.history() }),
+                        None => server.send(client_id, GameMessage::Error(GameError::with_detail(ErrorCode::InvalidSlot, MessageCode::NoCreatureInSlot))),
+                    }
+                }
+                _ => server.send(client_id, GameMessage::Error(ErrorCode::NotAtTable.into())),
+            }
+        }
+        GameMessage::SetProfile { avatar, title } => {
+            if let Err(e) = accounts.set_profile(client_id, avatar, title) {
+                server.send(client_id, GameMessage::Error(GameError::with_detail(ErrorCode::ProfileRejected, e)));
+            }
+            server.send(client_id, GameMessage::Profile(accounts.profile(client_id)));
+        }
+        GameMessage::ListMatches => {
+            let matches = rooms.iter()
+                // Private lobbies are only for whoever has the code, and nobody signs up to watch a bot game
+                .filter(|(_, _, _, game_state, private, bot)| matches!(game_state.state, GameState::InProgress) && !private && !bot)
+                .map(|(_, players, room, game_state, ..)| MatchListing {
+                    room_id: room.room_id.clone(),
+                    players: players.set.iter().map(|&p| accounts.profile(p)).collect(),
+                    turn: game_state.match_log.turns.len() as u32,
+                })
+                .collect();
+            server.send(client_id, GameMessage::MatchList(matches));
+        }
+        GameMessage::RequestStats => match storage {
+            Some(storage) => server.send(client_id, GameMessage::StatsResponse(storage.stats(client_id))),
+            None => server.send(client_id, GameMessage::Error(ErrorCode::StatsUnavailable.into())),
+        },
+        // Players are seated on connect, so joining without a deck changes nothing
+        GameMessage::JoinGame { deck: None } => {}
+        // Sending anything already counted as readying up
+        GameMessage::Ready => {}
+        GameMessage::ListPuzzles => send_puzzle_list(server, accounts, puzzles, cards, client_id),
+        GameMessage::SubmitPuzzle { puzzle_id, moves } => match puzzles.0.get(&puzzle_id) {
+            Some(puzzle) => {
+                let failure = puzzle.check(&moves, cards).err();
+                if failure.is_none() {
+                    accounts.complete_puzzle(client_id, &puzzle_id);
                 }
+                server.send(client_id, GameMessage::PuzzleResult { puzzle_id, failure });
             }
+            None => server.send(client_id, GameMessage::Error(GameError::with_detail(
+                ErrorCode::PuzzleUnavailable,
+                MessageCode::UnknownPuzzle { id: puzzle_id },
+            ))),
+        },
+        GameMessage::SearchCards(filter) => {
+            server.send(client_id, GameMessage::CardSearchResults(card_filter::search(&filter, cards)));
         }
         _ => {
             println!(
@@ -124,4 +862,82 @@ fn handle_non_event_message(
             );
         }
     }
-}
\ No newline at end of file
+}
+
+// Stamps the sender's username on the message, so nobody can speak for someone else
+fn route_chat(
+    server: &Server,
+    players: &Players,
+    credentials: &CredentialStore,
+    chat: &mut ChatLimiter,
+    now: f32,
+    client_id: ClientId,
+    message: MessageType,
+) {
+    let sender = Some(credentials.username(client_id).map_or_else(|| format!("Player {client_id}"), str::to_string));
+    let content = match &message {
+        MessageType::Room { content, .. } | MessageType::Private { content, .. } => content.trim().to_string(),
+        // Only the server speaks for the system
+        MessageType::System(_) => {
+            server.send(client_id, GameMessage::Error(GameError::with_detail(ErrorCode::ChatRejected, MessageCode::SystemChatReserved)));
+            return;
+        }
+    };
+    if content.is_empty() {
+        return;
+    }
+    if content.chars().count() > MAX_CHAT_LENGTH {
+        server.send(client_id, GameMessage::Error(GameError::with_detail(ErrorCode::ChatRejected, MessageCode::ChatTooLong { max: MAX_CHAT_LENGTH })));
+        return;
+    }
+    if !chat.allow(client_id, now) {
+        server.send(client_id, GameMessage::Error(GameError::with_detail(ErrorCode::ChatRejected, MessageCode::ChatCooldown)));
+        return;
+    }
+
+    match message {
+        MessageType::Private { recipient, .. } => {
+            let target = players.set.iter().copied().find(|&player_id| {
+                credentials.username(player_id).is_some_and(|name| name.eq_ignore_ascii_case(recipient.trim()))
+            });
+            let Some(target) = target else {
+                server.send(client_id, GameMessage::Error(GameError::with_detail(ErrorCode::NotAtTable, MessageCode::PlayerNotAtTable { name: recipient.clone() })));
+                return;
+            };
+            // Echoed back so the sender's history shows what they whispered
+            let whisper = GameMessage::Chat(MessageType::Private { sender, recipient, content });
+            if target != client_id {
+                server.send(target, whisper.clone());
+            }
+            server.send(client_id, whisper);
+        }
+        _ => {
+            for &player_id in &players.set {
+                server.send(player_id, GameMessage::Chat(MessageType::Room { sender: sender.clone(), content: content.clone() }));
+            }
+        }
+    }
+}
+
+// Puzzles that can't be set up, e.g. after a card they use was removed, are left out
+fn send_puzzle_list(server: &Server, accounts: &AccountStore, puzzles: &PuzzleBook, cards: &CardConfig, client_id: ClientId) {
+    let list = puzzles.0.iter()
+        .filter_map(|(id, puzzle)| match puzzle.info(id, accounts.completed_puzzle(client_id, id), cards) {
+            Ok(info) => Some(info),
+            Err(e) => {
+                warn!("Skipping puzzle {}: {}", id, e);
+                None
+            }
+        })
+        .collect();
+    server.send(client_id, GameMessage::PuzzleList(list));
+}
+
+// Every deck request is answered with the current list so the client never has to guess what changed
+fn send_deck_result(server: &Server, decks: &DeckStore, client_id: ClientId, result: Result<(), MessageCode>) {
+    if let Err(e) = result {
+        server.send(client_id, GameMessage::Error(GameError::with_detail(ErrorCode::DeckRejected, e)));
+    }
+    let (list, default) = decks.list(client_id);
+    server.send(client_id, GameMessage::DeckList(list, default));
+}
@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use std::time::{SystemTime, UNIX_EPOCH};
+use bevy::prelude::*;
+use bevy_simplenet::ClientId;
+use serde::{Deserialize, Serialize};
+use shared::channel::{MatchOutcome, PlayerStats, RecentMatch};
+use shared::EntityID;
+use crate::deck_store::AccountDecks;
+use crate::room::room_components::MatchFinished;
+use crate::room_log::RoomLog;
+
+const DECKS: &str = "decks";
+const MATCHES: &str = "matches";
+const RECORDS: &str = "records";
+const ROOM_LOGS: &str = "room_logs";
+
+// Matches listed in a stats answer
+pub const RECENT_MATCHES: usize = 10;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MatchRecord {
+    pub players: Vec<EntityID>,
+    // None for a draw
+    pub winner: Option<EntityID>,
+    pub turns: u32,
+    // Seconds since the Unix epoch
+    pub finished_at: u64,
+    #[serde(default)]
+    pub conceded_by: Option<EntityID>,
+}
+
+impl MatchRecord {
+    pub fn seen_by(&self, player_id: EntityID) -> RecentMatch {
+        RecentMatch {
+            opponent: self.players.iter().copied().find(|&p| p != player_id),
+            outcome: match self.winner {
+                None => MatchOutcome::Draw,
+                Some(winner) if winner == player_id => MatchOutcome::Win,
+                Some(_) => MatchOutcome::Loss,
+            },
+            turns: self.turns,
+            finished_at: self.finished_at,
+            conceded: self.conceded_by.is_some(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PlayerRecord {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+enum Write {
+    Decks(ClientId, AccountDecks),
+    Match(MatchRecord),
+    Record(EntityID, PlayerRecord),
+    RoomLog(RoomLog),
+    // Answered once every write queued before it is on disk
+    Flush(mpsc::Sender<()>),
+    Close,
+}
+
+// Queues writes for the storage thread so saving never blocks an update
+#[derive(Clone)]
+pub struct StorageWriter {
+    sender: mpsc::Sender<Write>,
+}
+
+impl StorageWriter {
+    fn queue(&self, write: Write) {
+        if self.sender.send(write).is_err() {
+            warn!("Storage is closed, dropping a write");
+        }
+    }
+
+    pub fn save_decks(&self, player_id: ClientId, decks: AccountDecks) {
+        self.queue(Write::Decks(player_id, decks));
+    }
+
+    pub fn record_match(&self, record: MatchRecord) {
+        self.queue(Write::Match(record));
+    }
+
+    pub fn save_record(&self, player_id: EntityID, record: PlayerRecord) {
+        self.queue(Write::Record(player_id, record));
+    }
+
+    pub fn save_room_log(&self, log: RoomLog) {
+        self.queue(Write::RoomLog(log));
+    }
+
+    // Blocks until everything queued so far has been written; meant for shutdown and tests
+    pub fn flush(&self) {
+        let (done, wait) = mpsc::channel();
+        self.queue(Write::Flush(done));
+        let _ = wait.recv();
+    }
+}
+
+// Saved decks, finished matches and win/loss records in a sled database, written from a background thread
+#[derive(Resource)]
+pub struct Storage {
+    db: sled::Db,
+    writer: StorageWriter,
+    thread: Option<JoinHandle<()>>,
+    // Kept in memory so finishing a match never waits on a read
+    records: HashMap<EntityID, PlayerRecord>,
+}
+
+impl Storage {
+    // Creates the database directory if it doesn't exist yet
+    pub fn open(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let db = sled::open(path)?;
+        let records = read_tree(&db, RECORDS)?;
+        let (sender, receiver) = mpsc::channel();
+        let thread_db = db.clone();
+        let thread = std::thread::Builder::new()
+            .name("storage".to_string())
+            .spawn(move || run_writes(thread_db, receiver))?;
+        Ok(Self { db, writer: StorageWriter { sender }, thread: Some(thread), records })
+    }
+
+    pub fn writer(&self) -> StorageWriter {
+        self.writer.clone()
+    }
+
+    pub fn load_decks(&self) -> Result<HashMap<ClientId, AccountDecks>, Box<dyn std::error::Error>> {
+        read_tree(&self.db, DECKS)
+    }
+
+    // Oldest first
+    pub fn matches(&self) -> Result<Vec<MatchRecord>, Box<dyn std::error::Error>> {
+        let tree = self.db.open_tree(MATCHES)?;
+        tree.iter()
+            .map(|entry| -> Result<MatchRecord, Box<dyn std::error::Error>> {
+                Ok(serde_json::from_slice(&entry?.1)?)
+            })
+            .collect()
+    }
+
+    // None if no finished match has that id
+    pub fn room_log(&self, match_id: &str) -> Result<Option<RoomLog>, Box<dyn std::error::Error>> {
+        let Some(value) = self.db.open_tree(ROOM_LOGS)?.get(match_id.trim().to_lowercase())? else {
+            return Ok(None);
+        };
+        Ok(Some(serde_json::from_slice(&value)?))
+    }
+
+    pub fn record(&self, player_id: EntityID) -> PlayerRecord {
+        self.records.get(&player_id).copied().unwrap_or_default()
+    }
+
+    // Counts come from memory, so a match still waiting on the storage thread is counted but not yet listed
+    pub fn stats(&self, player_id: EntityID) -> PlayerStats {
+        let record = self.record(player_id);
+        let recent = match self.db.open_tree(MATCHES) {
+            Ok(tree) => tree.iter()
+                .rev()
+                .filter_map(|entry| entry.ok().and_then(|(_, value)| serde_json::from_slice::<MatchRecord>(&value).ok()))
+                .filter(|record| record.players.contains(&player_id))
+                .take(RECENT_MATCHES)
+                .map(|record| record.seen_by(player_id))
+                .collect(),
+            Err(e) => {
+                warn!("Failed to read match history: {}", e);
+                Vec::new()
+            }
+        };
+        PlayerStats {
+            games_played: record.wins + record.losses + record.draws,
+            wins: record.wins,
+            losses: record.losses,
+            draws: record.draws,
+            recent,
+        }
+    }
+
+    // Stores the match and updates everyone's win/loss record
+    pub fn finish_match(&mut self, record: MatchRecord) {
+        for &player_id in &record.players {
+            let player = self.records.entry(player_id).or_default();
+            match record.winner {
+                None => player.draws += 1,
+                Some(winner) if winner == player_id => player.wins += 1,
+                Some(_) => player.losses += 1,
+            }
+            self.writer.save_record(player_id, *player);
+        }
+        self.writer.record_match(record);
+    }
+
+    pub fn flush(&self) {
+        self.writer.flush();
+    }
+
+    // Writes everything still queued and releases the database so it can be opened again
+    pub fn close(mut self) {
+        self.writer.queue(Write::Close);
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                warn!("Storage thread panicked");
+            }
+        }
+    }
+}
+
+fn read_tree<K, V>(db: &sled::Db, name: &str) -> Result<HashMap<K, V>, Box<dyn std::error::Error>>
+where
+    K: std::hash::Hash + Eq + From<u128>,
+    V: for<'de> Deserialize<'de>,
+{
+    let tree = db.open_tree(name)?;
+    tree.iter()
+        .map(|entry| -> Result<(K, V), Box<dyn std::error::Error>> {
+            let (key, value) = entry?;
+            let key: [u8; 16] = key.as_ref().try_into()?;
+            Ok((u128::from_be_bytes(key).into(), serde_json::from_slice(&value)?))
+        })
+        .collect()
+}
+
+fn run_writes(db: sled::Db, receiver: mpsc::Receiver<Write>) {
+    // Stops once every writer is gone or the storage is closed
+    while let Ok(write) = receiver.recv() {
+        let result = match write {
+            Write::Decks(player_id, decks) => insert(&db, DECKS, player_id.to_be_bytes(), &decks),
+            Write::Record(player_id, record) => insert(&db, RECORDS, player_id.to_be_bytes(), &record),
+            Write::RoomLog(log) => insert(&db, ROOM_LOGS, log.match_id.clone(), &log),
+            // Generated ids only grow, so matches iterate in the order they finished
+            Write::Match(record) => db.generate_id()
+                .map_err(Into::into)
+                .and_then(|id| insert(&db, MATCHES, id.to_be_bytes(), &record)),
+            Write::Flush(done) => {
+                let result = db.flush().map(|_| ()).map_err(Into::into);
+                let _ = done.send(());
+                result
+            }
+            Write::Close => break,
+        };
+        if let Err(e) = result {
+            warn!("Failed to write to storage: {}", e);
+        }
+    }
+    if let Err(e) = db.flush() {
+        warn!("Failed to flush storage: {}", e);
+    }
+}
+
+fn insert<K: AsRef<[u8]>>(db: &sled::Db, tree: &str, key: K, value: &impl Serialize) -> Result<(), Box<dyn std::error::Error>> {
+    db.open_tree(tree)?.insert(key, serde_json::to_vec(value)?)?;
+    Ok(())
+}
+
+// Turns finished games into match records, when the server was started with storage
+pub fn record_match_results(mut finished: EventReader<MatchFinished>, storage: Option<ResMut<Storage>>) {
+    let Some(mut storage) = storage else {
+        finished.clear();
+        return;
+    };
+    let finished_at = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+    for event in finished.read() {
+        info!("Recording match in room {:?}: winner {:?} after {} turns", event.room_entity, event.winner, event.turns);
+        storage.finish_match(MatchRecord {
+            players: event.players.clone(),
+            winner: event.winner,
+            turns: event.turns,
+            finished_at,
+            conceded_by: event.conceded_by,
+        });
+    }
+}
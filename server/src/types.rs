@@ -1,4 +1,91 @@
-use shared::channel::GameChannel;
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use bevy::prelude::Resource;
+use bevy_simplenet::ClientId;
+use shared::channel::{GameChannel, GameMessage};
+use shared::codec::Codec;
+use shared::errors::{ErrorCode, GameError};
+use shared::messages::MessageCode;
+use shared::EntityID;
 
-pub type Server = bevy_simplenet::Server<GameChannel>;
 pub type ServerEvent = bevy_simplenet::ServerEventFrom<GameChannel>;
+
+// The simplenet server plus the codec each connection negotiated, so game code keeps sending plain GameMessages.
+// Also tracks which player each connection logged in as, so game code only ever deals in player ids
+#[derive(Resource)]
+pub struct Server {
+    inner: bevy_simplenet::Server<GameChannel>,
+    codecs: HashMap<ClientId, Codec>,
+    players: HashMap<ClientId, EntityID>,
+    // A player's messages go to the connection they logged in from most recently
+    connections: HashMap<EntityID, ClientId>,
+}
+
+impl Server {
+    pub fn new(inner: bevy_simplenet::Server<GameChannel>) -> Self {
+        Self { inner, codecs: HashMap::new(), players: HashMap::new(), connections: HashMap::new() }
+    }
+
+    // Players without a connection, like bots or anyone mid-reconnect, are skipped
+    pub fn send(&self, player_id: EntityID, message: GameMessage) {
+        if let Some(&client_id) = self.connections.get(&player_id) {
+            self.send_to_connection(client_id, message);
+        }
+    }
+
+    // For connections that haven't logged in yet. Those that haven't negotiated yet get JSON
+    pub fn send_to_connection(&self, client_id: ClientId, message: GameMessage) {
+        let codec = self.codecs.get(&client_id).copied().unwrap_or(Codec::Json);
+        self.inner.send(client_id, codec.encode(message));
+    }
+
+    // Logging in again takes over from the player's older connection, which is closed so it can't act for them.
+    // Its Disconnected report then finds no player and is ignored
+    pub fn log_in(&mut self, client_id: ClientId, player_id: EntityID) {
+        if let Some(older) = self.connections.insert(player_id, client_id).filter(|&older| older != client_id) {
+            self.players.remove(&older);
+            self.send_to_connection(older, GameMessage::Error(GameError::with_detail(ErrorCode::AlreadyConnected, MessageCode::LoggedInElsewhere)));
+            let _ = self.inner.close_session(older, None);
+        }
+        self.players.insert(client_id, player_id);
+    }
+
+    // Returns the player the connection was logged in as
+    pub fn log_out(&mut self, client_id: ClientId) -> Option<EntityID> {
+        let player_id = self.players.remove(&client_id)?;
+        if self.connections.get(&player_id) == Some(&client_id) {
+            self.connections.remove(&player_id);
+        }
+        Some(player_id)
+    }
+
+    pub fn player(&self, client_id: ClientId) -> Option<EntityID> {
+        self.players.get(&client_id).copied()
+    }
+
+    pub fn connection(&self, player_id: EntityID) -> Option<ClientId> {
+        self.connections.get(&player_id).copied()
+    }
+
+    pub fn set_codec(&mut self, client_id: ClientId, codec: Codec) {
+        self.codecs.insert(client_id, codec);
+    }
+
+    pub fn forget_codec(&mut self, client_id: ClientId) {
+        self.codecs.remove(&client_id);
+    }
+}
+
+impl Deref for Server {
+    type Target = bevy_simplenet::Server<GameChannel>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for Server {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
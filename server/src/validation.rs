@@ -0,0 +1,79 @@
+use shared::channel::GameMessage;
+use shared::errors::{ErrorCode, GameError};
+use shared::rules::{check_attack, check_play, check_play_target, check_turn};
+use shared::EntityID;
+use crate::game::costs::effective_cost;
+use crate::game::game_event_structs::{GameState, GameStateComponent};
+use crate::room::room_components::{CurrentTurn, Players};
+
+// Checks a request against the room's authoritative state before it's queued as a GameEvent. The event
+// handlers run the same checks again, since earlier events in the queue can change things before it resolves
+pub fn validate_action(
+    message: &GameMessage,
+    player_id: EntityID,
+    players: &Players,
+    current_turn: &CurrentTurn,
+    game_state: &GameStateComponent,
+) -> Result<(), GameError> {
+    let needs_turn = matches!(message, GameMessage::EndTurn | GameMessage::DrawCard(_) | GameMessage::PlayCard { .. } | GameMessage::Attack { .. });
+    if !needs_turn {
+        return Ok(());
+    }
+    if !players.set.contains(&player_id) {
+        return Err(ErrorCode::NotSeated.into());
+    }
+    if matches!(game_state.state, GameState::Finished(_)) {
+        return Err(ErrorCode::GameOver.into());
+    }
+    // Nobody has the turn until StartGame resolves, so actions sent before then are refused here
+    check_turn(current_turn.player, player_id)?;
+
+    match message {
+        GameMessage::PlayCard { card_id, target, slot } => validate_play(game_state, players, player_id, *card_id, *target, *slot),
+        GameMessage::Attack { attacker, target } => validate_attack(game_state, players, player_id, *attacker, *target),
+        _ => Ok(()),
+    }
+}
+
+pub fn validate_play(
+    game_state: &GameStateComponent,
+    players: &Players,
+    player_id: EntityID,
+    card_id: EntityID,
+    target: Option<EntityID>,
+    slot: Option<usize>,
+) -> Result<(), GameError> {
+    let card = game_state.player_hands.get(&player_id)
+        .and_then(|hand| hand.cards.iter().find(|card| card.get_id() == card_id))
+        .ok_or(ErrorCode::CardNotInHand)?;
+
+    let cost = effective_cost(game_state, player_id, card);
+    let available = game_state.player_mana.get(&player_id).map_or(0, |mana| mana.current);
+    let board = game_state.player_boards.get(&player_id);
+    check_play(card.data(), cost, available, slot, |slot| board.is_none_or(|board| board.is_free(slot)))?;
+
+    if let Some(target) = target {
+        let on_board = |owner: EntityID| game_state.player_boards.get(&owner)
+            .and_then(|board| board.slots.iter().flatten().find(|card| card.get_id() == target));
+        let enemy = players.set.iter().find(|&&p| p != player_id).copied();
+        check_play_target(on_board(player_id).is_some(), enemy.and_then(on_board).map(|card| card.data()))?;
+    }
+    Ok(())
+}
+
+pub fn validate_attack(
+    game_state: &GameStateComponent,
+    players: &Players,
+    player_id: EntityID,
+    attacker: usize,
+    target: Option<usize>,
+) -> Result<(), GameError> {
+    let attacking = game_state.player_boards.get(&player_id)
+        .and_then(|board| board.slots.get(attacker)?.as_ref());
+    let enemy = players.set.iter().find(|&&p| p != player_id).copied();
+    let defending = |slot: usize| enemy
+        .and_then(|enemy| game_state.player_boards.get(&enemy))
+        .and_then(|board| board.slots.get(slot)?.as_ref())
+        .map(|card| card.data());
+    check_attack(attacking.map(|card| card.data()), attacking.is_some_and(|card| card.can_attack()), target.map(defending))
+}
@@ -0,0 +1,95 @@
+use std::collections::HashSet;
+use bots::mcts::ForwardModel;
+use bots::BotAction;
+use shared::channel::CardType;
+use shared::test_support::CardBuilder;
+use shared::EntityID;
+use server_backend::bot::{choose_action, visible_state, RulesModel};
+use server_backend::game::game_event_structs::{BoardComponent, CardComponent, CardLibrary, GameEvent, GameStateComponent, HandComponent, ManaComponent};
+use server_backend::room::room_components::{CurrentTurn, Players};
+
+const BOT: EntityID = 1;
+const HUMAN: EntityID = 2;
+
+fn card(card_id: EntityID, card_type: CardType, cost: u32, power: u32, health: u32) -> CardComponent {
+    CardComponent::new(CardBuilder::new(card_id).card_type(card_type).cost(cost).stats(power, health).build())
+}
+
+fn seats() -> Players {
+    Players { set: HashSet::from([BOT, HUMAN]) }
+}
+
+fn setup(mana: u32, hand: Vec<CardComponent>) -> GameStateComponent {
+    let mut game_state = GameStateComponent::default();
+    let mut pool = ManaComponent::new(BOT);
+    pool.max = mana;
+    pool.current = mana;
+    game_state.player_mana.insert(BOT, pool);
+    game_state.player_hands.insert(BOT, HandComponent { player_id: BOT, cards: hand });
+    game_state.player_boards.insert(BOT, BoardComponent::new(BOT));
+    game_state.player_boards.insert(HUMAN, BoardComponent::new(HUMAN));
+    game_state
+}
+
+#[test]
+fn plays_the_most_expensive_affordable_card() {
+    let game_state = setup(3, vec![
+        card(10, CardType::Creature, 1, 1, 1),
+        card(11, CardType::Creature, 3, 3, 3),
+        card(12, CardType::Creature, 5, 5, 5),
+    ]);
+    assert!(matches!(
+        choose_action(&seats(), &game_state, BOT),
+        GameEvent::PlayCard { player_id: BOT, card_id: 11, slot: Some(0), .. }
+    ));
+}
+
+#[test]
+fn trades_when_it_survives_and_goes_face_otherwise() {
+    let mut game_state = setup(0, Vec::new());
+    game_state.player_boards.get_mut(&BOT).unwrap().slots[0] = Some(card(10, CardType::Creature, 0, 3, 3));
+
+    game_state.player_boards.get_mut(&HUMAN).unwrap().slots[2] = Some(card(20, CardType::Creature, 0, 5, 2));
+    assert!(matches!(choose_action(&seats(), &game_state, BOT), GameEvent::Attack { attacker: 0, target: None, .. }));
+
+    game_state.player_boards.get_mut(&HUMAN).unwrap().slots[4] = Some(card(21, CardType::Creature, 0, 1, 2));
+    assert!(matches!(choose_action(&seats(), &game_state, BOT), GameEvent::Attack { attacker: 0, target: Some(4), .. }));
+}
+
+#[test]
+fn ends_the_turn_with_nothing_to_do() {
+    let game_state = setup(1, vec![card(10, CardType::Spell, 4, 0, 0)]);
+    assert!(matches!(choose_action(&seats(), &game_state, BOT), GameEvent::EndTurn { player_id: BOT }));
+}
+
+#[test]
+fn sees_an_opponent_who_has_not_played_a_creature_yet() {
+    let mut game_state = setup(0, Vec::new());
+    game_state.player_boards.remove(&HUMAN);
+    game_state.player_health.insert(HUMAN, 3);
+    game_state.player_boards.get_mut(&BOT).unwrap().slots[0] = Some(card(10, CardType::Creature, 0, 3, 3));
+
+    let view = visible_state(&seats(), &game_state, BOT);
+    assert_eq!(view.opponent_health, 3);
+    assert!(view.opponent_board.iter().all(Option::is_none));
+    // Lethal on an empty board goes face
+    assert!(matches!(choose_action(&seats(), &game_state, BOT), GameEvent::Attack { attacker: 0, target: None, .. }));
+}
+
+#[test]
+fn searches_with_the_server_rules() {
+    // The heuristic model guesses a spell hits the opponent for its power; the real rules know it has no effect
+    let game_state = setup(4, vec![card(10, CardType::Spell, 2, 5, 0), card(11, CardType::Creature, 1, 2, 2)]);
+    let model = RulesModel { bot_id: BOT, players: seats(), rules: None, cards: CardLibrary::default().0 };
+    let root = RulesModel::root(&CurrentTurn { player: Some(BOT) }, &game_state);
+
+    let after_spell = model.apply(&root, BotAction::PlayCard { card_id: 10, target: None, slot: None });
+    let view = model.view(&after_spell);
+    assert_eq!(view.opponent_health, model.view(&root).opponent_health);
+    assert_eq!(view.mana, 2);
+
+    let after_creature = model.apply(&after_spell, BotAction::PlayCard { card_id: 11, target: None, slot: Some(0) });
+    let view = model.view(&after_creature);
+    assert!(view.board[0].as_ref().is_some_and(|creature| creature.card.card_id == 11 && !creature.can_attack));
+    assert!(view.hand.is_empty());
+}
@@ -0,0 +1,63 @@
+use shared::card_details::{AuraScope, CostAura};
+use shared::test_support::CardBuilder;
+use shared::EntityID;
+use server_backend::game::costs::{effective_cost, hand_costs};
+use server_backend::game::game_event_structs::{BoardComponent, CardComponent, GameStateComponent, HandComponent};
+
+const OWNER: EntityID = 1;
+const ENEMY: EntityID = 2;
+
+fn card(card_id: EntityID, cost: u32, aura: Option<CostAura>) -> CardComponent {
+    let mut card = CardBuilder::new(card_id).cost(cost);
+    if let Some(aura) = aura {
+        card = card.aura(aura);
+    }
+    CardComponent::new(card.build())
+}
+
+fn with_aura(game_state: &mut GameStateComponent, board_owner: EntityID, cost: i32, applies_to: AuraScope) {
+    let mut board = BoardComponent::new(board_owner);
+    board.slots[0] = Some(card(100, 0, Some(CostAura { cost, applies_to })));
+    game_state.player_boards.insert(board_owner, board);
+}
+
+#[test]
+fn printed_cost_without_modifiers() {
+    let game_state = GameStateComponent::default();
+    assert_eq!(effective_cost(&game_state, OWNER, &card(0, 4, None)), 4);
+}
+
+#[test]
+fn auras_apply_to_their_scope_only() {
+    let mut game_state = GameStateComponent::default();
+    with_aura(&mut game_state, OWNER, -1, AuraScope::Allies);
+    with_aura(&mut game_state, ENEMY, 2, AuraScope::Enemies);
+
+    // Own discount aura and the enemy's tax both land on the owner
+    assert_eq!(effective_cost(&game_state, OWNER, &card(0, 4, None)), 5);
+    // Neither applies to the enemy
+    assert_eq!(effective_cost(&game_state, ENEMY, &card(0, 4, None)), 4);
+}
+
+#[test]
+fn discounts_stack_with_auras_and_never_go_negative() {
+    let mut game_state = GameStateComponent::default();
+    with_aura(&mut game_state, OWNER, -1, AuraScope::Allies);
+    game_state.discounts.insert(OWNER, 3);
+
+    assert_eq!(effective_cost(&game_state, OWNER, &card(0, 5, None)), 1);
+    assert_eq!(effective_cost(&game_state, OWNER, &card(1, 2, None)), 0);
+}
+
+#[test]
+fn hand_costs_follow_hand_order() {
+    let mut game_state = GameStateComponent::default();
+    game_state.discounts.insert(OWNER, 1);
+    game_state.player_hands.insert(OWNER, HandComponent {
+        player_id: OWNER,
+        cards: vec![card(7, 3, None), card(2, 1, None)],
+    });
+
+    assert_eq!(hand_costs(&game_state, OWNER), vec![(7, 2), (2, 0)]);
+    assert!(hand_costs(&game_state, ENEMY).is_empty());
+}
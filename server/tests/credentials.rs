@@ -0,0 +1,94 @@
+use shared::errors::ErrorCode;
+use shared::messages::MessageCode;
+use server_backend::credential_store::{check_login, hash_password, CredentialStore, MIN_PASSWORD_LENGTH};
+
+#[test]
+fn registered_accounts_log_in_with_the_same_id() {
+    let mut store = CredentialStore::default();
+    let player_id = store.register("Navigator", "hunter2hunter2").expect("registration should succeed");
+    assert_eq!(store.log_in("navigator", "hunter2hunter2"), Ok(player_id));
+    assert_eq!(store.log_in(" Navigator ", "hunter2hunter2"), Ok(player_id));
+}
+
+#[test]
+fn wrong_passwords_and_unknown_names_look_the_same() {
+    let mut store = CredentialStore::default();
+    store.register("pilot", "correct horse").unwrap();
+    let wrong_password = store.log_in("pilot", "battery staple").unwrap_err();
+    let unknown_name = store.log_in("engineer", "correct horse").unwrap_err();
+    assert_eq!(wrong_password.code, ErrorCode::InvalidCredentials);
+    assert_eq!(wrong_password, unknown_name);
+}
+
+#[test]
+fn usernames_are_unique_ignoring_case() {
+    let mut store = CredentialStore::default();
+    store.register("Admiral", "correct horse").unwrap();
+    let error = store.register("admiral", "another password").unwrap_err();
+    assert_eq!(error.code, ErrorCode::UsernameTaken);
+}
+
+#[test]
+fn weak_credentials_are_rejected() {
+    let mut store = CredentialStore::default();
+    assert_eq!(store.register("ab", "correct horse").unwrap_err().code, ErrorCode::AccountRejected);
+    assert_eq!(store.register("space cadet", "correct horse").unwrap_err().code, ErrorCode::AccountRejected);
+    assert_eq!(store.register("cadet", "short").unwrap_err().code, ErrorCode::AccountRejected);
+}
+
+#[test]
+fn every_account_gets_its_own_id() {
+    let mut store = CredentialStore::default();
+    let first = store.register("smuggler", "correct horse").unwrap();
+    let second = store.register("android", "correct horse").unwrap();
+    assert_ne!(first, second);
+}
+
+#[test]
+fn usernames_are_found_by_player_id_as_typed() {
+    let mut store = CredentialStore::default();
+    let player_id = store.register("Navigator", "correct horse").unwrap();
+    assert_eq!(store.username(player_id), Some("Navigator"));
+    assert_eq!(store.username(player_id + 1), None);
+}
+
+#[test]
+fn rejections_carry_the_rule_as_a_message_code() {
+    let mut store = CredentialStore::default();
+    let error = store.register("cadet", "short").unwrap_err();
+    assert_eq!(error.detail, Some(MessageCode::PasswordTooShort { min: MIN_PASSWORD_LENGTH }));
+    assert_eq!(error.to_string(), format!("Passwords need at least {MIN_PASSWORD_LENGTH} characters"));
+}
+
+#[test]
+fn a_name_taken_while_hashing_is_refused() {
+    let mut store = CredentialStore::default();
+    let first = store.check_registration("pilot", "correct horse").unwrap();
+    let second = store.check_registration("Pilot", "battery staple").unwrap();
+    store.complete_registration(first, hash_password("correct horse").unwrap()).unwrap();
+    let error = store.complete_registration(second, hash_password("battery staple").unwrap()).unwrap_err();
+    assert_eq!(error.code, ErrorCode::UsernameTaken);
+}
+
+#[test]
+fn unknown_names_are_still_checked_against_a_hash() {
+    let store = CredentialStore::default();
+    assert_eq!(store.login_target("nobody"), None);
+    assert_eq!(check_login(None, "correct horse").unwrap_err().code, ErrorCode::InvalidCredentials);
+}
+
+#[test]
+fn registrations_are_saved_by_the_writer_thread() {
+    let path = std::env::temp_dir().join(format!("rust-game-credentials-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let player_id = {
+        let mut store = CredentialStore::load(&path).unwrap();
+        store.register("engineer", "correct horse").unwrap()
+    };
+    // Dropping the store waits for the writer
+    let store = CredentialStore::load(&path).unwrap();
+    assert_eq!(store.log_in("engineer", "correct horse"), Ok(player_id));
+    // Loaded accounts can be looked up by id as well as by name
+    assert_eq!(store.username(player_id), Some("engineer"));
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,261 @@
+use std::collections::HashSet;
+use shared::channel::{CardData, CreatureChange, BOARD_SLOTS};
+use shared::test_support::CardBuilder;
+use shared::EntityID;
+use shared::card_details::{CardEffect, EffectTarget};
+use shared::targeting::{ELUSIVE, STEALTH};
+use server_backend::game::effects::{apply_effects, resolve_targets, SlotRef, TargetPredicate};
+use server_backend::game::game_event_structs::{BoardComponent, CardComponent, CardLibrary, GameEvent, GameState, GameStateComponent, HandComponent, ManaComponent, MAX_CONSECUTIVE_TURNS, MAX_MANA};
+use server_backend::room::room_components::Players;
+
+const OWNER: EntityID = 1;
+const ENEMY: EntityID = 2;
+
+fn creature(card_id: EntityID) -> CardComponent {
+    CardComponent::new(creature_data(card_id))
+}
+
+fn creature_data(card_id: EntityID) -> CardData {
+    CardBuilder::new(card_id).build()
+}
+
+// Both boards with creatures in the given slots
+fn setup(own_slots: &[usize], enemy_slots: &[usize]) -> (GameStateComponent, Players) {
+    let mut game_state = GameStateComponent::default();
+    for (player_id, slots) in [(OWNER, own_slots), (ENEMY, enemy_slots)] {
+        let mut board = BoardComponent::new(player_id);
+        for &slot in slots {
+            board.slots[slot] = Some(creature(slot as EntityID));
+        }
+        game_state.player_boards.insert(player_id, board);
+    }
+    (game_state, Players { set: HashSet::from([OWNER, ENEMY]) })
+}
+
+fn slots(targets: Vec<SlotRef>, player_id: EntityID) -> Vec<usize> {
+    assert!(targets.iter().all(|t| t.player_id == player_id), "targets on the wrong board: {:?}", targets);
+    targets.into_iter().map(|t| t.slot).collect()
+}
+
+#[test]
+fn adjacent_allies_in_the_middle() {
+    let (game_state, players) = setup(&[2, 3, 4], &[]);
+    let targets = resolve_targets(&game_state, &players, OWNER, Some(3), TargetPredicate::AdjacentAllies);
+    assert_eq!(slots(targets, OWNER), vec![2, 4]);
+}
+
+#[test]
+fn adjacent_allies_at_the_edges() {
+    let last = BOARD_SLOTS - 1;
+    let (game_state, players) = setup(&[0, 1, last - 1, last], &[]);
+
+    let left = resolve_targets(&game_state, &players, OWNER, Some(0), TargetPredicate::AdjacentAllies);
+    assert_eq!(slots(left, OWNER), vec![1]);
+
+    let right = resolve_targets(&game_state, &players, OWNER, Some(last), TargetPredicate::AdjacentAllies);
+    assert_eq!(slots(right, OWNER), vec![last - 1]);
+}
+
+#[test]
+fn adjacent_allies_skip_empty_neighbours() {
+    let (game_state, players) = setup(&[1, 3], &[2]);
+    let targets = resolve_targets(&game_state, &players, OWNER, Some(3), TargetPredicate::AdjacentAllies);
+    assert!(targets.is_empty());
+}
+
+#[test]
+fn other_allies_exclude_the_source() {
+    let (game_state, players) = setup(&[0, 3, 6], &[]);
+    let targets = resolve_targets(&game_state, &players, OWNER, Some(3), TargetPredicate::OtherAllies);
+    assert_eq!(slots(targets, OWNER), vec![0, 6]);
+}
+
+#[test]
+fn leftmost_and_rightmost_enemy() {
+    let last = BOARD_SLOTS - 1;
+    let (game_state, players) = setup(&[0], &[0, 4, last]);
+
+    let leftmost = resolve_targets(&game_state, &players, OWNER, None, TargetPredicate::LeftmostEnemy);
+    assert_eq!(slots(leftmost, ENEMY), vec![0]);
+
+    let rightmost = resolve_targets(&game_state, &players, OWNER, None, TargetPredicate::RightmostEnemy);
+    assert_eq!(slots(rightmost, ENEMY), vec![last]);
+}
+
+#[test]
+fn single_enemy_is_both_leftmost_and_rightmost() {
+    let (game_state, players) = setup(&[], &[5]);
+    for predicate in [TargetPredicate::LeftmostEnemy, TargetPredicate::RightmostEnemy] {
+        let targets = resolve_targets(&game_state, &players, OWNER, None, predicate);
+        assert_eq!(slots(targets, ENEMY), vec![5]);
+    }
+}
+
+#[test]
+fn empty_enemy_board_has_no_targets() {
+    let (game_state, players) = setup(&[0, 1], &[]);
+    for predicate in [TargetPredicate::LeftmostEnemy, TargetPredicate::RightmostEnemy, TargetPredicate::OpposingEnemy] {
+        assert!(resolve_targets(&game_state, &players, OWNER, Some(0), predicate).is_empty());
+    }
+}
+
+#[test]
+fn opposing_enemy_matches_slot_index() {
+    let (game_state, players) = setup(&[2], &[1, 2]);
+    let targets = resolve_targets(&game_state, &players, OWNER, Some(2), TargetPredicate::OpposingEnemy);
+    assert_eq!(slots(targets, ENEMY), vec![2]);
+}
+
+#[test]
+fn damage_destroys_creatures_at_zero_health() {
+    let (mut game_state, players) = setup(&[0], &[0, 3]);
+    let effects = [CardEffect::Damage { amount: 1, target: EffectTarget::LeftmostEnemy }];
+    apply_effects(&mut game_state, &players, &CardLibrary::default().0, OWNER, None, "Test card", &effects);
+
+    let enemy_board = &game_state.player_boards[&ENEMY];
+    assert!(enemy_board.slots[0].is_none());
+    assert!(enemy_board.slots[3].is_some());
+
+    // The destroyed creature lands in its owner's graveyard
+    let graveyard = &game_state.graveyards[&ENEMY];
+    assert_eq!(graveyard.iter().map(|card| card.card_id).collect::<Vec<_>>(), vec![0]);
+    assert!(!game_state.graveyards.contains_key(&OWNER));
+}
+
+#[test]
+fn discarding_moves_the_card_from_hand_to_graveyard() {
+    let (mut game_state, _) = setup(&[], &[]);
+    game_state.player_hands.insert(OWNER, HandComponent { player_id: OWNER, cards: vec![creature(10), creature(11)] });
+
+    let discarded = game_state.discard(OWNER, 11).expect("card 11 is in the hand");
+    assert_eq!(discarded.card_id, 11);
+    assert_eq!(game_state.player_hands[&OWNER].cards.iter().map(|card| card.get_id()).collect::<Vec<_>>(), vec![10]);
+    assert_eq!(game_state.graveyards[&OWNER].iter().map(|card| card.card_id).collect::<Vec<_>>(), vec![11]);
+
+    // Cards that aren't in the hand can't be discarded
+    assert!(game_state.discard(OWNER, 11).is_none());
+    assert!(game_state.discard(ENEMY, 10).is_none());
+}
+
+#[test]
+fn buff_source_only_touches_the_played_creature() {
+    let (mut game_state, players) = setup(&[1, 2], &[]);
+    let effects = [CardEffect::Buff { power: 1, health: 1, target: EffectTarget::Source }];
+    apply_effects(&mut game_state, &players, &CardLibrary::default().0, OWNER, Some(2), "Test card", &effects);
+
+    let board = &game_state.player_boards[&OWNER];
+    assert_eq!(board.slots[2].as_ref().map(|card| (card.power(), card.health())), Some((2, 2)));
+    assert_eq!(board.slots[1].as_ref().map(|card| (card.power(), card.health())), Some((1, 1)));
+}
+
+#[test]
+fn buffed_creatures_remember_where_their_stats_came_from() {
+    let (mut game_state, players) = setup(&[1, 2], &[]);
+    let effects = [CardEffect::Buff { power: 0, health: 2, target: EffectTarget::Source }];
+    apply_effects(&mut game_state, &players, &CardLibrary::default().0, OWNER, Some(2), "Rally", &effects);
+
+    let board = &game_state.player_boards[&OWNER];
+    let history = board.slots[2].as_ref().map(|card| card.history()).expect("creature in slot 2");
+    assert_eq!((history.printed_power, history.printed_health), (1, 1));
+    assert_eq!(history.changes, vec![CreatureChange::Buffed { power: 0, health: 2, source: "Rally".to_string() }]);
+    assert!(board.slots[1].as_ref().is_some_and(|card| card.history().changes.is_empty()));
+}
+
+#[test]
+fn draw_becomes_a_follow_up_event() {
+    let (mut game_state, players) = setup(&[], &[]);
+    let next = apply_effects(&mut game_state, &players, &CardLibrary::default().0, OWNER, None, "Test card", &[CardEffect::Draw { amount: 2 }]).next_events;
+    assert!(matches!(next.as_slice(), [GameEvent::DrawCard { player_id: OWNER, amount: 2 }]));
+}
+
+#[test]
+fn lethal_damage_to_the_opponent_ends_the_game() {
+    let (mut game_state, players) = setup(&[], &[]);
+    game_state.player_health.insert(ENEMY, 3);
+    let effects = [CardEffect::Damage { amount: 5, target: EffectTarget::Opponent }];
+    let next = apply_effects(&mut game_state, &players, &CardLibrary::default().0, OWNER, None, "Test card", &effects).next_events;
+
+    assert_eq!(game_state.player_health[&ENEMY], 0);
+    assert!(matches!(
+        next.as_slice(),
+        [GameEvent::GameStateChange { new_state: GameState::Finished(Some(OWNER)) }]
+    ));
+}
+
+#[test]
+fn stealthed_and_elusive_enemies_are_skipped() {
+    let (mut game_state, players) = setup(&[3], &[1, 3, 5]);
+    let enemy_board = game_state.player_boards.get_mut(&ENEMY).unwrap();
+    for (slot, keyword) in [(1, STEALTH), (3, ELUSIVE)] {
+        let mut card = creature_data(slot as EntityID);
+        card.keywords.push(keyword.to_string());
+        enemy_board.slots[slot] = Some(CardComponent::new(card));
+    }
+
+    let leftmost = resolve_targets(&game_state, &players, OWNER, None, TargetPredicate::LeftmostEnemy);
+    assert_eq!(slots(leftmost, ENEMY), vec![5]);
+    assert!(resolve_targets(&game_state, &players, OWNER, Some(3), TargetPredicate::OpposingEnemy).is_empty());
+}
+
+#[test]
+fn transform_replaces_the_creature_in_place() {
+    let (mut game_state, players) = setup(&[], &[2, 4]);
+    let cards = CardLibrary::default();
+    let buff = [CardEffect::Buff { power: 1, health: 1, target: EffectTarget::RightmostEnemy }];
+    apply_effects(&mut game_state, &players, &cards.0, OWNER, None, "Rally", &buff);
+    let effects = [CardEffect::Transform { into: "scout_drone".to_string(), target: EffectTarget::RightmostEnemy }];
+    let outcome = apply_effects(&mut game_state, &players, &cards.0, OWNER, None, "Test card", &effects);
+
+    assert_eq!(outcome.transformed, vec![SlotRef { player_id: ENEMY, slot: 4 }]);
+    let board = &game_state.player_boards[&ENEMY];
+    let drone = board.slots[4].as_ref().unwrap();
+    assert_eq!((drone.get_id(), drone.power(), drone.health()), (4, 1, 1));
+    assert!(drone.effects().is_empty());
+    // The buff belonged to the old creature
+    let transformed = CreatureChange::Transformed { from: "Card 4".to_string(), source: "Test card".to_string() };
+    assert_eq!(drone.history().changes, vec![transformed]);
+    assert!(board.slots[2].is_some());
+}
+
+#[test]
+fn skipping_the_opponent_gives_the_owner_another_turn() {
+    let (mut game_state, players) = setup(&[], &[]);
+    apply_effects(&mut game_state, &players, &CardLibrary::default().0, OWNER, None, "Test card", &[CardEffect::SkipOpponentTurn]);
+    game_state.consecutive_turns = 1;
+
+    assert!(game_state.take_bonus_turn(OWNER, ENEMY));
+    assert!(!game_state.take_bonus_turn(OWNER, ENEMY));
+}
+
+#[test]
+fn extra_turns_stop_at_the_cap() {
+    let (mut game_state, players) = setup(&[], &[]);
+    let effects = vec![CardEffect::ExtraTurn; MAX_CONSECUTIVE_TURNS as usize + 2];
+    apply_effects(&mut game_state, &players, &CardLibrary::default().0, OWNER, None, "Test card", &effects);
+
+    let mut turns = 1;
+    game_state.consecutive_turns = turns;
+    while game_state.take_bonus_turn(OWNER, ENEMY) {
+        turns += 1;
+        game_state.consecutive_turns = turns;
+    }
+    assert_eq!(turns, MAX_CONSECUTIVE_TURNS);
+    // The leftover extra turns were dropped, not saved for later
+    game_state.consecutive_turns = 1;
+    assert!(!game_state.take_bonus_turn(OWNER, ENEMY));
+}
+
+#[test]
+fn mana_crystals_arrive_empty_and_respect_the_cap() {
+    let (mut game_state, players) = setup(&[], &[]);
+    let mut mana = ManaComponent::new(OWNER);
+    mana.start_turn();
+    game_state.player_mana.insert(OWNER, mana);
+
+    apply_effects(&mut game_state, &players, &CardLibrary::default().0, OWNER, None, "Test card", &[CardEffect::ManaCrystal { amount: 1 }]);
+    let mana = &game_state.player_mana[&OWNER];
+    assert_eq!((mana.current, mana.max), (1, 2));
+
+    apply_effects(&mut game_state, &players, &CardLibrary::default().0, OWNER, None, "Test card", &[CardEffect::ManaCrystal { amount: MAX_MANA }]);
+    assert_eq!(game_state.player_mana[&OWNER].max, MAX_MANA);
+}
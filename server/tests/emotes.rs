@@ -0,0 +1,26 @@
+use server_backend::server_plugin::{ChatLimiter, EmoteLimiter, CHAT_BURST, CHAT_WINDOW_SECS, EMOTE_BURST, EMOTE_WINDOW_SECS};
+
+const PLAYER: u128 = 7;
+
+#[test]
+fn emotes_past_the_burst_are_refused_until_the_window_passes() {
+    let mut limiter = EmoteLimiter::default();
+    for _ in 0..EMOTE_BURST {
+        assert!(limiter.allow(PLAYER, 1.0));
+    }
+    assert!(!limiter.allow(PLAYER, 2.0));
+    // Other players have their own allowance
+    assert!(limiter.allow(PLAYER + 1, 2.0));
+    assert!(limiter.allow(PLAYER, 1.0 + EMOTE_WINDOW_SECS));
+}
+
+#[test]
+fn chat_past_the_burst_is_refused_until_the_window_passes() {
+    let mut limiter = ChatLimiter::default();
+    for _ in 0..CHAT_BURST {
+        assert!(limiter.allow(PLAYER, 1.0));
+    }
+    assert!(!limiter.allow(PLAYER, 2.0));
+    assert!(limiter.allow(PLAYER + 1, 2.0));
+    assert!(limiter.allow(PLAYER, 1.0 + CHAT_WINDOW_SECS));
+}
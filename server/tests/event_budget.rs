@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+use bevy::prelude::*;
+use bevy::core::TaskPoolPlugin;
+use server_backend::action_history::InvalidAction;
+use server_backend::game::game_event_processing::{process_game_events, EventBudget, EventBudgetMetrics};
+use server_backend::game::game_event_structs::{CardLibrary, GameEvent, GameEventContext, GameEventQueue, GameEventWithContext, GameState, GameStateComponent};
+use server_backend::room::room_components::{CurrentTurn, GameRng, Players, TurnTimer};
+use server_backend::server::setup_server;
+
+const EVENTS_PER_ROOM: usize = 10;
+
+// A room whose queue is full of out-of-turn EndTurns, which are refused without queueing anything else
+fn flooded_room(world: &mut World) -> Entity {
+    let room_entity = world.spawn((
+        Players { set: HashSet::from([1, 2]) },
+        CurrentTurn { player: Some(1) },
+        TurnTimer { timer: Timer::from_seconds(60.0, TimerMode::Once) },
+        GameStateComponent::default(),
+        GameRng::seeded(0),
+    )).id();
+    let mut queue = GameEventQueue::default();
+    for _ in 0..EVENTS_PER_ROOM {
+        queue.push(GameEventWithContext {
+            context: GameEventContext { room_entity },
+            event: GameEvent::EndTurn { player_id: 2 },
+        });
+    }
+    world.entity_mut(room_entity).insert(queue);
+    room_entity
+}
+
+fn processed_last_update(app: &App) -> usize {
+    app.world().resource::<Events<InvalidAction>>().iter_current_update_events().count()
+}
+
+#[test]
+fn flooded_rooms_are_capped_and_carry_over() {
+    let mut app = App::new();
+    app
+        .add_plugins(TaskPoolPlugin::default())
+        .insert_resource(setup_server("127.0.0.1:0"))
+        .insert_resource(EventBudget { per_room: 4, per_tick: 6 })
+        .init_resource::<EventBudgetMetrics>()
+        .init_resource::<CardLibrary>()
+        .add_event::<InvalidAction>()
+        .add_systems(Update, process_game_events);
+    let rooms = [flooded_room(app.world_mut()), flooded_room(app.world_mut())];
+
+    app.update();
+    assert_eq!(processed_last_update(&app), 6);
+    let metrics = app.world().resource::<EventBudgetMetrics>();
+    assert_eq!(metrics.room_caps, 2);
+    assert_eq!(metrics.tick_caps, 1);
+    let carried: usize = metrics.last_capped.iter().map(|(_, carried)| carried).sum();
+    assert_eq!(carried, 2 * EVENTS_PER_ROOM - 6);
+
+    // Leftovers run on later updates until both queues are drained
+    for _ in 0..EVENTS_PER_ROOM {
+        app.update();
+    }
+    for room_entity in rooms {
+        assert!(app.world().get::<GameEventQueue>(room_entity).unwrap().is_empty());
+    }
+    assert!(app.world().resource::<EventBudgetMetrics>().last_capped.is_empty());
+}
+
+#[test]
+fn a_room_at_its_cap_waits_for_the_next_update() {
+    let mut app = App::new();
+    app
+        .add_plugins(TaskPoolPlugin::default())
+        .insert_resource(setup_server("127.0.0.1:0"))
+        .insert_resource(EventBudget { per_room: 3, per_tick: 100 })
+        .init_resource::<EventBudgetMetrics>()
+        .init_resource::<CardLibrary>()
+        .add_event::<InvalidAction>()
+        .add_systems(Update, process_game_events);
+    flooded_room(app.world_mut());
+
+    app.update();
+    assert_eq!(processed_last_update(&app), 3);
+    let metrics = app.world().resource::<EventBudgetMetrics>();
+    assert_eq!(metrics.room_caps, 1);
+    assert_eq!(metrics.tick_caps, 0);
+}
+
+#[test]
+fn a_whole_resolution_chain_finishes_in_one_update() {
+    let mut app = App::new();
+    app
+        .add_plugins(TaskPoolPlugin::default())
+        .insert_resource(setup_server("127.0.0.1:0"))
+        .init_resource::<EventBudget>()
+        .init_resource::<EventBudgetMetrics>()
+        .init_resource::<CardLibrary>()
+        .add_event::<InvalidAction>()
+        .add_systems(Update, process_game_events);
+    let room_entity = app.world_mut().spawn((
+        Players { set: HashSet::from([1, 2]) },
+        CurrentTurn { player: Some(1) },
+        TurnTimer { timer: Timer::from_seconds(60.0, TimerMode::Once) },
+        GameStateComponent::default(),
+        GameRng::seeded(0),
+    )).id();
+    let mut queue = GameEventQueue::default();
+    queue.push(GameEventWithContext {
+        context: GameEventContext { room_entity },
+        event: GameEvent::StartGame {},
+    });
+    app.world_mut().entity_mut(room_entity).insert(queue);
+
+    // Starting queues deck building and opening draws, which all resolve before the update ends
+    app.update();
+    assert!(app.world().get::<GameEventQueue>(room_entity).unwrap().is_empty());
+    let game_state = app.world().get::<GameStateComponent>(room_entity).unwrap();
+    assert!(matches!(game_state.state, GameState::InProgress));
+    // Going second comes with an extra card and a power cell
+    assert_eq!(game_state.player_hands[&1].cards.len(), 5);
+    assert_eq!(game_state.player_hands[&2].cards.len(), 7);
+    assert!(app.world().resource::<EventBudgetMetrics>().last_capped.is_empty());
+}
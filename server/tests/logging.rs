@@ -0,0 +1,29 @@
+use server_backend::admin::AdminCommand;
+use server_backend::logging::{LogConfig, LogRotation};
+
+#[test]
+fn module_overrides_become_filter_directives() {
+    let config: LogConfig = serde_json::from_str(r#"{
+        "level": "warn",
+        "modules": { "server_backend::game": "debug", "server_backend::room": "trace" },
+        "directory": "logs",
+        "rotation": "hourly"
+    }"#).unwrap();
+    assert_eq!(config.directives(), "warn,server_backend::game=debug,server_backend::room=trace");
+    assert_eq!(config.rotation, LogRotation::Hourly);
+    // Anything left out keeps its default
+    assert_eq!(config.max_files, LogConfig::default().max_files);
+    assert_eq!(LogConfig::default().directives(), "info");
+}
+
+#[test]
+fn admin_log_commands_parse() {
+    assert_eq!(AdminCommand::parse("log"), Ok(AdminCommand::ShowLogLevels));
+    assert_eq!(
+        AdminCommand::parse("log info,server_backend::game=debug"),
+        Ok(AdminCommand::SetLogLevels("info,server_backend::game=debug".to_string())),
+    );
+    assert!(AdminCommand::parse("log info debug").is_err());
+    assert_eq!(AdminCommand::parse("match 3f2a"), Ok(AdminCommand::ShowMatch("3f2a".to_string())));
+    assert!(AdminCommand::parse("shutdown").is_err());
+}
@@ -0,0 +1,25 @@
+use std::time::Duration;
+use server_backend::room::room_components::QueuePenalties;
+
+const PLAYER: u128 = 7;
+const COOLDOWN: Duration = Duration::from_secs(60);
+
+#[test]
+fn the_first_no_show_is_forgiven() {
+    let mut penalties = QueuePenalties::default();
+    assert_eq!(penalties.offend(PLAYER, 0.0, COOLDOWN), None);
+    assert_eq!(penalties.cooldown_left(PLAYER, 0.0), None);
+}
+
+#[test]
+fn repeat_no_shows_wait_longer_each_time() {
+    let mut penalties = QueuePenalties::default();
+    penalties.offend(PLAYER, 0.0, COOLDOWN);
+    assert_eq!(penalties.offend(PLAYER, 10.0, COOLDOWN), Some(COOLDOWN));
+    assert!(penalties.cooldown_left(PLAYER, 30.0).is_some());
+    assert_eq!(penalties.cooldown_left(PLAYER, 71.0), None);
+
+    assert_eq!(penalties.offend(PLAYER, 100.0, COOLDOWN), Some(COOLDOWN * 2));
+    assert!(penalties.cooldown_left(PLAYER, 200.0).is_some());
+    assert_eq!(penalties.cooldown_left(PLAYER + 1, 200.0), None);
+}
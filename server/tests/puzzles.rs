@@ -0,0 +1,89 @@
+use std::path::Path;
+use server_backend::account_store::AccountStore;
+use server_backend::puzzle::{Puzzle, PuzzleBook};
+use shared::card_details::{load_cards, CardConfig};
+use shared::channel::{PuzzleInfo, PuzzleMove};
+use shared::messages::MessageCode;
+
+const PUZZLES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/puzzles");
+
+fn book() -> PuzzleBook {
+    PuzzleBook::load(Path::new(PUZZLES)).unwrap()
+}
+
+fn puzzle(id: &str) -> (Puzzle, PuzzleInfo, CardConfig) {
+    let cards = load_cards().unwrap();
+    let puzzle = book().0.remove(id).expect("puzzle exists");
+    let info = puzzle.info(id, false, &cards).unwrap();
+    (puzzle, info, cards)
+}
+
+fn card_id(info: &PuzzleInfo, name: &str) -> u128 {
+    info.hand.iter().find(|card| card.card_name == name).expect("card in hand").card_id
+}
+
+#[test]
+fn every_puzzle_can_be_set_up() {
+    let cards = load_cards().unwrap();
+    let puzzles = book();
+    assert!(!puzzles.0.is_empty());
+    for (id, puzzle) in &puzzles.0 {
+        assert!(puzzle.setup(&cards).is_ok(), "{id} can't be set up");
+        assert!(puzzle.move_limit > 0, "{id} has no moves");
+    }
+}
+
+#[test]
+fn spare_power_needs_the_power_cell_first() {
+    let (puzzle, info, cards) = puzzle("01_spare_power");
+    let cell = card_id(&info, "Spare Power Cell");
+    let fighter = card_id(&info, "Stealth Fighter");
+    let solution = [
+        PuzzleMove::PlayCard { card_id: cell, slot: None },
+        PuzzleMove::PlayCard { card_id: fighter, slot: Some(1) },
+        PuzzleMove::Attack { attacker: 0, target: None },
+        PuzzleMove::Attack { attacker: 1, target: None },
+    ];
+    assert_eq!(puzzle.check(&solution, &cards), Ok(()));
+
+    // The fighter can't be afforded before the cell is played
+    let result = puzzle.check(&[PuzzleMove::PlayCard { card_id: fighter, slot: Some(1) }], &cards);
+    assert!(matches!(result, Err(MessageCode::PuzzleMoveRefused { step: 1, .. })), "{result:?}");
+    // Attacking alone falls short
+    assert_eq!(puzzle.check(&[PuzzleMove::Attack { attacker: 0, target: None }], &cards), Err(MessageCode::PuzzleObjectiveMissed));
+}
+
+#[test]
+fn clear_the_sky_depends_on_the_order() {
+    let (puzzle, info, cards) = puzzle("02_clear_the_sky");
+    let well = card_id(&info, "Gravity Well");
+    assert_eq!(puzzle.check(&[
+        PuzzleMove::PlayCard { card_id: well, slot: None },
+        PuzzleMove::Attack { attacker: 0, target: Some(2) },
+    ], &cards), Ok(()));
+    assert_eq!(puzzle.check(&[
+        PuzzleMove::Attack { attacker: 0, target: Some(0) },
+        PuzzleMove::PlayCard { card_id: well, slot: None },
+    ], &cards), Err(MessageCode::PuzzleObjectiveMissed));
+}
+
+#[test]
+fn solutions_over_the_move_limit_are_refused() {
+    let (puzzle, _, cards) = puzzle("02_clear_the_sky");
+    let moves = vec![PuzzleMove::Attack { attacker: 0, target: None }; puzzle.move_limit + 1];
+    assert_eq!(puzzle.check(&moves, &cards), Err(MessageCode::PuzzleMoveLimit { limit: puzzle.move_limit }));
+}
+
+#[test]
+fn completed_puzzles_are_kept_with_the_account() {
+    let path = std::env::temp_dir().join(format!("rust-game-puzzle-accounts-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let mut accounts = AccountStore::load(&path).unwrap();
+    accounts.complete_puzzle(1, "02_clear_the_sky");
+    assert!(accounts.completed_puzzle(1, "02_clear_the_sky"));
+    assert!(!accounts.completed_puzzle(2, "02_clear_the_sky"));
+
+    let accounts = AccountStore::load(&path).unwrap();
+    assert!(accounts.completed_puzzle(1, "02_clear_the_sky"));
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,111 @@
+use std::time::{Duration, Instant};
+use bevy::prelude::*;
+use bevy::core::TaskPoolPlugin;
+use bevy::time::TimePlugin;
+use bevy_simplenet::ClientReport;
+use shared::api::API_VERSION;
+use shared::channel::{GameChannel, GameMessage};
+use shared::codec::ConnectOptions;
+use shared::EntityID;
+use server_backend::player_component::Player;
+use server_backend::room::room_plugin::RoomPlugin;
+use server_backend::server::setup_server;
+use server_backend::server_plugin::handle_server_events;
+use server_backend::types::Server;
+
+type Client = bevy_simplenet::Client<GameChannel>;
+type ClientEvent = bevy_simplenet::ClientEventFrom<GameChannel>;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+fn connect(app: &App, client_id: EntityID) -> Client {
+    let server = app.world().resource::<Server>();
+    bevy_simplenet::ClientFactory::<GameChannel>::new(API_VERSION).new_client(
+        enfync::builtin::Handle::default(),
+        server.url(),
+        bevy_simplenet::AuthRequest::None { client_id },
+        bevy_simplenet::ClientConfig::default(),
+        ConnectOptions::default(),
+    )
+}
+
+// Sends `login` once connected, then reports whether the server logged us in or closed the connection
+#[derive(Default)]
+struct Seen {
+    logged_in: Option<EntityID>,
+    closed: bool,
+}
+
+fn poll(client: &Client, login: &GameMessage, seen: &mut Seen) {
+    while let Some(event) = client.next() {
+        match event {
+            ClientEvent::Report(ClientReport::Connected) => {
+                let _ = client.request(login.clone().into());
+            }
+            ClientEvent::Report(ClientReport::ClosedByServer(_) | ClientReport::Disconnected) => seen.closed = true,
+            ClientEvent::Msg(frame) => {
+                if let Ok(GameMessage::LoggedIn(player_id)) = frame.decode() {
+                    seen.logged_in = Some(player_id);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn player_entities(app: &mut App, player_id: EntityID) -> Vec<Entity> {
+    let mut players = app.world_mut().query::<(Entity, &Player)>();
+    players.iter(app.world()).filter(|(_, player)| player.id == player_id).map(|(entity, _)| entity).collect()
+}
+
+fn run_until(app: &mut App, mut done: impl FnMut() -> bool) {
+    let start = Instant::now();
+    while !done() {
+        assert!(start.elapsed() < TIMEOUT, "timed out after {:?}", TIMEOUT);
+        app.update();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn logging_in_again_moves_the_seat_to_the_newer_connection() {
+    let mut app = App::new();
+    app
+        .add_plugins((TaskPoolPlugin::default(), TimePlugin, RoomPlugin))
+        .insert_resource(setup_server("127.0.0.1:0"))
+        .add_systems(Update, handle_server_events);
+
+    let register = GameMessage::Register { username: "twice".to_string(), password: "correct horse".to_string() };
+    let login = GameMessage::Login { username: "twice".to_string(), password: "correct horse".to_string() };
+
+    let older = connect(&app, 1);
+    let mut older_seen = Seen::default();
+    run_until(&mut app, || {
+        poll(&older, &register, &mut older_seen);
+        older_seen.logged_in.is_some()
+    });
+    let player_id = older_seen.logged_in.unwrap();
+    // The account gets a seat in the queue once it has joined
+    let mut seat = player_entities(&mut app, player_id);
+    let start = Instant::now();
+    while seat.is_empty() {
+        assert!(start.elapsed() < TIMEOUT, "timed out after {:?}", TIMEOUT);
+        app.update();
+        seat = player_entities(&mut app, player_id);
+    }
+
+    let newer = connect(&app, 2);
+    let mut newer_seen = Seen::default();
+    run_until(&mut app, || {
+        poll(&older, &register, &mut older_seen);
+        poll(&newer, &login, &mut newer_seen);
+        newer_seen.logged_in.is_some() && older_seen.closed
+    });
+
+    assert_eq!(newer_seen.logged_in, Some(player_id), "both connections should be the same account");
+    app.update();
+    assert_eq!(player_entities(&mut app, player_id), seat, "the newer connection should keep the same seat");
+    let server = app.world().resource::<Server>();
+    assert_eq!(server.player(older.id()), None, "the older connection can no longer act as the player");
+    assert_eq!(server.connection(player_id), Some(newer.id()));
+}
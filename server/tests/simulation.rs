@@ -0,0 +1,309 @@
+use std::collections::HashSet;
+use shared::card_details::CardEffect;
+use shared::channel::GameMessage;
+use shared::errors::ErrorCode;
+use shared::test_support::CardBuilder;
+use shared::EntityID;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use server_backend::game::game_event_structs::{BoardComponent, CardComponent, CardLibrary, GameEvent, GameState, GameStateComponent, HandComponent, ManaComponent, STARTING_HEALTH};
+use server_backend::game::simulation::{resolve_all, Outbox, Table};
+use server_backend::room::room_components::{CurrentTurn, Players};
+
+const FIRST: EntityID = 1;
+const SECOND: EntityID = 2;
+
+// Cards a player owns wherever they are; the rules move cards around but never create or lose them
+fn cards_owned(game_state: &GameStateComponent, player_id: EntityID) -> usize {
+    game_state.player_decks.get(&player_id).map_or(0, |deck| deck.cards.len())
+        + game_state.player_hands.get(&player_id).map_or(0, |hand| hand.cards.len())
+        + game_state.player_boards.get(&player_id).map_or(0, |board| board.slots.iter().flatten().count())
+        + game_state.graveyards.get(&player_id).map_or(0, |cards| cards.len())
+}
+
+// A started game between FIRST and SECOND, with FIRST to act
+fn started(cards: &CardLibrary) -> (Players, CurrentTurn, GameStateComponent, Outbox, StdRng) {
+    let mut rng = StdRng::seed_from_u64(0);
+    let players = Players { set: HashSet::from([FIRST, SECOND]) };
+    let mut current_turn = CurrentTurn { player: Some(FIRST) };
+    let mut game_state = GameStateComponent::default();
+    let mut outbox = Outbox::default();
+    let mut table = Table { players: &players, current_turn: &mut current_turn, game_state: &mut game_state, rules: None, time_bank: None, cards: &cards.0, rng: &mut rng };
+    let refused = resolve_all(&mut table, GameEvent::StartGame {}, &mut outbox);
+    assert!(refused.is_empty());
+    (players, current_turn, game_state, outbox, rng)
+}
+
+#[test]
+fn starting_a_game_deals_opening_hands() {
+    let cards = CardLibrary::default();
+    let (_, _, game_state, outbox, _) = started(&cards);
+    assert!(matches!(game_state.state, GameState::InProgress));
+    assert_eq!(game_state.player_hands[&FIRST].cards.len(), 5);
+    assert!(outbox.to(FIRST).any(|message| matches!(message, GameMessage::CardsDrawn(cards) if cards.len() == 5)));
+}
+
+#[test]
+fn going_second_comes_with_an_extra_card_and_a_power_cell() {
+    let cards = CardLibrary::default();
+    let (players, mut current_turn, mut game_state, mut outbox, mut rng) = started(&cards);
+    let hand = &game_state.player_hands[&SECOND].cards;
+    assert_eq!(hand.len(), 7);
+    assert!(outbox.to(SECOND).any(|message| matches!(message, GameMessage::CardsDrawn(cards) if cards.len() == 6)));
+    let cell = hand.iter()
+        .find(|card| card.effects().iter().any(|effect| matches!(effect, CardEffect::TemporaryMana { .. })))
+        .expect("second player gets the token");
+    let cell_id = cell.get_id();
+    // The token's id doesn't clash with any card the player already has
+    assert_eq!(hand.iter().filter(|card| card.get_id() == cell_id).count(), 1);
+    assert!(game_state.player_decks[&SECOND].cards.iter().all(|card| card.get_id() != cell_id));
+
+    let mut table = Table { players: &players, current_turn: &mut current_turn, game_state: &mut game_state, rules: None, time_bank: None, cards: &cards.0, rng: &mut rng };
+    assert!(resolve_all(&mut table, GameEvent::EndTurn { player_id: FIRST }, &mut outbox).is_empty());
+    assert_eq!(current_turn.player, Some(SECOND));
+    let mana = &game_state.player_mana[&SECOND];
+    assert_eq!((mana.current, mana.max), (1, 1));
+
+    // Playing the cell is an extra mana for this turn only
+    let mut table = Table { players: &players, current_turn: &mut current_turn, game_state: &mut game_state, rules: None, time_bank: None, cards: &cards.0, rng: &mut rng };
+    let play = GameEvent::PlayCard { player_id: SECOND, card_id: cell_id, target: None, slot: None };
+    assert!(resolve_all(&mut table, play, &mut outbox).is_empty());
+    let mana = &game_state.player_mana[&SECOND];
+    assert_eq!((mana.current, mana.max), (2, 1));
+    assert!(game_state.player_hands[&SECOND].cards.iter().all(|card| card.get_id() != cell_id));
+}
+
+#[test]
+fn passing_every_turn_past_the_end_of_the_deck_loses_no_cards() {
+    let cards = CardLibrary::default();
+    let (players, mut current_turn, mut game_state, mut outbox, mut rng) = started(&cards);
+    let deck_size = cards_owned(&game_state, FIRST);
+    // Plus the power cell for going second
+    assert_eq!(cards_owned(&game_state, SECOND), deck_size + 1);
+    for _ in 0..2 * deck_size + 4 {
+        let player_id = current_turn.player.expect("someone always has the turn");
+        let mut table = Table { players: &players, current_turn: &mut current_turn, game_state: &mut game_state, rules: None, time_bank: None, cards: &cards.0, rng: &mut rng };
+        assert!(resolve_all(&mut table, GameEvent::EndTurn { player_id }, &mut outbox).is_empty());
+        assert_eq!(cards_owned(&game_state, FIRST), deck_size);
+        assert_eq!(cards_owned(&game_state, SECOND), deck_size + 1);
+    }
+    // Empty decks just stop drawing
+    assert!(game_state.player_decks.values().all(|deck| deck.cards.is_empty()));
+    assert!(matches!(game_state.state, GameState::InProgress), "{:?}", game_state.state);
+}
+
+#[test]
+fn refused_actions_change_nothing() {
+    let cards = CardLibrary::default();
+    let (players, mut current_turn, mut game_state, _, mut rng) = started(&cards);
+    let mut outbox = Outbox::default();
+    let hand_before: Vec<_> = game_state.player_hands[&SECOND].cards.iter().map(|card| card.get_id()).collect();
+
+    let mut table = Table { players: &players, current_turn: &mut current_turn, game_state: &mut game_state, rules: None, time_bank: None, cards: &cards.0, rng: &mut rng };
+    let refused = resolve_all(&mut table, GameEvent::EndTurn { player_id: SECOND }, &mut outbox);
+    assert_eq!(refused, vec![SECOND]);
+    let refused = resolve_all(&mut table, GameEvent::PlayCard { player_id: SECOND, card_id: EntityID::MAX, target: None, slot: Some(0) }, &mut outbox);
+    assert_eq!(refused, vec![SECOND]);
+
+    assert_eq!(current_turn.player, Some(FIRST));
+    let hand_after: Vec<_> = game_state.player_hands[&SECOND].cards.iter().map(|card| card.get_id()).collect();
+    assert_eq!(hand_before, hand_after);
+    assert!(outbox.to(FIRST).next().is_none());
+    assert!(outbox.to(SECOND).any(|message| matches!(message, GameMessage::ActionRejected(_))));
+}
+
+#[test]
+fn starting_a_game_hands_out_the_first_turn() {
+    let cards = CardLibrary::default();
+    let mut rng = StdRng::seed_from_u64(0);
+    let players = Players { set: HashSet::from([FIRST, SECOND]) };
+    let mut current_turn = CurrentTurn { player: None };
+    let mut game_state = GameStateComponent::default();
+    let mut outbox = Outbox::default();
+    let mut table = Table { players: &players, current_turn: &mut current_turn, game_state: &mut game_state, rules: None, time_bank: None, cards: &cards.0, rng: &mut rng };
+    assert!(resolve_all(&mut table, GameEvent::StartGame {}, &mut outbox).is_empty());
+
+    let first_player = current_turn.player.expect("starting picks who goes first");
+    assert!(players.set.contains(&first_player));
+    for player_id in [FIRST, SECOND] {
+        assert!(outbox.to(player_id).any(|message| matches!(message, GameMessage::CurrentTurn(Some(p), None) if *p == first_player)));
+    }
+}
+
+#[test]
+fn conceding_off_turn_hands_the_game_to_the_opponent() {
+    let cards = CardLibrary::default();
+    let (players, mut current_turn, mut game_state, mut outbox, mut rng) = started(&cards);
+    let mut table = Table { players: &players, current_turn: &mut current_turn, game_state: &mut game_state, rules: None, time_bank: None, cards: &cards.0, rng: &mut rng };
+    assert!(resolve_all(&mut table, GameEvent::Concede { player_id: SECOND }, &mut outbox).is_empty());
+    assert!(matches!(game_state.state, GameState::Finished(Some(FIRST))));
+    for player_id in [FIRST, SECOND] {
+        assert!(outbox.to(player_id).any(|message| matches!(message, GameMessage::GameOver(Some(FIRST)))));
+        assert!(outbox.to(player_id).any(|message| matches!(message, GameMessage::MatchSummary(summary) if summary.conceded_by == Some(SECOND))));
+    }
+
+    // Once it's over there's nothing left to give up
+    let mut table = Table { players: &players, current_turn: &mut current_turn, game_state: &mut game_state, rules: None, time_bank: None, cards: &cards.0, rng: &mut rng };
+    assert_eq!(resolve_all(&mut table, GameEvent::Concede { player_id: FIRST }, &mut outbox), vec![FIRST]);
+    assert!(matches!(game_state.state, GameState::Finished(Some(FIRST))));
+}
+
+#[test]
+fn turns_stop_once_the_game_is_over() {
+    let cards = CardLibrary::default();
+    let (players, mut current_turn, mut game_state, mut outbox, mut rng) = started(&cards);
+    let mut table = Table { players: &players, current_turn: &mut current_turn, game_state: &mut game_state, rules: None, time_bank: None, cards: &cards.0, rng: &mut rng };
+    resolve_all(&mut table, GameEvent::Concede { player_id: SECOND }, &mut outbox);
+    let messages = outbox.messages.len();
+    let turns = table.game_state.match_log.turns.len();
+
+    // A timed out turn or a StartTurn already queued behind the concession
+    resolve_all(&mut table, GameEvent::EndTurn { player_id: FIRST }, &mut outbox);
+    resolve_all(&mut table, GameEvent::StartTurn { player_id: SECOND }, &mut outbox);
+    assert_eq!(current_turn.player, Some(FIRST));
+    assert_eq!(game_state.match_log.turns.len(), turns);
+    assert_eq!(outbox.messages.len(), messages);
+}
+
+// Coin flip, shuffles and draws, from nothing but the seed
+fn played_out(cards: &CardLibrary, seed: u64) -> (Option<EntityID>, Vec<Vec<EntityID>>, GameState) {
+    let players = Players { set: HashSet::from([FIRST, SECOND]) };
+    let mut current_turn = CurrentTurn { player: None };
+    let mut game_state = GameStateComponent::default();
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut outbox = Outbox::default();
+    let mut table = Table { players: &players, current_turn: &mut current_turn, game_state: &mut game_state, rules: None, time_bank: None, cards: &cards.0, rng: &mut rng };
+    resolve_all(&mut table, GameEvent::StartGame {}, &mut outbox);
+    let first_player = table.current_turn.player;
+    for _ in 0..10 {
+        let player_id = table.current_turn.player.expect("someone always has the turn");
+        resolve_all(&mut table, GameEvent::EndTurn { player_id }, &mut outbox);
+    }
+    let ids = |cards: &[CardComponent]| cards.iter().map(|card| card.get_id()).collect::<Vec<_>>();
+    let piles = [FIRST, SECOND].into_iter()
+        .flat_map(|player_id| [ids(&game_state.player_hands[&player_id].cards), ids(&game_state.player_decks[&player_id].cards)])
+        .collect();
+    (first_player, piles, game_state.state)
+}
+
+#[test]
+fn the_same_seed_replays_the_same_game() {
+    // Loaded twice, so neither the seats nor the card maps share an iteration order
+    let (first, piles, state) = played_out(&CardLibrary::default(), 7);
+    let (replayed_first, replayed_piles, replayed_state) = played_out(&CardLibrary::default(), 7);
+    assert_eq!(first, replayed_first);
+    assert_eq!(piles, replayed_piles);
+    assert_eq!(format!("{state:?}"), format!("{replayed_state:?}"));
+}
+
+fn creature(card_id: EntityID, power: u32, health: u32) -> CardComponent {
+    CardComponent::new(CardBuilder::new(card_id).cost(1).stats(power, health).build())
+}
+
+// FIRST's ready creature in slot 0 facing SECOND's in slot 0, mid-game on FIRST's turn
+fn facing(attacker: CardComponent, defender: CardComponent) -> (Players, CurrentTurn, GameStateComponent) {
+    let mut game_state = GameStateComponent { state: GameState::InProgress, ..Default::default() };
+    for (player_id, card) in [(FIRST, attacker), (SECOND, defender)] {
+        let mut board = BoardComponent::new(player_id);
+        board.slots[0] = Some(card);
+        game_state.player_boards.insert(player_id, board);
+        game_state.player_health.insert(player_id, STARTING_HEALTH);
+    }
+    (Players { set: HashSet::from([FIRST, SECOND]) }, CurrentTurn { player: Some(FIRST) }, game_state)
+}
+
+fn attack(players: &Players, current_turn: &mut CurrentTurn, game_state: &mut GameStateComponent, target: Option<usize>) -> (Vec<EntityID>, Outbox) {
+    let cards = CardLibrary::default();
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut outbox = Outbox::default();
+    let mut table = Table { players, current_turn, game_state, rules: None, time_bank: None, cards: &cards.0, rng: &mut rng };
+    let refused = resolve_all(&mut table, GameEvent::Attack { player_id: FIRST, attacker: 0, target }, &mut outbox);
+    (refused, outbox)
+}
+
+#[test]
+fn fighting_creatures_damage_each_other() {
+    let (players, mut current_turn, mut game_state) = facing(creature(10, 2, 5), creature(20, 3, 4));
+    let (refused, outbox) = attack(&players, &mut current_turn, &mut game_state, Some(0));
+    assert!(refused.is_empty());
+
+    let attacker = game_state.player_boards[&FIRST].slots[0].as_ref().expect("attacker survives");
+    let defender = game_state.player_boards[&SECOND].slots[0].as_ref().expect("defender survives");
+    assert_eq!((attacker.power(), attacker.health()), (2, 2));
+    assert_eq!((defender.power(), defender.health()), (3, 2));
+    assert!(!attacker.can_attack(), "each creature attacks once per turn");
+    // Creature fights never touch the players
+    assert_eq!(game_state.player_health[&SECOND], STARTING_HEALTH);
+    for player_id in [FIRST, SECOND] {
+        assert!(outbox.to(player_id).any(|message| matches!(message, GameMessage::BoardUpdate(..))));
+    }
+
+    // Attacking again the same turn is refused and changes nothing
+    let (refused, _) = attack(&players, &mut current_turn, &mut game_state, Some(0));
+    assert_eq!(refused, vec![FIRST]);
+    assert_eq!(game_state.player_boards[&SECOND].slots[0].as_ref().map(CardComponent::health), Some(2));
+}
+
+#[test]
+fn creatures_without_health_left_go_to_the_graveyard() {
+    let (players, mut current_turn, mut game_state) = facing(creature(10, 3, 2), creature(20, 2, 3));
+    let (refused, _) = attack(&players, &mut current_turn, &mut game_state, Some(0));
+    assert!(refused.is_empty());
+
+    assert!(game_state.player_boards[&FIRST].slots[0].is_none());
+    assert!(game_state.player_boards[&SECOND].slots[0].is_none());
+    for (player_id, card_id) in [(FIRST, 10), (SECOND, 20)] {
+        let graveyard: Vec<_> = game_state.graveyards[&player_id].iter().map(|card| card.card_id).collect();
+        assert_eq!(graveyard, vec![card_id]);
+    }
+}
+
+#[test]
+fn attacking_the_opponent_down_to_zero_wins() {
+    let (players, mut current_turn, mut game_state) = facing(creature(10, 4, 1), creature(20, 1, 1));
+    game_state.player_health.insert(SECOND, 6);
+
+    let (refused, _) = attack(&players, &mut current_turn, &mut game_state, None);
+    assert!(refused.is_empty());
+    assert_eq!(game_state.player_health[&SECOND], 2);
+    assert!(matches!(game_state.state, GameState::InProgress));
+
+    game_state.player_boards.get_mut(&FIRST).unwrap().slots[1] = Some(creature(11, 3, 1));
+    let cards = CardLibrary::default();
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut outbox = Outbox::default();
+    let mut table = Table { players: &players, current_turn: &mut current_turn, game_state: &mut game_state, rules: None, time_bank: None, cards: &cards.0, rng: &mut rng };
+    assert!(resolve_all(&mut table, GameEvent::Attack { player_id: FIRST, attacker: 1, target: None }, &mut outbox).is_empty());
+    assert_eq!(game_state.player_health[&SECOND], 0);
+    assert!(matches!(game_state.state, GameState::Finished(Some(FIRST))), "{:?}", game_state.state);
+    assert!(outbox.to(SECOND).any(|message| matches!(message, GameMessage::GameOver(Some(FIRST)))));
+}
+
+#[test]
+fn actions_queued_behind_lethal_are_refused() {
+    let (players, mut current_turn, mut game_state) = facing(creature(10, 4, 1), creature(20, 1, 1));
+    game_state.player_health.insert(SECOND, 4);
+    game_state.player_boards.get_mut(&FIRST).unwrap().slots[1] = Some(creature(11, 3, 1));
+    let mut mana = ManaComponent::new(FIRST);
+    mana.start_turn();
+    game_state.player_mana.insert(FIRST, mana);
+    game_state.player_hands.insert(FIRST, HandComponent { player_id: FIRST, cards: vec![creature(12, 1, 1)] });
+
+    let cards = CardLibrary::default();
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut outbox = Outbox::default();
+    let mut table = Table { players: &players, current_turn: &mut current_turn, game_state: &mut game_state, rules: None, time_bank: None, cards: &cards.0, rng: &mut rng };
+    assert!(resolve_all(&mut table, GameEvent::Attack { player_id: FIRST, attacker: 0, target: None }, &mut outbox).is_empty());
+    assert!(matches!(table.game_state.state, GameState::Finished(Some(FIRST))));
+
+    // Sent before the hit landed, resolving after it
+    let play = GameEvent::PlayCard { player_id: FIRST, card_id: 12, target: None, slot: Some(2) };
+    assert_eq!(resolve_all(&mut table, play, &mut outbox), vec![FIRST]);
+    assert_eq!(resolve_all(&mut table, GameEvent::Attack { player_id: FIRST, attacker: 1, target: Some(0) }, &mut outbox), vec![FIRST]);
+
+    assert!(game_state.player_boards[&FIRST].slots[2].is_none());
+    assert_eq!(game_state.player_hands[&FIRST].cards.len(), 1);
+    assert!(game_state.player_boards[&SECOND].slots[0].is_some());
+    let refusals = outbox.to(FIRST).filter(|message| matches!(message, GameMessage::ActionRejected(error) if error.code == ErrorCode::GameOver));
+    assert_eq!(refusals.count(), 2);
+}
@@ -0,0 +1,183 @@
+use std::time::{Duration, Instant};
+use bevy::prelude::*;
+use bevy::core::TaskPoolPlugin;
+use bevy::time::TimePlugin;
+use bevy_simplenet::ClientReport;
+use shared::api::API_VERSION;
+use shared::channel::{GameChannel, GameMessage};
+use shared::checksum::player_state_checksum;
+use shared::codec::ConnectOptions;
+use shared::EntityID;
+use server_backend::game::game_event_structs::GameStateComponent;
+use server_backend::room::room_components::{CurrentTurn, Players, RoomTimeouts};
+use server_backend::room::room_plugin::RoomPlugin;
+use server_backend::server::setup_server;
+use server_backend::server_plugin::handle_server_events;
+use server_backend::types::Server;
+
+type Client = bevy_simplenet::Client<GameChannel>;
+type ClientEvent = bevy_simplenet::ClientEventFrom<GameChannel>;
+
+const TIMEOUT: Duration = Duration::from_secs(120);
+// Whoever gets this many turns first concedes, so the game has a winner
+const TURNS_BEFORE_CONCEDING: u32 = 3;
+
+// The slice of game state a headless client mirrors from server messages
+struct ScriptedClient {
+    client: Client,
+    username: String,
+    // Set once the server accepts the registration
+    player_id: Option<EntityID>,
+    current_turn: Option<EntityID>,
+    hand: Vec<EntityID>,
+    ended_turn: bool,
+    turns_taken: u32,
+    game_over: Option<Option<EntityID>>,
+}
+
+impl ScriptedClient {
+    fn connect(server: &Server, client_id: EntityID) -> Self {
+        let client = bevy_simplenet::ClientFactory::<GameChannel>::new(API_VERSION).new_client(
+            enfync::builtin::Handle::default(),
+            server.url(),
+            bevy_simplenet::AuthRequest::None { client_id },
+            bevy_simplenet::ClientConfig::default(),
+            ConnectOptions::default(),
+        );
+
+        Self {
+            client,
+            username: format!("player{client_id}"),
+            player_id: None,
+            current_turn: None,
+            hand: Vec::new(),
+            ended_turn: false,
+            turns_taken: 0,
+            game_over: None,
+        }
+    }
+
+    // Mirrors incoming messages and ends the turn whenever it is ours, conceding once it has had enough turns
+    fn poll(&mut self) {
+        while let Some(event) = self.client.next() {
+            let frame = match event {
+                ClientEvent::Report(ClientReport::Connected) => {
+                    let _ = self.client.request(GameMessage::Register {
+                        username: self.username.clone(),
+                        password: "correct horse".to_string(),
+                    }.into());
+                    continue;
+                }
+                ClientEvent::Msg(frame) => frame,
+                _ => continue,
+            };
+            match frame.decode().expect("server frames should decode") {
+                GameMessage::LoggedIn(player_id) => self.player_id = Some(player_id),
+                GameMessage::CurrentTurn(player, _) => {
+                    self.current_turn = player;
+                    self.ended_turn = false;
+                }
+                GameMessage::CardsDrawn(cards) => {
+                    self.hand.extend(cards.iter().map(|card| card.card_id));
+                }
+                GameMessage::GameOver(winner) => self.game_over = Some(winner),
+                _ => {}
+            }
+        }
+
+        if self.game_over.is_none() && !self.ended_turn && self.player_id.is_some() && self.current_turn == self.player_id {
+            let action = if self.turns_taken >= TURNS_BEFORE_CONCEDING { GameMessage::Concede } else { GameMessage::EndTurn };
+            if self.client.request(action.into()).is_ok() {
+                self.ended_turn = true;
+                self.turns_taken += 1;
+            }
+        }
+    }
+
+    fn checksum(&self) -> u64 {
+        player_state_checksum(self.current_turn, &self.hand)
+    }
+}
+
+fn server_checksum(world: &mut World, player_id: EntityID) -> u64 {
+    let mut rooms = world.query::<(&Players, &CurrentTurn, &GameStateComponent)>();
+    let (_, current_turn, game_state) = rooms.iter(world)
+        .find(|(players, _, _)| players.set.contains(&player_id))
+        .expect("player should still be in a room");
+
+    game_state.player_checksum(player_id, current_turn.player)
+}
+
+#[test]
+fn two_clients_play_until_game_over() {
+    let mut app = App::new();
+    app
+        .add_plugins((TaskPoolPlugin::default(), TimePlugin, RoomPlugin))
+        .insert_resource(setup_server("127.0.0.1:0"))
+        .add_systems(Update, handle_server_events);
+
+    let mut clients = {
+        let server = app.world().resource::<Server>();
+        [ScriptedClient::connect(server, 1), ScriptedClient::connect(server, 2)]
+    };
+
+    let start = Instant::now();
+    while clients.iter().any(|c| c.game_over.is_none()) {
+        assert!(start.elapsed() < TIMEOUT, "game did not finish within {:?}", TIMEOUT);
+        app.update();
+        for client in clients.iter_mut() {
+            client.poll();
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    assert_eq!(clients[0].game_over, clients[1].game_over, "clients disagree on the winner");
+    assert!(clients[0].game_over.unwrap().is_some(), "game should end with a winner");
+
+    for client in clients.iter() {
+        let player_id = client.player_id.expect("client should have logged in");
+        assert_ne!(player_id, client.client.id(), "players should get account ids, not connection ids");
+        let expected = server_checksum(app.world_mut(), player_id);
+        assert_eq!(client.checksum(), expected, "client {} diverged from the server", client.username);
+    }
+}
+
+#[test]
+fn a_player_who_never_comes_back_loses_the_game() {
+    let mut app = App::new();
+    app
+        .add_plugins((TaskPoolPlugin::default(), TimePlugin, RoomPlugin))
+        .insert_resource(RoomTimeouts { reconnect_grace: Duration::from_millis(200), ..default() })
+        .insert_resource(setup_server("127.0.0.1:0"))
+        .add_systems(Update, handle_server_events);
+
+    let mut clients = {
+        let server = app.world().resource::<Server>();
+        [ScriptedClient::connect(server, 1), ScriptedClient::connect(server, 2)]
+    };
+
+    // Once both know whose turn it is, the game is underway
+    let start = Instant::now();
+    while clients.iter().any(|c| c.current_turn.is_none()) {
+        assert!(start.elapsed() < TIMEOUT, "game did not start within {:?}", TIMEOUT);
+        app.update();
+        for client in clients.iter_mut() {
+            client.poll();
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    let [mut remaining, dropped] = clients;
+    let dropped_id = dropped.player_id.expect("client should have logged in");
+    dropped.client.close();
+
+    while remaining.game_over.is_none() {
+        assert!(start.elapsed() < TIMEOUT, "game did not finish within {:?}", TIMEOUT);
+        app.update();
+        remaining.poll();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    assert_eq!(remaining.game_over, Some(remaining.player_id), "the player who stayed should win");
+    assert_ne!(remaining.player_id, Some(dropped_id));
+}
@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+use server_backend::deck_store::DeckStore;
+use server_backend::room_log::{RoomLog, RoomLogEntry};
+use server_backend::storage::{MatchRecord, PlayerRecord, Storage};
+use shared::card_details::load_cards;
+use shared::channel::{GameMessage, MatchOutcome, MessageType};
+use shared::deck_rules::MIN_DECK_SIZE;
+
+const FIRST: u128 = 1;
+const SECOND: u128 = 2;
+
+// A fresh database directory per test, so tests can run side by side
+fn storage_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rust-game-storage-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn match_between(winner: Option<u128>, turns: u32) -> MatchRecord {
+    MatchRecord { players: vec![FIRST, SECOND], winner, turns, finished_at: 0, conceded_by: None }
+}
+
+#[test]
+fn match_results_and_records_survive_a_restart() {
+    let dir = storage_dir("matches");
+    let mut storage = Storage::open(&dir).unwrap();
+    storage.finish_match(match_between(Some(FIRST), 7));
+    storage.finish_match(match_between(None, 12));
+    storage.finish_match(match_between(Some(FIRST), 9));
+    assert_eq!(storage.record(FIRST), PlayerRecord { wins: 2, losses: 0, draws: 1 });
+    storage.close();
+
+    let storage = Storage::open(&dir).unwrap();
+    assert_eq!(storage.record(FIRST), PlayerRecord { wins: 2, losses: 0, draws: 1 });
+    assert_eq!(storage.record(SECOND), PlayerRecord { wins: 0, losses: 2, draws: 1 });
+    let turns: Vec<_> = storage.matches().unwrap().iter().map(|record| record.turns).collect();
+    assert_eq!(turns, vec![7, 12, 9]);
+    storage.close();
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn room_logs_can_be_looked_up_by_match_id_after_a_restart() {
+    let dir = storage_dir("room-logs");
+    let mut log = RoomLog::new([FIRST, SECOND], 0.0);
+    let chat = GameMessage::Chat(MessageType::Room { sender: None, content: "gg".to_string() });
+    log.push(3.0, RoomLogEntry::from_request(SECOND, &chat).unwrap());
+    log.push(4.0, RoomLogEntry::Result { winner: Some(FIRST), turns: 5 });
+    let storage = Storage::open(&dir).unwrap();
+    storage.writer().save_room_log(log.clone());
+    storage.close();
+
+    let storage = Storage::open(&dir).unwrap();
+    let saved = storage.room_log(&log.match_id.to_uppercase()).unwrap().unwrap();
+    assert_eq!(saved.entries, log.entries);
+    assert_eq!(saved.entries[2].entry, RoomLogEntry::Chat { player_id: SECOND, content: "gg".to_string() });
+    assert!(storage.room_log("0000000000000000").unwrap().is_none());
+    storage.close();
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn saved_decks_survive_a_restart() {
+    let dir = storage_dir("decks");
+    // Two copies of ten different cards is the smallest legal deck
+    let known = load_cards().unwrap();
+    let mut cards: Vec<String> = known.cards.iter()
+        .filter(|(_, card)| !card.token)
+        .map(|(key, _)| key.clone())
+        .take(MIN_DECK_SIZE / 2)
+        .collect();
+    cards.extend(cards.clone());
+
+    let storage = Storage::open(&dir).unwrap();
+    let mut decks = DeckStore::with_storage(&storage).unwrap();
+    decks.save_deck(FIRST, "Aggro".to_string(), cards.clone(), &known).unwrap();
+    storage.close();
+
+    let storage = Storage::open(&dir).unwrap();
+    let decks = DeckStore::with_storage(&storage).unwrap();
+    assert_eq!(decks.queued_deck(FIRST), Some(cards));
+    assert_eq!(decks.queued_deck(SECOND), None);
+    storage.close();
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn stats_list_recent_matches_newest_first() {
+    let dir = storage_dir("stats");
+    let mut storage = Storage::open(&dir).unwrap();
+    storage.finish_match(match_between(Some(FIRST), 7));
+    storage.finish_match(MatchRecord { players: vec![SECOND, 3], winner: Some(3), turns: 4, finished_at: 0, conceded_by: None });
+    storage.finish_match(match_between(Some(SECOND), 11));
+    storage.flush();
+
+    let stats = storage.stats(FIRST);
+    assert_eq!((stats.games_played, stats.wins, stats.losses, stats.draws), (2, 1, 1, 0));
+    let recent: Vec<_> = stats.recent.iter().map(|recent| (recent.outcome, recent.opponent, recent.turns)).collect();
+    assert_eq!(recent, vec![(MatchOutcome::Loss, Some(SECOND), 11), (MatchOutcome::Win, Some(SECOND), 7)]);
+    assert_eq!(storage.stats(SECOND).recent.len(), 3);
+    assert_eq!(storage.stats(4).games_played, 0);
+    storage.close();
+    let _ = std::fs::remove_dir_all(&dir);
+}
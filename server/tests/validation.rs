@@ -0,0 +1,60 @@
+use std::collections::HashSet;
+use shared::channel::GameMessage;
+use shared::errors::ErrorCode;
+use shared::test_support::CardBuilder;
+use shared::EntityID;
+use server_backend::game::game_event_structs::{BoardComponent, CardComponent, GameState, GameStateComponent, HandComponent, ManaComponent};
+use server_backend::room::room_components::{CurrentTurn, Players};
+use server_backend::validation::validate_action;
+
+const PLAYER: EntityID = 1;
+const OPPONENT: EntityID = 2;
+
+fn card(card_id: EntityID, cost: u32) -> CardComponent {
+    CardComponent::new(CardBuilder::new(card_id).cost(cost).build())
+}
+
+fn setup() -> (GameStateComponent, Players, CurrentTurn) {
+    let mut game_state = GameStateComponent::default();
+    game_state.state = GameState::InProgress;
+    let mut mana = ManaComponent::new(PLAYER);
+    mana.start_turn();
+    game_state.player_mana.insert(PLAYER, mana);
+    game_state.player_hands.insert(PLAYER, HandComponent { player_id: PLAYER, cards: vec![card(10, 1), card(11, 5)] });
+    game_state.player_boards.insert(PLAYER, BoardComponent::new(PLAYER));
+    game_state.player_boards.insert(OPPONENT, BoardComponent::new(OPPONENT));
+    (game_state, Players { set: HashSet::from([PLAYER, OPPONENT]) }, CurrentTurn { player: Some(PLAYER) })
+}
+
+fn play(card_id: EntityID, slot: usize) -> GameMessage {
+    GameMessage::PlayCard { card_id, target: None, slot: Some(slot) }
+}
+
+#[test]
+fn accepts_a_legal_play() {
+    let (game_state, players, current_turn) = setup();
+    assert_eq!(validate_action(&play(10, 0), PLAYER, &players, &current_turn, &game_state), Ok(()));
+}
+
+#[test]
+fn rejects_actions_out_of_turn() {
+    let (game_state, players, current_turn) = setup();
+    let result = validate_action(&GameMessage::EndTurn, OPPONENT, &players, &current_turn, &game_state);
+    assert_eq!(result.map_err(|e| e.code), Err(ErrorCode::NotYourTurn));
+}
+
+#[test]
+fn rejects_cards_not_in_hand_or_too_expensive() {
+    let (game_state, players, current_turn) = setup();
+    let code = |message: &GameMessage| validate_action(message, PLAYER, &players, &current_turn, &game_state).map_err(|e| e.code);
+    assert_eq!(code(&play(99, 0)), Err(ErrorCode::CardNotInHand));
+    assert_eq!(code(&play(11, 0)), Err(ErrorCode::InsufficientMana));
+}
+
+#[test]
+fn rejects_attacks_from_empty_slots() {
+    let (game_state, players, current_turn) = setup();
+    let attack = GameMessage::Attack { attacker: 0, target: None };
+    let result = validate_action(&attack, PLAYER, &players, &current_turn, &game_state);
+    assert_eq!(result.map_err(|e| e.code), Err(ErrorCode::InvalidSlot));
+}
@@ -1,43 +1,190 @@
-use serde::Deserialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use crate::channel::{CardData, CardType};
+use crate::keywords::load_keywords;
 use crate::EntityID;
 
-#[derive(Debug, Deserialize)]
-pub struct CardDefinition {
-    pub name: String,
-    pub text: String,
-    pub c_type: String,
-    pub cost: u32,
-    pub power: u32,
-}
+pub use protocol::cards::{AuraScope, CardDefinition, CardEffect, CostAura, EffectTarget};
+
+// Where load_cards reads from at compile time, for tools that edit the file on disk
+pub const CARDS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/cards.toml");
+
+// Every c_type the game knows how to map to a CardType
+pub const CARD_TYPES: &[&str] = &["Ship", "Station", "Weapon", "Event", "Defense", "Support"];
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CardConfig {
     pub cards: HashMap<String, CardDefinition>,
 }
 
+impl CardConfig {
+    // Returns a message for every problem found, empty if the config is valid
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        let mut names = HashMap::new();
+        let keywords = match load_keywords() {
+            Ok(keywords) => Some(keywords),
+            Err(e) => {
+                errors.push(format!("keywords.toml: {e}"));
+                None
+            }
+        };
+
+        for (key, card) in self.cards.iter() {
+            if key.is_empty() || !key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') {
+                errors.push(format!("{key}: id must be lowercase letters, digits or underscores"));
+            }
+            if card.name.trim().is_empty() {
+                errors.push(format!("{key}: name cannot be empty"));
+            }
+            if card.text.trim().is_empty() {
+                errors.push(format!("{key}: text cannot be empty"));
+            }
+            if !CARD_TYPES.contains(&card.c_type.as_str()) {
+                errors.push(format!("{key}: unknown c_type '{}'", card.c_type));
+            }
+            for keyword in card.keywords.iter() {
+                if keywords.as_ref().is_some_and(|k| !k.contains(keyword)) {
+                    errors.push(format!("{key}: unknown keyword '{keyword}'"));
+                }
+            }
+            for effect in card.effects.iter() {
+                match effect.target() {
+                    Some(EffectTarget::Opponent) if !matches!(effect, CardEffect::Damage { .. }) => {
+                        errors.push(format!("{key}: only damage can target the opponent"));
+                    }
+                    Some(target) if target.needs_source() && !matches!(card.card_type(), CardType::Creature) => {
+                        errors.push(format!("{key}: {target:?} effects need the card to be a creature"));
+                    }
+                    _ => {}
+                }
+            }
+            for effect in card.effects.iter() {
+                if let CardEffect::Transform { into, .. } = effect {
+                    match self.cards.get(into) {
+                        Some(other) if matches!(other.card_type(), CardType::Creature) => {}
+                        Some(_) => errors.push(format!("{key}: can only transform into creatures, '{into}' isn't one")),
+                        None => errors.push(format!("{key}: transforms into unknown card '{into}'")),
+                    }
+                }
+            }
+            if card.token && card.set.is_some() {
+                errors.push(format!("{key}: tokens can't belong to a set"));
+            }
+            if card.aura.is_some() && !matches!(card.card_type(), CardType::Creature) {
+                errors.push(format!("{key}: only creatures can have a cost aura"));
+            }
+            if let Some(other) = names.insert(card.name.as_str(), key) {
+                errors.push(format!("{key}: name '{}' is already used by {other}", card.name));
+            }
+        }
+
+        errors.sort();
+        errors
+    }
+}
+
 pub fn load_cards() -> Result<CardConfig, Box<dyn std::error::Error>> {
     let config_str = include_str!("../assets/cards.toml");
     let config: CardConfig = toml::from_str(config_str)?;
     Ok(config)
 }
 
-pub fn build_default_deck() -> Vec<(EntityID, String, String)> {
-    let config = load_cards().expect("Failed to load card definitions");
+// Languages with a cards.<language>.toml overlay; anything else gets the English text
+pub const CARD_LANGUAGES: &[&str] = &["en", "fr", "de"];
+
+// Translated name and text for one card, either of which may be left out
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CardText {
+    pub name: Option<String>,
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct CardTextOverlay {
+    #[serde(default)]
+    pub cards: HashMap<String, CardText>,
+}
+
+impl CardConfig {
+    // Overlay entries for cards that don't exist are ignored
+    pub fn apply_text(&mut self, overlay: CardTextOverlay) {
+        for (key, translated) in overlay.cards {
+            let Some(card) = self.cards.get_mut(&key) else { continue };
+            if let Some(name) = translated.name.filter(|name| !name.trim().is_empty()) {
+                card.name = name;
+            }
+            if let Some(text) = translated.text.filter(|text| !text.trim().is_empty()) {
+                card.text = text;
+            }
+        }
+    }
+}
+
+// "fr_FR.UTF-8", "fr-FR" and "FR" all mean French
+pub fn card_language(locale: &str) -> &'static str {
+    let language = locale.split(['_', '-', '.']).next().unwrap_or_default().to_lowercase();
+    CARD_LANGUAGES.iter().find(|&&known| known == language).copied().unwrap_or("en")
+}
+
+fn card_text_overlay(language: &str) -> Option<&'static str> {
+    match language {
+        "fr" => Some(include_str!("../assets/cards.fr.toml")),
+        "de" => Some(include_str!("../assets/cards.de.toml")),
+        _ => None,
+    }
+}
+
+// Card definitions with names and text in the locale's language where translated, English otherwise
+pub fn load_cards_for(locale: &str) -> Result<CardConfig, Box<dyn std::error::Error>> {
+    let mut config = load_cards()?;
+    if let Some(overlay_str) = card_text_overlay(card_language(locale)) {
+        config.apply_text(toml::from_str(overlay_str)?);
+    }
+    Ok(config)
+}
+
+pub fn load_cards_from(path: &Path) -> Result<CardConfig, Box<dyn std::error::Error>> {
+    let config_str = std::fs::read_to_string(path)?;
+    let config: CardConfig = toml::from_str(&config_str)?;
+    Ok(config)
+}
+
+pub fn save_cards(config: &CardConfig, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    // Sort by id so saving doesn't reshuffle the whole file
+    #[derive(Serialize)]
+    struct SortedConfig<'a> {
+        cards: BTreeMap<&'a String, &'a CardDefinition>,
+    }
+
+    let sorted = SortedConfig { cards: config.cards.iter().collect() };
+    std::fs::write(path, toml::to_string(&sorted)?)?;
+    Ok(())
+}
+
+pub fn build_default_deck(config: &CardConfig) -> Vec<CardData> {
     let mut deck = Vec::new();
     let mut card_id = 0;
 
-    // Add two of each card to the deck
-    for (_, card_def) in config.cards.iter() {
+    // Add two of each collectible card to the deck, by key so card ids don't depend on map order
+    let mut collectible: Vec<_> = config.cards.iter().filter(|(_, card)| !card.token).collect();
+    collectible.sort_by_key(|(key, _)| *key);
+    for (key, card_def) in collectible {
         for _ in 0..2 {
-            deck.push((
-                card_id,
-                card_def.name.clone(),
-                card_def.text.clone(),
-            ));
+            deck.push(card_def.to_card_data(key, card_id));
             card_id += 1;
         }
     }
 
     deck
-}
\ No newline at end of file
+}
+
+// Deck from saved card ids, skipping any that no longer exist
+pub fn build_deck(config: &CardConfig, card_keys: &[String]) -> Vec<CardData> {
+    card_keys.iter()
+        .filter_map(|key| config.cards.get(key).map(|card_def| (key, card_def)))
+        .enumerate()
+        .map(|(card_id, (key, card_def))| card_def.to_card_data(key, card_id as EntityID))
+        .collect()
+}
@@ -0,0 +1,13 @@
+use crate::card_details::{CardConfig, CardDefinition};
+
+pub use protocol::cards::CardFilter;
+
+// Matching cards with their ids, sorted by id so results are stable
+pub fn search(filter: &CardFilter, config: &CardConfig) -> Vec<(String, CardDefinition)> {
+    let mut results: Vec<_> = config.cards.iter()
+        .filter(|(_, card)| !card.token && filter.matches(card))
+        .map(|(key, card)| (key.clone(), card.clone()))
+        .collect();
+    results.sort_by(|a, b| a.0.cmp(&b.0));
+    results
+}
@@ -0,0 +1,38 @@
+use std::collections::HashMap;
+use crate::card_details::CardConfig;
+use crate::messages::MessageCode;
+
+pub const MIN_DECK_SIZE: usize = 20;
+pub const MAX_DECK_SIZE: usize = 40;
+pub const MAX_COPIES_PER_CARD: usize = 2;
+
+// Copies of each card id in the deck
+pub fn card_counts(cards: &[String]) -> HashMap<&str, usize> {
+    let mut counts = HashMap::new();
+    for card in cards {
+        *counts.entry(card.as_str()).or_default() += 1;
+    }
+    counts
+}
+
+// Every deck the server accepts, from the builder or from storage
+pub fn validate_deck(config: &CardConfig, cards: &[String]) -> Result<(), MessageCode> {
+    if let Some(unknown) = cards.iter().find(|card| !config.cards.contains_key(*card)) {
+        return Err(MessageCode::UnknownCard { card: unknown.clone() });
+    }
+    if let Some(token) = cards.iter().find(|card| config.cards[*card].token) {
+        return Err(MessageCode::TokenInDeck { card: config.cards[token].name.clone() });
+    }
+    if cards.len() < MIN_DECK_SIZE || cards.len() > MAX_DECK_SIZE {
+        return Err(MessageCode::DeckSize { min: MIN_DECK_SIZE, max: MAX_DECK_SIZE, actual: cards.len() });
+    }
+    let mut over_limit: Vec<_> = card_counts(cards).into_iter()
+        .filter(|&(_, count)| count > MAX_COPIES_PER_CARD)
+        .map(|(card, _)| card)
+        .collect();
+    over_limit.sort();
+    if let Some(card) = over_limit.first() {
+        return Err(MessageCode::TooManyCopies { max: MAX_COPIES_PER_CARD, card: config.cards[*card].name.clone() });
+    }
+    Ok(())
+}
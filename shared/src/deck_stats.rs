@@ -0,0 +1,44 @@
+use std::collections::BTreeMap;
+use crate::card_details::{CardConfig, CardDefinition};
+
+// Costs at or above this share the last mana curve bucket
+pub const CURVE_MAX_COST: u32 = 7;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeckStats {
+    pub card_count: u32,
+    // Cards per cost from 0 to CURVE_MAX_COST, the last bucket meaning "this or more"
+    pub mana_curve: [u32; CURVE_MAX_COST as usize + 1],
+    // Cards per c_type
+    pub type_breakdown: BTreeMap<String, u32>,
+    pub average_cost: f32,
+}
+
+impl DeckStats {
+    pub fn from_cards<'a>(cards: impl IntoIterator<Item = &'a CardDefinition>) -> Self {
+        let mut stats = Self::default();
+        let mut total_cost = 0;
+
+        for card in cards {
+            stats.card_count += 1;
+            total_cost += card.cost;
+            stats.mana_curve[card.cost.min(CURVE_MAX_COST) as usize] += 1;
+            *stats.type_breakdown.entry(card.c_type.clone()).or_default() += 1;
+        }
+
+        if stats.card_count > 0 {
+            stats.average_cost = total_cost as f32 / stats.card_count as f32;
+        }
+        stats
+    }
+
+    // Deck lists store card ids; ids missing from the config are left out
+    pub fn from_keys(config: &CardConfig, card_keys: &[String]) -> Self {
+        Self::from_cards(card_keys.iter().filter_map(|key| config.cards.get(key)))
+    }
+
+    // Tallest curve bucket, for scaling histograms
+    pub fn curve_peak(&self) -> u32 {
+        self.mana_curve.iter().copied().max().unwrap_or(0)
+    }
+}
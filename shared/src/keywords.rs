@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub use protocol::cards::Keyword;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeywordDefinition {
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KeywordConfig {
+    pub keywords: HashMap<String, KeywordDefinition>,
+}
+
+impl KeywordConfig {
+    pub fn contains(&self, id: &str) -> bool {
+        self.keywords.contains_key(id)
+    }
+
+    // Sorted by id so every client builds the same tooltip order
+    pub fn glossary(&self) -> Vec<Keyword> {
+        let mut glossary: Vec<Keyword> = self.keywords.iter()
+            .map(|(id, keyword)| Keyword {
+                id: id.clone(),
+                name: keyword.name.clone(),
+                description: keyword.description.clone(),
+            })
+            .collect();
+        glossary.sort_by(|a, b| a.id.cmp(&b.id));
+        glossary
+    }
+}
+
+pub fn load_keywords() -> Result<KeywordConfig, Box<dyn std::error::Error>> {
+    let config_str = include_str!("../assets/keywords.toml");
+    let config: KeywordConfig = toml::from_str(config_str)?;
+    Ok(config)
+}
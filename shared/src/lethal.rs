@@ -0,0 +1,82 @@
+use crate::card_details::{CardEffect, EffectTarget};
+use crate::channel::{CardData, CardType};
+use crate::EntityID;
+
+// Keyword id that lets a creature attack the turn it's played
+pub const RUSH: &str = "rush";
+
+// Hand cards past this many are left out, which keeps the search to a few thousand combinations
+const MAX_HAND_CARDS: usize = 12;
+
+// One way to take the opponent's remaining health this turn
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LethalPlan {
+    // Own board slots that should attack the opponent
+    pub attackers: Vec<usize>,
+    // Hand cards to play, by card id
+    pub cards: Vec<EntityID>,
+    pub damage: u32,
+}
+
+// What playing a card adds towards a lethal turn
+struct Contribution {
+    card_id: EntityID,
+    cost: u32,
+    damage: u32,
+    mana: u32,
+    needs_slot: bool,
+}
+
+fn contribution(card: &CardData) -> Option<Contribution> {
+    let creature = matches!(card.card_type, CardType::Creature);
+    let mut damage = if creature && card.has_keyword(RUSH) { card.power } else { 0 };
+    let mut mana = 0;
+    for effect in &card.effects {
+        match *effect {
+            CardEffect::Damage { amount, target: EffectTarget::Opponent } => damage += amount,
+            CardEffect::TemporaryMana { amount } => mana += amount,
+            _ => {}
+        }
+    }
+    (damage > 0 || mana > 0).then_some(Contribution { card_id: card.card_id, cost: card.play_cost(), damage, mana, needs_slot: creature })
+}
+
+// Nothing stops an attack on the opponent, so lethal is every ready attacker plus the smallest set of hand cards
+// that mana and free slots allow. Which creatures can still attack this turn is up to the caller
+pub fn find_lethal(
+    board: &[Option<CardData>],
+    ready: impl Fn(&CardData) -> bool,
+    hand: &[CardData],
+    mana: u32,
+    opponent_health: u32,
+) -> Option<LethalPlan> {
+    if opponent_health == 0 {
+        return None;
+    }
+    let attackers: Vec<usize> = board.iter().enumerate()
+        .filter(|(_, card)| card.as_ref().is_some_and(|card| card.power > 0 && ready(card)))
+        .map(|(slot, _)| slot)
+        .collect();
+    let board_damage: u32 = attackers.iter().filter_map(|&slot| board[slot].as_ref()).map(|card| card.power).sum();
+    if board_damage >= opponent_health {
+        return Some(LethalPlan { attackers, cards: Vec::new(), damage: board_damage });
+    }
+
+    let free_slots = board.iter().filter(|slot| slot.is_none()).count();
+    let options: Vec<Contribution> = hand.iter().filter_map(contribution).take(MAX_HAND_CARDS).collect();
+    let (cards, damage) = (1u32..1 << options.len())
+        .filter_map(|mask| {
+            let chosen: Vec<&Contribution> = options.iter().enumerate()
+                .filter(|(i, _)| mask & (1 << i) != 0)
+                .map(|(_, option)| option)
+                .collect();
+            let cost: u32 = chosen.iter().map(|option| option.cost).sum();
+            let gained: u32 = chosen.iter().map(|option| option.mana).sum();
+            let slots = chosen.iter().filter(|option| option.needs_slot).count();
+            let damage = board_damage + chosen.iter().map(|option| option.damage).sum::<u32>();
+            (cost <= mana + gained && slots <= free_slots && damage >= opponent_health)
+                .then(|| (chosen.iter().map(|option| option.card_id).collect::<Vec<_>>(), damage))
+        })
+        .min_by_key(|(cards, _)| cards.len())?;
+    Some(LethalPlan { attackers, cards, damage })
+}
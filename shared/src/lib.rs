@@ -1,8 +1,17 @@
 pub mod message_utils;
-pub mod channel;
 pub mod card_details;
+pub mod keywords;
+pub mod deck_stats;
+pub mod card_filter;
+pub mod deck_rules;
+pub mod targeting;
+pub mod lethal;
+pub mod rules;
 
-pub type EntityID = u128;
+// The wire protocol lives in its own crate; re-exported so game code keeps one import root
+pub use protocol::{api, channel, checksum, codec, errors, messages, text, EntityID};
+#[cfg(feature = "test_support")]
+pub use protocol::test_support;
 
 pub mod models {
     use serde::{Deserialize, Serialize};
@@ -13,7 +22,3 @@ pub mod models {
         pub name: String,
     }
 }
-
-pub mod api {
-    pub const API_VERSION: &str = "v0.0.1";
-}
\ No newline at end of file
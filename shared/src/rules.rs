@@ -0,0 +1,72 @@
+use crate::channel::{CardData, CardType, BOARD_SLOTS};
+use crate::errors::{ErrorCode, GameError};
+use crate::messages::MessageCode;
+use crate::targeting::{check_enemy_target, TargetSource};
+use crate::EntityID;
+
+// Legality checks that need nothing the client can't see. The server validates requests with these and the
+// client uses them to disable what would be refused, so the two always give the same reason
+
+// Nobody has the turn until the game hands out the first one
+pub fn check_turn(turn_player: Option<EntityID>, player_id: EntityID) -> Result<(), GameError> {
+    if turn_player != Some(player_id) {
+        return Err(ErrorCode::NotYourTurn.into());
+    }
+    Ok(())
+}
+
+// `cost` is what the card costs right now, after auras and discounts. Creatures need a free slot on their
+// owner's board, which `slot_free` answers
+pub fn check_play(
+    card: &CardData,
+    cost: u32,
+    available: u32,
+    slot: Option<usize>,
+    slot_free: impl Fn(usize) -> bool,
+) -> Result<(), GameError> {
+    if cost > available {
+        return Err(GameError::with_detail(
+            ErrorCode::InsufficientMana,
+            MessageCode::ManaCost { cost, available },
+        ));
+    }
+
+    if matches!(card.card_type, CardType::Creature) {
+        let slot = slot.filter(|&slot| slot < BOARD_SLOTS)
+            .ok_or_else(|| GameError::with_detail(
+                ErrorCode::InvalidSlot,
+                MessageCode::SlotOutOfRange { max_slot: BOARD_SLOTS - 1 },
+            ))?;
+        if !slot_free(slot) {
+            return Err(GameError::with_detail(ErrorCode::SlotOccupied, MessageCode::SlotTaken { slot }));
+        }
+    }
+    Ok(())
+}
+
+// A card's chosen target has to be a creature in play. Own creatures can always be chosen; enemy ones have to be
+// targetable by effects. Pass `own` when the target is on the player's own board, otherwise the enemy creature if any
+pub fn check_play_target(own: bool, enemy: Option<&CardData>) -> Result<(), GameError> {
+    if own {
+        return Ok(());
+    }
+    let enemy = enemy.ok_or_else(|| GameError::with_detail(ErrorCode::InvalidTarget, MessageCode::TargetNotInPlay))?;
+    check_enemy_target(enemy, TargetSource::Effect)
+        .map_err(|e| GameError::with_detail(ErrorCode::InvalidTarget, e))
+}
+
+// `ready` is whether the attacker can still attack this turn, which only the server knows for certain.
+// `target` is None for an attack on the opponent, otherwise whatever is in the chosen enemy slot
+pub fn check_attack(attacker: Option<&CardData>, ready: bool, target: Option<Option<&CardData>>) -> Result<(), GameError> {
+    let attacker = attacker.ok_or_else(|| GameError::with_detail(ErrorCode::InvalidSlot, MessageCode::NoCreatureInSlot))?;
+    if !ready {
+        return Err(GameError::with_detail(ErrorCode::CannotAttack, MessageCode::NotReadyToAttack { card: attacker.card_name.clone() }));
+    }
+
+    if let Some(target) = target {
+        let defender = target.ok_or_else(|| GameError::with_detail(ErrorCode::InvalidTarget, MessageCode::NoEnemyInSlot))?;
+        check_enemy_target(defender, TargetSource::Attack)
+            .map_err(|e| GameError::with_detail(ErrorCode::InvalidTarget, e))?;
+    }
+    Ok(())
+}
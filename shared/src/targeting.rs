@@ -0,0 +1,29 @@
+use crate::channel::CardData;
+use crate::messages::MessageCode;
+
+// Keyword ids with targeting rules
+pub const STEALTH: &str = "stealth";
+pub const ELUSIVE: &str = "elusive";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetSource {
+    Attack,
+    // Damage or other effects from a played card
+    Effect,
+}
+
+// Stealth wears off for good once the creature attacks
+pub fn is_stealthed(card: &CardData) -> bool {
+    card.has_keyword(STEALTH) && !card.revealed
+}
+
+// Whether the opponent may pick this creature with an attack or an effect
+pub fn check_enemy_target(target: &CardData, source: TargetSource) -> Result<(), MessageCode> {
+    if is_stealthed(target) {
+        return Err(MessageCode::Stealthed { card: target.card_name.clone() });
+    }
+    if source == TargetSource::Effect && target.has_keyword(ELUSIVE) {
+        return Err(MessageCode::Elusive { card: target.card_name.clone() });
+    }
+    Ok(())
+}
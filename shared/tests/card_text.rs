@@ -0,0 +1,39 @@
+use shared::card_details::{card_language, load_cards, load_cards_for, CardText, CardTextOverlay};
+
+#[test]
+fn locales_pick_a_translated_language_or_english() {
+    assert_eq!(card_language("fr_FR.UTF-8"), "fr");
+    assert_eq!(card_language("de-AT"), "de");
+    assert_eq!(card_language("FR"), "fr");
+    assert_eq!(card_language("ja_JP"), "en");
+    assert_eq!(card_language(""), "en");
+}
+
+#[test]
+fn translations_cover_existing_cards_and_keep_the_rules() {
+    let english = load_cards().unwrap();
+    for locale in ["fr", "de"] {
+        let translated = load_cards_for(locale).unwrap();
+        assert!(translated.validate().is_empty(), "{locale}: {:?}", translated.validate());
+        for (key, card) in &translated.cards {
+            let base = &english.cards[key];
+            assert_ne!(card.name, base.name, "{locale}: {key} isn't translated");
+            assert_eq!((card.cost, card.power, card.health), (base.cost, base.power, base.health));
+        }
+    }
+}
+
+#[test]
+fn missing_translations_fall_back_to_english() {
+    let mut cards = load_cards().unwrap();
+    let english = cards.cards["plasma_cannon"].clone();
+    let mut overlay = CardTextOverlay::default();
+    overlay.cards.insert("plasma_cannon".to_string(), CardText { name: Some("Canon à plasma".to_string()), text: None });
+    overlay.cards.insert("no_such_card".to_string(), CardText { name: Some("Rien".to_string()), text: None });
+    cards.apply_text(overlay);
+
+    assert_eq!(cards.cards["plasma_cannon"].name, "Canon à plasma");
+    assert_eq!(cards.cards["plasma_cannon"].text, english.text);
+    assert_eq!(cards.cards["void_rift"].name, load_cards().unwrap().cards["void_rift"].name);
+    assert!(!cards.cards.contains_key("no_such_card"));
+}
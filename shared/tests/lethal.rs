@@ -0,0 +1,62 @@
+use protocol::test_support::CardBuilder;
+use shared::card_details::{CardEffect, EffectTarget};
+use shared::channel::{CardData, CardType, BOARD_SLOTS};
+use shared::lethal::{find_lethal, RUSH};
+use shared::EntityID;
+
+fn card(card_id: EntityID, card_type: CardType, cost: u32, power: u32) -> CardData {
+    CardBuilder::new(card_id).card_type(card_type).cost(cost).stats(power, 1).build()
+}
+
+fn board(creatures: &[CardData]) -> Vec<Option<CardData>> {
+    let mut slots = vec![None; BOARD_SLOTS];
+    for (slot, creature) in creatures.iter().enumerate() {
+        slots[slot] = Some(creature.clone());
+    }
+    slots
+}
+
+fn bolt(card_id: EntityID, cost: u32, amount: u32) -> CardData {
+    CardBuilder::new(card_id)
+        .card_type(CardType::Spell)
+        .cost(cost)
+        .stats(0, 1)
+        .effect(CardEffect::Damage { amount, target: EffectTarget::Opponent })
+        .build()
+}
+
+#[test]
+fn ready_attackers_alone_can_be_lethal() {
+    let board = board(&[card(1, CardType::Creature, 2, 3), card(2, CardType::Creature, 2, 2)]);
+    let plan = find_lethal(&board, |_| true, &[], 0, 5).expect("five damage on the board");
+    assert_eq!(plan.attackers, vec![0, 1]);
+    assert!(plan.cards.is_empty());
+
+    // A creature that already attacked doesn't count
+    assert_eq!(find_lethal(&board, |card| card.card_id != 2, &[], 0, 5), None);
+}
+
+#[test]
+fn burn_in_hand_makes_up_the_difference_when_mana_allows() {
+    let board = board(&[card(1, CardType::Creature, 2, 3)]);
+    let hand = [bolt(10, 2, 2), bolt(11, 4, 4), card(12, CardType::Creature, 1, 5)];
+    let plan = find_lethal(&board, |_| true, &hand, 2, 5).expect("three on the board and a two damage bolt");
+    assert_eq!(plan.cards, vec![10]);
+    assert_eq!(plan.damage, 5);
+
+    // Creatures without Rush can't attack this turn, so they don't help
+    assert_eq!(find_lethal(&board, |_| true, &hand, 1, 5), None);
+}
+
+#[test]
+fn temporary_mana_and_rush_creatures_count() {
+    let cell = CardBuilder::new(20).card_type(CardType::Spell).stats(0, 1).effect(CardEffect::TemporaryMana { amount: 1 }).build();
+    let charger = CardBuilder::new(21).cost(3).stats(4, 1).keyword(RUSH).build();
+
+    let plan = find_lethal(&board(&[]), |_| true, &[cell, charger.clone()], 2, 4).expect("the cell pays for the charger");
+    assert_eq!(plan.cards.len(), 2);
+
+    // A Rush creature needs somewhere to go
+    let full: Vec<_> = (0..BOARD_SLOTS as EntityID).map(|id| card(id, CardType::Creature, 0, 0)).collect();
+    assert_eq!(find_lethal(&board(&full), |_| true, &[charger], 3, 4), None);
+}
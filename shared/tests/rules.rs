@@ -0,0 +1,49 @@
+use protocol::test_support::CardBuilder;
+use shared::channel::{CardType, BOARD_SLOTS};
+use shared::errors::ErrorCode;
+use shared::rules::{check_attack, check_play, check_play_target, check_turn};
+use shared::targeting::{ELUSIVE, STEALTH};
+
+#[test]
+fn only_the_turn_player_may_act() {
+    assert!(check_turn(Some(1), 1).is_ok());
+    assert_eq!(check_turn(Some(2), 1).unwrap_err().code, ErrorCode::NotYourTurn);
+    assert_eq!(check_turn(None, 1).unwrap_err().code, ErrorCode::NotYourTurn);
+}
+
+#[test]
+fn plays_need_mana_and_a_free_slot() {
+    let creature = CardBuilder::new(1).cost(3).build();
+    assert_eq!(check_play(&creature, 3, 2, Some(0), |_| true).unwrap_err().code, ErrorCode::InsufficientMana);
+    assert_eq!(check_play(&creature, 3, 3, Some(BOARD_SLOTS), |_| true).unwrap_err().code, ErrorCode::InvalidSlot);
+    assert_eq!(check_play(&creature, 3, 3, None, |_| true).unwrap_err().code, ErrorCode::InvalidSlot);
+    assert_eq!(check_play(&creature, 3, 3, Some(0), |_| false).unwrap_err().code, ErrorCode::SlotOccupied);
+    assert!(check_play(&creature, 3, 3, Some(0), |_| true).is_ok());
+
+    // Spells never need a slot
+    let spell = CardBuilder::new(2).card_type(CardType::Spell).cost(1).build();
+    assert!(check_play(&spell, 1, 1, None, |_| false).is_ok());
+}
+
+#[test]
+fn effects_cannot_target_hidden_enemies() {
+    assert!(check_play_target(true, None).is_ok());
+    assert_eq!(check_play_target(false, None).unwrap_err().code, ErrorCode::InvalidTarget);
+    let elusive = CardBuilder::new(1).cost(1).keyword(ELUSIVE).build();
+    assert_eq!(check_play_target(false, Some(&elusive)).unwrap_err().code, ErrorCode::InvalidTarget);
+}
+
+#[test]
+fn attacks_need_a_ready_attacker_and_a_visible_target() {
+    let attacker = CardBuilder::new(1).cost(1).build();
+    assert_eq!(check_attack(None, true, None).unwrap_err().code, ErrorCode::InvalidSlot);
+    assert_eq!(check_attack(Some(&attacker), false, None).unwrap_err().code, ErrorCode::CannotAttack);
+    assert!(check_attack(Some(&attacker), true, None).is_ok());
+    assert_eq!(check_attack(Some(&attacker), true, Some(None)).unwrap_err().code, ErrorCode::InvalidTarget);
+
+    let stealthed = CardBuilder::new(2).cost(1).keyword(STEALTH).build();
+    assert_eq!(check_attack(Some(&attacker), true, Some(Some(&stealthed))).unwrap_err().code, ErrorCode::InvalidTarget);
+    // Elusive only keeps effects off, attacks still land
+    let elusive = CardBuilder::new(3).cost(1).keyword(ELUSIVE).build();
+    assert!(check_attack(Some(&attacker), true, Some(Some(&elusive))).is_ok());
+}